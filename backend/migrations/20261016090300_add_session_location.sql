@@ -0,0 +1,2 @@
+-- Approximate location of the client that started a session
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS location VARCHAR(100);
@@ -0,0 +1,2 @@
+-- Extra data bound to a token (e.g. the pending address of an email change)
+ALTER TABLE action_tokens ADD COLUMN IF NOT EXISTS payload TEXT;
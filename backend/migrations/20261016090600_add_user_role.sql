@@ -0,0 +1,3 @@
+-- Global (instance-wide) role, separate from per-room member roles
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'moderator', 'admin'));
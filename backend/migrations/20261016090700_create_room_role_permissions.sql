@@ -0,0 +1,9 @@
+-- Per-room customization of what each member role may do.
+-- Roles without a row use the built-in defaults.
+CREATE TABLE IF NOT EXISTS room_role_permissions (
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL,
+    permissions TEXT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (room_id, role)
+);
@@ -0,0 +1,6 @@
+-- Account deletion: requested (grace period), then purged into an anonymized tombstone
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deletion_requested_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_pending_deletion ON users(deletion_requested_at)
+    WHERE deletion_requested_at IS NOT NULL AND deleted_at IS NULL;
@@ -0,0 +1,5 @@
+-- Self-service deactivation (reversible by logging in within the reactivation window)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deactivated ON users(deactivated_at)
+    WHERE deactivated_at IS NOT NULL;
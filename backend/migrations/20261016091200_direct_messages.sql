@@ -0,0 +1,17 @@
+-- Direct (1:1) conversations are rooms of type 'dm' with exactly two members
+ALTER TYPE room_type ADD VALUE IF NOT EXISTS 'dm';
+
+-- One conversation per pair of users (stored in canonical order)
+CREATE TABLE IF NOT EXISTS direct_conversations (
+    room_id UUID PRIMARY KEY REFERENCES rooms(id) ON DELETE CASCADE,
+    user_low UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_high UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (user_low < user_high),
+    UNIQUE (user_low, user_high)
+);
+
+CREATE INDEX IF NOT EXISTS idx_direct_conversations_user_high ON direct_conversations(user_high);
+
+-- Read marker for unread counts
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS last_read_at TIMESTAMPTZ;
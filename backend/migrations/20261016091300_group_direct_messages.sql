@@ -0,0 +1,2 @@
+-- Group direct messages: ad-hoc private conversations with a handful of participants
+ALTER TYPE room_type ADD VALUE IF NOT EXISTS 'group_dm';
@@ -0,0 +1,16 @@
+-- Invitations to join a room (the only way into private rooms)
+CREATE TABLE IF NOT EXISTS room_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    inviter_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    invitee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'accepted', 'declined')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    responded_at TIMESTAMPTZ
+);
+
+-- At most one pending invitation per user and room
+CREATE UNIQUE INDEX IF NOT EXISTS idx_room_invitations_pending
+    ON room_invitations(room_id, invitee_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_room_invitations_invitee ON room_invitations(invitee_id, status);
@@ -0,0 +1,2 @@
+-- Room image shown in room lists
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS avatar_url VARCHAR(500);
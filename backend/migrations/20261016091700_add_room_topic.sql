@@ -0,0 +1,2 @@
+-- Topic / announcement shown pinned at the top of a room
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS topic VARCHAR(500);
@@ -0,0 +1,3 @@
+-- Who may post in a room ('admins_only' makes announcement rooms)
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS post_policy VARCHAR(20) NOT NULL DEFAULT 'everyone'
+    CHECK (post_policy IN ('everyone', 'admins_only'));
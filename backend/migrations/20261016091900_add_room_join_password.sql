@@ -0,0 +1,2 @@
+-- Optional join password (Argon2 hash); lets private rooms be joined without an invite
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS join_password_hash VARCHAR(255);
@@ -0,0 +1,10 @@
+-- Per-room daily activity, aggregated from messages by a background job
+CREATE TABLE IF NOT EXISTS room_daily_stats (
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    message_count BIGINT NOT NULL DEFAULT 0,
+    active_members BIGINT NOT NULL DEFAULT 0,
+    peak_concurrency INTEGER,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (room_id, day)
+);
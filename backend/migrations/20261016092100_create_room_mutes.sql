@@ -0,0 +1,8 @@
+-- Rooms a user muted (no push notifications or unread badges); expires_at NULL = until unmuted
+CREATE TABLE IF NOT EXISTS room_mutes (
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (room_id, user_id)
+);
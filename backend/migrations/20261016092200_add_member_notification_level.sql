@@ -0,0 +1,3 @@
+-- Per-member notification level, synced across the member's devices
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS notification_level VARCHAR(20) NOT NULL DEFAULT 'all'
+    CHECK (notification_level IN ('all', 'mentions', 'none'));
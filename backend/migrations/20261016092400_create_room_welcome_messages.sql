@@ -0,0 +1,8 @@
+-- Per-room welcome message template sent to new members
+CREATE TABLE IF NOT EXISTS room_welcome_messages (
+    room_id UUID PRIMARY KEY REFERENCES rooms(id) ON DELETE CASCADE,
+    template TEXT NOT NULL,
+    delivery VARCHAR(10) NOT NULL DEFAULT 'room', -- 'room' or 'dm'
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
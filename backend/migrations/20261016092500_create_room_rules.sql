@@ -0,0 +1,12 @@
+-- Room rules that members accept before posting, versioned on every change
+CREATE TABLE IF NOT EXISTS room_rules (
+    room_id UUID PRIMARY KEY REFERENCES rooms(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    version INTEGER NOT NULL DEFAULT 1,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Rules version each member last accepted (NULL = never)
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS rules_accepted_version INTEGER;
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS rules_accepted_at TIMESTAMPTZ;
@@ -0,0 +1,3 @@
+-- Per-member favorite flag and custom sidebar order, synced across devices
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS is_favorite BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS sort_order INTEGER; -- NULL = after ordered rooms
@@ -0,0 +1,9 @@
+-- Tombstones of rooms merged into another room
+CREATE TABLE IF NOT EXISTS room_redirects (
+    old_room_id UUID PRIMARY KEY,
+    new_room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    merged_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    merged_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_redirects_new_room ON room_redirects(new_room_id);
@@ -0,0 +1,22 @@
+-- Scheduled events of a room, with a reminder sent shortly before start
+CREATE TABLE IF NOT EXISTS room_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    starts_at TIMESTAMPTZ NOT NULL,
+    reminder_sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_events_room ON room_events(room_id, starts_at);
+CREATE INDEX IF NOT EXISTS idx_room_events_reminder ON room_events(starts_at) WHERE reminder_sent_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS room_event_rsvps (
+    event_id UUID NOT NULL REFERENCES room_events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('going', 'maybe', 'not_going')),
+    responded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, user_id)
+);
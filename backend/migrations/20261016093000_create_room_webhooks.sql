@@ -0,0 +1,13 @@
+-- Incoming webhooks: external systems post into a room with a secret URL
+CREATE TABLE IF NOT EXISTS room_webhooks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    name VARCHAR(80) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL, -- SHA-256 of the URL token
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_webhooks_room ON room_webhooks(room_id) WHERE revoked_at IS NULL;
@@ -0,0 +1,4 @@
+-- Custom status shown next to a user's name, optionally cleared at an expiry
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status_text VARCHAR(100);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status_emoji VARCHAR(32);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status_expires_at TIMESTAMPTZ; -- NULL = until cleared
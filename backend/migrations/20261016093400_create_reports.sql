@@ -0,0 +1,25 @@
+-- User and message reports, reviewed by global moderators
+CREATE TABLE IF NOT EXISTS reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reporter_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    target_type VARCHAR(10) NOT NULL CHECK (target_type IN ('message', 'user')),
+    target_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE, -- reported user or message author
+    message_id UUID, -- message reports only; kept if the message is deleted
+    room_id UUID REFERENCES rooms(id) ON DELETE SET NULL,
+    reason VARCHAR(20) NOT NULL,
+    details TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'reviewing', 'resolved')),
+    reviewer_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolution_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_reports_queue ON reports(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_reports_target_user ON reports(target_user_id);
+
+-- One unresolved report per reporter and target
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reports_unresolved_per_reporter
+    ON reports(reporter_id, target_type, COALESCE(message_id, target_user_id))
+    WHERE status <> 'resolved';
@@ -0,0 +1,14 @@
+-- Slash commands registered by bots per room, dispatched to the bot's callback URL
+CREATE TABLE IF NOT EXISTS room_bot_commands (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+    bot_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(32) NOT NULL,
+    description VARCHAR(200) NOT NULL DEFAULT '',
+    usage_hint VARCHAR(100),
+    callback_url VARCHAR(2048) NOT NULL,
+    signing_secret VARCHAR(64) NOT NULL, -- HMAC key for callback requests
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (room_id, name)
+);
@@ -0,0 +1,3 @@
+-- Per-user timezone (IANA name) and locale (BCP 47 tag) for server-rendered times
+ALTER TABLE users ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
+ALTER TABLE users ADD COLUMN IF NOT EXISTS locale VARCHAR(35) NOT NULL DEFAULT 'en';
@@ -0,0 +1,2 @@
+-- Per-room nickname, shown instead of the global display name in that room
+ALTER TABLE room_members ADD COLUMN IF NOT EXISTS nickname VARCHAR(50);
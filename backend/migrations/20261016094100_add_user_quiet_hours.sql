@@ -0,0 +1,3 @@
+-- Do-not-disturb window in the user's timezone (both NULL = off; may span midnight)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS quiet_hours_start TIME;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS quiet_hours_end TIME;
@@ -0,0 +1,15 @@
+-- Additional email addresses linked to an account (the primary one stays in users.email)
+CREATE TABLE IF NOT EXISTS user_emails (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    verified_at TIMESTAMPTZ, -- NULL until the emailed link is confirmed
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, email)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_emails_user ON user_emails(user_id);
+
+-- Only a verified address is owned; unverified ones can't block anybody else
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_emails_verified_email ON user_emails(email)
+    WHERE verified_at IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Admin user management: instance-wide suspension and forced password resets
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspension_reason VARCHAR(500);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS password_reset_required BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_users_suspended ON users(suspended_at)
+    WHERE suspended_at IS NOT NULL;
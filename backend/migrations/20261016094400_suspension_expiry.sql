@@ -0,0 +1,2 @@
+-- Suspensions may expire; NULL means the account stays suspended (banned) until lifted
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_until TIMESTAMPTZ;
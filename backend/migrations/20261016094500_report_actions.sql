@@ -0,0 +1,13 @@
+-- Admin review of reports: the action taken on resolution and warnings issued to users
+ALTER TABLE reports ADD COLUMN IF NOT EXISTS action VARCHAR(20);
+
+CREATE TABLE IF NOT EXISTS user_warnings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    issued_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    report_id UUID REFERENCES reports(id) ON DELETE SET NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_warnings_user ON user_warnings(user_id, created_at DESC);
@@ -0,0 +1,12 @@
+-- Banned words and patterns applied to messages, managed by admins at runtime
+CREATE TABLE IF NOT EXISTS word_filters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    pattern VARCHAR(200) NOT NULL, -- a plain word/phrase, or a regex when is_regex
+    is_regex BOOLEAN NOT NULL DEFAULT false,
+    action VARCHAR(10) NOT NULL DEFAULT 'mask' CHECK (action IN ('mask', 'block')),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_word_filters_pattern ON word_filters(LOWER(pattern), is_regex);
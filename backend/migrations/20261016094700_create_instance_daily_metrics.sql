@@ -0,0 +1,11 @@
+-- Instance-wide daily metrics for the admin dashboard, snapshotted by a background job
+CREATE TABLE IF NOT EXISTS instance_daily_metrics (
+    day DATE PRIMARY KEY, -- UTC day
+    total_users BIGINT NOT NULL DEFAULT 0, -- Non-bot accounts that are not deleted
+    daily_active_users BIGINT NOT NULL DEFAULT 0,
+    monthly_active_users BIGINT NOT NULL DEFAULT 0, -- Active within the 30 days ending on `day`
+    total_rooms BIGINT NOT NULL DEFAULT 0,
+    message_count BIGINT NOT NULL DEFAULT 0, -- Messages sent that day
+    storage_bytes BIGINT NOT NULL DEFAULT 0, -- Database size
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
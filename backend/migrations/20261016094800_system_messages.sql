@@ -0,0 +1,18 @@
+-- Reserved "System" account that sends official DMs (no password, no API key)
+INSERT INTO users (id, username, email, password_hash, display_name, status, is_bot, is_verified, is_staff, email_verified_at)
+VALUES ('00000000-0000-0000-0000-000000000001', 'system', 'system@system.invalid', '!', 'System', 'offline',
+        true, true, true, NOW())
+ON CONFLICT DO NOTHING;
+
+-- Official messages sent by admins through the System account
+CREATE TABLE IF NOT EXISTS system_messages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    room_id UUID NOT NULL REFERENCES rooms(id) ON DELETE CASCADE, -- The recipient's conversation with System
+    recipient_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    sent_by UUID REFERENCES users(id) ON DELETE SET NULL, -- Admin who wrote it
+    kind VARCHAR(20) NOT NULL DEFAULT 'notice' CHECK (kind IN ('notice', 'warning', 'policy')),
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_system_messages_recipient ON system_messages(recipient_id, created_at DESC);
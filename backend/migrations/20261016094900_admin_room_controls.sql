@@ -0,0 +1,3 @@
+-- Rooms locked by an admin are frozen: no posting, joining or settings changes
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS locked_at TIMESTAMPTZ;
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS lock_reason VARCHAR(500);
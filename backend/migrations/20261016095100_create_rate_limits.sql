@@ -0,0 +1,10 @@
+-- Admin overrides of the rate limits in the environment config, one row per route class.
+-- Sliding-window classes read max_requests per window_seconds; token-bucket classes
+-- read max_requests as the burst size and window_seconds as the refill interval.
+CREATE TABLE IF NOT EXISTS rate_limits (
+    class VARCHAR(50) PRIMARY KEY,
+    max_requests INTEGER NOT NULL CHECK (max_requests > 0),
+    window_seconds INTEGER NOT NULL CHECK (window_seconds > 0),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Support the admin audit log query: newest-first keyset pagination, optionally by target
+CREATE INDEX IF NOT EXISTS idx_audit_logs_created ON audit_logs(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_target ON audit_logs(target_id, created_at DESC);
@@ -0,0 +1,5 @@
+-- Deleted accounts are anonymized after the grace period (deleted_at) and their
+-- remaining content is removed after the retention window (purged_at)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS purged_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deleted_unpurged ON users(deleted_at) WHERE deleted_at IS NOT NULL AND purged_at IS NULL;
@@ -0,0 +1,11 @@
+-- Client IPs and networks refused before any handler runs, managed by admins
+CREATE TABLE IF NOT EXISTS ip_bans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    cidr VARCHAR(50) NOT NULL, -- normalized, e.g. '203.0.113.0/24' or '2001:db8::1/128'
+    reason VARCHAR(500) NOT NULL,
+    expires_at TIMESTAMPTZ, -- permanent when NULL
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_ip_bans_cidr ON ip_bans(cidr);
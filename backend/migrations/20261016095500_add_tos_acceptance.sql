@@ -0,0 +1,4 @@
+-- Version of the terms of service each user last accepted; users behind the
+-- instance's current version are asked to accept it again
+ALTER TABLE users ADD COLUMN IF NOT EXISTS tos_version VARCHAR(50);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS tos_accepted_at TIMESTAMPTZ;
@@ -0,0 +1,29 @@
+-- Denormalized member count of each room, kept in step with room_members by a
+-- trigger so it changes in the same transaction as the membership itself
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS member_count INTEGER NOT NULL DEFAULT 0;
+
+UPDATE rooms r
+SET member_count = (SELECT COUNT(*) FROM room_members rm WHERE rm.room_id = r.id);
+
+CREATE OR REPLACE FUNCTION update_room_member_count() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.room_id = NEW.room_id THEN
+        RETURN NULL;
+    END IF;
+
+    IF TG_OP IN ('DELETE', 'UPDATE') THEN
+        UPDATE rooms SET member_count = member_count - 1 WHERE id = OLD.room_id;
+    END IF;
+
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE rooms SET member_count = member_count + 1 WHERE id = NEW.room_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS room_members_count ON room_members;
+CREATE TRIGGER room_members_count
+AFTER INSERT OR DELETE OR UPDATE OF room_id ON room_members
+FOR EACH ROW EXECUTE FUNCTION update_room_member_count();
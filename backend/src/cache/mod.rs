@@ -1,40 +1,408 @@
-use redis::{Client, Connection};
+use redis::aio::ConnectionManager;
+use redis::Client;
 use crate::error::AppError;
 
-/// Create a Redis client
-pub fn create_client(redis_url: &str) -> Result<Client, AppError> {
-    Client::open(redis_url)
-        .map_err(|e| AppError::RedisError(format!("Failed to create Redis client: {}", e)))
+mod service;
+
+pub use service::CacheService;
+
+/// Shared Redis handle (cheap to clone): one multiplexed async connection that
+/// reconnects on its own, plus the client that opens dedicated pub/sub connections
+#[derive(Clone)]
+pub struct RedisPool {
+    client: Client,
+    connection: ConnectionManager,
+}
+
+/// Connect to Redis
+pub async fn create_pool(redis_url: &str) -> Result<RedisPool, AppError> {
+    let client = Client::open(redis_url)
+        .map_err(|e| AppError::RedisError(format!("Failed to create Redis client: {}", e)))?;
+
+    let connection = ConnectionManager::new(client.clone())
+        .await
+        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
+
+    Ok(RedisPool { client, connection })
 }
 
-/// Get a connection from the Redis client
-pub fn get_connection(client: &Client) -> Result<Connection, AppError> {
-    client
-        .get_connection()
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))
+/// Get a handle on the shared connection (commands are pipelined, never blocking a worker)
+pub fn get_connection(pool: &RedisPool) -> ConnectionManager {
+    pool.connection.clone()
 }
 
 /// Test Redis connection
-pub fn test_connection(client: &Client) -> Result<(), AppError> {
-    let mut conn = get_connection(client)?;
+pub async fn test_connection(pool: &RedisPool) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
 
     // Test basic operations
     redis::cmd("SET")
         .arg("test_key")
         .arg("test_value")
-        .query::<()>(&mut conn)
+        .query_async::<_, ()>(&mut conn).await
         .map_err(|e| AppError::RedisError(format!("Redis SET failed: {}", e)))?;
 
     let _value: String = redis::cmd("GET")
         .arg("test_key")
-        .query(&mut conn)
+        .query_async(&mut conn).await
         .map_err(|e| AppError::RedisError(format!("Redis GET failed: {}", e)))?;
 
     redis::cmd("DEL")
         .arg("test_key")
-        .query::<()>(&mut conn)
+        .query_async::<_, ()>(&mut conn).await
         .map_err(|e| AppError::RedisError(format!("Redis DEL failed: {}", e)))?;
 
     log::info!("✅ Redis connection test successful");
     Ok(())
 }
+
+/// Store a string value that expires after `ttl_seconds`
+pub async fn set_with_ttl(pool: &RedisPool, key: &str, value: &str, ttl_seconds: u64) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
+
+    redis::cmd("SET")
+        .arg(key)
+        .arg(value)
+        .arg("EX")
+        .arg(ttl_seconds)
+        .query_async::<_, ()>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Read a string value
+pub async fn get_value(pool: &RedisPool, key: &str) -> Result<Option<String>, AppError> {
+    let mut conn = get_connection(pool);
+
+    let value: Option<String> = redis::cmd("GET")
+        .arg(key)
+        .query_async(&mut conn).await?;
+
+    Ok(value)
+}
+
+/// Read and delete a value atomically (for single-use values)
+pub async fn take_value(pool: &RedisPool, key: &str) -> Result<Option<String>, AppError> {
+    let mut conn = get_connection(pool);
+
+    let value: Option<String> = redis::cmd("GETDEL")
+        .arg(key)
+        .query_async(&mut conn).await?;
+
+    Ok(value)
+}
+
+/// Delete a key (no-op when missing)
+pub async fn delete_value(pool: &RedisPool, key: &str) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
+
+    redis::cmd("DEL")
+        .arg(key)
+        .query_async::<_, ()>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Redis key for a revoked token ID
+fn revoked_token_key(jti: &str) -> String {
+    format!("revoked_token:{}", jti)
+}
+
+/// Add a token ID to the revocation denylist until the token would expire anyway
+pub async fn revoke_token(pool: &RedisPool, jti: &str, ttl_seconds: i64) -> Result<(), AppError> {
+    // Already-expired tokens are rejected by signature validation
+    if ttl_seconds <= 0 {
+        return Ok(());
+    }
+
+    let mut conn = get_connection(pool);
+
+    redis::cmd("SET")
+        .arg(revoked_token_key(jti))
+        .arg(1)
+        .arg("EX")
+        .arg(ttl_seconds)
+        .query_async::<_, ()>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Check whether a token ID has been revoked
+pub async fn is_token_revoked(pool: &RedisPool, jti: &str) -> Result<bool, AppError> {
+    let mut conn = get_connection(pool);
+
+    let exists: bool = redis::cmd("EXISTS")
+        .arg(revoked_token_key(jti))
+        .query_async(&mut conn).await?;
+
+    Ok(exists)
+}
+
+/// Record a hit in a sliding-window counter and return whether it is within `max_requests`
+pub async fn hit_sliding_window(
+    pool: &RedisPool,
+    key: &str,
+    max_requests: u32,
+    window_seconds: u64,
+) -> Result<bool, AppError> {
+    let mut conn = get_connection(pool);
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let window_start = now_ms - (window_seconds as i64 * 1000);
+
+    // Drop hits older than the window, record this one and count what remains
+    let (count,): (u64,) = redis::pipe()
+        .atomic()
+        .cmd("ZREMRANGEBYSCORE").arg(key).arg(0).arg(window_start).ignore()
+        .cmd("ZADD").arg(key).arg(now_ms).arg(uuid::Uuid::new_v4().to_string()).ignore()
+        .cmd("ZCARD").arg(key)
+        .cmd("EXPIRE").arg(key).arg(window_seconds).ignore()
+        .query_async(&mut conn).await?;
+
+    Ok(count <= max_requests as u64)
+}
+
+/// Token bucket refill-and-take, atomic on the Redis side
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local refill_ms = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or capacity
+local ts = tonumber(state[2]) or now
+
+tokens = math.min(capacity, tokens + (now - ts) / refill_ms)
+
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], capacity * refill_ms)
+return allowed
+"#;
+
+/// Take a token from a bucket of `capacity` that regains one token every `refill_seconds`.
+/// Returns false when the bucket is empty.
+pub async fn take_bucket_token(
+    pool: &RedisPool,
+    key: &str,
+    capacity: u32,
+    refill_seconds: u64,
+) -> Result<bool, AppError> {
+    let mut conn = get_connection(pool);
+
+    let allowed: i32 = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+        .key(key)
+        .arg(capacity)
+        .arg(refill_seconds.max(1) * 1000)
+        .arg(chrono::Utc::now().timestamp_millis())
+        .invoke_async(&mut conn).await?;
+
+    Ok(allowed == 1)
+}
+
+/// Count a message towards its author's spam window: returns how often this exact
+/// content was sent and how many messages were sent within the window
+pub async fn spam_counters(
+    pool: &RedisPool,
+    author_key: &str,
+    fingerprint: &str,
+    window_seconds: u64,
+) -> Result<(u64, u64), AppError> {
+    let mut conn = get_connection(pool);
+
+    let repeat_key = format!("spam:{}:content:{}", author_key, fingerprint);
+    let burst_key = format!("spam:{}:messages", author_key);
+
+    // Counters start at the first message and expire a window later (fixed windows)
+    let (repeats, recent_messages): (u64, u64) = redis::pipe()
+        .atomic()
+        .cmd("SET").arg(&repeat_key).arg(0).arg("EX").arg(window_seconds).arg("NX").ignore()
+        .cmd("INCR").arg(&repeat_key)
+        .cmd("SET").arg(&burst_key).arg(0).arg("EX").arg(window_seconds).arg("NX").ignore()
+        .cmd("INCR").arg(&burst_key)
+        .query_async(&mut conn).await?;
+
+    Ok((repeats, recent_messages))
+}
+
+/// Count a use of a quota in a fixed window: returns the uses so far, including this one
+pub async fn quota_hit(pool: &RedisPool, key: &str, window_seconds: u64) -> Result<u64, AppError> {
+    let mut conn = get_connection(pool);
+
+    let (count,): (u64,) = redis::pipe()
+        .atomic()
+        .cmd("SET").arg(key).arg(0).arg("EX").arg(window_seconds).arg("NX").ignore()
+        .cmd("INCR").arg(key)
+        .query_async(&mut conn).await?;
+
+    Ok(count)
+}
+
+/// Pub/sub channel carrying realtime events of a room
+pub fn room_channel(room_id: uuid::Uuid) -> String {
+    format!("room:{}:events", room_id)
+}
+
+/// Pub/sub channel carrying realtime events of a single user
+pub fn user_channel(user_id: uuid::Uuid) -> String {
+    format!("user:{}:events", user_id)
+}
+
+/// Pub/sub channel telling a user's open WebSocket connections to close;
+/// the message is the close reason
+pub fn user_disconnect_channel(user_id: uuid::Uuid) -> String {
+    format!("user:{}:disconnect", user_id)
+}
+
+/// Pub/sub channel carrying realtime events for every connected user
+pub fn broadcast_channel() -> String {
+    "broadcast:events".to_string()
+}
+
+/// Subscribe to pub/sub channels on a dedicated async connection
+pub async fn subscribe(pool: &RedisPool, channels: &[String]) -> Result<redis::aio::PubSub, AppError> {
+    let mut pubsub = pool
+        .client
+        .get_async_connection()
+        .await
+        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?
+        .into_pubsub();
+
+    pubsub.subscribe(channels).await?;
+
+    Ok(pubsub)
+}
+
+/// Publish a message on a pub/sub channel
+pub async fn publish(pool: &RedisPool, channel: &str, message: &str) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
+
+    redis::cmd("PUBLISH")
+        .arg(channel)
+        .arg(message)
+        .query_async::<_, ()>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Redis key counting a user's open realtime connections
+fn presence_key(user_id: uuid::Uuid) -> String {
+    format!("presence:{}", user_id)
+}
+
+/// Record a new realtime connection of a user; the count expires unless refreshed
+pub async fn presence_connect(pool: &RedisPool, user_id: uuid::Uuid, ttl_seconds: u64) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
+
+    redis::pipe()
+        .atomic()
+        .cmd("INCR").arg(presence_key(user_id)).ignore()
+        .cmd("EXPIRE").arg(presence_key(user_id)).arg(ttl_seconds).ignore()
+        .query_async::<_, ()>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Keep a connected user's presence from expiring
+pub async fn presence_refresh(pool: &RedisPool, user_id: uuid::Uuid, ttl_seconds: u64) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
+
+    redis::cmd("EXPIRE")
+        .arg(presence_key(user_id))
+        .arg(ttl_seconds)
+        .query_async::<_, ()>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Drop a connection from the count, removing the key with the last one
+const PRESENCE_DISCONNECT_SCRIPT: &str = r#"
+local remaining = redis.call('DECR', KEYS[1])
+if remaining <= 0 then
+    redis.call('DEL', KEYS[1])
+end
+return remaining
+"#;
+
+/// Record a closed realtime connection of a user
+pub async fn presence_disconnect(pool: &RedisPool, user_id: uuid::Uuid) -> Result<(), AppError> {
+    let mut conn = get_connection(pool);
+
+    redis::Script::new(PRESENCE_DISCONNECT_SCRIPT)
+        .key(presence_key(user_id))
+        .invoke_async::<_, i64>(&mut conn).await?;
+
+    Ok(())
+}
+
+/// Whether each user has at least one open realtime connection (in input order)
+pub async fn presence_online(pool: &RedisPool, user_ids: &[uuid::Uuid]) -> Result<Vec<bool>, AppError> {
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = get_connection(pool);
+
+    let mut cmd = redis::cmd("MGET");
+    for user_id in user_ids {
+        cmd.arg(presence_key(*user_id));
+    }
+    let counts: Vec<Option<i64>> = cmd.query_async(&mut conn).await?;
+
+    Ok(counts.into_iter().map(|count| count.unwrap_or(0) > 0).collect())
+}
+
+/// Hash of user id -> unix time of last activity, waiting to be flushed to Postgres
+const LAST_ACTIVE_PENDING_KEY: &str = "last_active:pending";
+
+/// Record activity at most once per throttle window per user
+const LAST_ACTIVE_TOUCH_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[2], 'NX', 'EX', ARGV[3]) then
+    redis.call('HSET', KEYS[2], ARGV[1], ARGV[2])
+    return 1
+end
+return 0
+"#;
+
+/// Note that a user was just active; returns whether it was recorded (false when throttled)
+pub async fn last_active_touch(
+    pool: &RedisPool,
+    user_id: uuid::Uuid,
+    unix_time: i64,
+    throttle_seconds: u64,
+) -> Result<bool, AppError> {
+    let mut conn = get_connection(pool);
+
+    let recorded: i64 = redis::Script::new(LAST_ACTIVE_TOUCH_SCRIPT)
+        .key(format!("last_active:{}", user_id))
+        .key(LAST_ACTIVE_PENDING_KEY)
+        .arg(user_id.to_string())
+        .arg(unix_time)
+        .arg(throttle_seconds)
+        .invoke_async(&mut conn).await?;
+
+    Ok(recorded == 1)
+}
+
+/// Read and clear pending activity in one step
+const LAST_ACTIVE_TAKE_SCRIPT: &str = r#"
+local pending = redis.call('HGETALL', KEYS[1])
+redis.call('DEL', KEYS[1])
+return pending
+"#;
+
+/// Take all pending (user id, unix time) activity records
+pub async fn last_active_take(pool: &RedisPool) -> Result<Vec<(String, i64)>, AppError> {
+    let mut conn = get_connection(pool);
+
+    let pending: Vec<(String, i64)> = redis::Script::new(LAST_ACTIVE_TAKE_SCRIPT)
+        .key(LAST_ACTIVE_PENDING_KEY)
+        .invoke_async(&mut conn).await?;
+
+    Ok(pending)
+}
@@ -0,0 +1,77 @@
+use std::sync::OnceLock;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+use super::{delete_value, get_value, set_with_ttl, RedisPool};
+
+/// Connection of the read-through cache, set once at startup. Repositories only
+/// hold the database pool, so the cache is reached through here rather than threaded
+/// into every lookup.
+static CACHE_POOL: OnceLock<RedisPool> = OnceLock::new();
+
+/// Typed read-through cache of JSON values. Every operation is best effort: Redis
+/// failures are logged and treated as a miss, so the database stays the source of truth.
+pub struct CacheService;
+
+impl CacheService {
+    /// Enable the cache (until then every lookup is a miss)
+    pub fn init(redis_pool: RedisPool) {
+        if CACHE_POOL.set(redis_pool).is_err() {
+            log::warn!("Cache already initialized");
+        }
+    }
+
+    /// Read a cached value (None on a miss, a Redis error or an undecodable entry)
+    pub async fn get<T: DeserializeOwned>(key: &str) -> Option<T> {
+        let redis_pool = CACHE_POOL.get()?;
+
+        match get_value(redis_pool, key).await {
+            Ok(Some(cached)) => serde_json::from_str(&cached).ok(),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!("Cache read of {} failed: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Cache a value for `ttl_seconds`
+    pub async fn set<T: Serialize>(key: &str, value: &T, ttl_seconds: u64) {
+        let Some(redis_pool) = CACHE_POOL.get() else {
+            return;
+        };
+
+        let serialized = match serde_json::to_string(value) {
+            Ok(serialized) => serialized,
+            Err(e) => {
+                log::warn!("Failed to serialize cache entry {}: {}", key, e);
+                return;
+            }
+        };
+
+        if let Err(e) = set_with_ttl(redis_pool, key, &serialized, ttl_seconds).await {
+            log::warn!("Cache write of {} failed: {}", key, e);
+        }
+    }
+
+    /// Drop a cached value after the data behind it changed
+    pub async fn invalidate(key: &str) {
+        let Some(redis_pool) = CACHE_POOL.get() else {
+            return;
+        };
+
+        if let Err(e) = delete_value(redis_pool, key).await {
+            log::warn!("Failed to invalidate cache entry {}: {}", key, e);
+        }
+    }
+
+    /// Cache key of a user row
+    pub fn user_key(user_id: Uuid) -> String {
+        format!("cache:user:{}", user_id)
+    }
+
+    /// Cache key of a room row
+    pub fn room_key(room_id: Uuid) -> String {
+        format!("cache:room:{}", room_id)
+    }
+}
@@ -1,13 +1,292 @@
 use std::env;
+use crate::utils::ip_net::IpNet;
+
+/// Password requirements applied on registration
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Minimum estimated strength score (0-4, zxcvbn scale)
+    pub min_strength: u8,
+    /// Reject passwords found in public breach corpora (Have I Been Pwned)
+    pub check_breached: bool,
+}
+
+impl PasswordPolicy {
+    fn from_env() -> Self {
+        let flag = |name: &str| {
+            env::var(name)
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false)
+        };
+
+        PasswordPolicy {
+            min_length: env::var("PASSWORD_MIN_LENGTH")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .unwrap_or(8),
+            require_uppercase: flag("PASSWORD_REQUIRE_UPPERCASE"),
+            require_lowercase: flag("PASSWORD_REQUIRE_LOWERCASE"),
+            require_digit: flag("PASSWORD_REQUIRE_DIGIT"),
+            require_symbol: flag("PASSWORD_REQUIRE_SYMBOL"),
+            min_strength: env::var("PASSWORD_MIN_STRENGTH")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            check_breached: flag("PASSWORD_CHECK_BREACHED"),
+        }
+    }
+}
+
+/// Argon2id cost parameters for password hashing
+#[derive(Debug, Clone)]
+pub struct Argon2Config {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Config {
+    /// OWASP-recommended baseline (same as the argon2 crate defaults)
+    fn default() -> Self {
+        Argon2Config {
+            memory_kib: 19456,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl Argon2Config {
+    fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Argon2Config {
+            memory_kib: env::var("ARGON2_MEMORY_KIB")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.memory_kib),
+            iterations: env::var("ARGON2_ITERATIONS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.iterations),
+            parallelism: env::var("ARGON2_PARALLELISM")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.parallelism),
+        }
+    }
+}
+
+/// Automatic spam detection on incoming messages
+#[derive(Debug, Clone)]
+pub struct SpamConfig {
+    /// Score (0.0-1.0) at which a message files a report in the moderation queue
+    pub flag_threshold: f64,
+    /// Score at which a message is also held back: the sender sees it as sent,
+    /// but nobody else receives it
+    pub hold_threshold: f64,
+    /// Window for counting repeats and bursts
+    pub window_seconds: u64,
+    /// Messages within the window that count as a full burst
+    pub burst_messages: u64,
+}
+
+impl SpamConfig {
+    fn from_env() -> Self {
+        SpamConfig {
+            flag_threshold: env::var("SPAM_FLAG_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.5),
+            hold_threshold: env::var("SPAM_HOLD_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.8),
+            window_seconds: env::var("SPAM_WINDOW_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            burst_messages: env::var("SPAM_BURST_MESSAGES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+        }
+    }
+}
+
+/// Per-user resource quotas (0 = unlimited)
+#[derive(Debug, Clone)]
+pub struct QuotaConfig {
+    /// Rooms a user can own at once (direct messages don't count)
+    pub max_rooms_owned: u32,
+    /// Authenticated API requests per user per UTC day (admins are exempt)
+    pub max_api_requests_per_day: u32,
+}
+
+impl QuotaConfig {
+    fn from_env() -> Self {
+        QuotaConfig {
+            max_rooms_owned: env::var("QUOTA_MAX_ROOMS_OWNED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(50),
+            max_api_requests_per_day: env::var("QUOTA_MAX_API_REQUESTS_PER_DAY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20000),
+        }
+    }
+}
+
+/// Read a comma-separated environment variable (empty entries are skipped)
+fn env_list(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Username and display name moderation
+#[derive(Debug, Clone)]
+pub struct NamePolicy {
+    /// Names nobody may take (compared after confusables normalization)
+    pub reserved_names: Vec<String>,
+    /// Words no name may contain
+    pub banned_words: Vec<String>,
+}
+
+impl NamePolicy {
+    fn from_env() -> Self {
+        let reserved_names = match env::var("NAME_RESERVED") {
+            Ok(_) => env_list("NAME_RESERVED"),
+            Err(_) => [
+                "admin", "administrator", "root", "system", "moderator", "mod", "staff", "support",
+                "official", "security", "ngobrol", "everyone", "here",
+            ]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        };
+
+        NamePolicy {
+            reserved_names,
+            banned_words: env_list("NAME_BANNED_WORDS"),
+        }
+    }
+}
+
+/// Request rate limits (Redis-backed); defaults that admins can override per
+/// route class at runtime (see RateLimitService)
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Requests per IP to unauthenticated auth endpoints within the window
+    pub auth_max_requests: u32,
+    pub auth_window_seconds: u64,
+    /// Room creations per user: burst size, then one more every N seconds
+    pub room_create_burst: u32,
+    pub room_create_refill_seconds: u64,
+    /// Messages per incoming webhook: burst size, then one more every N seconds
+    pub webhook_burst: u32,
+    pub webhook_refill_seconds: u64,
+    /// Messages per user: burst size, then one more every N seconds
+    pub message_burst: u32,
+    pub message_refill_seconds: u64,
+}
+
+impl RateLimitConfig {
+    fn from_env() -> Self {
+        RateLimitConfig {
+            auth_max_requests: env::var("RATE_LIMIT_AUTH_MAX_REQUESTS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+            auth_window_seconds: env::var("RATE_LIMIT_AUTH_WINDOW_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            room_create_burst: env::var("RATE_LIMIT_ROOM_CREATE_BURST")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            room_create_refill_seconds: env::var("RATE_LIMIT_ROOM_CREATE_REFILL_SECONDS")
+                .unwrap_or_else(|_| "360".to_string())
+                .parse()
+                .unwrap_or(360),
+            webhook_burst: env::var("RATE_LIMIT_WEBHOOK_BURST")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            webhook_refill_seconds: env::var("RATE_LIMIT_WEBHOOK_REFILL_SECONDS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            message_burst: env::var("RATE_LIMIT_MESSAGE_BURST")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            message_refill_seconds: env::var("RATE_LIMIT_MESSAGE_REFILL_SECONDS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub redis_url: String,
+    pub run_migrations: bool,
     pub jwt_secret: String,
+    pub jwt_algorithm: String,
+    pub jwt_private_key_path: Option<String>,
+    pub jwt_public_key_path: Option<String>,
+    pub jwt_previous_secrets: Vec<String>,
+    pub jwt_previous_public_key_paths: Vec<String>,
+    pub jwt_issuer: String,
+    pub jwt_audience: String,
     pub jwt_expires_in: i64,
     pub server_host: String,
     pub server_port: u16,
+    pub trusted_proxies: Vec<IpNet>,
+    pub shutdown_timeout_seconds: u64,
+    pub vapid_public_key: Option<String>,
+    pub vapid_private_key: Option<String>,
+    pub vapid_subject: Option<String>,
+    pub fcm_service_account_path: Option<String>,
+    pub apns_key_path: Option<String>,
+    pub apns_key_id: Option<String>,
+    pub apns_team_id: Option<String>,
+    pub apns_topic: Option<String>,
+    pub apns_sandbox: bool,
+    pub geo_country_header: String,
+    pub geo_city_header: String,
+    pub app_base_url: String,
+    pub smtp_url: Option<String>,
+    pub mail_from: String,
+    pub require_verified_email: bool,
+    pub tos_version: Option<String>,
+    pub captcha_provider: Option<String>,
+    pub captcha_secret: Option<String>,
+    pub password_policy: PasswordPolicy,
+    pub name_policy: NamePolicy,
+    pub argon2: Argon2Config,
+    pub rate_limit: RateLimitConfig,
+    pub spam: SpamConfig,
+    pub quota: QuotaConfig,
+    pub account_deletion_grace_days: i64,
+    pub account_reactivation_window_days: i64,
+    pub account_retention_days: i64,
+    pub new_device_alerts: bool,
+    pub auth_cookies: bool,
+    pub cookie_secure: bool,
 }
 
 impl Config {
@@ -15,7 +294,23 @@ impl Config {
         Ok(Config {
             database_url: env::var("DATABASE_URL")?,
             redis_url: env::var("REDIS_URL")?,
-            jwt_secret: env::var("JWT_SECRET")?,
+            // Apply the migrations embedded in the binary on startup
+            run_migrations: env::var("RUN_MIGRATIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Only required for HS256 (checked when keys are loaded)
+            jwt_secret: env::var("JWT_SECRET").unwrap_or_default(),
+            // 'HS256' (shared secret), 'RS256' or 'EdDSA' (PEM key files)
+            jwt_algorithm: env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".to_string()),
+            jwt_private_key_path: env::var("JWT_PRIVATE_KEY_PATH").ok(),
+            jwt_public_key_path: env::var("JWT_PUBLIC_KEY_PATH").ok(),
+            // Comma-separated keys still accepted for verification during a rotation
+            jwt_previous_secrets: env_list("JWT_PREVIOUS_SECRETS"),
+            jwt_previous_public_key_paths: env_list("JWT_PREVIOUS_PUBLIC_KEY_PATHS"),
+            // Set per environment so tokens can't be replayed across deployments
+            jwt_issuer: env::var("JWT_ISSUER").unwrap_or_else(|_| "ngobrol".to_string()),
+            jwt_audience: env::var("JWT_AUDIENCE").unwrap_or_else(|_| "ngobrol-api".to_string()),
             jwt_expires_in: env::var("JWT_EXPIRES_IN")
                 .unwrap_or_else(|_| "86400".to_string()) // 24 hours default
                 .parse()
@@ -25,6 +320,93 @@ impl Config {
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .unwrap_or(8080),
+            // Comma-separated proxies (IPs or CIDR networks) whose forwarding headers are
+            // believed; requests from anywhere else are attributed to the connecting address
+            trusted_proxies: env_list("TRUSTED_PROXIES")
+                .iter()
+                .filter_map(|value| {
+                    let net = IpNet::parse(value);
+                    if net.is_none() {
+                        log::warn!("Ignoring invalid TRUSTED_PROXIES entry '{}'", value);
+                    }
+                    net
+                })
+                .collect(),
+            // How long a shutdown waits for in-flight requests and WebSocket sessions
+            shutdown_timeout_seconds: env::var("SHUTDOWN_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            vapid_public_key: env::var("VAPID_PUBLIC_KEY").ok(),
+            // Signs Web Push requests (base64url, the pair of VAPID_PUBLIC_KEY); unset disables sending
+            vapid_private_key: env::var("VAPID_PRIVATE_KEY").ok(),
+            // Contact push services can reach ("mailto:..." or an https URL), APP_BASE_URL by default
+            vapid_subject: env::var("VAPID_SUBJECT").ok(),
+            // Firebase service account key (JSON) for FCM pushes, unset disables FCM
+            fcm_service_account_path: env::var("FCM_SERVICE_ACCOUNT_PATH").ok(),
+            // APNs token auth (.p8 key, its key ID, the team ID and the app's bundle ID),
+            // APNs is disabled unless all four are set
+            apns_key_path: env::var("APNS_KEY_PATH").ok(),
+            apns_key_id: env::var("APNS_KEY_ID").ok(),
+            apns_team_id: env::var("APNS_TEAM_ID").ok(),
+            apns_topic: env::var("APNS_TOPIC").ok(),
+            // Use the APNs development environment (builds signed for development)
+            apns_sandbox: env::var("APNS_SANDBOX")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Location headers set by the reverse proxy / CDN (Cloudflare by default)
+            geo_country_header: env::var("GEO_COUNTRY_HEADER").unwrap_or_else(|_| "CF-IPCountry".to_string()),
+            geo_city_header: env::var("GEO_CITY_HEADER").unwrap_or_else(|_| "CF-IPCity".to_string()),
+            // Frontend URL used to build links in emails
+            app_base_url: env::var("APP_BASE_URL").unwrap_or_else(|_| "http://localhost:5173".to_string()),
+            smtp_url: env::var("SMTP_URL").ok(),
+            mail_from: env::var("MAIL_FROM").unwrap_or_else(|_| "Ngobrol <no-reply@ngobrol.local>".to_string()),
+            require_verified_email: env::var("REQUIRE_VERIFIED_EMAIL")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Current terms of service; users must accept it before using the API (unset disables)
+            tos_version: env::var("TOS_VERSION").ok().filter(|v| !v.trim().is_empty()),
+            // 'hcaptcha' or 'turnstile', unset disables CAPTCHA
+            captcha_provider: env::var("CAPTCHA_PROVIDER").ok(),
+            captcha_secret: env::var("CAPTCHA_SECRET").ok(),
+            password_policy: PasswordPolicy::from_env(),
+            name_policy: NamePolicy::from_env(),
+            argon2: Argon2Config::from_env(),
+            rate_limit: RateLimitConfig::from_env(),
+            spam: SpamConfig::from_env(),
+            quota: QuotaConfig::from_env(),
+            // Deleted accounts can still be restored by support during this period
+            account_deletion_grace_days: env::var("ACCOUNT_DELETION_GRACE_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            // Deactivated accounts are purged when not reactivated within this period
+            account_reactivation_window_days: env::var("ACCOUNT_REACTIVATION_WINDOW_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .unwrap_or(90),
+            // Content of deleted accounts is kept (anonymized) this long before it is purged
+            account_retention_days: env::var("ACCOUNT_RETENTION_DAYS")
+                .unwrap_or_else(|_| "365".to_string())
+                .parse()
+                .unwrap_or(365),
+            // Email users about logins from devices they haven't used before
+            new_device_alerts: env::var("NEW_DEVICE_ALERTS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            // Issue tokens as HttpOnly cookies (with CSRF protection) instead of in the body
+            auth_cookies: env::var("AUTH_COOKIES")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Disable only for local development over plain HTTP
+            cookie_secure: env::var("COOKIE_SECURE")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
         })
     }
 
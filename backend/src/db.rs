@@ -1,7 +1,10 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
 use std::time::Duration;
 use crate::error::AppError;
 
+/// Schema migrations, embedded in the binary at build time
+static MIGRATOR: Migrator = sqlx::migrate!();
+
 /// Create a PostgreSQL connection pool
 pub async fn create_pool(database_url: &str) -> Result<PgPool, AppError> {
     let pool = PgPoolOptions::new()
@@ -30,3 +33,16 @@ pub async fn test_connection(pool: &PgPool) -> Result<(), AppError> {
     
     Ok(())
 }
+
+/// Apply pending schema migrations. Already applied ones are skipped, and a lock
+/// keeps instances starting at the same time from running them twice.
+pub async fn run_migrations(pool: &PgPool) -> Result<(), AppError> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to run database migrations: {}", e)))?;
+
+    log::info!("✅ Database migrations applied");
+
+    Ok(())
+}
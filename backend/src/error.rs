@@ -53,7 +53,16 @@ pub enum AppError {
     InvalidToken,
     InvalidCredentials,
     TokenExpired,
+    TokenRevoked,
+    SessionNotFound,
+    InvalidActionToken,
+    EmailNotVerified,
+    CaptchaFailed,
+    CsrfFailed,
     AccountLocked,
+    PasswordResetRequired,
+    IpBanned,
+    TosAcceptanceRequired,
     InsufficientPermissions,
 
     // User errors (USER_*)
@@ -62,6 +71,10 @@ pub enum AppError {
     UsernameExists,
     InvalidEmail,
     WeakPassword,
+    ExportNotFound,
+    KeyBundleNotFound,
+    ActivityNotFound,
+    LinkedEmailNotFound,
 
     // Room errors (ROOM_*)
     RoomNotFound,
@@ -71,6 +84,32 @@ pub enum AppError {
     RoomNameExists,
     PrivateNoAccess,
     OwnerRequired,
+    DirectMessageRoom,
+    SystemConversation,
+    RoomLocked,
+    InvitationNotFound,
+    InvitationExists,
+    BannedFromRoom,
+    BanNotFound,
+    RoomPasswordInvalid,
+    RulesNotFound,
+    RulesOutdated,
+    RoomMerged(uuid::Uuid),
+    EventNotFound,
+    WebhookNotFound,
+    CommandNotFound,
+    CommandNameTaken,
+    CommandCallbackFailed,
+
+    // Space errors (SPACE_*)
+    SpaceNotFound,
+    SpaceNameExists,
+    NotSpaceMember,
+
+    // Report errors (REPORT_*)
+    ReportNotFound,
+    ReportExists,
+    ReportTransitionInvalid,
 
     // Message errors (MESSAGE_*)
     MessageNotFound,
@@ -78,12 +117,31 @@ pub enum AppError {
     MessageTooLong,
     NotMessageOwner,
     MessageAlreadyDeleted,
+    MessageBlocked,
+    WordFilterNotFound,
+    WordFilterExists,
+
+    // Push errors (PUSH_*)
+    DeviceNotFound,
+    PushNotConfigured,
+
+    // Announcement errors (ANNOUNCEMENT_*)
+    AnnouncementNotFound,
+    AnnouncementNotDismissible,
+
+    // IP ban errors (IP_BAN_*)
+    IpBanNotFound,
+    IpBanExists,
+
+    // Quota errors (QUOTA_*)
+    QuotaExceeded(String),
 
     // Validation errors (VALIDATION_*)
     ValidationError(ValidationErrors),
     MissingField(String),
     InvalidFormat(String),
     InvalidUuid(String),
+    UrlNotAllowed(String),
 
     // Rate limiting (RATE_LIMIT_*)
     RateLimitExceeded,
@@ -105,7 +163,16 @@ impl AppError {
             Self::InvalidToken => "AUTH_INVALID_TOKEN",
             Self::InvalidCredentials => "AUTH_INVALID_CREDENTIALS",
             Self::TokenExpired => "AUTH_TOKEN_EXPIRED",
+            Self::TokenRevoked => "AUTH_TOKEN_REVOKED",
+            Self::SessionNotFound => "AUTH_SESSION_NOT_FOUND",
+            Self::InvalidActionToken => "AUTH_INVALID_ACTION_TOKEN",
+            Self::EmailNotVerified => "AUTH_EMAIL_NOT_VERIFIED",
+            Self::CaptchaFailed => "AUTH_CAPTCHA_FAILED",
+            Self::CsrfFailed => "AUTH_CSRF_FAILED",
             Self::AccountLocked => "AUTH_ACCOUNT_LOCKED",
+            Self::PasswordResetRequired => "AUTH_PASSWORD_RESET_REQUIRED",
+            Self::IpBanned => "AUTH_IP_BANNED",
+            Self::TosAcceptanceRequired => "TOS_ACCEPTANCE_REQUIRED",
             Self::InsufficientPermissions => "AUTH_INSUFFICIENT_PERMISSIONS",
 
             // User errors
@@ -114,6 +181,10 @@ impl AppError {
             Self::UsernameExists => "USER_USERNAME_EXISTS",
             Self::InvalidEmail => "USER_INVALID_EMAIL",
             Self::WeakPassword => "USER_WEAK_PASSWORD",
+            Self::ExportNotFound => "USER_EXPORT_NOT_FOUND",
+            Self::KeyBundleNotFound => "USER_KEY_BUNDLE_NOT_FOUND",
+            Self::ActivityNotFound => "USER_ACTIVITY_NOT_FOUND",
+            Self::LinkedEmailNotFound => "USER_LINKED_EMAIL_NOT_FOUND",
 
             // Room errors
             Self::RoomNotFound => "ROOM_NOT_FOUND",
@@ -123,6 +194,32 @@ impl AppError {
             Self::RoomNameExists => "ROOM_NAME_EXISTS",
             Self::PrivateNoAccess => "ROOM_PRIVATE_NO_ACCESS",
             Self::OwnerRequired => "ROOM_OWNER_REQUIRED",
+            Self::DirectMessageRoom => "ROOM_DIRECT_MESSAGE",
+            Self::SystemConversation => "ROOM_SYSTEM_CONVERSATION",
+            Self::RoomLocked => "ROOM_LOCKED",
+            Self::InvitationNotFound => "ROOM_INVITATION_NOT_FOUND",
+            Self::InvitationExists => "ROOM_INVITATION_EXISTS",
+            Self::BannedFromRoom => "ROOM_BANNED",
+            Self::BanNotFound => "ROOM_BAN_NOT_FOUND",
+            Self::RoomPasswordInvalid => "ROOM_PASSWORD_INVALID",
+            Self::RulesNotFound => "ROOM_RULES_NOT_FOUND",
+            Self::RulesOutdated => "ROOM_RULES_OUTDATED",
+            Self::RoomMerged(_) => "ROOM_MERGED",
+            Self::EventNotFound => "ROOM_EVENT_NOT_FOUND",
+            Self::WebhookNotFound => "ROOM_WEBHOOK_NOT_FOUND",
+            Self::CommandNotFound => "ROOM_COMMAND_NOT_FOUND",
+            Self::CommandNameTaken => "ROOM_COMMAND_NAME_TAKEN",
+            Self::CommandCallbackFailed => "ROOM_COMMAND_CALLBACK_FAILED",
+
+            // Space errors
+            Self::SpaceNotFound => "SPACE_NOT_FOUND",
+            Self::SpaceNameExists => "SPACE_NAME_EXISTS",
+            Self::NotSpaceMember => "SPACE_NOT_MEMBER",
+
+            // Report errors
+            Self::ReportNotFound => "REPORT_NOT_FOUND",
+            Self::ReportExists => "REPORT_EXISTS",
+            Self::ReportTransitionInvalid => "REPORT_TRANSITION_INVALID",
 
             // Message errors
             Self::MessageNotFound => "MESSAGE_NOT_FOUND",
@@ -130,12 +227,29 @@ impl AppError {
             Self::MessageTooLong => "MESSAGE_TOO_LONG",
             Self::NotMessageOwner => "MESSAGE_NOT_OWNER",
             Self::MessageAlreadyDeleted => "MESSAGE_ALREADY_DELETED",
+            Self::MessageBlocked => "MESSAGE_BLOCKED",
+            Self::WordFilterNotFound => "MESSAGE_FILTER_NOT_FOUND",
+            Self::WordFilterExists => "MESSAGE_FILTER_EXISTS",
+
+            // Push errors
+            Self::DeviceNotFound => "PUSH_DEVICE_NOT_FOUND",
+            Self::PushNotConfigured => "PUSH_NOT_CONFIGURED",
+
+            // Announcement errors
+            Self::AnnouncementNotFound => "ANNOUNCEMENT_NOT_FOUND",
+            Self::AnnouncementNotDismissible => "ANNOUNCEMENT_NOT_DISMISSIBLE",
+
+            // IP ban errors
+            Self::IpBanNotFound => "IP_BAN_NOT_FOUND",
+            Self::IpBanExists => "IP_BAN_EXISTS",
+            Self::QuotaExceeded(_) => "QUOTA_EXCEEDED",
 
             // Validation
             Self::ValidationError(_) => "VALIDATION_ERROR",
             Self::MissingField(_) => "VALIDATION_MISSING_FIELD",
             Self::InvalidFormat(_) => "VALIDATION_INVALID_FORMAT",
             Self::InvalidUuid(_) => "VALIDATION_INVALID_UUID",
+            Self::UrlNotAllowed(_) => "VALIDATION_URL_NOT_ALLOWED",
 
             // Rate limit
             Self::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
@@ -157,7 +271,16 @@ impl AppError {
             Self::InvalidToken => "Invalid or expired authentication token",
             Self::InvalidCredentials => "Invalid email or password",
             Self::TokenExpired => "Authentication token has expired",
+            Self::TokenRevoked => "Authentication token has been revoked",
+            Self::SessionNotFound => "Session not found",
+            Self::InvalidActionToken => "This link is invalid or has expired",
+            Self::EmailNotVerified => "Please verify your email address first",
+            Self::CaptchaFailed => "CAPTCHA verification failed",
+            Self::CsrfFailed => "Missing or invalid CSRF token",
             Self::AccountLocked => "Your account has been locked",
+            Self::PasswordResetRequired => "Your password must be reset; check your email for a reset link",
+            Self::IpBanned => "Access from your network has been blocked",
+            Self::TosAcceptanceRequired => "The terms of service have changed, please review and accept the latest version",
             Self::InsufficientPermissions => "You don't have permission to perform this action",
 
             // User errors
@@ -166,6 +289,10 @@ impl AppError {
             Self::UsernameExists => "Username is already taken",
             Self::InvalidEmail => "Invalid email format",
             Self::WeakPassword => "Password does not meet requirements",
+            Self::ExportNotFound => "Data export not found or has expired",
+            Self::KeyBundleNotFound => "User has not published encryption keys",
+            Self::ActivityNotFound => "Activity item not found",
+            Self::LinkedEmailNotFound => "Linked email address not found",
 
             // Room errors
             Self::RoomNotFound => "Room not found",
@@ -175,6 +302,32 @@ impl AppError {
             Self::RoomNameExists => "Room name is already taken",
             Self::PrivateNoAccess => "This is a private room",
             Self::OwnerRequired => "Only room owner can perform this action",
+            Self::DirectMessageRoom => "Not available in direct message conversations",
+            Self::SystemConversation => "Official system conversations can't be muted or joined",
+            Self::RoomLocked => "This room has been locked by an administrator",
+            Self::InvitationNotFound => "Invitation not found",
+            Self::InvitationExists => "User already has a pending invitation to this room",
+            Self::BannedFromRoom => "You are banned from this room",
+            Self::BanNotFound => "User is not banned from this room",
+            Self::RoomPasswordInvalid => "Incorrect room password",
+            Self::RulesNotFound => "This room has no rules",
+            Self::RulesOutdated => "Room rules have changed, please review the latest version",
+            Self::RoomMerged(room_id) => return format!("Room was merged into room {}", room_id),
+            Self::EventNotFound => "Event not found",
+            Self::WebhookNotFound => "Webhook not found",
+            Self::CommandNotFound => "Command not found",
+            Self::CommandNameTaken => "Another bot already registered this command in the room",
+            Self::CommandCallbackFailed => "The bot did not answer the command",
+
+            // Space errors
+            Self::SpaceNotFound => "Space not found",
+            Self::SpaceNameExists => "Space name is already taken",
+            Self::NotSpaceMember => "You are not a member of this space",
+
+            // Report errors
+            Self::ReportNotFound => "Report not found",
+            Self::ReportExists => "You have already reported this",
+            Self::ReportTransitionInvalid => "Report cannot move to this status",
 
             // Message errors
             Self::MessageNotFound => "Message not found",
@@ -182,12 +335,29 @@ impl AppError {
             Self::MessageTooLong => "Message exceeds maximum length",
             Self::NotMessageOwner => "You can only edit/delete your own messages",
             Self::MessageAlreadyDeleted => "Message has already been deleted",
+            Self::MessageBlocked => "Message contains blocked content",
+            Self::WordFilterNotFound => "Word filter not found",
+            Self::WordFilterExists => "This word filter already exists",
+
+            // Push errors
+            Self::DeviceNotFound => "Device not found",
+            Self::PushNotConfigured => "Push notifications are not configured on this server",
+
+            // Announcement errors
+            Self::AnnouncementNotFound => "Announcement not found",
+            Self::AnnouncementNotDismissible => "This announcement can't be dismissed",
+
+            // IP ban errors
+            Self::IpBanNotFound => "IP ban not found",
+            Self::IpBanExists => "This IP or network is already banned",
+            Self::QuotaExceeded(quota) => return format!("Quota exceeded: {}", quota),
 
             // Validation
             Self::ValidationError(_) => "Input validation failed",
             Self::MissingField(field) => return format!("Required field '{}' is missing", field),
             Self::InvalidFormat(field) => return format!("Invalid format for field '{}'", field),
             Self::InvalidUuid(field) => return format!("Invalid UUID format for field '{}'", field),
+            Self::UrlNotAllowed(reason) => return format!("URL not allowed: {}", reason),
 
             // Rate limit
             Self::RateLimitExceeded => "Too many requests. Please try again later",
@@ -205,21 +375,58 @@ impl AppError {
     /// Get HTTP status code
     pub fn status_code(&self) -> StatusCode {
         match self {
+            // 400 Bad Request
+            Self::InvalidActionToken | Self::CaptchaFailed => StatusCode::BAD_REQUEST,
+
             // 401 Unauthorized
-            Self::MissingToken | Self::InvalidToken | Self::InvalidCredentials | Self::TokenExpired => {
-                StatusCode::UNAUTHORIZED
-            }
+            Self::MissingToken
+            | Self::InvalidToken
+            | Self::InvalidCredentials
+            | Self::TokenExpired
+            | Self::TokenRevoked => StatusCode::UNAUTHORIZED,
 
             // 403 Forbidden
             Self::AccountLocked
+            | Self::PasswordResetRequired
+            | Self::IpBanned
+            | Self::TosAcceptanceRequired
+            | Self::CsrfFailed
+            | Self::EmailNotVerified
             | Self::InsufficientPermissions
             | Self::NotMember
             | Self::NotMessageOwner
             | Self::PrivateNoAccess
-            | Self::OwnerRequired => StatusCode::FORBIDDEN,
+            | Self::OwnerRequired
+            | Self::DirectMessageRoom
+            | Self::SystemConversation
+            | Self::RoomLocked
+            | Self::BannedFromRoom
+            | Self::RoomPasswordInvalid
+            | Self::NotSpaceMember
+            | Self::AnnouncementNotDismissible
+            | Self::QuotaExceeded(_) => StatusCode::FORBIDDEN,
 
             // 404 Not Found
-            Self::UserNotFound | Self::RoomNotFound | Self::MessageNotFound => StatusCode::NOT_FOUND,
+            Self::UserNotFound
+            | Self::SessionNotFound
+            | Self::ExportNotFound
+            | Self::KeyBundleNotFound
+            | Self::ActivityNotFound
+            | Self::LinkedEmailNotFound
+            | Self::RoomNotFound
+            | Self::InvitationNotFound
+            | Self::BanNotFound
+            | Self::RulesNotFound
+            | Self::EventNotFound
+            | Self::WebhookNotFound
+            | Self::CommandNotFound
+            | Self::SpaceNotFound
+            | Self::ReportNotFound
+            | Self::MessageNotFound
+            | Self::WordFilterNotFound
+            | Self::DeviceNotFound
+            | Self::AnnouncementNotFound
+            | Self::IpBanNotFound => StatusCode::NOT_FOUND,
 
             // 409 Conflict
             Self::EmailExists
@@ -227,23 +434,42 @@ impl AppError {
             | Self::AlreadyJoined
             | Self::RoomFull
             | Self::RoomNameExists
-            | Self::MessageAlreadyDeleted => StatusCode::CONFLICT,
+            | Self::InvitationExists
+            | Self::RulesOutdated
+            | Self::SpaceNameExists
+            | Self::CommandNameTaken
+            | Self::ReportExists
+            | Self::ReportTransitionInvalid
+            | Self::MessageAlreadyDeleted
+            | Self::WordFilterExists
+            | Self::IpBanExists => StatusCode::CONFLICT,
+
+            // 410 Gone
+            Self::RoomMerged(_) => StatusCode::GONE,
 
             // 422 Unprocessable Entity (for validation)
             Self::ValidationError(_)
             | Self::MissingField(_)
             | Self::InvalidFormat(_)
             | Self::InvalidUuid(_)
+            | Self::UrlNotAllowed(_)
             | Self::InvalidEmail
             | Self::WeakPassword
             | Self::MessageEmpty
-            | Self::MessageTooLong => StatusCode::UNPROCESSABLE_ENTITY,
+            | Self::MessageTooLong
+            | Self::MessageBlocked => StatusCode::UNPROCESSABLE_ENTITY,
 
             // 429 Too Many Requests
             Self::RateLimitExceeded | Self::MessageSpam | Self::LoginAttempts => {
                 StatusCode::TOO_MANY_REQUESTS
             }
 
+            // 502 Bad Gateway
+            Self::CommandCallbackFailed => StatusCode::BAD_GATEWAY,
+
+            // 503 Service Unavailable
+            Self::PushNotConfigured => StatusCode::SERVICE_UNAVAILABLE,
+
             // 500 Internal Server Error
             Self::DatabaseError(_) | Self::RedisError(_) | Self::InternalError(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
@@ -257,6 +483,7 @@ impl AppError {
             Self::ValidationError(validation_errors) => {
                 Some(serde_json::to_value(&validation_errors.fields).unwrap())
             }
+            Self::RoomMerged(room_id) => Some(serde_json::json!({ "redirect_room_id": room_id })),
             _ => None,
         };
 
@@ -315,6 +542,12 @@ impl From<sqlx::Error> for AppError {
                             return AppError::EmailExists;
                         } else if constraint.contains("username") {
                             return AppError::UsernameExists;
+                        } else if constraint.contains("reports") {
+                            return AppError::ReportExists;
+                        } else if constraint.contains("word_filters") {
+                            return AppError::WordFilterExists;
+                        } else if constraint.contains("ip_bans") {
+                            return AppError::IpBanExists;
                         }
                         // Default duplicate error
                         return AppError::EmailExists;
@@ -354,5 +587,12 @@ impl From<argon2::password_hash::Error> for AppError {
     }
 }
 
+impl From<async_zip::error::ZipError> for AppError {
+    fn from(err: async_zip::error::ZipError) -> Self {
+        log::error!("Zip archive error: {:?}", err);
+        AppError::InternalError("Archive creation failed".to_string())
+    }
+}
+
 /// Type alias for Result with AppError
 pub type AppResult<T> = Result<T, AppError>;
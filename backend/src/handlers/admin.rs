@@ -0,0 +1,573 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::handlers::auth::session_meta;
+use crate::middleware::AdminOnly;
+use crate::models::announcement::{CreateAnnouncementDto, UpdateAnnouncementDto};
+use crate::models::audit::AuditLogFilter;
+use crate::models::dm::SendSystemMessageDto;
+use crate::models::ip_ban::CreateIpBanDto;
+use crate::models::rate_limit::UpdateRateLimitDto;
+use crate::models::report::ReportActionDto;
+use crate::models::response::{created_response, no_content_response, paginated_response, success_response};
+use crate::models::room::{AdminRoomActionDto, MergeRoomDto, ReassignOwnerDto};
+use crate::models::user::{
+    AdminUserFilter, DeleteUserDto, SuspendUserDto, UpdateBadgesDto, UpdateRoleDto, USER_EXPORT_CSV,
+};
+use crate::models::word_filter::{CreateWordFilterDto, UpdateWordFilterDto};
+use crate::services::{
+    AdminService, AnnouncementService, IpBanService, RateLimitService, ReportService, UserExportService,
+    WordFilterService,
+};
+use crate::utils::client_ip;
+use crate::utils::jwt::JwtKeys;
+
+/// Query params for the active user count
+#[derive(Deserialize)]
+pub struct ActiveUsersQuery {
+    #[serde(default = "default_active_days")]
+    pub days: i64,
+}
+
+fn default_active_days() -> i64 {
+    7
+}
+
+/// Query params for the metrics summary
+#[derive(Deserialize)]
+pub struct MetricsSummaryQuery {
+    #[serde(default = "default_summary_days")]
+    pub days: i64,
+}
+
+fn default_summary_days() -> i64 {
+    30
+}
+
+/// Query params for the audit log query
+#[derive(Deserialize)]
+pub struct AuditLogQuery {
+    pub actor_id: Option<Uuid>,
+    /// Exact action (e.g. "admin.user_suspended") or a category (e.g. "admin")
+    pub action: Option<String>,
+    pub target_id: Option<Uuid>,
+    /// Inclusive start of the time range (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive end of the time range (RFC 3339)
+    pub until: Option<DateTime<Utc>>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    #[serde(default = "default_audit_limit")]
+    pub limit: u32,
+}
+
+fn default_audit_limit() -> u32 {
+    50
+}
+
+/// Query params for the admin user list
+#[derive(Deserialize)]
+pub struct AdminUserQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    /// Username or email prefix
+    pub q: Option<String>,
+    pub role: Option<String>,
+    /// active, suspended, deactivated, pending_deletion or deleted
+    pub status: Option<String>,
+    pub is_bot: Option<bool>,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+/// GET /api/admin/users
+/// List users with filters, newest first
+pub async fn list_users(
+    pool: web::Data<PgPool>,
+    _admin: AdminOnly,
+    query: web::Query<AdminUserQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query.into_inner();
+    let filter = AdminUserFilter {
+        q: query.q,
+        role: query.role,
+        status: query.status,
+        is_bot: query.is_bot,
+    };
+
+    let (users, total) = AdminService::list_users(&pool, filter, query.page, query.per_page).await?;
+
+    Ok(paginated_response(users, query.page, query.per_page, total as u64))
+}
+
+/// Query params for the admin user export
+#[derive(Deserialize)]
+pub struct UserExportQuery {
+    /// csv or jsonl
+    #[serde(default = "default_export_format")]
+    pub format: String,
+    pub q: Option<String>,
+    pub role: Option<String>,
+    pub status: Option<String>,
+    pub is_bot: Option<bool>,
+}
+
+fn default_export_format() -> String {
+    USER_EXPORT_CSV.to_string()
+}
+
+/// GET /api/admin/users/export
+/// Download the users matching the filters as CSV or JSON Lines, without personal
+/// data (streamed with chunked transfer)
+pub async fn export_users(
+    pool: web::Data<PgPool>,
+    admin: AdminOnly,
+    query: web::Query<UserExportQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query.into_inner();
+    let filter = AdminUserFilter {
+        q: query.q,
+        role: query.role,
+        status: query.status,
+        is_bot: query.is_bot,
+    };
+
+    let export = UserExportService::export_users(&pool, admin.0, filter, &query.format).await?;
+
+    let content_type = if query.format == USER_EXPORT_CSV { "text/csv; charset=utf-8" } else { "application/x-ndjson" };
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"ngobrol-users-{}.{}\"", Utc::now().format("%Y%m%d"), query.format),
+        ))
+        .streaming(export))
+}
+
+/// GET /api/admin/users/:id
+/// View a user in any account state
+pub async fn get_user(
+    pool: web::Data<PgPool>,
+    _admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::get_user(&pool, *user_id).await?;
+    Ok(success_response(user))
+}
+
+/// DELETE /api/admin/users/:id
+/// Delete a user's account; it is anonymized after the grace period and its content
+/// purged after the retention window (body: {"reason": "..."})
+pub async fn delete_user(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+    dto: web::Json<DeleteUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::delete_user(&pool, &redis_pool, &config, admin.0, *user_id, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// POST /api/admin/users/:id/suspend
+/// Suspend a user instance-wide (body: {"reason": "...", "until": optional timestamp})
+pub async fn suspend_user(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+    dto: web::Json<SuspendUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::suspend_user(&pool, &redis_pool, admin.0, *user_id, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// DELETE /api/admin/users/:id/suspend
+/// Lift a suspension
+pub async fn unsuspend_user(
+    pool: web::Data<PgPool>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::unsuspend_user(&pool, admin.0, *user_id).await?;
+    Ok(success_response(user))
+}
+
+/// POST /api/admin/users/:id/password-reset
+/// Invalidate the user's password and email them a reset link
+pub async fn force_password_reset(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::force_password_reset(&pool, &redis_pool, &config, admin.0, *user_id).await?;
+    Ok(success_response(user))
+}
+
+/// Query params for the admin report review queue
+#[derive(Deserialize)]
+pub struct ReportReviewQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
+/// GET /api/admin/reports
+/// Unresolved reports, oldest first, with the reported content inline
+pub async fn list_reports(
+    pool: web::Data<PgPool>,
+    _admin: AdminOnly,
+    query: web::Query<ReportReviewQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (reports, total) = ReportService::review_queue(&pool, query.page, query.per_page).await?;
+
+    Ok(paginated_response(reports, query.page, query.per_page, total as u64))
+}
+
+/// POST /api/admin/reports/:id/action
+/// Resolve a report: dismiss, delete_message, warn or ban (body: {"action": "...", "note": "..."})
+pub async fn act_on_report(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    report_id: web::Path<Uuid>,
+    dto: web::Json<ReportActionDto>,
+) -> Result<HttpResponse, AppError> {
+    let report = ReportService::take_action(&pool, &redis_pool, *report_id, admin.0, dto.into_inner()).await?;
+    Ok(success_response(report))
+}
+
+/// GET /api/admin/word-filters
+/// List banned words and patterns
+pub async fn list_word_filters(pool: web::Data<PgPool>, _admin: AdminOnly) -> Result<HttpResponse, AppError> {
+    let filters = WordFilterService::list(&pool).await?;
+    Ok(success_response(filters))
+}
+
+/// POST /api/admin/word-filters
+/// Add a banned word or pattern (body: {"pattern": "...", "is_regex": false, "action": "mask" | "block"})
+pub async fn create_word_filter(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    dto: web::Json<CreateWordFilterDto>,
+) -> Result<HttpResponse, AppError> {
+    let filter = WordFilterService::create(&pool, &redis_pool, admin.0, dto.into_inner()).await?;
+    Ok(created_response(filter))
+}
+
+/// PUT /api/admin/word-filters/:id
+/// Change a banned word or pattern
+pub async fn update_word_filter(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    filter_id: web::Path<Uuid>,
+    dto: web::Json<UpdateWordFilterDto>,
+) -> Result<HttpResponse, AppError> {
+    let filter = WordFilterService::update(&pool, &redis_pool, admin.0, *filter_id, dto.into_inner()).await?;
+    Ok(success_response(filter))
+}
+
+/// DELETE /api/admin/word-filters/:id
+/// Remove a banned word or pattern
+pub async fn delete_word_filter(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    filter_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    WordFilterService::delete(&pool, &redis_pool, admin.0, *filter_id).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/admin/ip-bans
+/// List banned IPs and networks, including expired bans
+pub async fn list_ip_bans(pool: web::Data<PgPool>, _admin: AdminOnly) -> Result<HttpResponse, AppError> {
+    let bans = IpBanService::list(&pool).await?;
+    Ok(success_response(bans))
+}
+
+/// POST /api/admin/ip-bans
+/// Ban an IP or network (body: {"cidr": "203.0.113.0/24", "reason": "...", "expires_at": optional timestamp})
+pub async fn create_ip_ban(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    dto: web::Json<CreateIpBanDto>,
+) -> Result<HttpResponse, AppError> {
+    let admin_ip = client_ip::get(&req);
+    let ban = IpBanService::create(&pool, &redis_pool, admin.0, admin_ip, dto.into_inner()).await?;
+    Ok(created_response(ban))
+}
+
+/// DELETE /api/admin/ip-bans/:id
+/// Lift a ban
+pub async fn delete_ip_ban(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    ban_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    IpBanService::delete(&pool, &redis_pool, admin.0, *ban_id).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/admin/rate-limits
+/// List the rate limit in effect for each route class
+pub async fn list_rate_limits(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    _admin: AdminOnly,
+) -> Result<HttpResponse, AppError> {
+    let limits = RateLimitService::list(&pool, &config).await?;
+    Ok(success_response(limits))
+}
+
+/// PUT /api/admin/rate-limits/:class
+/// Override a route class's limit, live (body: {"max_requests": 20, "window_seconds": 60})
+pub async fn update_rate_limit(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    admin: AdminOnly,
+    class: web::Path<String>,
+    dto: web::Json<UpdateRateLimitDto>,
+) -> Result<HttpResponse, AppError> {
+    let limit = RateLimitService::update(&pool, &redis_pool, &config, admin.0, &class, dto.into_inner()).await?;
+    Ok(success_response(limit))
+}
+
+/// DELETE /api/admin/rate-limits/:class
+/// Drop a route class's override, back to the server config
+pub async fn reset_rate_limit(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    admin: AdminOnly,
+    class: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let limit = RateLimitService::reset(&pool, &redis_pool, &config, admin.0, &class).await?;
+    Ok(success_response(limit))
+}
+
+/// GET /api/admin/announcements
+/// List all banners, including scheduled and expired ones
+pub async fn list_announcements(pool: web::Data<PgPool>, _admin: AdminOnly) -> Result<HttpResponse, AppError> {
+    let announcements = AnnouncementService::list(&pool).await?;
+    Ok(success_response(announcements))
+}
+
+/// POST /api/admin/announcements
+/// Add a banner (body: {"message": "...", "severity": "info" | "warning" | "critical", "dismissible": true,
+/// "starts_at": "...", "ends_at": "..."})
+pub async fn create_announcement(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    dto: web::Json<CreateAnnouncementDto>,
+) -> Result<HttpResponse, AppError> {
+    let announcement = AnnouncementService::create(&pool, &redis_pool, admin.0, dto.into_inner()).await?;
+    Ok(created_response(announcement))
+}
+
+/// PUT /api/admin/announcements/:id
+/// Change a banner
+pub async fn update_announcement(
+    pool: web::Data<PgPool>,
+    admin: AdminOnly,
+    announcement_id: web::Path<Uuid>,
+    dto: web::Json<UpdateAnnouncementDto>,
+) -> Result<HttpResponse, AppError> {
+    let announcement = AnnouncementService::update(&pool, admin.0, *announcement_id, dto.into_inner()).await?;
+    Ok(success_response(announcement))
+}
+
+/// DELETE /api/admin/announcements/:id
+/// Remove a banner
+pub async fn delete_announcement(
+    pool: web::Data<PgPool>,
+    admin: AdminOnly,
+    announcement_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    AnnouncementService::delete(&pool, admin.0, *announcement_id).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/admin/users/:id/system-message
+/// Send an official DM from the System account (body: {"kind": "notice" | "warning" | "policy", "content": "..."})
+pub async fn send_system_message(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+    dto: web::Json<SendSystemMessageDto>,
+) -> Result<HttpResponse, AppError> {
+    let message = AdminService::send_system_message(&pool, &redis_pool, &config, admin.0, *user_id, dto.into_inner()).await?;
+    Ok(created_response(message))
+}
+
+/// PUT /api/admin/users/:id/role
+/// Change a user's global role
+pub async fn set_user_role(
+    pool: web::Data<PgPool>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+    dto: web::Json<UpdateRoleDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::set_user_role(&pool, admin.0, *user_id, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// PUT /api/admin/users/:id/badges
+/// Grant or revoke badges (body: {"verified": true, "staff": false})
+pub async fn update_badges(
+    pool: web::Data<PgPool>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+    dto: web::Json<UpdateBadgesDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = AdminService::update_badges(&pool, admin.0, *user_id, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// POST /api/admin/users/:id/impersonate
+/// Mint a short-lived token acting as a user (every request made with it is audited)
+pub async fn impersonate_user(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    keys: web::Data<JwtKeys>,
+    admin: AdminOnly,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let response = AdminService::impersonate(&pool, &keys, admin.0, *user_id, session_meta(&req, &config)).await?;
+    Ok(created_response(response))
+}
+
+/// GET /api/admin/metrics/active-users?days=7
+/// Count users active within the last few days
+pub async fn active_users(
+    pool: web::Data<PgPool>,
+    _admin: AdminOnly,
+    query: web::Query<ActiveUsersQuery>,
+) -> Result<HttpResponse, AppError> {
+    let response = AdminService::active_users(&pool, query.days).await?;
+    Ok(success_response(response))
+}
+
+/// GET /api/admin/audit-logs?actor_id=&action=&target_id=&from=&until=&cursor=&limit=50
+/// Search the audit log, newest first; follow `next_cursor` for older entries
+pub async fn list_audit_logs(
+    pool: web::Data<PgPool>,
+    _admin: AdminOnly,
+    query: web::Query<AuditLogQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query.into_inner();
+    let filter = AuditLogFilter {
+        actor_id: query.actor_id,
+        action: query.action,
+        target_id: query.target_id,
+        from: query.from,
+        until: query.until,
+    };
+
+    let page = AdminService::audit_logs(&pool, filter, query.cursor.as_deref(), query.limit).await?;
+    Ok(success_response(page))
+}
+
+/// GET /api/admin/metrics/summary?days=30
+/// Users, DAU/MAU, rooms, storage and messages per day, as of the last metrics job run
+pub async fn metrics_summary(
+    pool: web::Data<PgPool>,
+    _admin: AdminOnly,
+    query: web::Query<MetricsSummaryQuery>,
+) -> Result<HttpResponse, AppError> {
+    let response = AdminService::metrics_summary(&pool, query.days).await?;
+    Ok(success_response(response))
+}
+
+/// DELETE /api/admin/rooms/:id
+/// Force-delete any room (body: {"reason": "..."})
+pub async fn force_delete_room(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<AdminRoomActionDto>,
+) -> Result<HttpResponse, AppError> {
+    AdminService::force_delete_room(&pool, &redis_pool, admin.0, *room_id, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/admin/rooms/:id/lock
+/// Freeze a room (body: {"reason": "..."})
+pub async fn lock_room(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<AdminRoomActionDto>,
+) -> Result<HttpResponse, AppError> {
+    let room = AdminService::lock_room(&pool, &redis_pool, admin.0, *room_id, dto.into_inner()).await?;
+    Ok(success_response(room))
+}
+
+/// DELETE /api/admin/rooms/:id/lock
+/// Unfreeze a room (body: {"reason": "..."})
+pub async fn unlock_room(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<AdminRoomActionDto>,
+) -> Result<HttpResponse, AppError> {
+    let room = AdminService::unlock_room(&pool, &redis_pool, admin.0, *room_id, dto.into_inner()).await?;
+    Ok(success_response(room))
+}
+
+/// PUT /api/admin/rooms/:id/owner
+/// Hand a room to another user (body: {"user_id": "...", "reason": "..."})
+pub async fn reassign_room_owner(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<ReassignOwnerDto>,
+) -> Result<HttpResponse, AppError> {
+    let room = AdminService::reassign_room_owner(&pool, &redis_pool, admin.0, *room_id, dto.into_inner()).await?;
+    Ok(success_response(room))
+}
+
+/// POST /api/admin/rooms/:id/merge
+/// Merge the room into another one (body: {"into_room_id": "..."})
+pub async fn merge_room(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    admin: AdminOnly,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<MergeRoomDto>,
+) -> Result<HttpResponse, AppError> {
+    let response = AdminService::merge_rooms(&pool, &redis_pool, admin.0, *room_id, dto.into_inner()).await?;
+    Ok(success_response(response))
+}
@@ -0,0 +1,28 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::response::{no_content_response, success_response};
+use crate::services::AnnouncementService;
+
+/// GET /api/announcements
+/// Get the banners to show right now (dismissed ones excluded)
+pub async fn list_active(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let announcements = AnnouncementService::active(&pool, auth_user.0).await?;
+    Ok(success_response(announcements))
+}
+
+/// POST /api/announcements/:id/dismiss
+/// Hide a dismissible banner on all own devices
+pub async fn dismiss(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    announcement_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    AnnouncementService::dismiss(&pool, *announcement_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
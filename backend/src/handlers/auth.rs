@@ -1,32 +1,126 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::cache::RedisPool;
 use crate::config::Config;
 use crate::error::AppError;
-use crate::models::user::{CreateUserDto, LoginDto};
-use crate::models::response::{success_response, created_response};
-use crate::services::AuthService;
-use crate::middleware::AuthUser;
+use crate::models::action_token::ConsumeTokenDto;
+use crate::models::session::SessionMeta;
+use crate::models::user::{AcceptTosDto, AuthResponse, CreateUserDto, LoginDto, ChangeEmailDto, PasswordConfirmationDto, MagicLinkDto, ResetPasswordDto, ScopedTokenDto};
+use crate::models::response::{success_response, no_content_response};
+use crate::services::{AccountService, AuthService, LinkedEmailService};
+use crate::utils::jwt::JwtKeys;
+use crate::utils::{auth_cookie, client_ip, secure_token, user_agent};
+use crate::middleware::{AuthUser, AuthClaims};
 use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Read a non-empty header value as string
+fn header_value(req: &HttpRequest, name: &str) -> Option<String> {
+    req.headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Collect client metadata for a new session
+pub(crate) fn session_meta(req: &HttpRequest, config: &Config) -> SessionMeta {
+    let user_agent = header_value(req, "User-Agent");
+
+    // Approximate location from proxy geo headers ("XX" means unknown on Cloudflare),
+    // only believed when a trusted proxy set them
+    let (country, city) = if client_ip::from_trusted_proxy(req, config) {
+        (
+            header_value(req, &config.geo_country_header).filter(|c| c != "XX"),
+            header_value(req, &config.geo_city_header),
+        )
+    } else {
+        (None, None)
+    };
+    let location = match (city, country) {
+        (Some(city), Some(country)) => Some(format!("{}, {}", city, country)),
+        (None, Some(country)) => Some(country),
+        (Some(city), None) => Some(city),
+        (None, None) => None,
+    };
+
+    SessionMeta {
+        device_name: user_agent.as_deref().and_then(user_agent::describe),
+        ip_address: client_ip::get(req).map(|ip| ip.to_string()),
+        user_agent,
+        location,
+    }
+}
+
+/// Respond with a newly issued token: in the body, or as cookies in cookie auth mode
+fn token_response(config: &Config, mut response: actix_web::HttpResponseBuilder, auth: AuthResponse) -> HttpResponse {
+    if !config.auth_cookies {
+        return response.json(auth);
+    }
+
+    let (auth_cookie, csrf_cookie) = auth_cookie::issue(
+        &auth.token,
+        &secure_token::generate(),
+        config.jwt_expires_in,
+        config.cookie_secure,
+    );
+
+    // Keep the JWT out of reach of scripts
+    response
+        .cookie(auth_cookie)
+        .cookie(csrf_cookie)
+        .json(serde_json::json!({ "user": auth.user }))
+}
 
 /// POST /api/auth/register
 /// Register a new user
 pub async fn register(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
+    keys: web::Data<JwtKeys>,
     dto: web::Json<CreateUserDto>,
 ) -> Result<HttpResponse, AppError> {
-    let auth_response = AuthService::register(&pool, &config, dto.into_inner()).await?;
-    Ok(created_response(auth_response))
+    let auth_response = AuthService::register(&pool, &config, &keys, dto.into_inner(), session_meta(&req, &config)).await?;
+    Ok(token_response(&config, HttpResponse::Created(), auth_response))
 }
 
 /// POST /api/auth/login
 /// Login user
 pub async fn login(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
+    keys: web::Data<JwtKeys>,
     dto: web::Json<LoginDto>,
 ) -> Result<HttpResponse, AppError> {
-    let auth_response = AuthService::login(&pool, &config, dto.into_inner()).await?;
-    Ok(success_response(auth_response))
+    let auth_response = AuthService::login(&pool, &config, &keys, dto.into_inner(), session_meta(&req, &config)).await?;
+    Ok(token_response(&config, HttpResponse::Ok(), auth_response))
+}
+
+/// POST /api/auth/magic-link
+/// Email a one-time login link
+pub async fn request_magic_link(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    dto: web::Json<MagicLinkDto>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::request_magic_link(&pool, &config, dto.into_inner()).await?;
+    Ok(success_response(serde_json::json!({
+        "message": "If the account exists, a login link has been sent"
+    })))
+}
+
+/// POST /api/auth/magic-link/consume
+/// Login with the token from a magic link
+pub async fn consume_magic_link(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    keys: web::Data<JwtKeys>,
+    dto: web::Json<ConsumeTokenDto>,
+) -> Result<HttpResponse, AppError> {
+    let auth_response = AuthService::consume_magic_link(&pool, &config, &keys, dto.into_inner(), session_meta(&req, &config)).await?;
+    Ok(token_response(&config, HttpResponse::Ok(), auth_response))
 }
 
 /// GET /api/auth/me
@@ -39,14 +133,240 @@ pub async fn get_me(
     Ok(success_response(user))
 }
 
+/// DELETE /api/auth/me
+/// Delete own account (requires password; personal data is purged after a grace period)
+pub async fn delete_me(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<PasswordConfirmationDto>,
+) -> Result<HttpResponse, AppError> {
+    AccountService::delete_account(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/auth/me/deactivate
+/// Deactivate own account (requires password; logging in again reactivates it)
+pub async fn deactivate_me(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<PasswordConfirmationDto>,
+) -> Result<HttpResponse, AppError> {
+    AccountService::deactivate_account(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/auth/tos
+/// Current terms of service version and whether the user still has to accept it
+pub async fn tos_status(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let status = AccountService::tos_status(&pool, &config, auth_user.0).await?;
+    Ok(success_response(status))
+}
+
+/// POST /api/auth/tos/accept
+/// Accept the current terms of service (body: {"version": "..."})
+pub async fn accept_tos(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<AcceptTosDto>,
+) -> Result<HttpResponse, AppError> {
+    let status = AccountService::accept_tos(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(status))
+}
+
+/// POST /api/auth/me/export
+/// Request an export of own data (built in the background)
+pub async fn request_export(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let export = AccountService::request_export(&pool, &redis_pool, auth_user.0).await?;
+    Ok(HttpResponse::Accepted().json(export))
+}
+
+/// GET /api/auth/me/export/:id
+/// Download a finished data export, or get its status while pending
+pub async fn download_export(
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    export_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let (status, archive) = AccountService::get_export(&redis_pool, auth_user.0, *export_id).await?;
+
+    match archive {
+        Some(archive) => Ok(HttpResponse::Ok()
+            .content_type("application/json")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"ngobrol-export-{}.json\"", status.id),
+            ))
+            .body(archive)),
+        None => Ok(success_response(status)),
+    }
+}
+
+/// POST /api/auth/scoped-token
+/// Mint a short-lived token that can only get WebSocket tickets
+pub async fn scoped_token(
+    keys: web::Data<JwtKeys>,
+    auth_claims: AuthClaims,
+    dto: web::Json<ScopedTokenDto>,
+) -> Result<HttpResponse, AppError> {
+    let token = AuthService::issue_scoped_token(&keys, &auth_claims.0, dto.into_inner()).await?;
+    Ok(success_response(token))
+}
+
+/// POST /api/auth/ws-ticket
+/// Get a one-time ticket for the WebSocket handshake (keeps JWTs out of URLs)
+pub async fn ws_ticket(
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    auth_claims: Option<AuthClaims>,
+) -> Result<HttpResponse, AppError> {
+    // Bots authenticate with an API key, so their tickets carry no session
+    let session_id = auth_claims
+        .map(|claims| Uuid::parse_str(&claims.0.sid))
+        .transpose()
+        .map_err(|_| AppError::InvalidToken)?;
+
+    let ticket = AuthService::issue_ws_ticket(&redis_pool, auth_user.0, session_id).await?;
+    Ok(success_response(ticket))
+}
+
 /// POST /api/auth/logout
-/// Logout user (set status to offline)
+/// Logout user (revoke token and set status to offline)
 pub async fn logout(
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
     auth_user: AuthUser,
+    auth_claims: AuthClaims,
 ) -> Result<HttpResponse, AppError> {
-    AuthService::logout(&pool, auth_user.0).await?;
-    Ok(success_response(serde_json::json!({
+    AuthService::logout(&pool, &redis_pool, auth_user.0, &auth_claims.0).await?;
+
+    let mut response = HttpResponse::Ok();
+    if config.auth_cookies {
+        let (auth_cookie, csrf_cookie) = auth_cookie::clear(config.cookie_secure);
+        response.cookie(auth_cookie).cookie(csrf_cookie);
+    }
+
+    Ok(response.json(serde_json::json!({
         "message": "Logged out successfully"
     })))
 }
+
+/// POST /api/auth/logout-all
+/// Logout from all devices (revoke every session)
+pub async fn logout_all(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let revoked = AuthService::logout_all(&pool, auth_user.0).await?;
+    Ok(success_response(serde_json::json!({
+        "message": "Logged out from all devices",
+        "revoked_sessions": revoked
+    })))
+}
+
+/// GET /api/auth/sessions
+/// List active sessions of current user
+pub async fn list_sessions(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    auth_claims: AuthClaims,
+) -> Result<HttpResponse, AppError> {
+    let sessions = AuthService::list_sessions(&pool, auth_user.0, &auth_claims.0).await?;
+    Ok(success_response(sessions))
+}
+
+/// DELETE /api/auth/sessions/:id
+/// Revoke a session
+pub async fn revoke_session(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    session_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::revoke_session(&pool, auth_user.0, *session_id).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/auth/verify-email
+/// Verify email address with the token from the verification link
+pub async fn verify_email(
+    pool: web::Data<PgPool>,
+    dto: web::Json<ConsumeTokenDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = AuthService::verify_email(&pool, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// POST /api/auth/verify-email/resend
+/// Re-send the verification link (requires authentication)
+pub async fn resend_verification(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    AuthService::resend_verification(&pool, &config, auth_user.0).await?;
+    Ok(success_response(serde_json::json!({
+        "message": "Verification email sent"
+    })))
+}
+
+/// POST /api/auth/change-email
+/// Request an email change (confirmation is sent to the new address)
+pub async fn request_email_change(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<ChangeEmailDto>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::request_email_change(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(serde_json::json!({
+        "message": "Confirmation email sent to the new address"
+    })))
+}
+
+/// POST /api/auth/change-email/confirm
+/// Confirm an email change with the token from the confirmation link
+pub async fn confirm_email_change(
+    pool: web::Data<PgPool>,
+    dto: web::Json<ConsumeTokenDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = AuthService::confirm_email_change(&pool, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// POST /api/auth/password-reset/confirm
+/// Choose a new password with the token from a reset link
+pub async fn reset_password(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    dto: web::Json<ResetPasswordDto>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::reset_password(&pool, &config, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/auth/linked-email/confirm
+/// Confirm a linked email address with the token from the confirmation link
+pub async fn confirm_linked_email(
+    pool: web::Data<PgPool>,
+    dto: web::Json<ConsumeTokenDto>,
+) -> Result<HttpResponse, AppError> {
+    let linked = LinkedEmailService::confirm(&pool, dto.into_inner()).await?;
+    Ok(success_response(linked))
+}
+
+/// GET /.well-known/jwks.json
+/// Public keys for verifying tokens issued by this server
+pub async fn jwks(keys: web::Data<JwtKeys>) -> HttpResponse {
+    success_response(keys.jwks())
+}
@@ -0,0 +1,53 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::bot::CreateBotDto;
+use crate::models::response::{created_response, no_content_response, success_response};
+use crate::services::BotService;
+
+/// GET /api/bots
+/// List own bots
+pub async fn list_bots(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let bots = BotService::list_bots(&pool, auth_user.0).await?;
+    Ok(success_response(bots))
+}
+
+/// POST /api/bots
+/// Create a bot account (the API key is only shown once)
+pub async fn create_bot(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<CreateBotDto>,
+) -> Result<HttpResponse, AppError> {
+    let bot = BotService::create_bot(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(bot))
+}
+
+/// POST /api/bots/:id/key
+/// Issue a new API key for an own bot (the previous key stops working)
+pub async fn rotate_key(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    bot_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let key = BotService::rotate_key(&pool, auth_user.0, *bot_id).await?;
+    Ok(success_response(key))
+}
+
+/// DELETE /api/bots/:id
+/// Delete an own bot
+pub async fn delete_bot(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    bot_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    BotService::delete_bot(&pool, auth_user.0, *bot_id).await?;
+    Ok(no_content_response())
+}
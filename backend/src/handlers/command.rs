@@ -0,0 +1,59 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::command::{ExecuteCommandDto, RegisterCommandDto};
+use crate::models::response::{no_content_response, success_response};
+use crate::services::CommandService;
+
+/// GET /api/rooms/:id/commands
+/// List the room's slash commands for autocompletion (members only)
+pub async fn list_commands(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let commands = CommandService::list(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(commands))
+}
+
+/// PUT /api/rooms/:id/commands
+/// Register or update a slash command (bots in the room only)
+pub async fn register_command(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<RegisterCommandDto>,
+) -> Result<HttpResponse, AppError> {
+    let command = CommandService::register(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(command))
+}
+
+/// DELETE /api/rooms/:id/commands/:name
+/// Remove one of the calling bot's commands
+pub async fn unregister_command(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, name) = path.into_inner();
+    CommandService::unregister(&pool, room_id, auth_user.0, &name).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/rooms/:id/commands/execute
+/// Run a slash command typed in the room (body: {"text": "/weather Jakarta"})
+pub async fn execute_command(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<ExecuteCommandDto>,
+) -> Result<HttpResponse, AppError> {
+    let result = CommandService::execute(&pool, &redis_pool, &config, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(result))
+}
@@ -0,0 +1,122 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::dm::{AddParticipantDto, CreateGroupDmDto};
+use crate::models::response::{created_response, no_content_response, paginated_response, success_response};
+use crate::services::DmService;
+
+/// Query params for the system message list
+#[derive(Deserialize)]
+pub struct SystemMessagesQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+/// GET /api/dm
+/// List own direct conversations, most recently active first
+pub async fn list_conversations(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let conversations = DmService::list(&pool, auth_user.0).await?;
+    Ok(success_response(conversations))
+}
+
+/// POST /api/dm/:user_id
+/// Open the conversation with a user (created on first contact)
+pub async fn open_conversation(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let (conversation, created) = DmService::open(&pool, auth_user.0, *user_id).await?;
+
+    if created {
+        Ok(created_response(conversation))
+    } else {
+        Ok(success_response(conversation))
+    }
+}
+
+/// POST /api/dm/:room_id/read
+/// Mark a conversation (1:1 or group) as read
+pub async fn mark_read(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    DmService::mark_read(&pool, *room_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/dm/groups
+/// List own group DMs, most recently active first
+pub async fn list_groups(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let groups = DmService::list_groups(&pool, auth_user.0).await?;
+    Ok(success_response(groups))
+}
+
+/// POST /api/dm/groups
+/// Create a group DM with the listed users
+pub async fn create_group(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<CreateGroupDmDto>,
+) -> Result<HttpResponse, AppError> {
+    let group = DmService::create_group(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(group))
+}
+
+/// POST /api/dm/groups/:room_id/participants
+/// Add a participant to a group DM
+pub async fn add_participant(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<AddParticipantDto>,
+) -> Result<HttpResponse, AppError> {
+    let group = DmService::add_participant(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(group))
+}
+
+/// DELETE /api/dm/groups/:room_id/participants/:user_id
+/// Remove a participant from a group DM (or leave it)
+pub async fn remove_participant(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, user_id) = path.into_inner();
+    DmService::remove_participant(&pool, &redis_pool, room_id, auth_user.0, user_id).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/dm/system
+/// Official messages received from the System account, newest first
+pub async fn list_system_messages(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    query: web::Query<SystemMessagesQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (messages, total) = DmService::list_system_messages(&pool, auth_user.0, query.page, query.per_page).await?;
+
+    Ok(paginated_response(messages, query.page, query.per_page, total as u64))
+}
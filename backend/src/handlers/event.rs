@@ -0,0 +1,68 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::event::{CreateEventDto, RsvpDto};
+use crate::models::response::{created_response, no_content_response, success_response};
+use crate::services::EventService;
+
+/// GET /api/rooms/:id/events
+/// List the room's upcoming events with RSVP counts
+pub async fn list_events(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let events = EventService::list_events(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(events))
+}
+
+/// POST /api/rooms/:id/events
+/// Schedule an event (body: {"title": "...", "description": "...", "starts_at": "2026-11-01T18:00:00Z"})
+pub async fn create_event(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<CreateEventDto>,
+) -> Result<HttpResponse, AppError> {
+    let event = EventService::create_event(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(event))
+}
+
+/// DELETE /api/rooms/:id/events/:event_id
+/// Cancel an event (creator or moderators)
+pub async fn delete_event(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, event_id) = path.into_inner();
+    EventService::delete_event(&pool, room_id, event_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// PUT /api/rooms/:id/events/:event_id/rsvp
+/// Answer an event (body: {"status": "going" | "maybe" | "not_going"})
+pub async fn rsvp(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+    dto: web::Json<RsvpDto>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, event_id) = path.into_inner();
+    let event = EventService::rsvp(&pool, room_id, event_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(event))
+}
+
+/// DELETE /api/rooms/:id/events/:event_id/rsvp
+/// Withdraw own answer
+pub async fn remove_rsvp(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, event_id) = path.into_inner();
+    EventService::remove_rsvp(&pool, room_id, event_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
@@ -0,0 +1,41 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::response::{no_content_response, success_response};
+use crate::services::InvitationService;
+
+/// GET /api/invitations
+/// List own pending room invitations
+pub async fn list_invitations(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let invitations = InvitationService::list_pending(&pool, auth_user.0).await?;
+    Ok(success_response(invitations))
+}
+
+/// POST /api/invitations/:id/accept
+/// Accept an invitation and join the room
+pub async fn accept_invitation(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    invitation_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let member = InvitationService::accept(&pool, &redis_pool, *invitation_id, auth_user.0).await?;
+    Ok(success_response(member))
+}
+
+/// POST /api/invitations/:id/decline
+/// Decline an invitation
+pub async fn decline_invitation(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    invitation_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    InvitationService::decline(&pool, *invitation_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
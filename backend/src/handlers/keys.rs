@@ -0,0 +1,40 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::keys::PublishKeysDto;
+use crate::models::response::success_response;
+use crate::services::KeyService;
+
+/// PUT /api/keys
+/// Publish own E2E key bundle (identity key, signed prekey, one-time prekeys)
+pub async fn publish_keys(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<PublishKeysDto>,
+) -> Result<HttpResponse, AppError> {
+    let count = KeyService::publish(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(count))
+}
+
+/// GET /api/keys/count
+/// Get the number of own remaining one-time prekeys
+pub async fn prekey_count(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let count = KeyService::prekey_count(&pool, auth_user.0).await?;
+    Ok(success_response(count))
+}
+
+/// GET /api/keys/:user_id
+/// Fetch a user's key bundle to start an encrypted session
+pub async fn fetch_bundle(
+    pool: web::Data<PgPool>,
+    _auth_user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let bundle = KeyService::fetch_bundle(&pool, *user_id).await?;
+    Ok(success_response(bundle))
+}
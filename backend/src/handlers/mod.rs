@@ -1,4 +1,17 @@
 pub mod auth;
 pub mod room;
+pub mod push;
+pub mod admin;
+pub mod keys;
+pub mod dm;
+pub mod invitation;
+pub mod space;
+pub mod event;
+pub mod webhook;
+pub mod user;
+pub mod report;
+pub mod bot;
+pub mod command;
+pub mod announcement;
 
 pub use auth::{register, login, get_me, logout};
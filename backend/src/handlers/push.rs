@@ -0,0 +1,73 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::push::{RegisterDeviceDto, WebPushSubscriptionDto};
+use crate::models::response::{success_response, created_response, no_content_response};
+use crate::services::PushService;
+
+/// POST /api/push/devices
+/// Register a mobile device token
+pub async fn register_device(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<RegisterDeviceDto>,
+) -> Result<HttpResponse, AppError> {
+    let device = PushService::register_device(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(device))
+}
+
+/// GET /api/push/devices
+/// List registered devices of current user
+pub async fn list_devices(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let devices = PushService::list_devices(&pool, auth_user.0).await?;
+    Ok(success_response(devices))
+}
+
+/// DELETE /api/push/devices/:id
+/// Unregister a device
+pub async fn unregister_device(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    device_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    PushService::unregister_device(&pool, *device_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/push/vapid-public-key
+/// Get the application server key for browser push subscriptions
+pub async fn vapid_public_key(
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let key = PushService::vapid_public_key(&config)?;
+    Ok(success_response(key))
+}
+
+/// POST /api/push/subscriptions
+/// Register a browser push subscription
+pub async fn subscribe_web(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<WebPushSubscriptionDto>,
+) -> Result<HttpResponse, AppError> {
+    let subscription = PushService::subscribe_web(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(subscription))
+}
+
+/// DELETE /api/push/subscriptions/:id
+/// Remove a browser push subscription
+pub async fn unsubscribe_web(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    subscription_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    PushService::unsubscribe_web(&pool, *subscription_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
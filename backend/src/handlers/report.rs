@@ -0,0 +1,69 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::middleware::{AuthUser, StaffOnly};
+use crate::models::report::{CreateReportDto, UpdateReportStatusDto};
+use crate::models::response::{created_response, paginated_response, success_response};
+use crate::services::ReportService;
+
+/// Query params for the moderation queue
+#[derive(Deserialize)]
+pub struct ReportQueueQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    /// All unresolved reports when omitted
+    pub status: Option<String>,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+/// POST /api/reports
+/// Report a message or a user
+pub async fn create_report(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<CreateReportDto>,
+) -> Result<HttpResponse, AppError> {
+    let report = ReportService::create_report(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(report))
+}
+
+/// GET /api/reports
+/// Moderation queue, oldest first (moderators and admins)
+pub async fn list_reports(
+    pool: web::Data<PgPool>,
+    _staff: StaffOnly,
+    query: web::Query<ReportQueueQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (reports, total) = ReportService::list_queue(
+        &pool,
+        query.status.as_deref(),
+        query.page,
+        query.per_page,
+    )
+    .await?;
+
+    Ok(paginated_response(reports, query.page, query.per_page, total as u64))
+}
+
+/// PUT /api/reports/:id/status
+/// Move a report through the queue (moderators and admins)
+pub async fn update_report_status(
+    pool: web::Data<PgPool>,
+    staff: StaffOnly,
+    report_id: web::Path<Uuid>,
+    dto: web::Json<UpdateReportStatusDto>,
+) -> Result<HttpResponse, AppError> {
+    let report = ReportService::update_status(&pool, *report_id, staff.0, dto.into_inner()).await?;
+    Ok(success_response(report))
+}
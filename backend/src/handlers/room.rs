@@ -2,11 +2,19 @@ use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 use sqlx::PgPool;
 use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::config::Config;
 use crate::error::AppError;
 use crate::middleware::AuthUser;
-use crate::models::room::{CreateRoomDto, UpdateRoomDto};
+use crate::models::ban::BanUserDto;
+use crate::models::invitation::InviteUserDto;
+use crate::models::mute::MuteRoomDto;
+use crate::models::permission::UpdateRolePermissionsDto;
+use crate::models::rules::{AcceptRulesDto, SetRulesDto};
+use crate::models::welcome::SetWelcomeDto;
+use crate::models::room::{BulkAddMembersDto, CreateRoomDto, JoinRoomDto, ReorderRoomsDto, SetNicknameDto, UpdateMemberRoleDto, UpdateNotificationLevelDto, UpdateRoomDto};
 use crate::models::response::{success_response, created_response, paginated_response, no_content_response};
-use crate::services::RoomService;
+use crate::services::{InvitationService, RoomExportService, RoomService, StatsService};
 
 /// Query params for listing rooms
 #[derive(Deserialize)]
@@ -25,6 +33,30 @@ fn default_per_page() -> u32 {
     20
 }
 
+/// Query params for mention suggestions
+#[derive(Deserialize)]
+pub struct MentionSuggestQuery {
+    #[serde(default)]
+    pub q: String,
+    #[serde(default = "default_suggest_limit")]
+    pub limit: usize,
+}
+
+fn default_suggest_limit() -> usize {
+    10
+}
+
+/// Query params for room stats
+#[derive(Deserialize)]
+pub struct RoomStatsQuery {
+    #[serde(default = "default_stats_days")]
+    pub days: i64,
+}
+
+fn default_stats_days() -> i64 {
+    30
+}
+
 /// GET /api/rooms
 /// Get list of rooms accessible by user
 pub async fn list_rooms(
@@ -43,17 +75,48 @@ pub async fn list_rooms(
     Ok(paginated_response(rooms, query.page, query.per_page, total as u64))
 }
 
+/// GET /api/rooms/mine
+/// Get rooms the user is a member of, with own role and notification settings
+pub async fn list_my_rooms(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    query: web::Query<ListRoomsQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (rooms, total) = RoomService::get_my_rooms(
+        &pool,
+        auth_user.0,
+        query.page,
+        query.per_page,
+    )
+    .await?;
+
+    Ok(paginated_response(rooms, query.page, query.per_page, total as u64))
+}
+
 /// POST /api/rooms
 /// Create a new room
 pub async fn create_room(
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
     auth_user: AuthUser,
     dto: web::Json<CreateRoomDto>,
 ) -> Result<HttpResponse, AppError> {
-    let room = RoomService::create_room(&pool, dto.into_inner(), auth_user.0).await?;
+    let room = RoomService::create_room(&pool, &redis_pool, &config, dto.into_inner(), auth_user.0).await?;
     Ok(created_response(room))
 }
 
+/// PUT /api/rooms/mine/order
+/// Set the custom order of own rooms (body: {"room_ids": [...]}, unlisted rooms go after them)
+pub async fn reorder_my_rooms(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<ReorderRoomsDto>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::reorder_my_rooms(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
 /// GET /api/rooms/:id
 /// Get room details with members
 pub async fn get_room(
@@ -66,14 +129,16 @@ pub async fn get_room(
 }
 
 /// PUT /api/rooms/:id
-/// Update room (owner/admin only)
+/// Update room (requires edit_room permission)
 pub async fn update_room(
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
     auth_user: AuthUser,
     room_id: web::Path<Uuid>,
     dto: web::Json<UpdateRoomDto>,
 ) -> Result<HttpResponse, AppError> {
-    let room = RoomService::update_room(&pool, *room_id, dto.into_inner(), auth_user.0).await?;
+    let room = RoomService::update_room(&pool, &redis_pool, &config, *room_id, dto.into_inner(), auth_user.0).await?;
     Ok(success_response(room))
 }
 
@@ -89,13 +154,16 @@ pub async fn delete_room(
 }
 
 /// POST /api/rooms/:id/join
-/// Join a public room
+/// Join a public room, or a password-protected one (body: {"password": "..."})
 pub async fn join_room(
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
     auth_user: AuthUser,
     room_id: web::Path<Uuid>,
+    dto: Option<web::Json<JoinRoomDto>>,
 ) -> Result<HttpResponse, AppError> {
-    let member = RoomService::join_room(&pool, *room_id, auth_user.0).await?;
+    let dto = dto.map(|d| d.into_inner()).unwrap_or_default();
+    let member = RoomService::join_room(&pool, &redis_pool, *room_id, auth_user.0, dto).await?;
     Ok(created_response(member))
 }
 
@@ -103,10 +171,11 @@ pub async fn join_room(
 /// Leave a room (except owner)
 pub async fn leave_room(
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
     auth_user: AuthUser,
     room_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, AppError> {
-    RoomService::leave_room(&pool, *room_id, auth_user.0).await?;
+    RoomService::leave_room(&pool, &redis_pool, *room_id, auth_user.0).await?;
     Ok(no_content_response())
 }
 
@@ -120,3 +189,352 @@ pub async fn get_members(
     let members = RoomService::get_members(&pool, *room_id, auth_user.0).await?;
     Ok(success_response(members))
 }
+
+/// GET /api/rooms/:id/members/suggest?q=
+/// Shortlist members for @-mention autocompletion
+pub async fn suggest_members(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    query: web::Query<MentionSuggestQuery>,
+) -> Result<HttpResponse, AppError> {
+    let suggestions = RoomService::suggest_members(
+        &pool,
+        &redis_pool,
+        *room_id,
+        auth_user.0,
+        &query.q,
+        query.limit,
+    )
+    .await?;
+
+    Ok(success_response(suggestions))
+}
+
+/// GET /api/rooms/:id/permissions
+/// Get the room's permission matrix per role
+pub async fn get_role_permissions(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let matrix = RoomService::get_role_permissions(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(matrix))
+}
+
+/// PUT /api/rooms/:id/permissions/:role
+/// Customize the permissions of a role (owner only)
+pub async fn update_role_permissions(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, String)>,
+    dto: web::Json<UpdateRolePermissionsDto>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, role) = path.into_inner();
+    let permissions = RoomService::update_role_permissions(&pool, room_id, &role, dto.into_inner(), auth_user.0).await?;
+    Ok(success_response(permissions))
+}
+
+/// POST /api/rooms/:id/invite
+/// Invite a user to the room by ID or username
+pub async fn invite_user(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<InviteUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let invitation = InvitationService::invite(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(invitation))
+}
+
+/// DELETE /api/rooms/:id/members/:user_id
+/// Kick a member out of the room
+pub async fn kick_member(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, member_id) = path.into_inner();
+    RoomService::kick_member(&pool, &redis_pool, room_id, auth_user.0, member_id).await?;
+    Ok(no_content_response())
+}
+
+/// PUT /api/rooms/:id/members/:user_id/role
+/// Promote or demote a member
+pub async fn update_member_role(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+    dto: web::Json<UpdateMemberRoleDto>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, member_id) = path.into_inner();
+    let member = RoomService::update_member_role(&pool, &redis_pool, room_id, auth_user.0, member_id, dto.into_inner()).await?;
+    Ok(success_response(member))
+}
+
+/// GET /api/rooms/:id/bans
+/// List active bans of the room
+pub async fn list_bans(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let bans = RoomService::list_bans(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(bans))
+}
+
+/// POST /api/rooms/:id/bans
+/// Ban a user from the room (optionally for a limited time)
+pub async fn ban_member(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<BanUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let ban = RoomService::ban_member(&pool, &redis_pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(ban))
+}
+
+/// DELETE /api/rooms/:id/bans/:user_id
+/// Lift a ban
+pub async fn unban_member(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, user_id) = path.into_inner();
+    RoomService::unban_member(&pool, room_id, auth_user.0, user_id).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/rooms/:id/stats
+/// Get daily room activity (messages, active members) for the last `days` days
+pub async fn get_stats(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    query: web::Query<RoomStatsQuery>,
+) -> Result<HttpResponse, AppError> {
+    let stats = StatsService::get_room_stats(&pool, *room_id, auth_user.0, query.days).await?;
+    Ok(success_response(stats))
+}
+
+/// GET /api/rooms/:id/mute
+/// Get own mute state of the room
+pub async fn get_mute(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let status = RoomService::get_mute(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(status))
+}
+
+/// POST /api/rooms/:id/mute
+/// Mute the room for oneself (body: {"duration_minutes": 60}, omit to mute until unmuted)
+pub async fn mute_room(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: Option<web::Json<MuteRoomDto>>,
+) -> Result<HttpResponse, AppError> {
+    let dto = dto.map(|d| d.into_inner()).unwrap_or_default();
+    let status = RoomService::mute_room(&pool, *room_id, auth_user.0, dto).await?;
+    Ok(success_response(status))
+}
+
+/// DELETE /api/rooms/:id/mute
+/// Unmute the room
+pub async fn unmute_room(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::unmute_room(&pool, *room_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// PUT /api/rooms/:id/notifications
+/// Set own notification level in the room (synced across devices)
+pub async fn update_notification_level(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<UpdateNotificationLevelDto>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::update_notification_level(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// PUT /api/rooms/:id/members/me/nickname
+/// Set own nickname in the room (body: {"nickname": "..."}; empty or null clears it)
+pub async fn set_nickname(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<SetNicknameDto>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::set_nickname(&pool, &redis_pool, &config, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/rooms/:id/moderation-log
+/// Get the room's moderation log (moderators and above)
+pub async fn get_moderation_log(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    query: web::Query<ListRoomsQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (entries, total) = RoomService::get_moderation_log(
+        &pool,
+        *room_id,
+        auth_user.0,
+        query.page,
+        query.per_page,
+    )
+    .await?;
+
+    Ok(paginated_response(entries, query.page, query.per_page, total as u64))
+}
+
+/// GET /api/rooms/:id/welcome
+/// Get the room's welcome message (owner only, null when not set)
+pub async fn get_welcome(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let welcome = RoomService::get_welcome(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(welcome))
+}
+
+/// PUT /api/rooms/:id/welcome
+/// Set the welcome message sent to new members
+/// (body: {"template": "Welcome {username} to {room}!", "delivery": "room" | "dm"})
+pub async fn set_welcome(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<SetWelcomeDto>,
+) -> Result<HttpResponse, AppError> {
+    let welcome = RoomService::set_welcome(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(welcome))
+}
+
+/// DELETE /api/rooms/:id/welcome
+/// Remove the room's welcome message
+pub async fn remove_welcome(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::remove_welcome(&pool, *room_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/rooms/:id/rules
+/// Get the room's rules and whether the current user accepted them
+pub async fn get_rules(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let rules = RoomService::get_rules(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(rules))
+}
+
+/// PUT /api/rooms/:id/rules
+/// Set the room's rules (requires edit_room permission, bumps the version)
+pub async fn set_rules(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<SetRulesDto>,
+) -> Result<HttpResponse, AppError> {
+    let rules = RoomService::set_rules(&pool, &redis_pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(rules))
+}
+
+/// DELETE /api/rooms/:id/rules
+/// Remove the room's rules
+pub async fn remove_rules(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::remove_rules(&pool, *room_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/rooms/:id/rules/accept
+/// Accept the room's rules (body: {"version": 2})
+pub async fn accept_rules(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<AcceptRulesDto>,
+) -> Result<HttpResponse, AppError> {
+    let rules = RoomService::accept_rules(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(rules))
+}
+
+/// PUT /api/rooms/:id/favorite
+/// Star the room (listed first in own rooms)
+pub async fn add_favorite(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::set_favorite(&pool, *room_id, auth_user.0, true).await?;
+    Ok(no_content_response())
+}
+
+/// DELETE /api/rooms/:id/favorite
+/// Unstar the room
+pub async fn remove_favorite(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    RoomService::set_favorite(&pool, *room_id, auth_user.0, false).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/rooms/:id/export
+/// Download the room's metadata, members and message history as a zip of JSONL files
+/// (owner only, streamed with chunked transfer)
+pub async fn export_room(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let archive = RoomExportService::export_room(&pool, *room_id, auth_user.0).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"ngobrol-room-{}.zip\"", room_id),
+        ))
+        .streaming(archive))
+}
+
+/// POST /api/rooms/:id/members/bulk
+/// Import members by user ID or email (owner only, body: {"entries": [...]})
+pub async fn bulk_add_members(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<BulkAddMembersDto>,
+) -> Result<HttpResponse, AppError> {
+    let response = RoomService::bulk_add_members(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(response))
+}
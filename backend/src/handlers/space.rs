@@ -0,0 +1,75 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::response::{created_response, no_content_response, success_response};
+use crate::models::space::CreateSpaceDto;
+use crate::services::SpaceService;
+
+/// GET /api/spaces
+/// List own spaces, each with its channels
+pub async fn list_spaces(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let spaces = SpaceService::list_my_spaces(&pool, auth_user.0).await?;
+    Ok(success_response(spaces))
+}
+
+/// POST /api/spaces
+/// Create a new space (channels are created with POST /api/rooms and a space_id)
+pub async fn create_space(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<CreateSpaceDto>,
+) -> Result<HttpResponse, AppError> {
+    let space = SpaceService::create_space(&pool, dto.into_inner(), auth_user.0).await?;
+    Ok(created_response(space))
+}
+
+/// GET /api/spaces/:id
+/// Get a space with its channels
+pub async fn get_space(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    space_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let space = SpaceService::get_space(&pool, *space_id, auth_user.0).await?;
+    Ok(success_response(space))
+}
+
+/// DELETE /api/spaces/:id
+/// Delete a space and its channels (owner only)
+pub async fn delete_space(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    space_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    SpaceService::delete_space(&pool, *space_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/spaces/:id/join
+/// Join a space and its open channels
+pub async fn join_space(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    space_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let space = SpaceService::join_space(&pool, *space_id, auth_user.0).await?;
+    Ok(success_response(space))
+}
+
+/// POST /api/spaces/:id/leave
+/// Leave a space and all its channels (except owner)
+pub async fn leave_space(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    auth_user: AuthUser,
+    space_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    SpaceService::leave_space(&pool, &redis_pool, *space_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
@@ -0,0 +1,228 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::response::{created_response, no_content_response, paginated_response, success_response};
+use crate::models::linked_email::AddEmailDto;
+use crate::models::user::{PasswordConfirmationDto, SetCustomStatusDto, SetQuietHoursDto, UpdateUserDto};
+use crate::services::{ActivityService, LinkedEmailService, PresenceService, QuotaService, UserService};
+
+/// GET /api/avatars/:id
+/// Generated initials avatar of a user, for users without an uploaded one
+pub async fn get_avatar(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    user_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let svg = UserService::fallback_avatar(&pool, &redis_pool, *user_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .insert_header(("Content-Security-Policy", "default-src 'none'; style-src 'unsafe-inline'"))
+        .body(svg))
+}
+
+/// Query params for presence lookups
+#[derive(Deserialize)]
+pub struct PresenceQuery {
+    /// Comma-separated user IDs
+    pub ids: String,
+}
+
+/// Query params for the activity feed
+#[derive(Deserialize)]
+pub struct ActivityQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub unread_only: bool,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+/// GET /api/users/presence?ids=...
+/// Current presence of a batch of users
+pub async fn get_presence(
+    redis_pool: web::Data<RedisPool>,
+    query: web::Query<PresenceQuery>,
+) -> Result<HttpResponse, AppError> {
+    let presence = PresenceService::get_presence(&redis_pool, &query.ids).await?;
+    Ok(success_response(presence))
+}
+
+/// PUT /api/users/me
+/// Update own profile (username, display name, avatar and status)
+pub async fn update_profile(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<UpdateUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = UserService::update_profile(&pool, &redis_pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// PUT /api/users/me/status
+/// Set own custom status (body: {"status_text": "...", "status_emoji": "...", "expires_in_minutes": 60})
+pub async fn set_custom_status(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<SetCustomStatusDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = UserService::set_custom_status(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// DELETE /api/users/me/status
+/// Clear own custom status
+pub async fn clear_custom_status(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    UserService::clear_custom_status(&pool, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// PUT /api/users/me/quiet-hours
+/// Set own do-not-disturb window (body: {"start": "22:00", "end": "07:00"}, in own timezone)
+pub async fn set_quiet_hours(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    dto: web::Json<SetQuietHoursDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = UserService::set_quiet_hours(&pool, auth_user.0, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// DELETE /api/users/me/quiet-hours
+/// Turn off own do-not-disturb window
+pub async fn clear_quiet_hours(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    UserService::clear_quiet_hours(&pool, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/users/me/emails
+/// Own primary and linked email addresses
+pub async fn list_emails(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let emails = LinkedEmailService::list(&pool, auth_user.0).await?;
+    Ok(success_response(emails))
+}
+
+/// POST /api/users/me/emails
+/// Link an email address (a confirmation link is sent to it)
+pub async fn add_email(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+    dto: web::Json<AddEmailDto>,
+) -> Result<HttpResponse, AppError> {
+    let linked = LinkedEmailService::add(&pool, &config, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(linked))
+}
+
+/// DELETE /api/users/me/emails/:id
+/// Unlink an email address
+pub async fn remove_email(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    email_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    LinkedEmailService::remove(&pool, auth_user.0, *email_id).await?;
+    Ok(no_content_response())
+}
+
+/// PUT /api/users/me/emails/:id/primary
+/// Make a verified linked address the primary one (requires password)
+pub async fn make_primary_email(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    email_id: web::Path<Uuid>,
+    dto: web::Json<PasswordConfirmationDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = LinkedEmailService::make_primary(&pool, auth_user.0, *email_id, dto.into_inner()).await?;
+    Ok(success_response(user))
+}
+
+/// GET /api/users/me/quotas
+/// Own usage of each resource quota and its limit
+pub async fn get_quotas(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let usage = QuotaService::usage(&pool, &redis_pool, &config, auth_user.0).await?;
+    Ok(success_response(usage))
+}
+
+/// GET /api/users/me/activity
+/// Own activity feed: mentions, invites, role changes and replies, newest first
+pub async fn get_activity(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    query: web::Query<ActivityQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (items, total) = ActivityService::list(
+        &pool,
+        auth_user.0,
+        query.kind.as_deref(),
+        query.unread_only,
+        query.page,
+        query.per_page,
+    )
+    .await?;
+
+    Ok(paginated_response(items, query.page, query.per_page, total as u64))
+}
+
+/// GET /api/users/me/activity/unread-count
+/// Number of unread activity items
+pub async fn get_unread_activity_count(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    let unread = ActivityService::unread_count(&pool, auth_user.0).await?;
+    Ok(success_response(unread))
+}
+
+/// POST /api/users/me/activity/read
+/// Mark all activity read
+pub async fn mark_all_activity_read(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+) -> Result<HttpResponse, AppError> {
+    ActivityService::mark_all_read(&pool, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/users/me/activity/:id/read
+/// Mark one activity item read
+pub async fn mark_activity_read(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    activity_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    ActivityService::mark_read(&pool, auth_user.0, *activity_id).await?;
+    Ok(no_content_response())
+}
@@ -0,0 +1,117 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::handlers::room::ListRoomsQuery;
+use crate::middleware::AuthUser;
+use crate::models::response::{created_response, no_content_response, paginated_response, success_response};
+use crate::models::webhook::{CreateOutgoingWebhookDto, CreateWebhookDto, WebhookMessageDto};
+use crate::services::{OutgoingWebhookService, WebhookService};
+
+/// GET /api/rooms/:id/webhooks
+/// List the room's incoming webhooks (owner only)
+pub async fn list_webhooks(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let webhooks = WebhookService::list_webhooks(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(webhooks))
+}
+
+/// POST /api/rooms/:id/webhooks
+/// Create an incoming webhook (owner only, the secret URL is only shown once)
+pub async fn create_webhook(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<CreateWebhookDto>,
+) -> Result<HttpResponse, AppError> {
+    let webhook = WebhookService::create_webhook(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(webhook))
+}
+
+/// DELETE /api/rooms/:id/webhooks/:webhook_id
+/// Revoke an incoming webhook (owner only)
+pub async fn revoke_webhook(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, webhook_id) = path.into_inner();
+    WebhookService::revoke_webhook(&pool, room_id, webhook_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// POST /api/hooks/:id/:token
+/// Post a message into the room as the webhook (body: {"content": "...", "username": "..."})
+pub async fn post_message(
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, String)>,
+    dto: web::Json<WebhookMessageDto>,
+) -> Result<HttpResponse, AppError> {
+    let (webhook_id, token) = path.into_inner();
+    WebhookService::post_message(&pool, &redis_pool, &config, webhook_id, &token, dto.into_inner()).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/rooms/:id/outgoing-webhooks
+/// List the room's outgoing webhooks (owner only)
+pub async fn list_outgoing_webhooks(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let webhooks = OutgoingWebhookService::list_webhooks(&pool, *room_id, auth_user.0).await?;
+    Ok(success_response(webhooks))
+}
+
+/// POST /api/rooms/:id/outgoing-webhooks
+/// Subscribe a URL to room events (owner only, the signing secret is only shown once)
+pub async fn create_outgoing_webhook(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    room_id: web::Path<Uuid>,
+    dto: web::Json<CreateOutgoingWebhookDto>,
+) -> Result<HttpResponse, AppError> {
+    let webhook = OutgoingWebhookService::create_webhook(&pool, *room_id, auth_user.0, dto.into_inner()).await?;
+    Ok(created_response(webhook))
+}
+
+/// DELETE /api/rooms/:id/outgoing-webhooks/:webhook_id
+/// Delete an outgoing webhook (owner only)
+pub async fn delete_outgoing_webhook(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, webhook_id) = path.into_inner();
+    OutgoingWebhookService::delete_webhook(&pool, room_id, webhook_id, auth_user.0).await?;
+    Ok(no_content_response())
+}
+
+/// GET /api/rooms/:id/outgoing-webhooks/:webhook_id/deliveries
+/// Delivery log of an outgoing webhook, newest first (owner only)
+pub async fn list_deliveries(
+    pool: web::Data<PgPool>,
+    auth_user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<ListRoomsQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (room_id, webhook_id) = path.into_inner();
+    let (deliveries, total) = OutgoingWebhookService::list_deliveries(
+        &pool,
+        room_id,
+        webhook_id,
+        auth_user.0,
+        query.page,
+        query.per_page,
+    )
+    .await?;
+
+    Ok(paginated_response(deliveries, query.page, query.per_page, total as u64))
+}
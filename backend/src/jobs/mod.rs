@@ -0,0 +1,147 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::config::Config;
+use crate::services::{AccountService, EventService, OutgoingWebhookService, StatsService, UserService};
+
+/// How often to look for accounts past their deletion grace period
+const ACCOUNT_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often to refresh room activity stats
+const ROOM_STATS_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How often to snapshot instance-wide metrics
+const INSTANCE_METRICS_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How often to look for events about to start
+const EVENT_REMINDER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often to send due outgoing webhook deliveries
+const WEBHOOK_DELIVERY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often to flush last-active times from Redis to Postgres
+const LAST_ACTIVE_FLUSH_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// Start periodic background jobs on the current runtime
+pub fn start(pool: &PgPool, redis_pool: &RedisPool, config: &Config) {
+    spawn_account_purge(pool.clone(), config.clone());
+    spawn_room_stats(pool.clone());
+    spawn_instance_metrics(pool.clone());
+    spawn_event_reminders(pool.clone(), redis_pool.clone());
+    spawn_webhook_deliveries(pool.clone());
+    spawn_last_active_flush(pool.clone(), redis_pool.clone());
+}
+
+/// Anonymize accounts whose deletion grace period has passed, then purge the content
+/// of those past the retention window
+fn spawn_account_purge(pool: PgPool, config: Config) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ACCOUNT_PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match AccountService::purge_due_accounts(&pool, &config).await {
+                Ok(0) => {}
+                Ok(purged) => log::info!("Purged {} deleted account(s)", purged),
+                Err(e) => log::error!("Account purge job failed: {}", e),
+            }
+
+            match AccountService::purge_expired_content(&pool, &config).await {
+                Ok(0) => {}
+                Ok(purged) => log::info!("Purged the content of {} deleted account(s)", purged),
+                Err(e) => log::error!("Account content purge job failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Aggregate recent messages into the room stats table
+fn spawn_room_stats(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ROOM_STATS_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = StatsService::aggregate_recent(&pool).await {
+                log::error!("Room stats job failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Snapshot instance-wide metrics into the metrics table
+fn spawn_instance_metrics(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INSTANCE_METRICS_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = StatsService::aggregate_instance(&pool).await {
+                log::error!("Instance metrics job failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Post reminders of room events that start soon
+fn spawn_event_reminders(pool: PgPool, redis_pool: RedisPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EVENT_REMINDER_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match EventService::send_due_reminders(&pool, &redis_pool).await {
+                Ok(0) => {}
+                Ok(sent) => log::info!("Sent {} event reminder(s)", sent),
+                Err(e) => log::error!("Event reminder job failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Send outgoing webhook deliveries, including retries that are due
+fn spawn_webhook_deliveries(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(WEBHOOK_DELIVERY_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match OutgoingWebhookService::deliver_due(&pool).await {
+                Ok(0) => {}
+                Ok(delivered) => log::info!("Delivered {} outgoing webhook(s)", delivered),
+                Err(e) => log::error!("Webhook delivery job failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Build a user's data export without blocking the request that asked for it
+pub fn spawn_data_export(pool: PgPool, redis_pool: RedisPool, user_id: Uuid, export_id: Uuid) {
+    tokio::spawn(async move {
+        match AccountService::build_export(&pool, &redis_pool, user_id, export_id).await {
+            Ok(()) => log::info!("Data export {} for user {} is ready", export_id, user_id),
+            Err(e) => log::error!("Data export {} for user {} failed: {}", export_id, user_id, e),
+        }
+    });
+}
+
+/// Persist the last-active times recorded in Redis
+fn spawn_last_active_flush(pool: PgPool, redis_pool: RedisPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(LAST_ACTIVE_FLUSH_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = UserService::flush_last_active(&pool, &redis_pool).await {
+                log::error!("Last-active flush job failed: {}", e);
+            }
+        }
+    });
+}
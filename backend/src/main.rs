@@ -9,9 +9,13 @@ mod services;
 mod handlers;
 mod middleware;
 mod websocket;
+mod jobs;
+mod shutdown;
 
 use actix_web::{web, App, HttpServer, HttpResponse};
 use config::Config;
+use models::rate_limit::RATE_LIMIT_CLASS_AUTH;
+use services::UserService;
 use std::io;
 
 #[actix_web::main]
@@ -36,33 +40,112 @@ async fn main() -> io::Result<()> {
         .await
         .expect("Database connection test failed");
 
-    // Create Redis client
-    let redis_client = cache::create_client(&config.redis_url)
-        .expect("Failed to create Redis client");
+    // Bring the schema up to date (otherwise migrations are applied out of band)
+    if config.run_migrations {
+        db::run_migrations(&db_pool)
+            .await
+            .expect("Database migrations failed");
+    }
+
+    // Create Redis connection pool
+    let redis_pool = cache::create_pool(&config.redis_url)
+        .await
+        .expect("Failed to create Redis pool");
     
     // Test Redis connection
-    cache::test_connection(&redis_client)
+    cache::test_connection(&redis_pool)
+        .await
         .expect("Redis connection test failed");
 
+    // Read-through cache of hot room and user lookups
+    cache::CacheService::init(redis_pool.clone());
+
+    // Load JWT signing keys
+    let jwt_keys = web::Data::new(
+        utils::jwt::JwtKeys::from_config(&config).expect("Failed to load JWT keys"),
+    );
+    log::info!("✅ JWT keys loaded ({})", config.jwt_algorithm);
+
+    // Start background jobs
+    jobs::start(&db_pool, &redis_pool, &config);
+    log::info!("✅ Background jobs started");
+
     let server_address = config.server_address();
+    let shutdown_timeout = config.shutdown_timeout_seconds;
     log::info!("🚀 Starting server at http://{}", server_address);
 
+    // Kept for the cleanup once the server has stopped
+    let shutdown_db_pool = db_pool.clone();
+    let shutdown_redis_pool = redis_pool.clone();
+
     // Start HTTP server
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
+        // Shared budget for unauthenticated auth endpoints (per client IP)
+        let auth_rate_limit = middleware::RateLimit::new(RATE_LIMIT_CLASS_AUTH);
+
         App::new()
             .app_data(web::Data::new(db_pool.clone()))
-            .app_data(web::Data::new(redis_client.clone()))
+            .app_data(web::Data::new(redis_pool.clone()))
             .app_data(web::Data::new(config.clone()))
+            .app_data(jwt_keys.clone())
+            // Refuse banned client IPs before anything else runs
+            .wrap(middleware::IpBan)
             // Public routes
             .route("/", web::get().to(index))
             .route("/health", web::get().to(health_check))
+            .route("/.well-known/jwks.json", web::get().to(handlers::auth::jwks))
+            // WebSocket (authenticated by one-time ticket)
+            .route("/ws", web::get().to(websocket::handler::connect))
+            // Incoming webhooks (authenticated by the token in the URL)
+            .route("/api/hooks/{id}/{token}", web::post().to(handlers::webhook::post_message))
+            // Generated avatars (public, so they work as plain image URLs)
+            .route("/api/avatars/{id}", web::get().to(handlers::user::get_avatar))
             // Auth routes
             .service(
                 web::scope("/api/auth")
-                    .route("/register", web::post().to(handlers::auth::register))
-                    .route("/login", web::post().to(handlers::auth::login))
+                    .route("/register", web::post().to(handlers::auth::register).wrap(auth_rate_limit.clone()))
+                    .route("/login", web::post().to(handlers::auth::login).wrap(auth_rate_limit.clone()))
+                    .route("/magic-link", web::post().to(handlers::auth::request_magic_link).wrap(auth_rate_limit.clone()))
+                    .route("/magic-link/consume", web::post().to(handlers::auth::consume_magic_link).wrap(auth_rate_limit.clone()))
+                    .route("/verify-email", web::post().to(handlers::auth::verify_email).wrap(auth_rate_limit.clone()))
+                    .route("/verify-email/resend", web::post().to(handlers::auth::resend_verification).wrap(middleware::AuthMiddleware))
+                    .route("/change-email", web::post().to(handlers::auth::request_email_change).wrap(middleware::AuthMiddleware))
+                    .route("/change-email/confirm", web::post().to(handlers::auth::confirm_email_change).wrap(auth_rate_limit.clone()))
+                    .route("/password-reset/confirm", web::post().to(handlers::auth::reset_password).wrap(auth_rate_limit.clone()))
+                    .route("/linked-email/confirm", web::post().to(handlers::auth::confirm_linked_email).wrap(auth_rate_limit.clone()))
                     .route("/me", web::get().to(handlers::auth::get_me).wrap(middleware::AuthMiddleware))
+                    .route("/me", web::delete().to(handlers::auth::delete_me).wrap(middleware::AuthMiddleware))
+                    .route("/me/deactivate", web::post().to(handlers::auth::deactivate_me).wrap(middleware::AuthMiddleware))
+                    .route("/me/export", web::post().to(handlers::auth::request_export).wrap(middleware::AuthMiddleware))
+                    .route("/me/export/{id}", web::get().to(handlers::auth::download_export).wrap(middleware::AuthMiddleware))
+                    .route("/tos", web::get().to(handlers::auth::tos_status).wrap(middleware::AuthMiddleware))
+                    .route("/tos/accept", web::post().to(handlers::auth::accept_tos).wrap(middleware::AuthMiddleware))
+                    .route("/ws-ticket", web::post().to(handlers::auth::ws_ticket).wrap(middleware::AuthMiddleware))
+                    .route("/scoped-token", web::post().to(handlers::auth::scoped_token).wrap(middleware::AuthMiddleware))
                     .route("/logout", web::post().to(handlers::auth::logout).wrap(middleware::AuthMiddleware))
+                    .route("/logout-all", web::post().to(handlers::auth::logout_all).wrap(middleware::AuthMiddleware))
+                    .route("/sessions", web::get().to(handlers::auth::list_sessions).wrap(middleware::AuthMiddleware))
+                    .route("/sessions/{id}", web::delete().to(handlers::auth::revoke_session).wrap(middleware::AuthMiddleware))
+            )
+            // User routes (all protected)
+            .service(
+                web::scope("/api/users")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("/presence", web::get().to(handlers::user::get_presence))
+                    .route("/me", web::put().to(handlers::user::update_profile))
+                    .route("/me/status", web::put().to(handlers::user::set_custom_status))
+                    .route("/me/status", web::delete().to(handlers::user::clear_custom_status))
+                    .route("/me/quiet-hours", web::put().to(handlers::user::set_quiet_hours))
+                    .route("/me/quiet-hours", web::delete().to(handlers::user::clear_quiet_hours))
+                    .route("/me/emails", web::get().to(handlers::user::list_emails))
+                    .route("/me/emails", web::post().to(handlers::user::add_email))
+                    .route("/me/emails/{id}", web::delete().to(handlers::user::remove_email))
+                    .route("/me/emails/{id}/primary", web::put().to(handlers::user::make_primary_email))
+                    .route("/me/quotas", web::get().to(handlers::user::get_quotas))
+                    .route("/me/activity", web::get().to(handlers::user::get_activity))
+                    .route("/me/activity/unread-count", web::get().to(handlers::user::get_unread_activity_count))
+                    .route("/me/activity/read", web::post().to(handlers::user::mark_all_activity_read))
+                    .route("/me/activity/{id}/read", web::post().to(handlers::user::mark_activity_read))
             )
             // Room routes (all protected)
             .service(
@@ -70,17 +153,222 @@ async fn main() -> io::Result<()> {
                     .wrap(middleware::AuthMiddleware)
                     .route("", web::get().to(handlers::room::list_rooms))
                     .route("", web::post().to(handlers::room::create_room))
+                    .route("/mine", web::get().to(handlers::room::list_my_rooms))
+                    .route("/mine/order", web::put().to(handlers::room::reorder_my_rooms))
                     .route("/{id}", web::get().to(handlers::room::get_room))
                     .route("/{id}", web::put().to(handlers::room::update_room))
                     .route("/{id}", web::delete().to(handlers::room::delete_room))
                     .route("/{id}/join", web::post().to(handlers::room::join_room))
                     .route("/{id}/leave", web::post().to(handlers::room::leave_room))
                     .route("/{id}/members", web::get().to(handlers::room::get_members))
+                    .route("/{id}/members/bulk", web::post().to(handlers::room::bulk_add_members))
+                    .route("/{id}/members/suggest", web::get().to(handlers::room::suggest_members))
+                    .route("/{id}/members/me/nickname", web::put().to(handlers::room::set_nickname))
+                    .route("/{id}/stats", web::get().to(handlers::room::get_stats))
+                    .route("/{id}/mute", web::get().to(handlers::room::get_mute))
+                    .route("/{id}/mute", web::post().to(handlers::room::mute_room))
+                    .route("/{id}/mute", web::delete().to(handlers::room::unmute_room))
+                    .route("/{id}/notifications", web::put().to(handlers::room::update_notification_level))
+                    .route("/{id}/members/{user_id}", web::delete().to(handlers::room::kick_member))
+                    .route("/{id}/members/{user_id}/role", web::put().to(handlers::room::update_member_role))
+                    .route("/{id}/invite", web::post().to(handlers::room::invite_user))
+                    .route("/{id}/bans", web::get().to(handlers::room::list_bans))
+                    .route("/{id}/bans", web::post().to(handlers::room::ban_member))
+                    .route("/{id}/bans/{user_id}", web::delete().to(handlers::room::unban_member))
+                    .route("/{id}/moderation-log", web::get().to(handlers::room::get_moderation_log))
+                    .route("/{id}/welcome", web::get().to(handlers::room::get_welcome))
+                    .route("/{id}/welcome", web::put().to(handlers::room::set_welcome))
+                    .route("/{id}/welcome", web::delete().to(handlers::room::remove_welcome))
+                    .route("/{id}/rules", web::get().to(handlers::room::get_rules))
+                    .route("/{id}/rules", web::put().to(handlers::room::set_rules))
+                    .route("/{id}/rules", web::delete().to(handlers::room::remove_rules))
+                    .route("/{id}/rules/accept", web::post().to(handlers::room::accept_rules))
+                    .route("/{id}/favorite", web::put().to(handlers::room::add_favorite))
+                    .route("/{id}/favorite", web::delete().to(handlers::room::remove_favorite))
+                    .route("/{id}/export", web::get().to(handlers::room::export_room))
+                    .route("/{id}/events", web::get().to(handlers::event::list_events))
+                    .route("/{id}/events", web::post().to(handlers::event::create_event))
+                    .route("/{id}/events/{event_id}", web::delete().to(handlers::event::delete_event))
+                    .route("/{id}/events/{event_id}/rsvp", web::put().to(handlers::event::rsvp))
+                    .route("/{id}/events/{event_id}/rsvp", web::delete().to(handlers::event::remove_rsvp))
+                    .route("/{id}/webhooks", web::get().to(handlers::webhook::list_webhooks))
+                    .route("/{id}/webhooks", web::post().to(handlers::webhook::create_webhook))
+                    .route("/{id}/webhooks/{webhook_id}", web::delete().to(handlers::webhook::revoke_webhook))
+                    .route("/{id}/outgoing-webhooks", web::get().to(handlers::webhook::list_outgoing_webhooks))
+                    .route("/{id}/outgoing-webhooks", web::post().to(handlers::webhook::create_outgoing_webhook))
+                    .route("/{id}/outgoing-webhooks/{webhook_id}", web::delete().to(handlers::webhook::delete_outgoing_webhook))
+                    .route("/{id}/outgoing-webhooks/{webhook_id}/deliveries", web::get().to(handlers::webhook::list_deliveries))
+                    .route("/{id}/commands", web::get().to(handlers::command::list_commands))
+                    .route("/{id}/commands", web::put().to(handlers::command::register_command))
+                    .route("/{id}/commands/execute", web::post().to(handlers::command::execute_command))
+                    .route("/{id}/commands/{name}", web::delete().to(handlers::command::unregister_command))
+                    .route("/{id}/permissions", web::get().to(handlers::room::get_role_permissions))
+                    .route("/{id}/permissions/{role}", web::put().to(handlers::room::update_role_permissions))
+            )
+            // Room invitation routes (all protected)
+            .service(
+                web::scope("/api/invitations")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::get().to(handlers::invitation::list_invitations))
+                    .route("/{id}/accept", web::post().to(handlers::invitation::accept_invitation))
+                    .route("/{id}/decline", web::post().to(handlers::invitation::decline_invitation))
+            )
+            // Space routes (all protected)
+            .service(
+                web::scope("/api/spaces")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::get().to(handlers::space::list_spaces))
+                    .route("", web::post().to(handlers::space::create_space))
+                    .route("/{id}", web::get().to(handlers::space::get_space))
+                    .route("/{id}", web::delete().to(handlers::space::delete_space))
+                    .route("/{id}/join", web::post().to(handlers::space::join_space))
+                    .route("/{id}/leave", web::post().to(handlers::space::leave_space))
+            )
+            // Direct message routes (all protected)
+            .service(
+                web::scope("/api/dm")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::get().to(handlers::dm::list_conversations))
+                    .route("/system", web::get().to(handlers::dm::list_system_messages))
+                    .route("/groups", web::get().to(handlers::dm::list_groups))
+                    .route("/groups", web::post().to(handlers::dm::create_group))
+                    .route("/groups/{room_id}/participants", web::post().to(handlers::dm::add_participant))
+                    .route("/groups/{room_id}/participants/{user_id}", web::delete().to(handlers::dm::remove_participant))
+                    .route("/{user_id}", web::post().to(handlers::dm::open_conversation))
+                    .route("/{room_id}/read", web::post().to(handlers::dm::mark_read))
+            )
+            // Push notification routes (all protected)
+            .service(
+                web::scope("/api/push")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("/devices", web::get().to(handlers::push::list_devices))
+                    .route("/devices", web::post().to(handlers::push::register_device))
+                    .route("/devices/{id}", web::delete().to(handlers::push::unregister_device))
+                    .route("/vapid-public-key", web::get().to(handlers::push::vapid_public_key))
+                    .route("/subscriptions", web::post().to(handlers::push::subscribe_web))
+                    .route("/subscriptions/{id}", web::delete().to(handlers::push::unsubscribe_web))
+            )
+            // E2E encryption key routes (all protected)
+            .service(
+                web::scope("/api/keys")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::put().to(handlers::keys::publish_keys))
+                    .route("/count", web::get().to(handlers::keys::prekey_count))
+                    .route("/{user_id}", web::get().to(handlers::keys::fetch_bundle))
+            )
+            // Bot routes (all protected)
+            .service(
+                web::scope("/api/bots")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::get().to(handlers::bot::list_bots))
+                    .route("", web::post().to(handlers::bot::create_bot))
+                    .route("/{id}", web::delete().to(handlers::bot::delete_bot))
+                    .route("/{id}/key", web::post().to(handlers::bot::rotate_key))
+            )
+            // Report routes (protected, queue restricted to moderators and admins per handler)
+            .service(
+                web::scope("/api/reports")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::post().to(handlers::report::create_report))
+                    .route("", web::get().to(handlers::report::list_reports))
+                    .route("/{id}/status", web::put().to(handlers::report::update_report_status))
+            )
+            // Announcement banner routes (all protected)
+            .service(
+                web::scope("/api/announcements")
+                    .wrap(middleware::AuthMiddleware)
+                    .route("", web::get().to(handlers::announcement::list_active))
+                    .route("/{id}/dismiss", web::post().to(handlers::announcement::dismiss))
+            )
+            // Admin routes (protected, admin role checked per handler)
+            .service(
+                web::scope("/api/admin")
+                    .wrap(middleware::AuthMiddleware)
+                    .service(
+                        web::scope("/users")
+                            .route("", web::get().to(handlers::admin::list_users))
+                            .route("/export", web::get().to(handlers::admin::export_users))
+                            .route("/{id}", web::get().to(handlers::admin::get_user))
+                            .route("/{id}", web::delete().to(handlers::admin::delete_user))
+                            .route("/{id}/role", web::put().to(handlers::admin::set_user_role))
+                            .route("/{id}/badges", web::put().to(handlers::admin::update_badges))
+                            .route("/{id}/suspend", web::post().to(handlers::admin::suspend_user))
+                            .route("/{id}/suspend", web::delete().to(handlers::admin::unsuspend_user))
+                            .route("/{id}/password-reset", web::post().to(handlers::admin::force_password_reset))
+                            .route("/{id}/impersonate", web::post().to(handlers::admin::impersonate_user))
+                            .route("/{id}/system-message", web::post().to(handlers::admin::send_system_message))
+                    )
+                    .service(
+                        web::scope("/reports")
+                            .route("", web::get().to(handlers::admin::list_reports))
+                            .route("/{id}/action", web::post().to(handlers::admin::act_on_report))
+                    )
+                    .service(
+                        web::scope("/word-filters")
+                            .route("", web::get().to(handlers::admin::list_word_filters))
+                            .route("", web::post().to(handlers::admin::create_word_filter))
+                            .route("/{id}", web::put().to(handlers::admin::update_word_filter))
+                            .route("/{id}", web::delete().to(handlers::admin::delete_word_filter))
+                    )
+                    .service(
+                        web::scope("/ip-bans")
+                            .route("", web::get().to(handlers::admin::list_ip_bans))
+                            .route("", web::post().to(handlers::admin::create_ip_ban))
+                            .route("/{id}", web::delete().to(handlers::admin::delete_ip_ban))
+                    )
+                    .service(
+                        web::scope("/rate-limits")
+                            .route("", web::get().to(handlers::admin::list_rate_limits))
+                            .route("/{class}", web::put().to(handlers::admin::update_rate_limit))
+                            .route("/{class}", web::delete().to(handlers::admin::reset_rate_limit))
+                    )
+                    .service(
+                        web::scope("/announcements")
+                            .route("", web::get().to(handlers::admin::list_announcements))
+                            .route("", web::post().to(handlers::admin::create_announcement))
+                            .route("/{id}", web::put().to(handlers::admin::update_announcement))
+                            .route("/{id}", web::delete().to(handlers::admin::delete_announcement))
+                    )
+                    .service(
+                        web::scope("/rooms")
+                            .route("/{id}", web::delete().to(handlers::admin::force_delete_room))
+                            .route("/{id}/lock", web::post().to(handlers::admin::lock_room))
+                            .route("/{id}/lock", web::delete().to(handlers::admin::unlock_room))
+                            .route("/{id}/owner", web::put().to(handlers::admin::reassign_room_owner))
+                            .route("/{id}/merge", web::post().to(handlers::admin::merge_room))
+                    )
+                    .route("/audit-logs", web::get().to(handlers::admin::list_audit_logs))
+                    .route("/metrics/active-users", web::get().to(handlers::admin::active_users))
+                    .route("/metrics/summary", web::get().to(handlers::admin::metrics_summary))
             )
     })
     .bind(server_address)?
-    .run()
-    .await
+    // Signals are handled below, so SIGINT also shuts down gracefully
+    .disable_signals()
+    .shutdown_timeout(shutdown_timeout)
+    .run();
+
+    let server_handle = server.handle();
+    actix_web::rt::spawn(async move {
+        shutdown::signal().await;
+        log::info!("🛑 Shutting down (waiting up to {}s for open connections)", shutdown_timeout);
+
+        // Stop accepting connections, let in-flight requests finish and close WebSocket sessions
+        shutdown::trigger();
+        server_handle.stop(true).await;
+    });
+
+    server.await?;
+
+    // Don't lose activity still buffered in Redis
+    if let Err(e) = UserService::flush_last_active(&shutdown_db_pool, &shutdown_redis_pool).await {
+        log::error!("Failed to flush last-active times on shutdown: {}", e);
+    }
+
+    shutdown_db_pool.close().await;
+    log::info!("👋 Server stopped");
+
+    Ok(())
 }
 
 async fn index() -> HttpResponse {
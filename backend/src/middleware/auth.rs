@@ -2,17 +2,67 @@ use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
     Error, HttpMessage,
 };
+use actix_web::http::Method;
 use std::future::{ready, Ready};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use crate::cache::RedisPool;
 use crate::config::Config;
 use crate::error::AppError;
-use crate::services::AuthService;
+use crate::models::user::ROLE_ADMIN;
+use crate::services::{AdminService, AuthService, BotService, QuotaService, UserService};
+use crate::utils::{auth_cookie, secure_token};
+use crate::utils::jwt::{JwtKeys, SCOPE_FULL, SCOPE_WS};
 use sqlx::PgPool;
 
+/// Route prefixes that stay open while the user has outdated terms of service: reading
+/// and accepting them, logout (and logout-all), and everything under /api/auth/me, which
+/// covers own account info, data export, deactivation and deletion
+const TOS_EXEMPT_PATHS: &[&str] = &["/api/auth/tos", "/api/auth/me", "/api/auth/logout"];
+
+/// Whether a route stays open while the user has outdated terms of service
+fn is_tos_exempt(path: &str) -> bool {
+    TOS_EXEMPT_PATHS.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Routes that also take a restricted token, with the scope it must carry
+const SCOPED_ROUTES: &[(&str, &str)] = &[("/api/auth/ws-ticket", SCOPE_WS)];
+
+/// Token scopes a route accepts: full access, plus the restricted scope meant for it
+fn accepted_scopes(path: &str) -> Vec<&'static str> {
+    let mut scopes = vec![SCOPE_FULL];
+    scopes.extend(SCOPED_ROUTES.iter().filter(|(route, _)| *route == path).map(|(_, scope)| *scope));
+    scopes
+}
+
 /// Middleware for JWT authentication
+/// Reads the Authorization header, or the auth cookie (with CSRF check) in cookie auth mode
 pub struct AuthMiddleware;
 
+/// Take the token from the auth cookie, enforcing the double-submit CSRF check on unsafe methods
+fn token_from_cookie(req: &ServiceRequest) -> Result<String, AppError> {
+    let token = req
+        .cookie(auth_cookie::AUTH_COOKIE)
+        .map(|c| c.value().to_string())
+        .filter(|t| !t.is_empty())
+        .ok_or(AppError::MissingToken)?;
+
+    if !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        let csrf_cookie = req.cookie(auth_cookie::CSRF_COOKIE).map(|c| c.value().to_string());
+        let csrf_header = req
+            .headers()
+            .get(auth_cookie::CSRF_HEADER)
+            .and_then(|v| v.to_str().ok());
+
+        match (csrf_cookie, csrf_header) {
+            (Some(cookie), Some(header)) if !cookie.is_empty() && secure_token::constant_time_eq(&cookie, header) => {}
+            _ => return Err(AppError::CsrfFailed),
+        }
+    }
+
+    Ok(token)
+}
+
 impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
@@ -49,6 +99,42 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
+        // Bot accounts authenticate with "Authorization: Bot <api key>" and get no token claims
+        let bot_key = req
+            .headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bot "))
+            .map(|key| key.trim().to_string());
+
+        if let Some(api_key) = bot_key {
+            let pool = match req.app_data::<actix_web::web::Data<PgPool>>() {
+                Some(p) => p.clone(),
+                None => {
+                    let error = AppError::InternalError("Database pool not found".to_string());
+                    return Box::pin(async move { Err(error.into()) });
+                }
+            };
+            let service = self.service.clone();
+
+            return Box::pin(async move {
+                let bot = BotService::authenticate(&pool, &api_key).await?;
+                req.extensions_mut().insert(bot.id);
+
+                if let Some(redis_pool) = req.app_data::<actix_web::web::Data<RedisPool>>() {
+                    UserService::touch_last_active(redis_pool, bot.id).await;
+
+                    if let Some(config) = req.app_data::<actix_web::web::Data<Config>>() {
+                        QuotaService::hit_api_request(redis_pool, config, bot.id).await?;
+                    }
+                }
+
+                let res = service.call(req).await?;
+
+                Ok(res)
+            });
+        }
+
         // Get Authorization header
         let auth_header = req.headers().get("Authorization");
 
@@ -70,8 +156,23 @@ where
                 }
             }
             None => {
-                let error = AppError::MissingToken;
-                return Box::pin(async move { Err(error.into()) });
+                let cookies_enabled = req
+                    .app_data::<actix_web::web::Data<Config>>()
+                    .map(|c| c.auth_cookies)
+                    .unwrap_or(false);
+
+                let result = if cookies_enabled {
+                    token_from_cookie(&req)
+                } else {
+                    Err(AppError::MissingToken)
+                };
+
+                match result {
+                    Ok(t) => t,
+                    Err(e) => {
+                        return Box::pin(async move { Err(e.into()) });
+                    }
+                }
             }
         };
 
@@ -84,6 +185,22 @@ where
             }
         };
 
+        let redis_pool = match req.app_data::<actix_web::web::Data<RedisPool>>() {
+            Some(r) => r.clone(),
+            None => {
+                let error = AppError::InternalError("Redis pool not found".to_string());
+                return Box::pin(async move { Err(error.into()) });
+            }
+        };
+
+        let keys = match req.app_data::<actix_web::web::Data<JwtKeys>>() {
+            Some(k) => k.clone(),
+            None => {
+                let error = AppError::InternalError("JWT keys not found".to_string());
+                return Box::pin(async move { Err(error.into()) });
+            }
+        };
+
         let config = match req.app_data::<actix_web::web::Data<Config>>() {
             Some(c) => c.clone(),
             None => {
@@ -96,10 +213,37 @@ where
 
         Box::pin(async move {
             // Verify token and get user first
-            let user = AuthService::verify_token(&pool, &config, &token).await?;
+            let scopes = accepted_scopes(req.path());
+            let (user, claims) = AuthService::verify_token(&pool, &redis_pool, &keys, &token, &scopes).await?;
+
+            // Every request made while impersonating is audited; no audit entry, no request
+            let is_impersonated = claims.imp.is_some();
+            if is_impersonated {
+                let method = req.method().to_string();
+                AdminService::record_impersonated_request(&pool, &claims, &method, req.path()).await?;
+            }
 
-            // Insert user_id into request extensions BEFORE calling handler
+            // Insert user_id and claims into request extensions BEFORE calling handler
             req.extensions_mut().insert(user.id);
+            req.extensions_mut().insert(claims);
+
+            // Outdated terms block everything but reading/accepting them and leaving the account
+            // (an impersonating admin can't accept on the user's behalf, so isn't blocked)
+            if !is_impersonated
+                && user.needs_tos_acceptance(config.tos_version.as_deref())
+                && !is_tos_exempt(req.path())
+            {
+                return Err(AppError::TosAcceptanceRequired.into());
+            }
+
+            // Impersonated requests are not the user's own activity, nor their quota
+            if !is_impersonated {
+                UserService::touch_last_active(&redis_pool, user.id).await;
+
+                if user.role != ROLE_ADMIN {
+                    QuotaService::hit_api_request(&redis_pool, &config, user.id).await?;
+                }
+            }
 
             // Now call the handler
             let res = service.call(req).await?;
@@ -108,3 +252,36 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ws_scope_only_accepted_for_ws_ticket() {
+        assert_eq!(accepted_scopes("/api/auth/ws-ticket"), vec![SCOPE_FULL, SCOPE_WS]);
+        assert_eq!(accepted_scopes("/api/auth/ws-ticket/extra"), vec![SCOPE_FULL]);
+        assert_eq!(accepted_scopes("/api/auth/scoped-token"), vec![SCOPE_FULL]);
+        assert_eq!(accepted_scopes("/api/rooms"), vec![SCOPE_FULL]);
+    }
+
+    #[test]
+    fn test_tos_exempt_paths() {
+        for path in [
+            "/api/auth/tos",
+            "/api/auth/tos/accept",
+            "/api/auth/me",
+            "/api/auth/me/export",
+            "/api/auth/me/export/3f2b1c4e-0000-0000-0000-000000000000",
+            "/api/auth/me/deactivate",
+            "/api/auth/logout",
+            "/api/auth/logout-all",
+        ] {
+            assert!(is_tos_exempt(path), "{} should be exempt", path);
+        }
+
+        for path in ["/api/rooms", "/api/users/me", "/api/auth/sessions", "/api/auth/ws-ticket", "/api/admin/users"] {
+            assert!(!is_tos_exempt(path), "{} should not be exempt", path);
+        }
+    }
+}
@@ -2,6 +2,8 @@ use actix_web::{dev::Payload, Error, FromRequest, HttpRequest, HttpMessage};
 use std::future::{ready, Ready};
 use uuid::Uuid;
 use crate::error::AppError;
+use crate::models::user::{ROLE_ADMIN, ROLE_MODERATOR};
+use crate::utils::jwt::Claims;
 
 /// Extractor for authenticated user ID
 pub struct AuthUser(pub Uuid);
@@ -19,3 +21,60 @@ impl FromRequest for AuthUser {
         ready(user_id.map(AuthUser).map_err(Into::into))
     }
 }
+
+/// Extractor for the verified token claims of the current request
+pub struct AuthClaims(pub Claims);
+
+impl FromRequest for AuthClaims {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let claims = req.extensions()
+            .get::<Claims>()
+            .cloned()
+            .ok_or(AppError::MissingToken);
+
+        ready(claims.map(AuthClaims).map_err(Into::into))
+    }
+}
+
+/// Extractor for the ID of an authenticated admin (rejects everyone else)
+pub struct AdminOnly(pub Uuid);
+
+impl FromRequest for AdminOnly {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let admin_id = match req.extensions().get::<Claims>() {
+            Some(claims) if claims.role == ROLE_ADMIN => {
+                Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)
+            }
+            Some(_) => Err(AppError::InsufficientPermissions),
+            None => Err(AppError::MissingToken),
+        };
+
+        ready(admin_id.map(AdminOnly).map_err(Into::into))
+    }
+}
+
+/// Extractor for the ID of an authenticated moderator or admin (rejects everyone else)
+pub struct StaffOnly(pub Uuid);
+
+impl FromRequest for StaffOnly {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let staff_id = match req.extensions().get::<Claims>() {
+            Some(claims) if claims.role == ROLE_ADMIN || claims.role == ROLE_MODERATOR => {
+                Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)
+            }
+            Some(_) => Err(AppError::InsufficientPermissions),
+            None => Err(AppError::MissingToken),
+        };
+
+        ready(staff_id.map(StaffOnly).map_err(Into::into))
+    }
+}
@@ -0,0 +1,71 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use sqlx::PgPool;
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use crate::cache::RedisPool;
+use crate::error::AppError;
+use crate::services::IpBanService;
+use crate::utils::client_ip;
+
+/// Middleware refusing requests from banned client IPs with 403, before any
+/// other middleware or handler runs
+pub struct IpBan;
+
+impl<S, B> Transform<S, ServiceRequest> for IpBan
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IpBanMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IpBanMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct IpBanMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IpBanMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + 'static>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let ip = client_ip::get(req.request());
+
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+        let redis_pool = req.app_data::<web::Data<RedisPool>>().cloned();
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            if let (Some(ip), Some(pool), Some(redis_pool)) = (ip, pool, redis_pool) {
+                if IpBanService::is_banned(&pool, &redis_pool, ip).await {
+                    log::warn!("Refused request from banned IP {}", ip);
+                    return Err(AppError::IpBanned.into());
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
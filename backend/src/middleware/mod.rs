@@ -1,5 +1,9 @@
 pub mod auth;
 pub mod extractor;
+pub mod rate_limit;
+pub mod ip_ban;
 
 pub use auth::AuthMiddleware;
-pub use extractor::AuthUser;
+pub use rate_limit::RateLimit;
+pub use ip_ban::IpBan;
+pub use extractor::{AuthUser, AuthClaims, AdminOnly, StaffOnly};
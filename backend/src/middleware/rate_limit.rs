@@ -0,0 +1,96 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use sqlx::PgPool;
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::AppError;
+use crate::services;
+use crate::utils::client_ip;
+
+/// Middleware limiting requests per client IP within a sliding window.
+/// Routes sharing a `class` share one budget; its size is looked up per request
+/// so admin changes apply without a restart.
+#[derive(Clone)]
+pub struct RateLimit {
+    class: &'static str,
+}
+
+impl RateLimit {
+    pub fn new(class: &'static str) -> Self {
+        RateLimit { class }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(service),
+            limit: self.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+    limit: RateLimit,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + 'static>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let ip = client_ip::get(req.request())
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let key = format!("rate_limit:{}:{}", self.limit.class, ip);
+
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+        let redis_pool = req.app_data::<web::Data<RedisPool>>().cloned();
+        let config = req.app_data::<web::Data<Config>>().cloned();
+        let class = self.limit.class;
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            if let (Some(pool), Some(redis_pool), Some(config)) = (pool, redis_pool, config) {
+                let limit = services::RateLimitService::get(&pool, &redis_pool, &config, class).await;
+
+                // Fail open: a Redis outage shouldn't take authentication down with it
+                match cache::hit_sliding_window(&redis_pool, &key, limit.max_requests, limit.window_seconds).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        log::warn!("Rate limit '{}' exceeded by {}", class, ip);
+                        return Err(AppError::RateLimitExceeded.into());
+                    }
+                    Err(e) => log::error!("Rate limit check failed: {}", e),
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
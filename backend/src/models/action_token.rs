@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Token purposes
+pub const PURPOSE_VERIFY_EMAIL: &str = "verify_email";
+pub const PURPOSE_CHANGE_EMAIL: &str = "change_email";
+pub const PURPOSE_MAGIC_LOGIN: &str = "magic_login";
+pub const PURPOSE_LINK_EMAIL: &str = "link_email";
+pub const PURPOSE_RESET_PASSWORD: &str = "reset_password";
+
+/// Single-use emailed token from database (only the hash is stored)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ActionToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: String,
+    pub token_hash: String,
+    pub payload: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for consuming an emailed token
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConsumeTokenDto {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Activity kinds
+pub const ACTIVITY_MENTION: &str = "mention";
+pub const ACTIVITY_INVITE: &str = "invite";
+pub const ACTIVITY_ROLE_CHANGE: &str = "role_change";
+pub const ACTIVITY_REPLY: &str = "reply";
+
+pub const ACTIVITY_KINDS: &[&str] = &[ACTIVITY_MENTION, ACTIVITY_INVITE, ACTIVITY_ROLE_CHANGE, ACTIVITY_REPLY];
+
+/// Activity feed item with room and actor info
+#[derive(Debug, Serialize, FromRow)]
+pub struct ActivityItem {
+    pub id: Uuid,
+    pub kind: String,
+    pub room_id: Option<Uuid>,
+    pub room_name: Option<String>,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub data: serde_json::Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Unread activity count (for the notification badge)
+#[derive(Debug, Serialize)]
+pub struct UnreadActivityResponse {
+    pub unread: i64,
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// How prominently clients show a banner
+pub const ANNOUNCEMENT_SEVERITY_INFO: &str = "info";
+pub const ANNOUNCEMENT_SEVERITY_WARNING: &str = "warning";
+pub const ANNOUNCEMENT_SEVERITY_CRITICAL: &str = "critical";
+
+pub const ANNOUNCEMENT_SEVERITIES: [&str; 3] = [
+    ANNOUNCEMENT_SEVERITY_INFO,
+    ANNOUNCEMENT_SEVERITY_WARNING,
+    ANNOUNCEMENT_SEVERITY_CRITICAL,
+];
+
+/// Instance-wide banner from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Announcement {
+    pub id: Uuid,
+    pub message: String,
+    pub severity: String,
+    pub dismissible: bool,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for creating a banner (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAnnouncementDto {
+    #[validate(length(min = 1, max = 1000, message = "Message must be between 1-1000 characters"))]
+    pub message: String,
+
+    pub severity: Option<String>, // 'info' (default), 'warning' or 'critical'
+
+    pub dismissible: Option<bool>, // Defaults to true
+
+    pub starts_at: Option<DateTime<Utc>>, // Defaults to now
+
+    pub ends_at: Option<DateTime<Utc>>, // Shown until deleted when omitted
+}
+
+/// DTO for changing a banner (admin only; omitted fields are unchanged)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAnnouncementDto {
+    #[validate(length(min = 1, max = 1000, message = "Message must be between 1-1000 characters"))]
+    pub message: Option<String>,
+
+    pub severity: Option<String>,
+
+    pub dismissible: Option<bool>,
+
+    pub starts_at: Option<DateTime<Utc>>,
+
+    pub ends_at: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub clear_ends_at: bool, // Show until deleted
+}
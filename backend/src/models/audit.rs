@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Audit actions
+pub const AUDIT_ACCOUNT_DELETION_REQUESTED: &str = "account.deletion_requested";
+pub const AUDIT_ACCOUNT_PURGED: &str = "account.purged";
+pub const AUDIT_ACCOUNT_CONTENT_PURGED: &str = "account.content_purged";
+pub const AUDIT_ACCOUNT_DEACTIVATED: &str = "account.deactivated";
+pub const AUDIT_ACCOUNT_REACTIVATED: &str = "account.reactivated";
+pub const AUDIT_PRIMARY_EMAIL_CHANGED: &str = "account.primary_email_changed";
+pub const AUDIT_LOGIN_NEW_DEVICE: &str = "login.new_device";
+pub const AUDIT_IMPERSONATION_STARTED: &str = "admin.impersonation_started";
+pub const AUDIT_IMPERSONATED_REQUEST: &str = "admin.impersonated_request";
+pub const AUDIT_ROOM_MERGED: &str = "admin.room_merged";
+pub const AUDIT_ROOM_FORCE_DELETED: &str = "admin.room_force_deleted";
+pub const AUDIT_ROOM_LOCKED: &str = "admin.room_locked";
+pub const AUDIT_ROOM_UNLOCKED: &str = "admin.room_unlocked";
+pub const AUDIT_ROOM_OWNER_REASSIGNED: &str = "admin.room_owner_reassigned";
+pub const AUDIT_ROLE_CHANGED: &str = "admin.role_changed";
+pub const AUDIT_BADGES_UPDATED: &str = "admin.badges_updated";
+pub const AUDIT_USER_SUSPENDED: &str = "admin.user_suspended";
+pub const AUDIT_USER_UNSUSPENDED: &str = "admin.user_unsuspended";
+pub const AUDIT_USER_DELETED: &str = "admin.user_deleted";
+pub const AUDIT_USERS_EXPORTED: &str = "admin.users_exported";
+pub const AUDIT_PASSWORD_RESET_FORCED: &str = "admin.password_reset_forced";
+pub const AUDIT_REPORT_ACTIONED: &str = "admin.report_actioned";
+pub const AUDIT_WORD_FILTER_CREATED: &str = "admin.word_filter_created";
+pub const AUDIT_WORD_FILTER_UPDATED: &str = "admin.word_filter_updated";
+pub const AUDIT_WORD_FILTER_DELETED: &str = "admin.word_filter_deleted";
+pub const AUDIT_SYSTEM_MESSAGE_SENT: &str = "admin.system_message_sent";
+pub const AUDIT_ANNOUNCEMENT_CREATED: &str = "admin.announcement_created";
+pub const AUDIT_ANNOUNCEMENT_UPDATED: &str = "admin.announcement_updated";
+pub const AUDIT_ANNOUNCEMENT_DELETED: &str = "admin.announcement_deleted";
+pub const AUDIT_RATE_LIMIT_UPDATED: &str = "admin.rate_limit_updated";
+pub const AUDIT_RATE_LIMIT_RESET: &str = "admin.rate_limit_reset";
+pub const AUDIT_IP_BAN_CREATED: &str = "admin.ip_ban_created";
+pub const AUDIT_IP_BAN_DELETED: &str = "admin.ip_ban_deleted";
+
+/// Audit log entry from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditLog {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_id: Option<Uuid>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Audit log entry with its actor's username, for the admin query API
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_id: Option<Uuid>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters for the admin audit log query (all optional, combined with AND)
+#[derive(Debug, Default)]
+pub struct AuditLogFilter {
+    pub actor_id: Option<Uuid>,
+    /// Exact action (e.g. "admin.user_suspended") or a category (e.g. "admin")
+    pub action: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// One page of audit log entries, newest first
+#[derive(Debug, Serialize)]
+pub struct AuditLogPage {
+    pub items: Vec<AuditLogEntry>,
+    /// Pass back as `cursor` for the next (older) page; None on the last page
+    pub next_cursor: Option<String>,
+}
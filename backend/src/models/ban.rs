@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Room ban from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomBan {
+    pub room_id: Uuid,
+    pub user_id: Uuid,
+    pub banned_by: Option<Uuid>,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>, // None = permanent
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for banning a user from a room
+#[derive(Debug, Deserialize, Validate)]
+pub struct BanUserDto {
+    pub user_id: Uuid,
+
+    #[validate(length(max = 500, message = "Reason must not exceed 500 characters"))]
+    pub reason: Option<String>,
+
+    /// Ban length in minutes (permanent when omitted)
+    #[validate(range(min = 1, max = 525600, message = "Duration must be between 1 minute and 1 year"))]
+    pub duration_minutes: Option<i64>,
+}
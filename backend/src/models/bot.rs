@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Most bots a single account may own
+pub const BOT_MAX_PER_OWNER: i64 = 10;
+
+/// Prefix of bot API keys, so they are recognizable in configs and secret scanners
+pub const BOT_API_KEY_PREFIX: &str = "ngb_";
+
+/// Bot with its user profile
+#[derive(Debug, Serialize, FromRow)]
+pub struct BotResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_url: String,
+    pub is_verified: bool,
+    pub is_staff: bool,
+    pub owner_id: Uuid,
+    pub key_created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for creating a bot
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateBotDto {
+    #[validate(length(min = 3, max = 50, message = "Username must be between 3 and 50 characters"))]
+    pub username: String,
+
+    #[validate(length(max = 100, message = "Display name must be less than 100 characters"))]
+    pub display_name: Option<String>,
+}
+
+/// A bot with its API key (only shown once)
+#[derive(Debug, Serialize)]
+pub struct CreatedBotResponse {
+    pub bot: BotResponse,
+    pub api_key: String,
+}
+
+/// A newly issued bot API key (only shown once)
+#[derive(Debug, Serialize)]
+pub struct BotApiKeyResponse {
+    pub api_key: String,
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Longest bot response relayed into a room
+pub const COMMAND_RESPONSE_MAX_LEN: usize = 4000;
+
+/// Slash command registered by a bot in a room
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BotCommand {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub bot_id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub usage_hint: Option<String>,
+    pub callback_url: String,
+    #[serde(skip_serializing)]
+    pub signing_secret: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Command entry for client autocompletion
+#[derive(Debug, Serialize, FromRow)]
+pub struct CommandSummary {
+    pub name: String,
+    pub description: String,
+    pub usage_hint: Option<String>,
+    pub bot_id: Uuid,
+    pub bot_username: String,
+}
+
+/// DTO for a bot registering (or updating) a command in a room
+#[derive(Debug, Deserialize, Validate)]
+pub struct RegisterCommandDto {
+    #[validate(length(min = 1, max = 32, message = "Name must be between 1-32 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 200, message = "Description must not exceed 200 characters"))]
+    #[serde(default)]
+    pub description: String,
+
+    #[validate(length(max = 100, message = "Usage hint must not exceed 100 characters"))]
+    pub usage_hint: Option<String>,
+
+    #[validate(url(message = "Invalid URL"), length(max = 2048, message = "URL is too long"))]
+    pub callback_url: String,
+}
+
+/// A registered command with the secret its callbacks and their answers are signed with
+#[derive(Debug, Serialize)]
+pub struct RegisteredCommandResponse {
+    pub command: BotCommand,
+    pub signing_secret: String,
+}
+
+/// DTO for running a slash command typed in a room
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExecuteCommandDto {
+    #[validate(length(min = 2, max = 4000, message = "Text must be between 2-4000 characters"))]
+    pub text: String,
+}
+
+/// What a bot's callback answers with, signed like the request
+/// (`X-Ngobrol-Timestamp` and `X-Ngobrol-Signature` headers)
+#[derive(Debug, Deserialize)]
+pub struct CommandCallbackResponse {
+    #[serde(default)]
+    pub content: String,
+    /// Only shown to the user who ran the command
+    #[serde(default)]
+    pub ephemeral: bool,
+}
+
+/// Result of running a command
+#[derive(Debug, Serialize)]
+pub struct CommandExecutionResponse {
+    pub command: String,
+    pub bot_id: Uuid,
+    pub content: String,
+    pub ephemeral: bool,
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Participant limits of a group DM (including its creator)
+pub const GROUP_DM_MIN_PARTICIPANTS: usize = 3;
+pub const GROUP_DM_MAX_PARTICIPANTS: usize = 10;
+
+/// Kinds of official messages sent from the System account
+pub const SYSTEM_MESSAGE_NOTICE: &str = "notice";
+pub const SYSTEM_MESSAGE_WARNING: &str = "warning";
+pub const SYSTEM_MESSAGE_POLICY: &str = "policy";
+pub const SYSTEM_MESSAGE_KINDS: &[&str] = &[SYSTEM_MESSAGE_NOTICE, SYSTEM_MESSAGE_WARNING, SYSTEM_MESSAGE_POLICY];
+
+/// Direct conversation as seen by one participant
+#[derive(Debug, Serialize, FromRow)]
+pub struct DmConversationResponse {
+    pub room_id: Uuid,
+    pub user_id: Uuid, // The other participant
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_url: String,
+    pub is_bot: bool,
+    pub is_verified: bool,
+    pub is_staff: bool,
+    pub status: String,
+    pub unread_count: i64,
+    pub last_message_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for creating a group DM
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateGroupDmDto {
+    /// Other participants (the creator is added automatically)
+    #[validate(length(min = 2, max = 9, message = "A group needs 2-9 other participants"))]
+    pub user_ids: Vec<Uuid>,
+
+    #[validate(length(min = 1, max = 100, message = "Group name must be between 1-100 characters"))]
+    pub name: Option<String>,
+}
+
+/// DTO for adding a participant to a group DM
+#[derive(Debug, Deserialize)]
+pub struct AddParticipantDto {
+    pub user_id: Uuid,
+}
+
+/// Group DM as seen by one participant
+#[derive(Debug, Serialize, FromRow)]
+pub struct GroupDmResponse {
+    pub room_id: Uuid,
+    pub name: String,
+    pub owner_id: Uuid,
+    pub participant_count: i64,
+    pub unread_count: i64,
+    pub last_message_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Official message from the System account (the sending admin is not exposed)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SystemMessage {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub recipient_id: Uuid,
+    pub kind: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for sending an official system DM (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct SendSystemMessageDto {
+    pub kind: Option<String>, // 'notice' (default), 'warning' or 'policy'
+
+    #[validate(length(min = 1, max = 4000, message = "Content must be between 1-4000 characters"))]
+    pub content: String,
+}
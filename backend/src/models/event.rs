@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// RSVP answers
+pub const RSVP_GOING: &str = "going";
+pub const RSVP_MAYBE: &str = "maybe";
+pub const RSVP_NOT_GOING: &str = "not_going";
+
+/// Room event from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomEvent {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub reminder_sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for scheduling an event
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEventDto {
+    #[validate(length(min = 1, max = 200, message = "Title must be between 1-200 characters"))]
+    pub title: String,
+
+    #[validate(length(max = 2000, message = "Description must not exceed 2000 characters"))]
+    pub description: Option<String>,
+
+    pub starts_at: DateTime<Utc>,
+}
+
+/// DTO for answering an event invitation
+#[derive(Debug, Deserialize)]
+pub struct RsvpDto {
+    pub status: String, // 'going', 'maybe' or 'not_going'
+}
+
+/// Event with RSVP counts and the current user's answer
+#[derive(Debug, Serialize, FromRow)]
+pub struct EventResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub going_count: i64,
+    pub maybe_count: i64,
+    pub my_rsvp: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
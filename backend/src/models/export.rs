@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+use crate::models::user::UserResponse;
+
+/// Export job states
+pub const EXPORT_PENDING: &str = "pending";
+pub const EXPORT_READY: &str = "ready";
+pub const EXPORT_FAILED: &str = "failed";
+
+/// Room membership as included in a data export
+#[derive(Debug, Serialize, FromRow)]
+pub struct ExportedMembership {
+    pub room_id: Uuid,
+    pub room_name: String,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Everything stored about a user, as delivered to them
+#[derive(Debug, Serialize)]
+pub struct DataExportArchive {
+    pub exported_at: DateTime<Utc>,
+    pub profile: UserResponse,
+    pub memberships: Vec<ExportedMembership>,
+    pub messages: Vec<serde_json::Value>,
+}
+
+/// State of a requested data export
+#[derive(Debug, Serialize)]
+pub struct DataExportStatus {
+    pub id: Uuid,
+    pub status: String,
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Invitation statuses
+pub const INVITATION_PENDING: &str = "pending";
+pub const INVITATION_ACCEPTED: &str = "accepted";
+pub const INVITATION_DECLINED: &str = "declined";
+
+/// Room invitation from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub inviter_id: Option<Uuid>,
+    pub invitee_id: Uuid,
+    pub status: String, // 'pending', 'accepted' or 'declined'
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+/// DTO for inviting a user (by ID or username)
+#[derive(Debug, Deserialize)]
+pub struct InviteUserDto {
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+}
+
+/// Invitation with room and inviter info
+#[derive(Debug, Serialize, FromRow)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub room_name: String,
+    pub inviter_id: Option<Uuid>,
+    pub inviter_username: Option<String>,
+    pub invitee_id: Uuid,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
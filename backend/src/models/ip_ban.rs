@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Banned client IP or network from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IpBan {
+    pub id: Uuid,
+    pub cidr: String,
+    pub reason: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for banning an IP or network (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateIpBanDto {
+    #[validate(length(min = 1, max = 50, message = "CIDR must be between 1-50 characters"))]
+    pub cidr: String, // "203.0.113.7", "203.0.113.0/24" or "2001:db8::/32"
+
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1-500 characters"))]
+    pub reason: String,
+
+    pub expires_at: Option<DateTime<Utc>>, // Permanent when omitted
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Published identity key and signed prekey of a user from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IdentityKey {
+    pub user_id: Uuid,
+    pub identity_key: String,
+    pub signed_prekey_id: i32,
+    pub signed_prekey: String,
+    pub signed_prekey_signature: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Signed prekey as uploaded by the client
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SignedPrekeyDto {
+    #[validate(range(min = 0))]
+    pub key_id: i32,
+
+    #[validate(length(min = 1, max = 1024, message = "Public key must be between 1-1024 characters"))]
+    pub public_key: String,
+
+    #[validate(length(min = 1, max = 1024, message = "Signature must be between 1-1024 characters"))]
+    pub signature: String,
+}
+
+/// One-time prekey (public part)
+#[derive(Debug, Serialize, Deserialize, Validate, FromRow)]
+pub struct PrekeyDto {
+    #[validate(range(min = 0))]
+    pub key_id: i32,
+
+    #[validate(length(min = 1, max = 1024, message = "Public key must be between 1-1024 characters"))]
+    pub public_key: String,
+}
+
+/// DTO for publishing the own key bundle (base64-encoded public keys)
+#[derive(Debug, Deserialize, Validate)]
+pub struct PublishKeysDto {
+    #[validate(length(min = 1, max = 1024, message = "Identity key must be between 1-1024 characters"))]
+    pub identity_key: String,
+
+    #[validate(nested)]
+    pub signed_prekey: SignedPrekeyDto,
+
+    #[validate(length(max = 100, message = "At most 100 one-time prekeys per upload"), nested)]
+    #[serde(default)]
+    pub one_time_prekeys: Vec<PrekeyDto>,
+}
+
+/// Key bundle handed to a peer to start an encrypted session
+#[derive(Debug, Serialize)]
+pub struct KeyBundleResponse {
+    pub user_id: Uuid,
+    pub identity_key: String,
+    pub signed_prekey: SignedPrekeyDto,
+    /// None once the user has run out of one-time prekeys
+    pub one_time_prekey: Option<PrekeyDto>,
+}
+
+/// Remaining one-time prekeys of the own bundle
+#[derive(Debug, Serialize)]
+pub struct PrekeyCountResponse {
+    pub one_time_prekeys: i64,
+}
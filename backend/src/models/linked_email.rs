@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Most additional addresses an account can link
+pub const LINKED_EMAILS_MAX: i64 = 5;
+
+/// Additional email address of an account
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LinkedEmail {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for linking an email address
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddEmailDto {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+/// All addresses of an account
+#[derive(Debug, Serialize)]
+pub struct AccountEmailsResponse {
+    pub primary: String,
+    pub primary_verified: bool,
+    pub linked: Vec<LinkedEmail>,
+}
@@ -1,6 +1,35 @@
 pub mod user;
 pub mod room;
 pub mod response;
+pub mod push;
+pub mod session;
+pub mod action_token;
+pub mod permission;
+pub mod audit;
+pub mod export;
+pub mod keys;
+pub mod dm;
+pub mod invitation;
+pub mod ban;
+pub mod stats;
+pub mod mute;
+pub mod moderation;
+pub mod welcome;
+pub mod rules;
+pub mod space;
+pub mod event;
+pub mod webhook;
+pub mod presence;
+pub mod activity;
+pub mod report;
+pub mod bot;
+pub mod command;
+pub mod linked_email;
+pub mod word_filter;
+pub mod announcement;
+pub mod rate_limit;
+pub mod ip_ban;
+pub mod quota;
 
 pub use user::{User, CreateUserDto, LoginDto, UpdateUserDto, UserResponse, AuthResponse};
 pub use room::{Room, RoomMember, CreateRoomDto, UpdateRoomDto, RoomResponse, RoomMemberResponse, RoomWithMembersResponse};
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Room moderation actions
+pub const MOD_KICK: &str = "kick";
+pub const MOD_BAN: &str = "ban";
+pub const MOD_UNBAN: &str = "unban";
+pub const MOD_ROLE_CHANGE: &str = "role_change";
+
+/// Realtime events of moderation actions, so clients drop stale content right away
+pub const EVENT_MESSAGE_DELETED: &str = "moderation.message_deleted";
+pub const EVENT_USER_KICKED: &str = "moderation.user_kicked";
+pub const EVENT_USER_BANNED: &str = "moderation.user_banned";
+pub const EVENT_USER_SUSPENDED: &str = "moderation.user_suspended";
+
+/// Room moderation log entry from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ModerationLogEntry {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_user_id: Option<Uuid>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
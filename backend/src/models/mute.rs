@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Room mute from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomMute {
+    pub room_id: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: Option<DateTime<Utc>>, // None = until unmuted
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for muting a room
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct MuteRoomDto {
+    /// Mute length in minutes (until unmuted when omitted)
+    #[validate(range(min = 1, max = 525600, message = "Duration must be between 1 minute and 1 year"))]
+    pub duration_minutes: Option<i64>,
+}
+
+/// Mute state of a room for the current user
+#[derive(Debug, Serialize)]
+pub struct MuteStatusResponse {
+    pub room_id: Uuid,
+    pub muted: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+}
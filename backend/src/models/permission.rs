@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Room permissions
+pub const PERM_SEND_MESSAGES: &str = "send_messages";
+pub const PERM_PIN: &str = "pin";
+pub const PERM_KICK: &str = "kick";
+pub const PERM_EDIT_ROOM: &str = "edit_room";
+pub const PERM_MANAGE_ROLES: &str = "manage_roles";
+pub const PERM_INVITE: &str = "invite";
+pub const PERM_BAN: &str = "ban";
+
+pub const ALL_PERMISSIONS: &[&str] = &[
+    PERM_SEND_MESSAGES,
+    PERM_PIN,
+    PERM_KICK,
+    PERM_EDIT_ROOM,
+    PERM_MANAGE_ROLES,
+    PERM_INVITE,
+    PERM_BAN,
+];
+
+/// Member roles whose permissions owners can customize (owners always have everything)
+pub const CUSTOMIZABLE_ROLES: &[&str] = &["admin", "moderator", "member"];
+
+/// Built-in permissions of a role, used until the room owner customizes it
+pub fn default_permissions(role: &str) -> &'static [&'static str] {
+    match role {
+        "owner" => ALL_PERMISSIONS,
+        "admin" => &[PERM_SEND_MESSAGES, PERM_PIN, PERM_KICK, PERM_EDIT_ROOM, PERM_INVITE, PERM_BAN],
+        "moderator" => &[PERM_SEND_MESSAGES, PERM_PIN, PERM_KICK, PERM_INVITE],
+        "member" => &[PERM_SEND_MESSAGES],
+        _ => &[],
+    }
+}
+
+/// Rank of a member role; moderation only works on lower-ranked members
+pub fn role_rank(role: &str) -> u8 {
+    match role {
+        "owner" => 3,
+        "admin" => 2,
+        "moderator" => 1,
+        _ => 0,
+    }
+}
+
+/// Customized role permissions of a room from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomRolePermissions {
+    pub room_id: Uuid,
+    pub role: String,
+    pub permissions: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for customizing the permissions of a role
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRolePermissionsDto {
+    #[validate(length(max = 16, message = "Too many permissions"))]
+    pub permissions: Vec<String>,
+}
+
+/// Effective permissions of a role in a room
+#[derive(Debug, Serialize)]
+pub struct RolePermissionsResponse {
+    pub role: String,
+    pub permissions: Vec<String>,
+    pub customized: bool,
+}
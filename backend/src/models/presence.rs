@@ -0,0 +1,16 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Presence states
+pub const PRESENCE_ONLINE: &str = "online";
+pub const PRESENCE_OFFLINE: &str = "offline";
+
+/// Most user IDs per presence query
+pub const PRESENCE_QUERY_MAX_IDS: usize = 100;
+
+/// Current presence of a user
+#[derive(Debug, Serialize)]
+pub struct UserPresence {
+    pub user_id: Uuid,
+    pub status: String,
+}
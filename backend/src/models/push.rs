@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Mobile push platforms
+pub const PLATFORM_FCM: &str = "fcm";
+pub const PLATFORM_APNS: &str = "apns";
+
+/// Kinds of push notification
+pub const PUSH_MENTION: &str = "mention";
+pub const PUSH_DIRECT_MESSAGE: &str = "direct_message";
+
+/// Longest message excerpt shown in a push notification
+pub const PUSH_BODY_MAX_CHARS: usize = 200;
+
+/// Registered mobile push device from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeviceToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub platform: String, // 'fcm' or 'apns'
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Browser Web Push subscription from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebPushSubscription {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for registering a device token
+#[derive(Debug, Deserialize, Validate)]
+pub struct RegisterDeviceDto {
+    pub platform: String, // 'fcm' or 'apns'
+
+    #[validate(length(min = 1, max = 4096, message = "Device token must be between 1-4096 characters"))]
+    pub token: String,
+}
+
+/// Keys of a browser PushSubscription
+#[derive(Debug, Deserialize, Validate)]
+pub struct WebPushKeys {
+    #[validate(length(min = 1, max = 255, message = "p256dh key is required"))]
+    pub p256dh: String,
+
+    #[validate(length(min = 1, max = 255, message = "auth secret is required"))]
+    pub auth: String,
+}
+
+/// DTO for registering a Web Push subscription (mirrors `PushSubscription.toJSON()`)
+#[derive(Debug, Deserialize, Validate)]
+pub struct WebPushSubscriptionDto {
+    #[validate(url(message = "Invalid push endpoint URL"))]
+    pub endpoint: String,
+
+    #[validate(nested)]
+    pub keys: WebPushKeys,
+}
+
+/// VAPID application server key for browser subscriptions
+#[derive(Debug, Serialize)]
+pub struct VapidKeyResponse {
+    pub public_key: String,
+}
+
+/// Notification pushed to the devices of a user who isn't connected
+#[derive(Debug, Clone, Serialize)]
+pub struct PushNotification {
+    pub kind: &'static str,
+    pub room_id: Uuid,
+    pub title: String,
+    pub body: String,
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Per-user quotas (limits come from the instance config)
+pub const QUOTA_ROOMS_OWNED: &str = "rooms_owned";
+pub const QUOTA_API_REQUESTS: &str = "api_requests_daily";
+
+/// Usage of one quota
+#[derive(Debug, Serialize)]
+pub struct QuotaUsage {
+    pub quota: &'static str,
+    pub used: u64,
+    pub limit: Option<u64>, // None = unlimited
+    pub resets_at: Option<DateTime<Utc>>, // None for quotas that don't reset
+}
+
+/// Usage of all quotas of the current user
+#[derive(Debug, Serialize)]
+pub struct QuotaUsageResponse {
+    pub quotas: Vec<QuotaUsage>,
+}
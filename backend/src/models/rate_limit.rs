@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Route classes with their own rate limit budget
+pub const RATE_LIMIT_CLASS_AUTH: &str = "auth"; // Sliding window per client IP
+pub const RATE_LIMIT_CLASS_ROOM_CREATE: &str = "room_create"; // Token bucket per user
+pub const RATE_LIMIT_CLASS_WEBHOOK: &str = "webhook"; // Token bucket per incoming webhook
+pub const RATE_LIMIT_CLASS_MESSAGE: &str = "message"; // Token bucket per sending user
+
+pub const RATE_LIMIT_CLASSES: [&str; 4] = [
+    RATE_LIMIT_CLASS_AUTH,
+    RATE_LIMIT_CLASS_ROOM_CREATE,
+    RATE_LIMIT_CLASS_WEBHOOK,
+    RATE_LIMIT_CLASS_MESSAGE,
+];
+
+/// Admin override of a route class's limit from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RateLimitOverride {
+    pub class: String,
+    pub max_requests: i32,
+    pub window_seconds: i32,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Limit in effect for a route class. For token buckets, max_requests is the burst
+/// size and window_seconds the refill interval.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RateLimitSetting {
+    pub class: &'static str,
+    pub max_requests: u32,
+    pub window_seconds: u64,
+    pub is_default: bool, // From the environment config, no admin override
+}
+
+/// DTO for overriding a route class's limit (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRateLimitDto {
+    #[validate(range(min = 1, max = 100000, message = "max_requests must be between 1-100000"))]
+    pub max_requests: i32,
+
+    #[validate(range(min = 1, max = 86400, message = "window_seconds must be between 1-86400"))]
+    pub window_seconds: i32,
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// What a report is about
+pub const REPORT_TARGET_MESSAGE: &str = "message";
+pub const REPORT_TARGET_USER: &str = "user";
+
+/// Report reasons
+pub const REPORT_REASONS: &[&str] = &["spam", "harassment", "hate", "nsfw", "impersonation", "other"];
+
+/// Report statuses
+pub const REPORT_OPEN: &str = "open";
+pub const REPORT_REVIEWING: &str = "reviewing";
+pub const REPORT_RESOLVED: &str = "resolved";
+
+/// Actions an admin can take when resolving a report from the review queue
+pub const REPORT_ACTION_DISMISS: &str = "dismiss";
+pub const REPORT_ACTION_DELETE_MESSAGE: &str = "delete_message";
+pub const REPORT_ACTION_WARN: &str = "warn";
+pub const REPORT_ACTION_BAN: &str = "ban";
+pub const REPORT_ACTIONS: &[&str] = &[
+    REPORT_ACTION_DISMISS,
+    REPORT_ACTION_DELETE_MESSAGE,
+    REPORT_ACTION_WARN,
+    REPORT_ACTION_BAN,
+];
+
+/// Allowed status transitions: open -> reviewing -> resolved, a review can be released
+/// back to open, and obvious cases can be resolved straight away. Resolved is final.
+pub fn can_transition(from: &str, to: &str) -> bool {
+    matches!(
+        (from, to),
+        (REPORT_OPEN, REPORT_REVIEWING)
+            | (REPORT_OPEN, REPORT_RESOLVED)
+            | (REPORT_REVIEWING, REPORT_OPEN)
+            | (REPORT_REVIEWING, REPORT_RESOLVED)
+    )
+}
+
+/// Report from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter_id: Option<Uuid>,
+    pub target_type: String,
+    pub target_user_id: Uuid,
+    pub message_id: Option<Uuid>,
+    pub room_id: Option<Uuid>,
+    pub reason: String,
+    pub details: Option<String>,
+    pub status: String,
+    pub reviewer_id: Option<Uuid>,
+    pub resolution_note: Option<String>,
+    pub action: Option<String>, // One of REPORT_ACTIONS, once resolved from the admin queue
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Report in the moderation queue, with reporter and target usernames
+#[derive(Debug, Serialize, FromRow)]
+pub struct ReportQueueItem {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub report: Report,
+    pub reporter_username: Option<String>,
+    pub target_username: String,
+}
+
+/// Report in the admin review queue, with the offending content inline
+#[derive(Debug, Serialize, FromRow)]
+pub struct ReportReviewItem {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub report: Report,
+    pub reporter_username: Option<String>,
+    pub target_username: String,
+    pub target_display_name: Option<String>,
+    pub target_status_text: Option<String>,
+    pub message: Option<serde_json::Value>, // Raw message row; None for user reports or deleted messages
+}
+
+/// DTO for resolving a report with an action (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReportActionDto {
+    pub action: String, // One of REPORT_ACTIONS
+
+    /// Shown to the user for warnings and bans
+    #[validate(length(max = 1000, message = "Note must not exceed 1000 characters"))]
+    pub note: Option<String>,
+}
+
+/// DTO for reporting a message or a user
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReportDto {
+    pub target_type: String, // 'message' or 'user'
+    pub message_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub reason: String,
+
+    #[validate(length(max = 1000, message = "Details must not exceed 1000 characters"))]
+    pub details: Option<String>,
+}
+
+/// DTO for moving a report through the moderation queue
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateReportStatusDto {
+    pub status: String, // 'open', 'reviewing' or 'resolved'
+
+    #[validate(length(max = 1000, message = "Note must not exceed 1000 characters"))]
+    pub note: Option<String>,
+}
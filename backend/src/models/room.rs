@@ -4,19 +4,64 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
 
+/// Room types
+pub const ROOM_TYPE_PUBLIC: &str = "public";
+pub const ROOM_TYPE_PRIVATE: &str = "private";
+pub const ROOM_TYPE_DM: &str = "dm";
+pub const ROOM_TYPE_GROUP_DM: &str = "group_dm";
+
+/// Realtime event on a user's own channel when they stop being a member of a room;
+/// their open connections stop delivering that room's events
+pub const EVENT_MEMBERSHIP_ENDED: &str = "room.membership_ended";
+
+/// Post policies
+pub const POST_POLICY_EVERYONE: &str = "everyone";
+pub const POST_POLICY_ADMINS_ONLY: &str = "admins_only";
+
+/// Notification levels of a room member
+pub const NOTIFY_ALL: &str = "all";
+pub const NOTIFY_MENTIONS: &str = "mentions";
+pub const NOTIFY_NONE: &str = "none";
+
+/// Outcomes of a bulk member import entry
+pub const BULK_ADDED: &str = "added";
+pub const BULK_ALREADY_MEMBER: &str = "already_member";
+pub const BULK_BANNED: &str = "banned";
+pub const BULK_ROOM_FULL: &str = "room_full";
+pub const BULK_NOT_FOUND: &str = "not_found";
+pub const BULK_DUPLICATE: &str = "duplicate";
+
+/// Most entries accepted by one bulk member import
+pub const BULK_MAX_ENTRIES: u64 = 500;
+
 /// Room entity from database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Room {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
-    pub room_type: String, // 'public' or 'private'
+    pub room_type: String, // 'public', 'private', 'dm' or 'group_dm'
     pub owner_id: Uuid,
     pub max_members: Option<i32>,
+    pub avatar_url: Option<String>,
+    pub topic: Option<String>,
+    pub post_policy: String, // 'everyone' or 'admins_only'
+    #[serde(skip_serializing)]
+    pub join_password_hash: Option<String>,
+    pub space_id: Option<Uuid>, // None = standalone room
+    pub locked_at: Option<DateTime<Utc>>, // Frozen by an admin
+    pub lock_reason: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Room {
+    /// Direct message rooms (1:1 or group) have participants rather than open membership
+    pub fn is_direct_message(&self) -> bool {
+        self.room_type == ROOM_TYPE_DM || self.room_type == ROOM_TYPE_GROUP_DM
+    }
+}
+
 /// Room member entity from database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct RoomMember {
@@ -40,6 +85,15 @@ pub struct CreateRoomDto {
     
     #[validate(range(min = 2, max = 1000, message = "Max members must be between 2-1000"))]
     pub max_members: Option<i32>,
+
+    pub post_policy: Option<String>, // 'everyone' (default) or 'admins_only'
+
+    /// Required to join; lets private rooms be joined without an invite
+    #[validate(length(min = 4, max = 128, message = "Join password must be between 4-128 characters"))]
+    pub join_password: Option<String>,
+
+    /// Create the room as a channel of this space (space admins only)
+    pub space_id: Option<Uuid>,
 }
 
 /// DTO for updating a room
@@ -55,6 +109,125 @@ pub struct UpdateRoomDto {
     
     #[validate(range(min = 2, max = 1000, message = "Max members must be between 2-1000"))]
     pub max_members: Option<i32>,
+
+    #[validate(url(message = "Invalid avatar URL"), length(max = 500, message = "Avatar URL must not exceed 500 characters"))]
+    pub avatar_url: Option<String>,
+
+    pub post_policy: Option<String>, // 'everyone' or 'admins_only'
+
+    /// New join password (empty string removes it)
+    #[validate(length(max = 128, message = "Join password must not exceed 128 characters"))]
+    pub join_password: Option<String>,
+
+    /// Shown pinned at the top of the room (empty string clears it)
+    #[validate(length(max = 500, message = "Topic must not exceed 500 characters"))]
+    pub topic: Option<String>,
+}
+
+/// DTO for changing a member's role
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateMemberRoleDto {
+    #[validate(length(min = 1, max = 20, message = "Role is required"))]
+    pub role: String, // 'admin', 'moderator' or 'member'
+}
+
+/// DTO for joining a room
+#[derive(Debug, Default, Deserialize)]
+pub struct JoinRoomDto {
+    pub password: Option<String>,
+}
+
+/// DTO for changing own notification level in a room
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationLevelDto {
+    pub level: String, // 'all', 'mentions' or 'none'
+}
+
+/// DTO for setting own nickname in a room (empty or null clears it)
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetNicknameDto {
+    #[validate(length(max = 50, message = "Nickname must not exceed 50 characters"))]
+    pub nickname: Option<String>,
+}
+
+/// DTO for setting a custom order of own rooms (unlisted rooms go after them)
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReorderRoomsDto {
+    #[validate(length(max = 1000, message = "Too many rooms"))]
+    pub room_ids: Vec<Uuid>,
+}
+
+/// DTO for importing members in bulk (owner only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkAddMembersDto {
+    /// User IDs or email addresses
+    #[validate(length(min = 1, max = "BULK_MAX_ENTRIES", message = "Between 1 and 500 entries are allowed"))]
+    pub entries: Vec<String>,
+}
+
+/// Outcome of one bulk import entry
+#[derive(Debug, Serialize)]
+pub struct BulkMemberResult {
+    pub entry: String,
+    pub user_id: Option<Uuid>,
+    pub status: String,
+}
+
+/// Outcome of a bulk member import
+#[derive(Debug, Serialize)]
+pub struct BulkAddMembersResponse {
+    pub added: usize,
+    pub results: Vec<BulkMemberResult>,
+}
+
+/// DTO for merging a room into another one (admin only)
+#[derive(Debug, Deserialize)]
+pub struct MergeRoomDto {
+    pub into_room_id: Uuid,
+}
+
+/// Reason an admin gives for acting on a room (required, kept in the audit log)
+#[derive(Debug, Deserialize, Validate)]
+pub struct AdminRoomActionDto {
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1-500 characters"))]
+    pub reason: String,
+}
+
+/// DTO for handing a room to a new owner (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReassignOwnerDto {
+    pub user_id: Uuid,
+
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1-500 characters"))]
+    pub reason: String,
+}
+
+/// Outcome of a room merge
+#[derive(Debug, Serialize)]
+pub struct RoomMergeResponse {
+    pub source_room_id: Uuid,
+    pub target_room_id: Uuid,
+    pub members_moved: u64,
+    pub messages_moved: u64,
+}
+
+/// Room the current user is a member of, with their own settings
+#[derive(Debug, Serialize, FromRow)]
+pub struct MyRoomResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub room_type: String,
+    pub avatar_url: Option<String>,
+    pub topic: Option<String>,
+    pub space_id: Option<Uuid>,
+    pub member_count: i64,
+    pub role: String,
+    pub notification_level: String,
+    pub muted: bool,
+    pub is_favorite: bool,
+    pub sort_order: Option<i32>,
+    pub joined_at: DateTime<Utc>,
 }
 
 /// Room response (public data)
@@ -66,6 +239,13 @@ pub struct RoomResponse {
     pub room_type: String,
     pub owner_id: Uuid,
     pub max_members: Option<i32>,
+    pub avatar_url: Option<String>,
+    pub topic: Option<String>,
+    pub post_policy: String,
+    pub has_password: bool,
+    pub space_id: Option<Uuid>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub lock_reason: Option<String>,
     pub member_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -78,13 +258,35 @@ pub struct RoomMemberResponse {
     pub room_id: Uuid,
     pub user_id: Uuid,
     pub username: String,
-    pub display_name: String,
-    pub avatar_url: Option<String>,
+    pub display_name: String, // Room nickname, else global display name, else username
+    pub nickname: Option<String>,
+    pub avatar_url: String,
     pub role: String,
+    pub is_bot: bool,
+    pub is_verified: bool,
+    pub is_staff: bool,
     pub status: String,
+    pub status_text: Option<String>,
+    pub status_emoji: Option<String>,
     pub joined_at: DateTime<Utc>,
 }
 
+/// Member shortlisted for @-mention autocompletion
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MentionSuggestion {
+    pub user_id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_url: String,
+    pub role: String,
+    pub is_bot: bool,
+    pub is_verified: bool,
+    pub is_staff: bool,
+}
+
+/// Most suggestions returned for one query
+pub const MENTION_SUGGEST_MAX_LIMIT: usize = 25;
+
 /// Room with members response
 #[derive(Debug, Serialize)]
 pub struct RoomWithMembersResponse {
@@ -92,6 +294,8 @@ pub struct RoomWithMembersResponse {
     pub members: Vec<RoomMemberResponse>,
     pub is_member: bool,
     pub user_role: Option<String>,
+    pub user_permissions: Vec<String>,
+    pub can_post: bool,
 }
 
 impl From<Room> for RoomResponse {
@@ -103,6 +307,13 @@ impl From<Room> for RoomResponse {
             room_type: room.room_type,
             owner_id: room.owner_id,
             max_members: room.max_members,
+            avatar_url: room.avatar_url,
+            topic: room.topic,
+            post_policy: room.post_policy,
+            has_password: room.join_password_hash.is_some(),
+            space_id: room.space_id,
+            locked_at: room.locked_at,
+            lock_reason: room.lock_reason,
             member_count: 0, // Will be populated separately
             created_at: room.created_at,
             updated_at: room.updated_at,
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Room rules from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomRules {
+    pub room_id: Uuid,
+    pub content: String,
+    pub version: i32,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for setting a room's rules
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetRulesDto {
+    #[validate(length(min = 1, max = 10000, message = "Rules must be 1-10000 characters"))]
+    pub content: String,
+}
+
+/// DTO for accepting a room's rules (the version the member has read)
+#[derive(Debug, Deserialize)]
+pub struct AcceptRulesDto {
+    pub version: i32,
+}
+
+/// Room rules with the current user's acceptance state
+#[derive(Debug, Serialize)]
+pub struct RulesResponse {
+    pub room_id: Uuid,
+    pub content: String,
+    pub version: i32,
+    pub updated_at: DateTime<Utc>,
+    pub accepted_version: Option<i32>,
+    pub accepted: bool,
+}
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+use crate::models::room::RoomResponse;
+
+/// Space entity from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Space {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for creating a space
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateSpaceDto {
+    #[validate(length(min = 3, max = 100, message = "Space name must be between 3-100 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 500, message = "Description must not exceed 500 characters"))]
+    pub description: Option<String>,
+}
+
+/// Space response with the current user's role
+#[derive(Debug, Serialize, FromRow)]
+pub struct SpaceResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_id: Uuid,
+    pub member_count: i64,
+    pub role: Option<String>, // None = not a member
+    pub created_at: DateTime<Utc>,
+}
+
+/// Space with the channels visible to the current user
+#[derive(Debug, Serialize)]
+pub struct SpaceWithChannelsResponse {
+    pub space: SpaceResponse,
+    pub channels: Vec<RoomResponse>,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One day of room activity (UTC days)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomDailyStats {
+    pub day: NaiveDate,
+    pub message_count: i64,
+    pub active_members: i64,            // Distinct members who posted that day
+    pub peak_concurrency: Option<i32>,  // Not recorded without presence tracking
+}
+
+/// Room activity statistics
+#[derive(Debug, Serialize)]
+pub struct RoomStatsResponse {
+    pub room_id: Uuid,
+    pub member_count: i64,
+    pub days: Vec<RoomDailyStats>,
+}
+
+/// One day of instance-wide metrics (UTC days), as last snapshotted by the metrics job
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InstanceDailyMetrics {
+    pub day: NaiveDate,
+    pub total_users: i64,
+    pub daily_active_users: i64,
+    pub monthly_active_users: i64,
+    pub total_rooms: i64,
+    pub message_count: i64,
+    pub storage_bytes: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Messages sent on one day
+#[derive(Debug, Serialize)]
+pub struct DailyMessageCount {
+    pub day: NaiveDate,
+    pub message_count: i64,
+}
+
+/// Instance overview for the admin dashboard
+#[derive(Debug, Serialize)]
+pub struct MetricsSummaryResponse {
+    pub total_users: i64,
+    pub daily_active_users: i64,
+    pub monthly_active_users: i64,
+    pub total_rooms: i64,
+    pub storage_bytes: i64,
+    pub messages_per_day: Vec<DailyMessageCount>, // Oldest first
+    pub updated_at: DateTime<Utc>, // When the metrics job last ran
+}
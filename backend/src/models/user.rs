@@ -1,8 +1,31 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
+use crate::utils::{avatar, local_time};
+
+/// Global roles (instance-wide, independent of room roles)
+pub const ROLE_USER: &str = "user";
+pub const ROLE_MODERATOR: &str = "moderator";
+pub const ROLE_ADMIN: &str = "admin";
+
+/// Reserved account that sends official system DMs (created by migration)
+pub const SYSTEM_USER_ID: Uuid = Uuid::from_u128(1);
+
+/// Account lifecycle states, derived from the user's timestamps
+pub const ACCOUNT_ACTIVE: &str = "active";
+pub const ACCOUNT_SUSPENDED: &str = "suspended"; // Locked out by an admin
+pub const ACCOUNT_DEACTIVATED: &str = "deactivated"; // Taking a break; reversible by logging in
+pub const ACCOUNT_PENDING_DELETION: &str = "pending_deletion"; // Purged after the grace period
+pub const ACCOUNT_DELETED: &str = "deleted"; // Anonymized tombstone
+pub const ACCOUNT_STATUSES: &[&str] = &[
+    ACCOUNT_ACTIVE,
+    ACCOUNT_SUSPENDED,
+    ACCOUNT_DEACTIVATED,
+    ACCOUNT_PENDING_DELETION,
+    ACCOUNT_DELETED,
+];
 
 /// User model from database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -15,10 +38,121 @@ pub struct User {
     pub avatar_url: Option<String>,
     pub status: String,
     pub is_active: bool,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub role: String, // 'user', 'moderator' or 'admin'
+    pub is_bot: bool,
+    pub is_verified: bool, // Badge granted by an admin
+    pub is_staff: bool,    // Badge granted by an admin
+    pub status_text: Option<String>,
+    pub status_emoji: Option<String>,
+    pub status_expires_at: Option<DateTime<Utc>>, // None = until cleared
+    pub timezone: String, // IANA name, e.g. 'Asia/Jakarta'
+    pub locale: String,   // BCP 47 tag, e.g. 'id-ID'
+    pub quiet_hours_start: Option<NaiveTime>, // Local time; None = no quiet hours
+    pub quiet_hours_end: Option<NaiveTime>,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub deletion_requested_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub suspension_reason: Option<String>,
+    pub suspended_until: Option<DateTime<Utc>>, // None = until lifted (a ban)
+    pub password_reset_required: bool, // Set by an admin; login is refused until reset
+    pub tos_version: Option<String>, // Last accepted terms of service
+    pub tos_accepted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl User {
+    /// Whether the user's do-not-disturb window is in effect; push dispatch should
+    /// hold back all but critical notifications while it is
+    pub fn in_quiet_hours(&self, now: DateTime<Utc>) -> bool {
+        match (self.quiet_hours_start, self.quiet_hours_end) {
+            (Some(start), Some(end)) => local_time::within_daily_window(now, &self.timezone, start, end),
+            _ => false,
+        }
+    }
+
+    /// Where the account is in its lifecycle (one of the ACCOUNT_* states)
+    pub fn account_status(&self) -> &'static str {
+        if self.deleted_at.is_some() {
+            ACCOUNT_DELETED
+        } else if self.deletion_requested_at.is_some() {
+            ACCOUNT_PENDING_DELETION
+        } else if self.is_suspended() {
+            ACCOUNT_SUSPENDED
+        } else if self.deactivated_at.is_some() {
+            ACCOUNT_DEACTIVATED
+        } else {
+            ACCOUNT_ACTIVE
+        }
+    }
+
+    /// Whether the user still has to accept the instance's current terms of service
+    pub fn needs_tos_acceptance(&self, current_version: Option<&str>) -> bool {
+        current_version.is_some_and(|version| !self.is_bot && self.tos_version.as_deref() != Some(version))
+    }
+
+    /// Whether an admin suspension is in effect (it lapses at `suspended_until`)
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some() && self.suspended_until.is_none_or(|until| until > Utc::now())
+    }
+
+    /// Whether the custom status is set and not yet expired
+    pub fn has_custom_status(&self) -> bool {
+        (self.status_text.is_some() || self.status_emoji.is_some())
+            && self.status_expires_at.is_none_or(|expires_at| expires_at > Utc::now())
+    }
+}
+
+/// Regular active user with every optional field unset, for tests
+#[cfg(test)]
+pub fn test_user() -> User {
+    let now = Utc::now();
+    User {
+        id: Uuid::new_v4(),
+        username: "testuser".to_string(),
+        email: "test@example.com".to_string(),
+        password_hash: String::new(),
+        display_name: None,
+        avatar_url: None,
+        status: "offline".to_string(),
+        is_active: true,
+        email_verified_at: None,
+        role: "user".to_string(),
+        is_bot: false,
+        is_verified: false,
+        is_staff: false,
+        status_text: None,
+        status_emoji: None,
+        status_expires_at: None,
+        timezone: "UTC".to_string(),
+        locale: "en".to_string(),
+        quiet_hours_start: None,
+        quiet_hours_end: None,
+        deactivated_at: None,
+        deletion_requested_at: None,
+        deleted_at: None,
+        last_active_at: None,
+        suspended_at: None,
+        suspension_reason: None,
+        suspended_until: None,
+        password_reset_required: false,
+        tos_version: None,
+        tos_accepted_at: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// DTO for setting quiet hours ("HH:MM" in the user's timezone; may span midnight)
+#[derive(Debug, Deserialize)]
+pub struct SetQuietHoursDto {
+    pub start: String,
+    pub end: String,
+}
+
 /// DTO for user registration
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateUserDto {
@@ -28,11 +162,14 @@ pub struct CreateUserDto {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
     
-    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    /// Checked against the configured password policy
     pub password: String,
     
     #[validate(length(max = 100, message = "Display name must be less than 100 characters"))]
     pub display_name: Option<String>,
+
+    /// CAPTCHA response token (required when CAPTCHA is enabled)
+    pub captcha_token: Option<String>,
 }
 
 /// DTO for user login
@@ -43,6 +180,9 @@ pub struct LoginDto {
     
     #[validate(length(min = 1, message = "Password is required"))]
     pub password: String,
+
+    #[validate(length(max = 100, message = "Device name must be less than 100 characters"))]
+    pub device_name: Option<String>,
 }
 
 /// DTO for updating user profile
@@ -54,8 +194,72 @@ pub struct UpdateUserDto {
     #[validate(length(max = 100, message = "Display name must be less than 100 characters"))]
     pub display_name: Option<String>,
     
+    #[validate(url(message = "Invalid avatar URL"), length(max = 2048, message = "Avatar URL is too long"))]
     pub avatar_url: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<String>, // 'online', 'offline', 'away' or 'busy'
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// DTO for setting a custom status
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetCustomStatusDto {
+    #[validate(length(max = 100, message = "Status text must not exceed 100 characters"))]
+    pub status_text: Option<String>,
+
+    #[validate(length(min = 1, max = 32, message = "Status emoji must be between 1-32 characters"))]
+    pub status_emoji: Option<String>,
+
+    /// Clear the status after this many minutes (kept until cleared when omitted)
+    #[validate(range(min = 1, max = 525600, message = "Expiry must be between 1 minute and 1 year"))]
+    pub expires_in_minutes: Option<i64>,
+}
+
+/// DTO for requesting a magic login link
+#[derive(Debug, Deserialize, Validate)]
+pub struct MagicLinkDto {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+/// DTO for requesting an email change
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeEmailDto {
+    #[validate(email(message = "Invalid email format"))]
+    pub new_email: String,
+
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+/// DTO for sensitive account actions that require the current password
+#[derive(Debug, Deserialize, Validate)]
+pub struct PasswordConfirmationDto {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+/// DTO for accepting the terms of service (the version the user was shown)
+#[derive(Debug, Deserialize, Validate)]
+pub struct AcceptTosDto {
+    #[validate(length(min = 1, max = 50, message = "Version is required"))]
+    pub version: String,
+}
+
+/// Current terms of service and the user's acceptance of them
+#[derive(Debug, Serialize)]
+pub struct TosStatusResponse {
+    pub current_version: Option<String>, // None = not enforced on this instance
+    pub accepted_version: Option<String>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub acceptance_required: bool,
+}
+
+/// DTO for changing a user's global role (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRoleDto {
+    #[validate(length(min = 1, message = "Role is required"))]
+    pub role: String, // 'user', 'moderator' or 'admin'
 }
 
 /// User response (without password)
@@ -65,32 +269,286 @@ pub struct UserResponse {
     pub username: String,
     pub email: String,
     pub display_name: Option<String>,
-    pub avatar_url: Option<String>,
+    pub avatar_url: String, // Generated initials avatar when none is set
     pub status: String,
     pub is_active: bool,
+    pub email_verified: bool,
+    pub role: String,
+    pub is_bot: bool,
+    pub is_verified: bool,
+    pub is_staff: bool,
+    pub status_text: Option<String>,
+    pub status_emoji: Option<String>,
+    pub status_expires_at: Option<DateTime<Utc>>,
+    pub timezone: String,
+    pub locale: String,
+    pub quiet_hours_start: Option<NaiveTime>,
+    pub quiet_hours_end: Option<NaiveTime>,
+    pub quiet_hours_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl From<User> for UserResponse {
     fn from(user: User) -> Self {
+        let quiet_hours_active = user.in_quiet_hours(Utc::now());
+
+        // Expired statuses read as unset
+        let (status_text, status_emoji, status_expires_at) = if user.has_custom_status() {
+            (user.status_text, user.status_emoji, user.status_expires_at)
+        } else {
+            (None, None, None)
+        };
+
         UserResponse {
             id: user.id,
             username: user.username,
             email: user.email,
             display_name: user.display_name,
-            avatar_url: user.avatar_url,
+            avatar_url: user.avatar_url.unwrap_or_else(|| avatar::fallback_url(user.id)),
             status: user.status,
             is_active: user.is_active,
+            email_verified: user.email_verified_at.is_some(),
+            role: user.role,
+            is_bot: user.is_bot,
+            is_verified: user.is_verified,
+            is_staff: user.is_staff,
+            status_text,
+            status_emoji,
+            status_expires_at,
+            timezone: user.timezone,
+            locale: user.locale,
+            quiet_hours_start: user.quiet_hours_start,
+            quiet_hours_end: user.quiet_hours_end,
+            quiet_hours_active,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
 }
 
+/// DTO for suspending a user instance-wide (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct SuspendUserDto {
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1-500 characters"))]
+    pub reason: String,
+
+    /// When the suspension lapses; omit to suspend until lifted (a ban)
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// DTO for deleting a user's account (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct DeleteUserDto {
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1-500 characters"))]
+    pub reason: String,
+}
+
+/// DTO for setting a new password with an emailed reset token
+#[derive(Debug, Deserialize, Validate)]
+pub struct ResetPasswordDto {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+
+    /// Checked against the configured password policy
+    pub new_password: String,
+}
+
+/// Filters of the admin user list (all optional)
+#[derive(Debug, Default)]
+pub struct AdminUserFilter {
+    pub q: Option<String>, // Username or email prefix
+    pub role: Option<String>,
+    pub status: Option<String>, // One of the ACCOUNT_* states
+    pub is_bot: Option<bool>,
+}
+
+/// User as seen in the admin console
+#[derive(Debug, Serialize)]
+pub struct AdminUserResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub account_status: &'static str,
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub suspension_reason: Option<String>,
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub password_reset_required: bool,
+}
+
+impl From<User> for AdminUserResponse {
+    fn from(user: User) -> Self {
+        AdminUserResponse {
+            account_status: user.account_status(),
+            last_active_at: user.last_active_at,
+            suspended_at: user.suspended_at,
+            suspension_reason: user.suspension_reason.clone(),
+            suspended_until: user.suspended_until,
+            password_reset_required: user.password_reset_required,
+            user: user.into(),
+        }
+    }
+}
+
+/// Formats of the admin user export
+pub const USER_EXPORT_CSV: &str = "csv";
+pub const USER_EXPORT_JSONL: &str = "jsonl";
+
+/// User as included in the admin export, trimmed of personal data
+/// (no email, display name, status or suspension reason)
+#[derive(Debug, Serialize)]
+pub struct ExportedUser {
+    pub id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub account_status: &'static str,
+    pub is_bot: bool,
+    pub is_verified: bool,
+    pub is_staff: bool,
+    pub email_verified: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl ExportedUser {
+    /// Column names of the CSV export, in `csv_fields` order
+    pub const CSV_HEADER: &'static [&'static str] = &[
+        "id",
+        "username",
+        "role",
+        "account_status",
+        "is_bot",
+        "is_verified",
+        "is_staff",
+        "email_verified",
+        "created_at",
+        "last_active_at",
+        "suspended_at",
+        "deleted_at",
+    ];
+
+    /// Field values of a CSV record (timestamps as RFC 3339, empty when unset)
+    pub fn csv_fields(&self) -> Vec<String> {
+        let timestamp = |at: Option<DateTime<Utc>>| at.map(|at| at.to_rfc3339()).unwrap_or_default();
+
+        vec![
+            self.id.to_string(),
+            self.username.clone(),
+            self.role.clone(),
+            self.account_status.to_string(),
+            self.is_bot.to_string(),
+            self.is_verified.to_string(),
+            self.is_staff.to_string(),
+            self.email_verified.to_string(),
+            self.created_at.to_rfc3339(),
+            timestamp(self.last_active_at),
+            timestamp(self.suspended_at),
+            timestamp(self.deleted_at),
+        ]
+    }
+}
+
+impl From<User> for ExportedUser {
+    fn from(user: User) -> Self {
+        ExportedUser {
+            account_status: user.account_status(),
+            id: user.id,
+            username: user.username,
+            role: user.role,
+            is_bot: user.is_bot,
+            is_verified: user.is_verified,
+            is_staff: user.is_staff,
+            email_verified: user.email_verified_at.is_some(),
+            created_at: user.created_at,
+            last_active_at: user.last_active_at,
+            suspended_at: user.suspended_at,
+            deleted_at: user.deleted_at,
+        }
+    }
+}
+
+/// DTO for granting or revoking badges (admin only; omitted badges are unchanged)
+#[derive(Debug, Deserialize)]
+pub struct UpdateBadgesDto {
+    pub verified: Option<bool>,
+    pub staff: Option<bool>,
+}
+
+/// Number of users active within a recent window (admin metrics)
+#[derive(Debug, Serialize)]
+pub struct ActiveUsersResponse {
+    pub days: i64,
+    pub active_users: i64,
+}
+
+/// DTO for minting a restricted token
+#[derive(Debug, Deserialize)]
+pub struct ScopedTokenDto {
+    pub scope: String, // 'ws'
+}
+
+/// Restricted short-lived token
+#[derive(Debug, Serialize)]
+pub struct ScopedTokenResponse {
+    pub token: String,
+    pub scope: String,
+    pub expires_in: i64,
+}
+
+/// Time-boxed token for an admin acting as a user
+#[derive(Debug, Serialize)]
+pub struct ImpersonationResponse {
+    pub token: String,
+    pub user: UserResponse,
+    pub impersonator_id: Uuid,
+    pub expires_in: i64,
+}
+
+/// One-time WebSocket connection ticket
+#[derive(Debug, Serialize)]
+pub struct WsTicketResponse {
+    pub ticket: String,
+    pub expires_in: u64,
+}
+
 /// Auth response with token
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub user: UserResponse,
     pub token: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tos_not_required_without_configured_version() {
+        assert!(!test_user().needs_tos_acceptance(None));
+
+        let user = User { tos_version: Some("2026-01".to_string()), ..test_user() };
+        assert!(!user.needs_tos_acceptance(None));
+    }
+
+    #[test]
+    fn test_tos_not_required_for_current_version() {
+        let user = User { tos_version: Some("2026-01".to_string()), ..test_user() };
+        assert!(!user.needs_tos_acceptance(Some("2026-01")));
+    }
+
+    #[test]
+    fn test_tos_required_for_outdated_or_missing_version() {
+        let user = User { tos_version: Some("2025-06".to_string()), ..test_user() };
+        assert!(user.needs_tos_acceptance(Some("2026-01")));
+
+        assert!(test_user().needs_tos_acceptance(Some("2026-01")));
+    }
+
+    #[test]
+    fn test_tos_never_required_for_bots() {
+        let bot = User { is_bot: true, ..test_user() };
+        assert!(!bot.needs_tos_acceptance(Some("2026-01")));
+    }
+}
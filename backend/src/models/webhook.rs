@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Incoming room webhook from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomWebhook {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// DTO for creating an incoming webhook
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWebhookDto {
+    #[validate(length(min = 1, max = 80, message = "Name must be between 1-80 characters"))]
+    pub name: String,
+}
+
+/// A new webhook with its secret URL (only shown once)
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhookResponse {
+    pub webhook: RoomWebhook,
+    pub token: String,
+    pub url_path: String,
+}
+
+/// Message posted to an incoming webhook
+#[derive(Debug, Deserialize, Validate)]
+pub struct WebhookMessageDto {
+    #[validate(length(min = 1, max = 4000, message = "Content must be between 1-4000 characters"))]
+    pub content: String,
+
+    /// Display name for this message (defaults to the webhook name)
+    #[validate(length(min = 1, max = 80, message = "Username must be between 1-80 characters"))]
+    pub username: Option<String>,
+}
+
+/// Events outgoing webhooks can subscribe to
+pub const OUTGOING_EVENT_MESSAGE_CREATED: &str = "message.created";
+pub const OUTGOING_EVENT_MEMBER_JOINED: &str = "member.joined";
+pub const OUTGOING_EVENT_ROOM_UPDATED: &str = "room.updated";
+
+pub const OUTGOING_EVENTS: &[&str] = &[
+    OUTGOING_EVENT_MESSAGE_CREATED,
+    OUTGOING_EVENT_MEMBER_JOINED,
+    OUTGOING_EVENT_ROOM_UPDATED,
+];
+
+/// Delivery states
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_SUCCEEDED: &str = "succeeded";
+pub const DELIVERY_FAILED: &str = "failed";
+
+/// Outgoing webhook subscription from database (the signing secret is only read for deliveries)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OutgoingWebhook {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for subscribing a URL to room events
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOutgoingWebhookDto {
+    #[validate(url(message = "Invalid URL"), length(max = 2048, message = "URL is too long"))]
+    pub url: String,
+
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    pub events: Vec<String>,
+}
+
+/// A new outgoing webhook with its signing secret (only shown once)
+#[derive(Debug, Serialize)]
+pub struct CreatedOutgoingWebhookResponse {
+    pub webhook: OutgoingWebhook,
+    pub secret: String,
+}
+
+/// Delivery attempt log of an outgoing webhook
+#[derive(Debug, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Delivery due for an attempt, with what is needed to send it
+#[derive(Debug, FromRow)]
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
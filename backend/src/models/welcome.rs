@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Welcome message delivery: posted in the room, or sent to the new member only
+pub const WELCOME_DELIVERY_ROOM: &str = "room";
+pub const WELCOME_DELIVERY_DM: &str = "dm";
+
+/// Synthetic author of system-generated messages
+pub const SYSTEM_AUTHOR: &str = "system";
+
+/// Room welcome message from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomWelcome {
+    pub room_id: Uuid,
+    pub template: String,
+    pub delivery: String,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RoomWelcome {
+    /// Fill in the template placeholders ({username}, {room})
+    pub fn render(&self, username: &str, room_name: &str) -> String {
+        self.template
+            .replace("{username}", username)
+            .replace("{room}", room_name)
+    }
+}
+
+/// DTO for setting a room's welcome message
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetWelcomeDto {
+    #[validate(length(min = 1, max = 2000, message = "Template must be 1-2000 characters"))]
+    pub template: String,
+    pub delivery: Option<String>, // 'room' (default) or 'dm'
+}
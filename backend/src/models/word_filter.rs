@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// What happens to a message matching a filter
+pub const FILTER_ACTION_MASK: &str = "mask"; // Matches are replaced with ***
+pub const FILTER_ACTION_BLOCK: &str = "block"; // The whole message is rejected
+
+/// Banned word or pattern from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WordFilter {
+    pub id: Uuid,
+    pub pattern: String,
+    pub is_regex: bool,
+    pub action: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for adding a banned word or pattern (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWordFilterDto {
+    #[validate(length(min = 1, max = 200, message = "Pattern must be between 1-200 characters"))]
+    pub pattern: String,
+
+    #[serde(default)]
+    pub is_regex: bool,
+
+    pub action: Option<String>, // 'mask' (default) or 'block'
+}
+
+/// DTO for changing a banned word or pattern (admin only; omitted fields are unchanged)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWordFilterDto {
+    #[validate(length(min = 1, max = 200, message = "Pattern must be between 1-200 characters"))]
+    pub pattern: Option<String>,
+
+    pub is_regex: Option<bool>,
+
+    pub action: Option<String>,
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::action_token::ActionToken;
+
+pub struct ActionTokenRepository;
+
+impl ActionTokenRepository {
+    /// Store a new token hash
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        purpose: &str,
+        token_hash: &str,
+        payload: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<ActionToken, AppError> {
+        let token = sqlx::query_as::<_, ActionToken>(
+            r#"
+            INSERT INTO action_tokens (user_id, purpose, token_hash, payload, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .bind(token_hash)
+        .bind(payload)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Atomically mark a valid token as used, returns None if unknown, used or expired
+    pub async fn consume(
+        pool: &PgPool,
+        purpose: &str,
+        token_hash: &str,
+    ) -> Result<Option<ActionToken>, AppError> {
+        let token = sqlx::query_as::<_, ActionToken>(
+            r#"
+            UPDATE action_tokens
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND purpose = $2
+              AND used_at IS NULL AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(token_hash)
+        .bind(purpose)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Invalidate all outstanding tokens of a user for a purpose
+    pub async fn invalidate(pool: &PgPool, user_id: Uuid, purpose: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE action_tokens
+            SET used_at = NOW()
+            WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
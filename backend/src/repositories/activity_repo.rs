@@ -0,0 +1,127 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::activity::ActivityItem;
+
+pub struct ActivityRepository;
+
+impl ActivityRepository {
+    /// Add an item to a user's activity feed
+    pub async fn record(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: &str,
+        room_id: Option<Uuid>,
+        actor_id: Option<Uuid>,
+        data: serde_json::Value,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_activity (user_id, kind, room_id, actor_id, data)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(room_id)
+        .bind(actor_id)
+        .bind(data)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List a user's activity, newest first
+    pub async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: Option<&str>,
+        unread_only: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ActivityItem>, AppError> {
+        let items = sqlx::query_as::<_, ActivityItem>(
+            r#"
+            SELECT
+                a.id,
+                a.kind,
+                a.room_id,
+                r.name AS room_name,
+                a.actor_id,
+                u.username AS actor_username,
+                a.data,
+                a.read_at,
+                a.created_at
+            FROM user_activity a
+            LEFT JOIN rooms r ON r.id = a.room_id
+            LEFT JOIN users u ON u.id = a.actor_id
+            WHERE a.user_id = $1
+              AND ($2::text IS NULL OR a.kind = $2)
+              AND (NOT $3 OR a.read_at IS NULL)
+            ORDER BY a.created_at DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(unread_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Count a user's activity
+    pub async fn count(pool: &PgPool, user_id: Uuid, kind: Option<&str>, unread_only: bool) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM user_activity
+            WHERE user_id = $1
+              AND ($2::text IS NULL OR kind = $2)
+              AND (NOT $3 OR read_at IS NULL)
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(unread_only)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Mark one of a user's activity items read
+    pub async fn mark_read(pool: &PgPool, user_id: Uuid, activity_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_activity
+            SET read_at = COALESCE(read_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(activity_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark all of a user's activity read
+    pub async fn mark_all_read(pool: &PgPool, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_activity SET read_at = NOW()
+            WHERE user_id = $1 AND read_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::announcement::Announcement;
+
+pub struct AnnouncementRepository;
+
+impl AnnouncementRepository {
+    /// All banners, including scheduled and expired ones, newest first
+    pub async fn list(pool: &PgPool) -> Result<Vec<Announcement>, AppError> {
+        let announcements = sqlx::query_as::<_, Announcement>(
+            r#"
+            SELECT * FROM announcements ORDER BY starts_at DESC, created_at DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(announcements)
+    }
+
+    /// Banners currently in their display window that the user hasn't dismissed,
+    /// most severe first
+    pub async fn list_active_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Announcement>, AppError> {
+        let announcements = sqlx::query_as::<_, Announcement>(
+            r#"
+            SELECT a.* FROM announcements a
+            WHERE a.starts_at <= NOW()
+              AND (a.ends_at IS NULL OR a.ends_at > NOW())
+              AND NOT EXISTS (
+                  SELECT 1 FROM announcement_dismissals d
+                  WHERE d.announcement_id = a.id AND d.user_id = $1
+              )
+            ORDER BY CASE a.severity WHEN 'critical' THEN 0 WHEN 'warning' THEN 1 ELSE 2 END,
+                     a.starts_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(announcements)
+    }
+
+    /// Find a banner by ID
+    pub async fn find_by_id(pool: &PgPool, announcement_id: Uuid) -> Result<Announcement, AppError> {
+        sqlx::query_as::<_, Announcement>(
+            r#"
+            SELECT * FROM announcements WHERE id = $1
+            "#,
+        )
+        .bind(announcement_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::AnnouncementNotFound)
+    }
+
+    /// Add a banner
+    pub async fn create(
+        pool: &PgPool,
+        message: &str,
+        severity: &str,
+        dismissible: bool,
+        starts_at: DateTime<Utc>,
+        ends_at: Option<DateTime<Utc>>,
+        created_by: Uuid,
+    ) -> Result<Announcement, AppError> {
+        let announcement = sqlx::query_as::<_, Announcement>(
+            r#"
+            INSERT INTO announcements (message, severity, dismissible, starts_at, ends_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(message)
+        .bind(severity)
+        .bind(dismissible)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(announcement)
+    }
+
+    /// Replace a banner's content and display window
+    pub async fn update(
+        pool: &PgPool,
+        announcement_id: Uuid,
+        message: &str,
+        severity: &str,
+        dismissible: bool,
+        starts_at: DateTime<Utc>,
+        ends_at: Option<DateTime<Utc>>,
+    ) -> Result<Announcement, AppError> {
+        sqlx::query_as::<_, Announcement>(
+            r#"
+            UPDATE announcements
+            SET message = $2, severity = $3, dismissible = $4, starts_at = $5, ends_at = $6, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(announcement_id)
+        .bind(message)
+        .bind(severity)
+        .bind(dismissible)
+        .bind(starts_at)
+        .bind(ends_at)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::AnnouncementNotFound)
+    }
+
+    /// Remove a banner (and its dismissals)
+    pub async fn delete(pool: &PgPool, announcement_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM announcements WHERE id = $1
+            "#,
+        )
+        .bind(announcement_id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::AnnouncementNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Hide a banner for one user (idempotent)
+    pub async fn dismiss(pool: &PgPool, announcement_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO announcement_dismissals (announcement_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (announcement_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(announcement_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::audit::{AuditLog, AuditLogEntry, AuditLogFilter};
+
+pub struct AuditRepository;
+
+impl AuditRepository {
+    /// Append an entry to the audit log
+    pub async fn record(
+        pool: &PgPool,
+        actor_id: Option<Uuid>,
+        action: &str,
+        target_id: Option<Uuid>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<AuditLog, AppError> {
+        let entry = sqlx::query_as::<_, AuditLog>(
+            r#"
+            INSERT INTO audit_logs (actor_id, action, target_id, metadata)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(actor_id)
+        .bind(action)
+        .bind(target_id)
+        .bind(metadata)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Append an entry to the audit log as part of a larger transaction
+    pub async fn record_in(
+        conn: &mut PgConnection,
+        actor_id: Option<Uuid>,
+        action: &str,
+        target_id: Option<Uuid>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<AuditLog, AppError> {
+        let entry = sqlx::query_as::<_, AuditLog>(
+            r#"
+            INSERT INTO audit_logs (actor_id, action, target_id, metadata)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(actor_id)
+        .bind(action)
+        .bind(target_id)
+        .bind(metadata)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(entry)
+    }
+    /// Entries matching the filters, newest first, strictly after the cursor position
+    /// (keyset pagination on created_at, id)
+    pub async fn query(
+        pool: &PgPool,
+        filter: &AuditLogFilter,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>, AppError> {
+        let (after_created_at, after_id) = after.unzip();
+
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            SELECT a.id, a.actor_id, u.username AS actor_username, a.action, a.target_id, a.metadata, a.created_at
+            FROM audit_logs a
+            LEFT JOIN users u ON u.id = a.actor_id
+            WHERE ($1::uuid IS NULL OR a.actor_id = $1)
+              AND ($2::text IS NULL OR a.action = $2 OR a.action LIKE $2 || '.%')
+              AND ($3::uuid IS NULL OR a.target_id = $3)
+              AND ($4::timestamptz IS NULL OR a.created_at >= $4)
+              AND ($5::timestamptz IS NULL OR a.created_at < $5)
+              AND ($6::timestamptz IS NULL OR (a.created_at, a.id) < ($6, $7))
+            ORDER BY a.created_at DESC, a.id DESC
+            LIMIT $8
+            "#,
+        )
+        .bind(filter.actor_id)
+        .bind(&filter.action)
+        .bind(filter.target_id)
+        .bind(filter.from)
+        .bind(filter.until)
+        .bind(after_created_at)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::ban::RoomBan;
+
+pub struct BanRepository;
+
+impl BanRepository {
+    /// Ban a user from a room (replaces an existing ban)
+    pub async fn upsert(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        banned_by: Uuid,
+        reason: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<RoomBan, AppError> {
+        let ban = sqlx::query_as::<_, RoomBan>(
+            r#"
+            INSERT INTO room_bans (room_id, user_id, banned_by, reason, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (room_id, user_id) DO UPDATE
+            SET banned_by = EXCLUDED.banned_by,
+                reason = EXCLUDED.reason,
+                expires_at = EXCLUDED.expires_at,
+                created_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(banned_by)
+        .bind(reason)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(ban)
+    }
+
+    /// Check if a user is currently banned from a room
+    pub async fn is_banned(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let banned = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM room_bans
+                WHERE room_id = $1 AND user_id = $2
+                  AND (expires_at IS NULL OR expires_at > NOW())
+            )
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(banned)
+    }
+
+    /// List active bans of a room, newest first
+    pub async fn list_active(pool: &PgPool, room_id: Uuid) -> Result<Vec<RoomBan>, AppError> {
+        let bans = sqlx::query_as::<_, RoomBan>(
+            r#"
+            SELECT * FROM room_bans
+            WHERE room_id = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(bans)
+    }
+
+    /// Lift a ban (false if there was none)
+    pub async fn delete(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM room_bans WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
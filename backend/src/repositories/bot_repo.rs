@@ -0,0 +1,181 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::bot::{BotResponse, CreateBotDto};
+use crate::models::user::User;
+use crate::repositories::UserRepository;
+
+pub struct BotRepository;
+
+impl BotRepository {
+    /// Create a bot user and its key record in one transaction
+    pub async fn create(
+        pool: &PgPool,
+        owner_id: Uuid,
+        dto: &CreateBotDto,
+        password_hash: &str,
+        api_key_hash: &str,
+    ) -> Result<BotResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        // Bots have no mailbox; the address only satisfies the unique column
+        let bot_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, email, password_hash, display_name, status, is_bot, email_verified_at)
+            VALUES ($1, $2, $3, $4, $5, 'offline', true, NOW())
+            "#,
+        )
+        .bind(bot_id)
+        .bind(&dto.username)
+        .bind(format!("bot-{}@bots.invalid", bot_id))
+        .bind(password_hash)
+        .bind(&dto.display_name)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO bots (user_id, owner_id, api_key_hash) VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(bot_id)
+        .bind(owner_id)
+        .bind(api_key_hash)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Self::find_owned(pool, owner_id, bot_id).await
+    }
+
+    /// Find a bot owned by the given account
+    pub async fn find_owned(pool: &PgPool, owner_id: Uuid, bot_id: Uuid) -> Result<BotResponse, AppError> {
+        let bot = sqlx::query_as::<_, BotResponse>(
+            r#"
+            SELECT u.id, u.username, u.display_name, COALESCE(u.avatar_url, '/api/avatars/' || u.id) AS avatar_url,
+                   u.is_verified, u.is_staff, b.owner_id,
+                   b.key_created_at, b.last_used_at, b.created_at
+            FROM bots b
+            JOIN users u ON u.id = b.user_id
+            WHERE b.user_id = $1 AND b.owner_id = $2 AND u.is_active = true
+            "#,
+        )
+        .bind(bot_id)
+        .bind(owner_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Ok(bot)
+    }
+
+    /// List the bots owned by an account
+    pub async fn list_owned(pool: &PgPool, owner_id: Uuid) -> Result<Vec<BotResponse>, AppError> {
+        let bots = sqlx::query_as::<_, BotResponse>(
+            r#"
+            SELECT u.id, u.username, u.display_name, COALESCE(u.avatar_url, '/api/avatars/' || u.id) AS avatar_url,
+                   u.is_verified, u.is_staff, b.owner_id,
+                   b.key_created_at, b.last_used_at, b.created_at
+            FROM bots b
+            JOIN users u ON u.id = b.user_id
+            WHERE b.owner_id = $1 AND u.is_active = true
+            ORDER BY b.created_at
+            "#,
+        )
+        .bind(owner_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(bots)
+    }
+
+    /// Owner of a bot (None if the user isn't a bot)
+    pub async fn find_owner_id(pool: &PgPool, bot_id: Uuid) -> Result<Option<Uuid>, AppError> {
+        let owner_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT owner_id FROM bots WHERE user_id = $1
+            "#,
+        )
+        .bind(bot_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(owner_id)
+    }
+
+    /// Count the bots owned by an account
+    pub async fn count_owned(pool: &PgPool, owner_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM bots WHERE owner_id = $1
+            "#,
+        )
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Replace a bot's API key; the previous key stops working immediately
+    pub async fn rotate_key(pool: &PgPool, owner_id: Uuid, bot_id: Uuid, api_key_hash: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots SET api_key_hash = $3, key_created_at = NOW()
+            WHERE user_id = $1 AND owner_id = $2
+            "#,
+        )
+        .bind(bot_id)
+        .bind(owner_id)
+        .bind(api_key_hash)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a bot and its user account
+    pub async fn delete(pool: &PgPool, owner_id: Uuid, bot_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM users
+            WHERE id = $1 AND is_bot = true
+              AND EXISTS(SELECT 1 FROM bots WHERE user_id = $1 AND owner_id = $2)
+            "#,
+        )
+        .bind(bot_id)
+        .bind(owner_id)
+        .execute(pool)
+        .await?;
+
+        UserRepository::invalidate_cache(bot_id).await;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Find the active bot user of an API key and mark the key used. Its owner must be
+    /// active and not suspended, so suspending a user also silences their bots.
+    pub async fn authenticate(pool: &PgPool, api_key_hash: &str) -> Result<Option<User>, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            WITH used AS (
+                UPDATE bots b SET last_used_at = NOW()
+                FROM users owner
+                WHERE b.api_key_hash = $1 AND owner.id = b.owner_id AND owner.is_active = true
+                  AND (owner.suspended_at IS NULL OR owner.suspended_until <= NOW())
+                RETURNING b.user_id
+            )
+            SELECT u.* FROM users u
+            JOIN used ON used.user_id = u.id
+            WHERE u.is_active = true
+            "#,
+        )
+        .bind(api_key_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+}
@@ -0,0 +1,102 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::command::{BotCommand, CommandSummary, RegisterCommandDto};
+
+pub struct CommandRepository;
+
+impl CommandRepository {
+    /// Register a command, or update it if the same bot already owns the name.
+    /// Returns None when another bot owns the name in this room.
+    pub async fn upsert(
+        pool: &PgPool,
+        room_id: Uuid,
+        bot_id: Uuid,
+        dto: &RegisterCommandDto,
+        signing_secret: &str,
+    ) -> Result<Option<BotCommand>, AppError> {
+        // The signing secret is kept across updates so callbacks keep verifying
+        let command = sqlx::query_as::<_, BotCommand>(
+            r#"
+            INSERT INTO room_bot_commands (room_id, bot_id, name, description, usage_hint, callback_url, signing_secret)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (room_id, name) DO UPDATE
+            SET description = EXCLUDED.description,
+                usage_hint = EXCLUDED.usage_hint,
+                callback_url = EXCLUDED.callback_url,
+                updated_at = NOW()
+            WHERE room_bot_commands.bot_id = EXCLUDED.bot_id
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(bot_id)
+        .bind(&dto.name)
+        .bind(&dto.description)
+        .bind(&dto.usage_hint)
+        .bind(&dto.callback_url)
+        .bind(signing_secret)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(command)
+    }
+
+    /// List a room's commands for autocompletion (only of bots still in the room)
+    pub async fn list_for_room(pool: &PgPool, room_id: Uuid) -> Result<Vec<CommandSummary>, AppError> {
+        let commands = sqlx::query_as::<_, CommandSummary>(
+            r#"
+            SELECT c.name, c.description, c.usage_hint, c.bot_id, u.username AS bot_username
+            FROM room_bot_commands c
+            JOIN users u ON u.id = c.bot_id AND u.is_active = true
+            JOIN room_members rm ON rm.room_id = c.room_id AND rm.user_id = c.bot_id
+            WHERE c.room_id = $1
+            ORDER BY c.name
+            "#,
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(commands)
+    }
+
+    /// Find a room's command by name (only if its bot is still in the room, and neither
+    /// the bot nor its owner is inactive or suspended)
+    pub async fn find_by_name(pool: &PgPool, room_id: Uuid, name: &str) -> Result<Option<BotCommand>, AppError> {
+        let command = sqlx::query_as::<_, BotCommand>(
+            r#"
+            SELECT c.* FROM room_bot_commands c
+            JOIN users u ON u.id = c.bot_id AND u.is_active = true
+            JOIN bots b ON b.user_id = c.bot_id
+            JOIN users owner ON owner.id = b.owner_id AND owner.is_active = true
+            JOIN room_members rm ON rm.room_id = c.room_id AND rm.user_id = c.bot_id
+            WHERE c.room_id = $1 AND c.name = $2
+              AND (u.suspended_at IS NULL OR u.suspended_until <= NOW())
+              AND (owner.suspended_at IS NULL OR owner.suspended_until <= NOW())
+            "#,
+        )
+        .bind(room_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(command)
+    }
+
+    /// Remove a bot's command from a room
+    pub async fn delete(pool: &PgPool, room_id: Uuid, bot_id: Uuid, name: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM room_bot_commands WHERE room_id = $1 AND bot_id = $2 AND name = $3
+            "#,
+        )
+        .bind(room_id)
+        .bind(bot_id)
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
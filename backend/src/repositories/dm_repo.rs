@@ -0,0 +1,334 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::dm::{DmConversationResponse, GroupDmResponse, SystemMessage, GROUP_DM_MAX_PARTICIPANTS};
+use crate::models::user::SYSTEM_USER_ID;
+
+/// Conversations of a user, with the other participant and unread counts ($1 = user).
+/// Muted conversations report no unread messages.
+const CONVERSATIONS_QUERY: &str = r#"
+    SELECT * FROM (
+        SELECT
+            dc.room_id,
+            u.id as user_id,
+            u.username,
+            u.display_name,
+            COALESCE(u.avatar_url, '/api/avatars/' || u.id) as avatar_url,
+            u.is_bot,
+            u.is_verified,
+            u.is_staff,
+            u.status,
+            (
+                SELECT COUNT(*) FROM messages m
+                WHERE m.room_id = dc.room_id
+                  AND m.user_id <> $1
+                  AND m.created_at > COALESCE(me.last_read_at, me.joined_at)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM room_mutes rmu
+                      WHERE rmu.room_id = dc.room_id AND rmu.user_id = $1
+                        AND (rmu.expires_at IS NULL OR rmu.expires_at > NOW())
+                  )
+            ) as unread_count,
+            (SELECT MAX(m.created_at) FROM messages m WHERE m.room_id = dc.room_id) as last_message_at,
+            dc.created_at
+        FROM direct_conversations dc
+        JOIN room_members me ON me.room_id = dc.room_id AND me.user_id = $1
+        JOIN users u ON u.id = CASE WHEN dc.user_low = $1 THEN dc.user_high ELSE dc.user_low END
+        WHERE dc.user_low = $1 OR dc.user_high = $1
+    ) c
+"#;
+
+/// Group DMs of a user, with participant and unread counts ($1 = user).
+/// Muted groups report no unread messages.
+const GROUPS_QUERY: &str = r#"
+    SELECT * FROM (
+        SELECT
+            r.id as room_id,
+            r.name,
+            r.owner_id,
+            r.member_count::bigint as participant_count,
+            (
+                SELECT COUNT(*) FROM messages m
+                WHERE m.room_id = r.id
+                  AND m.user_id <> $1
+                  AND m.created_at > COALESCE(me.last_read_at, me.joined_at)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM room_mutes rmu
+                      WHERE rmu.room_id = r.id AND rmu.user_id = $1
+                        AND (rmu.expires_at IS NULL OR rmu.expires_at > NOW())
+                  )
+            ) as unread_count,
+            (SELECT MAX(m.created_at) FROM messages m WHERE m.room_id = r.id) as last_message_at,
+            r.created_at
+        FROM rooms r
+        JOIN room_members me ON me.room_id = r.id AND me.user_id = $1
+        WHERE r.room_type = 'group_dm'
+    ) g
+"#;
+
+/// Order a pair of users the way `direct_conversations` stores it
+fn canonical_pair(user_a: Uuid, user_b: Uuid) -> (Uuid, Uuid) {
+    if user_a < user_b {
+        (user_a, user_b)
+    } else {
+        (user_b, user_a)
+    }
+}
+
+pub struct DmRepository;
+
+impl DmRepository {
+    /// Find the conversation between two users
+    pub async fn find_between(pool: &PgPool, user_a: Uuid, user_b: Uuid) -> Result<Option<Uuid>, AppError> {
+        let (low, high) = canonical_pair(user_a, user_b);
+
+        let room_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT room_id FROM direct_conversations
+            WHERE user_low = $1 AND user_high = $2
+            "#,
+        )
+        .bind(low)
+        .bind(high)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(room_id)
+    }
+
+    /// Create a conversation (a 'dm' room with both users as members)
+    /// Fails with a unique violation if the pair already has one
+    pub async fn create(pool: &PgPool, opener_id: Uuid, other_id: Uuid) -> Result<Uuid, AppError> {
+        let (low, high) = canonical_pair(opener_id, other_id);
+        let mut tx = pool.begin().await?;
+
+        let room_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO rooms (name, room_type, owner_id, max_members)
+            VALUES ($1, 'dm'::room_type, $2, 2)
+            RETURNING id
+            "#,
+        )
+        .bind(format!("dm:{}:{}", low, high))
+        .bind(opener_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO direct_conversations (room_id, user_low, user_high)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(room_id)
+        .bind(low)
+        .bind(high)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO room_members (room_id, user_id, role)
+            VALUES ($1, $2, 'member'::member_role), ($1, $3, 'member'::member_role)
+            "#,
+        )
+        .bind(room_id)
+        .bind(low)
+        .bind(high)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(room_id)
+    }
+
+    /// Get one conversation as seen by a participant
+    pub async fn find_for_user(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<DmConversationResponse, AppError> {
+        let query = format!("{} WHERE c.room_id = $2", CONVERSATIONS_QUERY);
+
+        let conversation = sqlx::query_as::<_, DmConversationResponse>(&query)
+            .bind(user_id)
+            .bind(room_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::RoomNotFound)?;
+
+        Ok(conversation)
+    }
+
+    /// List a user's conversations, most recently active first
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<DmConversationResponse>, AppError> {
+        let query = format!(
+            "{} ORDER BY COALESCE(c.last_message_at, c.created_at) DESC",
+            CONVERSATIONS_QUERY
+        );
+
+        let conversations = sqlx::query_as::<_, DmConversationResponse>(&query)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(conversations)
+    }
+
+    /// Create a group DM (the creator owns it, everyone else is a member)
+    pub async fn create_group(
+        pool: &PgPool,
+        owner_id: Uuid,
+        name: &str,
+        participant_ids: &[Uuid],
+    ) -> Result<Uuid, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let room_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO rooms (name, room_type, owner_id, max_members)
+            VALUES ($1, 'group_dm'::room_type, $2, $3)
+            RETURNING id
+            "#,
+        )
+        .bind(name)
+        .bind(owner_id)
+        .bind(GROUP_DM_MAX_PARTICIPANTS as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO room_members (room_id, user_id, role)
+            SELECT $1, participant, CASE WHEN participant = $2 THEN 'owner' ELSE 'member' END::member_role
+            FROM UNNEST($3::uuid[]) AS participant
+            "#,
+        )
+        .bind(room_id)
+        .bind(owner_id)
+        .bind(participant_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(room_id)
+    }
+
+    /// Get one group DM as seen by a participant
+    pub async fn find_group_for_user(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<GroupDmResponse, AppError> {
+        let query = format!("{} WHERE g.room_id = $2", GROUPS_QUERY);
+
+        let group = sqlx::query_as::<_, GroupDmResponse>(&query)
+            .bind(user_id)
+            .bind(room_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::RoomNotFound)?;
+
+        Ok(group)
+    }
+
+    /// List a user's group DMs, most recently active first
+    pub async fn list_groups_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<GroupDmResponse>, AppError> {
+        let query = format!(
+            "{} ORDER BY COALESCE(g.last_message_at, g.created_at) DESC",
+            GROUPS_QUERY
+        );
+
+        let groups = sqlx::query_as::<_, GroupDmResponse>(&query)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(groups)
+    }
+
+    /// Whether a room is a user's conversation with the System account
+    pub async fn is_system_conversation(pool: &PgPool, room_id: Uuid) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM direct_conversations
+                WHERE room_id = $1 AND (user_low = $2 OR user_high = $2)
+            )
+            "#,
+        )
+        .bind(room_id)
+        .bind(SYSTEM_USER_ID)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Store an official message in a user's conversation with System
+    pub async fn create_system_message(
+        pool: &PgPool,
+        room_id: Uuid,
+        recipient_id: Uuid,
+        sent_by: Uuid,
+        kind: &str,
+        content: &str,
+    ) -> Result<SystemMessage, AppError> {
+        let message = sqlx::query_as::<_, SystemMessage>(
+            r#"
+            INSERT INTO system_messages (room_id, recipient_id, sent_by, kind, content)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(recipient_id)
+        .bind(sent_by)
+        .bind(kind)
+        .bind(content)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(message)
+    }
+
+    /// Official messages received by a user, newest first
+    pub async fn list_system_messages(
+        pool: &PgPool,
+        user_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<SystemMessage>, AppError> {
+        let messages = sqlx::query_as::<_, SystemMessage>(
+            r#"
+            SELECT * FROM system_messages
+            WHERE recipient_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Count official messages received by a user
+    pub async fn count_system_messages(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM system_messages WHERE recipient_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::event::{CreateEventDto, EventResponse, RoomEvent};
+
+/// Columns of EventResponse, for the event `e` as seen by user $2
+const EVENT_RESPONSE_COLUMNS: &str = r#"
+    e.id,
+    e.room_id,
+    e.created_by,
+    e.title,
+    e.description,
+    e.starts_at,
+    (SELECT COUNT(*) FROM room_event_rsvps g WHERE g.event_id = e.id AND g.status = 'going') as going_count,
+    (SELECT COUNT(*) FROM room_event_rsvps m WHERE m.event_id = e.id AND m.status = 'maybe') as maybe_count,
+    (SELECT r.status FROM room_event_rsvps r WHERE r.event_id = e.id AND r.user_id = $2) as my_rsvp,
+    e.created_at
+"#;
+
+pub struct EventRepository;
+
+impl EventRepository {
+    /// Schedule an event in a room
+    pub async fn create(
+        pool: &PgPool,
+        room_id: Uuid,
+        created_by: Uuid,
+        dto: &CreateEventDto,
+    ) -> Result<RoomEvent, AppError> {
+        let event = sqlx::query_as::<_, RoomEvent>(
+            r#"
+            INSERT INTO room_events (room_id, created_by, title, description, starts_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(created_by)
+        .bind(&dto.title)
+        .bind(&dto.description)
+        .bind(dto.starts_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Find an event of a room
+    pub async fn find(pool: &PgPool, room_id: Uuid, event_id: Uuid) -> Result<RoomEvent, AppError> {
+        let event = sqlx::query_as::<_, RoomEvent>(
+            r#"
+            SELECT * FROM room_events WHERE id = $1 AND room_id = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::EventNotFound)?;
+
+        Ok(event)
+    }
+
+    /// Get an event as seen by a user
+    pub async fn find_for_user(pool: &PgPool, event_id: Uuid, user_id: Uuid) -> Result<EventResponse, AppError> {
+        let event = sqlx::query_as::<_, EventResponse>(&format!(
+            "SELECT {} FROM room_events e WHERE e.id = $1",
+            EVENT_RESPONSE_COLUMNS
+        ))
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::EventNotFound)?;
+
+        Ok(event)
+    }
+
+    /// List a room's events starting after a point in time, soonest first
+    pub async fn list_for_room(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<EventResponse>, AppError> {
+        let events = sqlx::query_as::<_, EventResponse>(&format!(
+            "SELECT {} FROM room_events e WHERE e.room_id = $1 AND e.starts_at >= $3 ORDER BY e.starts_at",
+            EVENT_RESPONSE_COLUMNS
+        ))
+        .bind(room_id)
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Cancel an event
+    pub async fn delete(pool: &PgPool, event_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM room_events WHERE id = $1
+            "#,
+        )
+        .bind(event_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Set a user's RSVP (replaces an earlier answer)
+    pub async fn upsert_rsvp(pool: &PgPool, event_id: Uuid, user_id: Uuid, status: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO room_event_rsvps (event_id, user_id, status)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (event_id, user_id) DO UPDATE
+            SET status = EXCLUDED.status,
+                responded_at = NOW()
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .bind(status)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Withdraw a user's RSVP
+    pub async fn delete_rsvp(pool: &PgPool, event_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM room_event_rsvps WHERE event_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Claim events starting before `until` whose reminder hasn't been sent.
+    /// Marking them in the same statement keeps a reminder from going out twice.
+    pub async fn claim_due_reminders(pool: &PgPool, until: DateTime<Utc>) -> Result<Vec<RoomEvent>, AppError> {
+        let events = sqlx::query_as::<_, RoomEvent>(
+            r#"
+            UPDATE room_events SET reminder_sent_at = NOW()
+            WHERE reminder_sent_at IS NULL AND starts_at <= $1 AND starts_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(until)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+}
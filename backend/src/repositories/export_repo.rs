@@ -0,0 +1,76 @@
+use futures_util::stream::BoxStream;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::export::ExportedMembership;
+
+pub struct ExportRepository;
+
+impl ExportRepository {
+    /// Room memberships of a user
+    pub async fn memberships(pool: &PgPool, user_id: Uuid) -> Result<Vec<ExportedMembership>, AppError> {
+        let memberships = sqlx::query_as::<_, ExportedMembership>(
+            r#"
+            SELECT rm.room_id, r.name as room_name, rm.role::text as role, rm.joined_at
+            FROM room_members rm
+            INNER JOIN rooms r ON r.id = rm.room_id
+            WHERE rm.user_id = $1
+            ORDER BY rm.joined_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(memberships)
+    }
+
+    /// Messages authored by a user, as raw JSON rows
+    pub async fn messages(pool: &PgPool, user_id: Uuid) -> Result<Vec<serde_json::Value>, AppError> {
+        let messages = sqlx::query_scalar::<_, serde_json::Value>(
+            r#"
+            SELECT to_jsonb(m) FROM messages m
+            WHERE m.user_id = $1
+            ORDER BY m.created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Members of a room as JSON rows, streamed for large rooms
+    pub fn room_members(pool: &PgPool, room_id: Uuid) -> BoxStream<'_, Result<serde_json::Value, sqlx::Error>> {
+        sqlx::query_scalar::<_, serde_json::Value>(
+            r#"
+            SELECT jsonb_build_object(
+                'user_id', rm.user_id,
+                'username', u.username,
+                'role', rm.role::text,
+                'joined_at', rm.joined_at
+            )
+            FROM room_members rm
+            INNER JOIN users u ON u.id = rm.user_id
+            WHERE rm.room_id = $1
+            ORDER BY rm.joined_at
+            "#,
+        )
+        .bind(room_id)
+        .fetch(pool)
+    }
+
+    /// Message history of a room as raw JSON rows, streamed for large rooms
+    pub fn room_messages(pool: &PgPool, room_id: Uuid) -> BoxStream<'_, Result<serde_json::Value, sqlx::Error>> {
+        sqlx::query_scalar::<_, serde_json::Value>(
+            r#"
+            SELECT to_jsonb(m) FROM messages m
+            WHERE m.room_id = $1
+            ORDER BY m.created_at
+            "#,
+        )
+        .bind(room_id)
+        .fetch(pool)
+    }
+}
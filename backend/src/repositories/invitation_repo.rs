@@ -0,0 +1,115 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::invitation::{Invitation, InvitationResponse, INVITATION_PENDING};
+
+pub struct InvitationRepository;
+
+impl InvitationRepository {
+    /// Create a pending invitation
+    pub async fn create(
+        pool: &PgPool,
+        room_id: Uuid,
+        inviter_id: Uuid,
+        invitee_id: Uuid,
+    ) -> Result<Invitation, AppError> {
+        let invitation = sqlx::query_as::<_, Invitation>(
+            r#"
+            INSERT INTO room_invitations (room_id, inviter_id, invitee_id)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(inviter_id)
+        .bind(invitee_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// Check if a user already has a pending invitation to a room
+    pub async fn pending_exists(pool: &PgPool, room_id: Uuid, invitee_id: Uuid) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM room_invitations
+                WHERE room_id = $1 AND invitee_id = $2 AND status = 'pending'
+            )
+            "#,
+        )
+        .bind(room_id)
+        .bind(invitee_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Find a pending invitation addressed to a user
+    pub async fn find_pending(
+        pool: &PgPool,
+        invitation_id: Uuid,
+        invitee_id: Uuid,
+    ) -> Result<Invitation, AppError> {
+        let invitation = sqlx::query_as::<_, Invitation>(
+            r#"
+            SELECT * FROM room_invitations
+            WHERE id = $1 AND invitee_id = $2 AND status = 'pending'
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(invitee_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::InvitationNotFound)?;
+
+        Ok(invitation)
+    }
+
+    /// List pending invitations of a user, newest first
+    pub async fn list_pending(pool: &PgPool, invitee_id: Uuid) -> Result<Vec<InvitationResponse>, AppError> {
+        let invitations = sqlx::query_as::<_, InvitationResponse>(
+            r#"
+            SELECT
+                i.id,
+                i.room_id,
+                r.name as room_name,
+                i.inviter_id,
+                u.username as inviter_username,
+                i.invitee_id,
+                i.status,
+                i.created_at
+            FROM room_invitations i
+            JOIN rooms r ON r.id = i.room_id
+            LEFT JOIN users u ON u.id = i.inviter_id
+            WHERE i.invitee_id = $1 AND i.status = 'pending'
+            ORDER BY i.created_at DESC
+            "#,
+        )
+        .bind(invitee_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Resolve a pending invitation (false if it was no longer pending)
+    pub async fn respond(pool: &PgPool, invitation_id: Uuid, status: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_invitations
+            SET status = $2, responded_at = NOW()
+            WHERE id = $1 AND status = $3
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(status)
+        .bind(INVITATION_PENDING)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
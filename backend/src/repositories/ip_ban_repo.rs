@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::ip_ban::IpBan;
+
+pub struct IpBanRepository;
+
+impl IpBanRepository {
+    /// All bans, including expired ones, newest first
+    pub async fn list(pool: &PgPool) -> Result<Vec<IpBan>, AppError> {
+        let bans = sqlx::query_as::<_, IpBan>(
+            r#"
+            SELECT * FROM ip_bans ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(bans)
+    }
+
+    /// Bans that haven't expired
+    pub async fn list_active(pool: &PgPool) -> Result<Vec<IpBan>, AppError> {
+        let bans = sqlx::query_as::<_, IpBan>(
+            r#"
+            SELECT * FROM ip_bans
+            WHERE expires_at IS NULL OR expires_at > NOW()
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(bans)
+    }
+
+    /// Find a ban by ID
+    pub async fn find_by_id(pool: &PgPool, ban_id: Uuid) -> Result<IpBan, AppError> {
+        sqlx::query_as::<_, IpBan>(
+            r#"
+            SELECT * FROM ip_bans WHERE id = $1
+            "#,
+        )
+        .bind(ban_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::IpBanNotFound)
+    }
+
+    /// Add a ban
+    pub async fn create(
+        pool: &PgPool,
+        cidr: &str,
+        reason: &str,
+        expires_at: Option<DateTime<Utc>>,
+        created_by: Uuid,
+    ) -> Result<IpBan, AppError> {
+        let ban = sqlx::query_as::<_, IpBan>(
+            r#"
+            INSERT INTO ip_bans (cidr, reason, expires_at, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(cidr)
+        .bind(reason)
+        .bind(expires_at)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(ban)
+    }
+
+    /// Lift a ban
+    pub async fn delete(pool: &PgPool, ban_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM ip_bans WHERE id = $1
+            "#,
+        )
+        .bind(ban_id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::IpBanNotFound);
+        }
+
+        Ok(())
+    }
+}
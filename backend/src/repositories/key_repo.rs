@@ -0,0 +1,119 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::keys::{IdentityKey, PrekeyDto, PublishKeysDto};
+
+pub struct KeyRepository;
+
+impl KeyRepository {
+    /// Store the identity key and signed prekey, adding one-time prekeys.
+    /// A new identity key invalidates the one-time prekeys of the old one.
+    pub async fn publish(pool: &PgPool, user_id: Uuid, dto: &PublishKeysDto) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        let previous = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT identity_key FROM identity_keys WHERE user_id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if previous.is_some_and(|key| key != dto.identity_key) {
+            sqlx::query("DELETE FROM one_time_prekeys WHERE user_id = $1")
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO identity_keys (user_id, identity_key, signed_prekey_id, signed_prekey, signed_prekey_signature)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id) DO UPDATE
+            SET identity_key = EXCLUDED.identity_key,
+                signed_prekey_id = EXCLUDED.signed_prekey_id,
+                signed_prekey = EXCLUDED.signed_prekey,
+                signed_prekey_signature = EXCLUDED.signed_prekey_signature,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(&dto.identity_key)
+        .bind(dto.signed_prekey.key_id)
+        .bind(&dto.signed_prekey.public_key)
+        .bind(&dto.signed_prekey.signature)
+        .execute(&mut *tx)
+        .await?;
+
+        for prekey in &dto.one_time_prekeys {
+            sqlx::query(
+                r#"
+                INSERT INTO one_time_prekeys (user_id, key_id, public_key)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, key_id) DO NOTHING
+                "#,
+            )
+            .bind(user_id)
+            .bind(prekey.key_id)
+            .bind(&prekey.public_key)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Get the identity key and signed prekey of a user
+    pub async fn find_identity(pool: &PgPool, user_id: Uuid) -> Result<Option<IdentityKey>, AppError> {
+        let identity = sqlx::query_as::<_, IdentityKey>(
+            r#"
+            SELECT * FROM identity_keys WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(identity)
+    }
+
+    /// Remove and return one one-time prekey of a user (each is handed out once)
+    pub async fn claim_prekey(pool: &PgPool, user_id: Uuid) -> Result<Option<PrekeyDto>, AppError> {
+        let prekey = sqlx::query_as::<_, PrekeyDto>(
+            r#"
+            DELETE FROM one_time_prekeys
+            WHERE id = (
+                SELECT id FROM one_time_prekeys
+                WHERE user_id = $1
+                ORDER BY key_id
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING key_id, public_key
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(prekey)
+    }
+
+    /// Count remaining one-time prekeys of a user
+    pub async fn count_prekeys(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM one_time_prekeys WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,159 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::linked_email::LinkedEmail;
+use crate::models::user::User;
+use crate::repositories::UserRepository;
+
+pub struct LinkedEmailRepository;
+
+impl LinkedEmailRepository {
+    /// Add an unverified address to an account
+    pub async fn create(pool: &PgPool, user_id: Uuid, email: &str) -> Result<LinkedEmail, AppError> {
+        let linked = sqlx::query_as::<_, LinkedEmail>(
+            r#"
+            INSERT INTO user_emails (user_id, email)
+            VALUES ($1, $2)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(email)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(linked)
+    }
+
+    /// Linked addresses of an account, oldest first
+    pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<Vec<LinkedEmail>, AppError> {
+        let linked = sqlx::query_as::<_, LinkedEmail>(
+            r#"
+            SELECT * FROM user_emails
+            WHERE user_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(linked)
+    }
+
+    /// Count the linked addresses of an account
+    pub async fn count(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM user_emails WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Find a linked address of an account
+    pub async fn find(pool: &PgPool, user_id: Uuid, email_id: Uuid) -> Result<LinkedEmail, AppError> {
+        sqlx::query_as::<_, LinkedEmail>(
+            r#"
+            SELECT * FROM user_emails WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(email_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::LinkedEmailNotFound)
+    }
+
+    /// Mark a linked address as verified
+    pub async fn mark_verified(pool: &PgPool, user_id: Uuid, email_id: Uuid) -> Result<LinkedEmail, AppError> {
+        sqlx::query_as::<_, LinkedEmail>(
+            r#"
+            UPDATE user_emails SET verified_at = COALESCE(verified_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(email_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::LinkedEmailNotFound)
+    }
+
+    /// Unlink an address, returns false if it wasn't linked to the account
+    pub async fn delete(pool: &PgPool, user_id: Uuid, email_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_emails WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(email_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Swap a verified linked address with the primary one in one transaction;
+    /// the old primary stays linked with its own verification state
+    pub async fn swap_primary(pool: &PgPool, user_id: Uuid, email_id: Uuid) -> Result<User, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let linked = sqlx::query_as::<_, LinkedEmail>(
+            r#"
+            SELECT * FROM user_emails WHERE id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+        )
+        .bind(email_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::LinkedEmailNotFound)?;
+
+        let current = sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users WHERE id = $1 AND is_active = true FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user_emails SET email = $2, verified_at = $3 WHERE id = $1
+            "#,
+        )
+        .bind(linked.id)
+        .bind(&current.email)
+        .bind(current.email_verified_at)
+        .execute(&mut *tx)
+        .await?;
+
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET email = $2, email_verified_at = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&linked.email)
+        .bind(linked.verified_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        UserRepository::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+}
@@ -1,5 +1,59 @@
 pub mod user_repo;
 pub mod room_repo;
+pub mod push_repo;
+pub mod session_repo;
+pub mod action_token_repo;
+pub mod audit_repo;
+pub mod export_repo;
+pub mod key_repo;
+pub mod dm_repo;
+pub mod invitation_repo;
+pub mod ban_repo;
+pub mod stats_repo;
+pub mod mute_repo;
+pub mod moderation_repo;
+pub mod welcome_repo;
+pub mod rules_repo;
+pub mod space_repo;
+pub mod event_repo;
+pub mod webhook_repo;
+pub mod outgoing_webhook_repo;
+pub mod activity_repo;
+pub mod report_repo;
+pub mod bot_repo;
+pub mod command_repo;
+pub mod linked_email_repo;
+pub mod word_filter_repo;
+pub mod announcement_repo;
+pub mod rate_limit_repo;
+pub mod ip_ban_repo;
 
 pub use user_repo::UserRepository;
 pub use room_repo::RoomRepository;
+pub use push_repo::PushRepository;
+pub use session_repo::SessionRepository;
+pub use action_token_repo::ActionTokenRepository;
+pub use audit_repo::AuditRepository;
+pub use export_repo::ExportRepository;
+pub use key_repo::KeyRepository;
+pub use dm_repo::DmRepository;
+pub use invitation_repo::InvitationRepository;
+pub use ban_repo::BanRepository;
+pub use stats_repo::StatsRepository;
+pub use mute_repo::MuteRepository;
+pub use moderation_repo::ModerationRepository;
+pub use welcome_repo::WelcomeRepository;
+pub use rules_repo::RulesRepository;
+pub use space_repo::SpaceRepository;
+pub use event_repo::EventRepository;
+pub use webhook_repo::WebhookRepository;
+pub use outgoing_webhook_repo::OutgoingWebhookRepository;
+pub use activity_repo::ActivityRepository;
+pub use report_repo::ReportRepository;
+pub use bot_repo::BotRepository;
+pub use command_repo::CommandRepository;
+pub use linked_email_repo::LinkedEmailRepository;
+pub use word_filter_repo::WordFilterRepository;
+pub use announcement_repo::AnnouncementRepository;
+pub use rate_limit_repo::RateLimitRepository;
+pub use ip_ban_repo::IpBanRepository;
@@ -0,0 +1,73 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::moderation::ModerationLogEntry;
+
+pub struct ModerationRepository;
+
+impl ModerationRepository {
+    /// Append an entry to a room's moderation log
+    pub async fn record(
+        pool: &PgPool,
+        room_id: Uuid,
+        actor_id: Uuid,
+        action: &str,
+        target_user_id: Option<Uuid>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<ModerationLogEntry, AppError> {
+        let entry = sqlx::query_as::<_, ModerationLogEntry>(
+            r#"
+            INSERT INTO room_moderation_log (room_id, actor_id, action, target_user_id, metadata)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(actor_id)
+        .bind(action)
+        .bind(target_user_id)
+        .bind(metadata)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// List a room's moderation log, newest first
+    pub async fn list(
+        pool: &PgPool,
+        room_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ModerationLogEntry>, AppError> {
+        let entries = sqlx::query_as::<_, ModerationLogEntry>(
+            r#"
+            SELECT * FROM room_moderation_log
+            WHERE room_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(room_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count a room's moderation log entries
+    pub async fn count(pool: &PgPool, room_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM room_moderation_log WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
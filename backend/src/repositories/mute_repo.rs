@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::mute::RoomMute;
+
+pub struct MuteRepository;
+
+impl MuteRepository {
+    /// Mute a room for a user (replaces an existing mute)
+    pub async fn upsert(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<RoomMute, AppError> {
+        let mute = sqlx::query_as::<_, RoomMute>(
+            r#"
+            INSERT INTO room_mutes (room_id, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (room_id, user_id) DO UPDATE
+            SET expires_at = EXCLUDED.expires_at,
+                created_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(mute)
+    }
+
+    /// Find a user's active (not expired) mute of a room
+    pub async fn find_active(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<Option<RoomMute>, AppError> {
+        let mute = sqlx::query_as::<_, RoomMute>(
+            r#"
+            SELECT * FROM room_mutes
+            WHERE room_id = $1 AND user_id = $2
+              AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(mute)
+    }
+
+    /// Unmute a room
+    pub async fn delete(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM room_mutes WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
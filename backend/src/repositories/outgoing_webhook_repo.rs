@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::webhook::{DueDelivery, OutgoingWebhook, WebhookDelivery};
+
+pub struct OutgoingWebhookRepository;
+
+impl OutgoingWebhookRepository {
+    /// Subscribe a URL to room events
+    pub async fn create(
+        pool: &PgPool,
+        room_id: Uuid,
+        url: &str,
+        secret: &str,
+        events: &[String],
+        created_by: Uuid,
+    ) -> Result<OutgoingWebhook, AppError> {
+        let webhook = sqlx::query_as::<_, OutgoingWebhook>(
+            r#"
+            INSERT INTO outgoing_webhooks (room_id, url, secret, events, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, room_id, url, events, created_by, created_at
+            "#,
+        )
+        .bind(room_id)
+        .bind(url)
+        .bind(secret)
+        .bind(events)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// List a room's outgoing webhooks
+    pub async fn list_for_room(pool: &PgPool, room_id: Uuid) -> Result<Vec<OutgoingWebhook>, AppError> {
+        let webhooks = sqlx::query_as::<_, OutgoingWebhook>(
+            r#"
+            SELECT id, room_id, url, events, created_by, created_at
+            FROM outgoing_webhooks
+            WHERE room_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    /// Check that a webhook belongs to a room
+    pub async fn exists_in_room(pool: &PgPool, room_id: Uuid, webhook_id: Uuid) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM outgoing_webhooks WHERE id = $1 AND room_id = $2)
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(room_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Delete a room's outgoing webhook (pending deliveries go with it)
+    pub async fn delete(pool: &PgPool, room_id: Uuid, webhook_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM outgoing_webhooks WHERE id = $1 AND room_id = $2
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(room_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Queue a delivery of an event to every webhook of the room subscribed to it
+    pub async fn enqueue(
+        pool: &PgPool,
+        room_id: Uuid,
+        event_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO outgoing_webhook_deliveries (webhook_id, event_type, payload)
+            SELECT w.id, $2, $3
+            FROM outgoing_webhooks w
+            WHERE w.room_id = $1 AND $2 = ANY(w.events)
+            "#,
+        )
+        .bind(room_id)
+        .bind(event_type)
+        .bind(payload)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claim pending deliveries that are due. Claimed rows are pushed back by the lease,
+    /// so a crashed worker's deliveries are retried and concurrent workers skip them.
+    pub async fn claim_due(pool: &PgPool, limit: i64, lease_seconds: f64) -> Result<Vec<DueDelivery>, AppError> {
+        let deliveries = sqlx::query_as::<_, DueDelivery>(
+            r#"
+            UPDATE outgoing_webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $2)
+            FROM outgoing_webhooks w
+            WHERE w.id = d.webhook_id
+              AND d.id IN (
+                  SELECT id FROM outgoing_webhook_deliveries
+                  WHERE status = 'pending' AND next_attempt_at <= NOW()
+                  ORDER BY next_attempt_at
+                  LIMIT $1
+                  FOR UPDATE SKIP LOCKED
+              )
+            RETURNING d.id, d.event_type, d.payload, d.attempts, w.url, w.secret
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Record the outcome of a delivery attempt
+    pub async fn record_attempt(
+        pool: &PgPool,
+        delivery_id: Uuid,
+        status: &str,
+        response_status: Option<i32>,
+        last_error: Option<&str>,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE outgoing_webhook_deliveries
+            SET attempts = attempts + 1,
+                status = $2,
+                response_status = $3,
+                last_error = $4,
+                next_attempt_at = $5,
+                delivered_at = CASE WHEN $2 = 'succeeded' THEN NOW() ELSE NULL END
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(status)
+        .bind(response_status)
+        .bind(last_error)
+        .bind(next_attempt_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List a webhook's deliveries, newest first
+    pub async fn list_deliveries(
+        pool: &PgPool,
+        webhook_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, webhook_id, event_type, payload, status, attempts, next_attempt_at,
+                   response_status, last_error, created_at, delivered_at
+            FROM outgoing_webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Count a webhook's deliveries
+    pub async fn count_deliveries(pool: &PgPool, webhook_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM outgoing_webhook_deliveries WHERE webhook_id = $1
+            "#,
+        )
+        .bind(webhook_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,192 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::push::{DeviceToken, WebPushSubscription};
+
+pub struct PushRepository;
+
+impl PushRepository {
+    /// Register a device token (re-registering moves it to the given user)
+    pub async fn upsert_device(
+        pool: &PgPool,
+        user_id: Uuid,
+        platform: &str,
+        token: &str,
+    ) -> Result<DeviceToken, AppError> {
+        let device = sqlx::query_as::<_, DeviceToken>(
+            r#"
+            INSERT INTO device_tokens (user_id, platform, token)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (token) DO UPDATE
+            SET user_id = EXCLUDED.user_id,
+                platform = EXCLUDED.platform,
+                last_seen_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(platform)
+        .bind(token)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(device)
+    }
+
+    /// List device tokens registered by a user
+    pub async fn list_devices(pool: &PgPool, user_id: Uuid) -> Result<Vec<DeviceToken>, AppError> {
+        let devices = sqlx::query_as::<_, DeviceToken>(
+            r#"
+            SELECT * FROM device_tokens
+            WHERE user_id = $1
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(devices)
+    }
+
+    /// Remove a device token owned by a user
+    pub async fn delete_device(pool: &PgPool, device_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM device_tokens
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(device_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Forget a device token the push provider reports as no longer valid
+    pub async fn delete_device_token(pool: &PgPool, token: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM device_tokens WHERE token = $1
+            "#,
+        )
+        .bind(token)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Members of a room, given by ID or username, who want to be notified there: active,
+    /// not suspended, not muting the room and with a notification level other than none
+    pub async fn notifiable_members(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_ids: &[Uuid],
+        usernames: &[String],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let members = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT u.id FROM room_members rm
+            JOIN users u ON u.id = rm.user_id
+            WHERE rm.room_id = $1
+              AND (u.id = ANY($2) OR u.username = ANY($3))
+              AND u.is_active = true
+              AND (u.suspended_at IS NULL OR u.suspended_until <= NOW())
+              AND rm.notification_level <> 'none'
+              AND NOT EXISTS (
+                  SELECT 1 FROM room_mutes m
+                  WHERE m.room_id = rm.room_id AND m.user_id = rm.user_id
+                    AND (m.expires_at IS NULL OR m.expires_at > NOW())
+              )
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_ids)
+        .bind(usernames)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Store a Web Push subscription (re-subscribing refreshes its keys)
+    pub async fn upsert_web_subscription(
+        pool: &PgPool,
+        user_id: Uuid,
+        endpoint: &str,
+        p256dh: &str,
+        auth: &str,
+    ) -> Result<WebPushSubscription, AppError> {
+        let subscription = sqlx::query_as::<_, WebPushSubscription>(
+            r#"
+            INSERT INTO web_push_subscriptions (user_id, endpoint, p256dh, auth)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (endpoint) DO UPDATE
+            SET user_id = EXCLUDED.user_id,
+                p256dh = EXCLUDED.p256dh,
+                auth = EXCLUDED.auth
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(p256dh)
+        .bind(auth)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// Remove a Web Push subscription owned by a user
+    pub async fn delete_web_subscription(
+        pool: &PgPool,
+        subscription_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM web_push_subscriptions
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List Web Push subscriptions of a user
+    pub async fn list_web_subscriptions(pool: &PgPool, user_id: Uuid) -> Result<Vec<WebPushSubscription>, AppError> {
+        let subscriptions = sqlx::query_as::<_, WebPushSubscription>(
+            r#"
+            SELECT * FROM web_push_subscriptions
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    /// Forget a subscription the push service reports as expired
+    pub async fn delete_web_subscription_endpoint(pool: &PgPool, endpoint: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM web_push_subscriptions WHERE endpoint = $1
+            "#,
+        )
+        .bind(endpoint)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
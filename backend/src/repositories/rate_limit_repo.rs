@@ -0,0 +1,65 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::rate_limit::RateLimitOverride;
+
+pub struct RateLimitRepository;
+
+impl RateLimitRepository {
+    /// All admin overrides
+    pub async fn list(pool: &PgPool) -> Result<Vec<RateLimitOverride>, AppError> {
+        let overrides = sqlx::query_as::<_, RateLimitOverride>(
+            r#"
+            SELECT * FROM rate_limits ORDER BY class
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(overrides)
+    }
+
+    /// Set (or replace) a route class's override
+    pub async fn upsert(
+        pool: &PgPool,
+        class: &str,
+        max_requests: i32,
+        window_seconds: i32,
+        updated_by: Uuid,
+    ) -> Result<RateLimitOverride, AppError> {
+        let limit = sqlx::query_as::<_, RateLimitOverride>(
+            r#"
+            INSERT INTO rate_limits (class, max_requests, window_seconds, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (class) DO UPDATE
+            SET max_requests = EXCLUDED.max_requests,
+                window_seconds = EXCLUDED.window_seconds,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(class)
+        .bind(max_requests)
+        .bind(window_seconds)
+        .bind(updated_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(limit)
+    }
+
+    /// Drop a route class's override; returns whether there was one
+    pub async fn delete(pool: &PgPool, class: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM rate_limits WHERE class = $1
+            "#,
+        )
+        .bind(class)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,295 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::audit::AUDIT_REPORT_ACTIONED;
+use crate::models::report::{
+    CreateReportDto, Report, ReportQueueItem, ReportReviewItem, REPORT_ACTION_BAN, REPORT_ACTION_DELETE_MESSAGE,
+    REPORT_ACTION_WARN,
+};
+use crate::repositories::{AuditRepository, UserRepository};
+
+pub struct ReportRepository;
+
+impl ReportRepository {
+    /// Author and room of a message
+    pub async fn find_message_context(pool: &PgPool, message_id: Uuid) -> Result<Option<(Uuid, Uuid)>, AppError> {
+        let context = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r#"
+            SELECT user_id, room_id FROM messages WHERE id = $1
+            "#,
+        )
+        .bind(message_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(context)
+    }
+
+    /// Check whether the reporter already has an unresolved report on the same target
+    pub async fn unresolved_exists(
+        pool: &PgPool,
+        reporter_id: Uuid,
+        target_user_id: Uuid,
+        message_id: Option<Uuid>,
+    ) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM reports
+                WHERE reporter_id = $1
+                  AND target_user_id = $2
+                  AND message_id IS NOT DISTINCT FROM $3
+                  AND status <> 'resolved'
+            )
+            "#,
+        )
+        .bind(reporter_id)
+        .bind(target_user_id)
+        .bind(message_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Create a report on a resolved target
+    pub async fn create(
+        pool: &PgPool,
+        reporter_id: Uuid,
+        dto: &CreateReportDto,
+        target_user_id: Uuid,
+        room_id: Option<Uuid>,
+    ) -> Result<Report, AppError> {
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            INSERT INTO reports (reporter_id, target_type, target_user_id, message_id, room_id, reason, details)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(reporter_id)
+        .bind(&dto.target_type)
+        .bind(target_user_id)
+        .bind(dto.message_id)
+        .bind(room_id)
+        .bind(&dto.reason)
+        .bind(&dto.details)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// Find a report by ID
+    pub async fn find_by_id(pool: &PgPool, report_id: Uuid) -> Result<Report, AppError> {
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            SELECT * FROM reports WHERE id = $1
+            "#,
+        )
+        .bind(report_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::ReportNotFound)?;
+
+        Ok(report)
+    }
+
+    /// List the moderation queue, oldest first (all unresolved reports when no status is given)
+    pub async fn list_queue(
+        pool: &PgPool,
+        status: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ReportQueueItem>, AppError> {
+        let items = sqlx::query_as::<_, ReportQueueItem>(
+            r#"
+            SELECT r.*, reporter.username AS reporter_username, target.username AS target_username
+            FROM reports r
+            LEFT JOIN users reporter ON reporter.id = r.reporter_id
+            JOIN users target ON target.id = r.target_user_id
+            WHERE ($1::text IS NULL AND r.status <> 'resolved') OR r.status = $1
+            ORDER BY r.created_at ASC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Count the moderation queue
+    pub async fn count_queue(pool: &PgPool, status: Option<&str>) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM reports
+            WHERE ($1::text IS NULL AND status <> 'resolved') OR status = $1
+            "#,
+        )
+        .bind(status)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Unresolved reports for admin review, oldest first, with the reported message inline
+    pub async fn list_for_review(pool: &PgPool, offset: i64, limit: i64) -> Result<Vec<ReportReviewItem>, AppError> {
+        let items = sqlx::query_as::<_, ReportReviewItem>(
+            r#"
+            SELECT r.*,
+                   reporter.username AS reporter_username,
+                   target.username AS target_username,
+                   target.display_name AS target_display_name,
+                   target.status_text AS target_status_text,
+                   (SELECT to_jsonb(m) FROM messages m WHERE m.id = r.message_id) AS message
+            FROM reports r
+            LEFT JOIN users reporter ON reporter.id = r.reporter_id
+            JOIN users target ON target.id = r.target_user_id
+            WHERE r.status <> 'resolved'
+            ORDER BY r.created_at ASC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Resolve a report and carry out its action in one transaction, with an audit entry.
+    /// Returns None (and changes nothing) if the report was resolved in the meantime.
+    pub async fn resolve_with_action(
+        pool: &PgPool,
+        report: &Report,
+        action: &str,
+        reviewer_id: Uuid,
+        note: Option<&str>,
+    ) -> Result<Option<Report>, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let resolved = sqlx::query_as::<_, Report>(
+            r#"
+            UPDATE reports
+            SET status = 'resolved', action = $2, reviewer_id = $3,
+                resolution_note = COALESCE($4, resolution_note),
+                resolved_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status <> 'resolved'
+            RETURNING *
+            "#,
+        )
+        .bind(report.id)
+        .bind(action)
+        .bind(reviewer_id)
+        .bind(note)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(resolved) = resolved else {
+            return Ok(None);
+        };
+
+        match action {
+            REPORT_ACTION_DELETE_MESSAGE => {
+                sqlx::query("DELETE FROM messages WHERE id = $1")
+                    .bind(report.message_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            REPORT_ACTION_WARN => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO user_warnings (user_id, issued_by, report_id, reason)
+                    VALUES ($1, $2, $3, $4)
+                    "#,
+                )
+                .bind(report.target_user_id)
+                .bind(reviewer_id)
+                .bind(report.id)
+                .bind(note.unwrap_or(&report.reason))
+                .execute(&mut *tx)
+                .await?;
+            }
+            REPORT_ACTION_BAN => {
+                sqlx::query(
+                    r#"
+                    UPDATE users
+                    SET suspended_at = NOW(), suspension_reason = $2, suspended_until = NULL,
+                        status = 'offline', updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(report.target_user_id)
+                .bind(note.unwrap_or(&report.reason))
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query("UPDATE sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+                    .bind(report.target_user_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            _ => {}
+        }
+
+        AuditRepository::record_in(
+            &mut tx,
+            Some(reviewer_id),
+            AUDIT_REPORT_ACTIONED,
+            Some(report.target_user_id),
+            Some(serde_json::json!({
+                "report_id": report.id,
+                "action": action,
+                "message_id": report.message_id,
+                "note": note,
+            })),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        if action == REPORT_ACTION_BAN {
+            UserRepository::invalidate_cache(report.target_user_id).await;
+        }
+
+        Ok(Some(resolved))
+    }
+
+    /// Move a report to a new status, if it is still in the expected one
+    pub async fn transition(
+        pool: &PgPool,
+        report_id: Uuid,
+        from: &str,
+        to: &str,
+        reviewer_id: Uuid,
+        note: Option<&str>,
+    ) -> Result<Option<Report>, AppError> {
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            UPDATE reports
+            SET status = $3,
+                reviewer_id = CASE WHEN $3 = 'open' THEN NULL ELSE $4 END,
+                resolution_note = COALESCE($5, resolution_note),
+                resolved_at = CASE WHEN $3 = 'resolved' THEN NOW() ELSE NULL END,
+                updated_at = NOW()
+            WHERE id = $1 AND status = $2
+            RETURNING *
+            "#,
+        )
+        .bind(report_id)
+        .bind(from)
+        .bind(to)
+        .bind(reviewer_id)
+        .bind(note)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(report)
+    }
+}
@@ -1,7 +1,26 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
+use crate::cache::CacheService;
 use crate::error::AppError;
-use crate::models::room::{Room, RoomMember, CreateRoomDto, UpdateRoomDto, RoomResponse, RoomMemberResponse};
+use crate::models::permission::RoomRolePermissions;
+use crate::models::room::{
+    BULK_ADDED, BULK_ALREADY_MEMBER, BULK_BANNED, BULK_ROOM_FULL,
+};
+use crate::models::room::{Room, RoomMember, CreateRoomDto, UpdateRoomDto, RoomResponse, RoomMemberResponse, MyRoomResponse, MentionSuggestion};
+
+/// How long a room row is served from the cache
+const ROOM_CACHE_TTL_SECONDS: u64 = 300;
+
+/// Cache entry of a room. The join password hash never goes into the cache, only
+/// whether the room has one (the room's own serialization skips the hash field).
+#[derive(Serialize, Deserialize)]
+struct CachedRoom {
+    #[serde(flatten)]
+    room: Room,
+    has_join_password: bool,
+}
 
 pub struct RoomRepository;
 
@@ -11,12 +30,13 @@ impl RoomRepository {
         pool: &PgPool,
         dto: &CreateRoomDto,
         owner_id: Uuid,
+        join_password_hash: Option<&str>,
     ) -> Result<Room, AppError> {
         let room = sqlx::query_as::<_, Room>(
             r#"
-            INSERT INTO rooms (name, description, room_type, owner_id, max_members)
-            VALUES ($1, $2, $3::room_type, $4, $5)
-            RETURNING id, name, description, room_type::text as room_type, owner_id, max_members, created_at, updated_at
+            INSERT INTO rooms (name, description, room_type, owner_id, max_members, post_policy, join_password_hash, space_id)
+            VALUES ($1, $2, $3::room_type, $4, $5, COALESCE($6, 'everyone'), $7, $8)
+            RETURNING id, name, description, room_type::text as room_type, owner_id, max_members, avatar_url, topic, post_policy, join_password_hash, space_id, locked_at, lock_reason, created_at, updated_at
             "#,
         )
         .bind(&dto.name)
@@ -24,26 +44,83 @@ impl RoomRepository {
         .bind(&dto.room_type)
         .bind(owner_id)
         .bind(dto.max_members)
+        .bind(&dto.post_policy)
+        .bind(join_password_hash)
+        .bind(dto.space_id)
         .fetch_one(pool)
         .await?;
 
         Ok(room)
     }
 
-    /// Find room by ID
+    /// Find room by ID. A set join password comes back as an empty hash, so it is
+    /// never copied into the cache; `join_room` reads it with `find_join_password_hash`
     pub async fn find_by_id(pool: &PgPool, room_id: Uuid) -> Result<Room, AppError> {
+        let cache_key = CacheService::room_key(room_id);
+        if let Some(cached) = CacheService::get::<CachedRoom>(&cache_key).await {
+            return Ok(Room {
+                join_password_hash: cached.has_join_password.then(String::new),
+                ..cached.room
+            });
+        }
+
         let room = sqlx::query_as::<_, Room>(
             r#"
-            SELECT id, name, description, room_type::text as room_type, owner_id, max_members, created_at, updated_at
+            SELECT id, name, description, room_type::text as room_type, owner_id, max_members, avatar_url, topic, post_policy, join_password_hash, space_id, locked_at, lock_reason, created_at, updated_at
             FROM rooms WHERE id = $1
             "#,
         )
         .bind(room_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|_| AppError::RoomNotFound)?;
+        .fetch_optional(pool)
+        .await?;
 
-        Ok(room)
+        match room {
+            Some(room) => {
+                let room = Room {
+                    join_password_hash: room.join_password_hash.map(|_| String::new()),
+                    ..room
+                };
+                let cached = CachedRoom {
+                    has_join_password: room.join_password_hash.is_some(),
+                    room: room.clone(),
+                };
+                CacheService::set(&cache_key, &cached, ROOM_CACHE_TTL_SECONDS).await;
+
+                Ok(room)
+            }
+            // Merged rooms leave a tombstone pointing at the room they were merged into
+            None => match Self::find_redirect(pool, room_id).await? {
+                Some(new_room_id) => Err(AppError::RoomMerged(new_room_id)),
+                None => Err(AppError::RoomNotFound),
+            },
+        }
+    }
+
+    /// Join password hash of a room (always read from the database)
+    pub async fn find_join_password_hash(pool: &PgPool, room_id: Uuid) -> Result<Option<String>, AppError> {
+        sqlx::query_scalar::<_, Option<String>>(
+            r#"
+            SELECT join_password_hash FROM rooms WHERE id = $1
+            "#,
+        )
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::RoomNotFound)
+    }
+
+    /// Find the room a merged room now redirects to
+    pub async fn find_redirect(pool: &PgPool, old_room_id: Uuid) -> Result<Option<Uuid>, AppError> {
+        let new_room_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT new_room_id FROM room_redirects WHERE old_room_id = $1
+            "#,
+        )
+        .bind(old_room_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(new_room_id)
     }
 
     /// List rooms with pagination
@@ -61,12 +138,18 @@ impl RoomRepository {
                 r.room_type::text as room_type,
                 r.owner_id, 
                 r.max_members, 
+                r.avatar_url,
+                r.topic,
+                r.post_policy,
+                r.join_password_hash IS NOT NULL as has_password,
+                r.space_id,
+                r.locked_at,
+                r.lock_reason,
                 r.created_at, 
                 r.updated_at,
-                COUNT(rm.id) as member_count
+                r.member_count::bigint as member_count
             FROM rooms r
-            LEFT JOIN room_members rm ON r.id = rm.room_id
-            GROUP BY r.id
+            WHERE r.room_type NOT IN ('dm', 'group_dm')
             ORDER BY r.created_at DESC
             LIMIT $1 OFFSET $2
             "#,
@@ -79,6 +162,83 @@ impl RoomRepository {
         Ok(rooms)
     }
 
+    /// List rooms a user is a member of (excluding DMs): favorites first, then custom order,
+    /// then most recently joined
+    pub async fn list_user_rooms(
+        pool: &PgPool,
+        user_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<MyRoomResponse>, AppError> {
+        let rooms = sqlx::query_as::<_, MyRoomResponse>(
+            r#"
+            SELECT
+                r.id,
+                r.name,
+                r.description,
+                r.room_type::text as room_type,
+                r.avatar_url,
+                r.topic,
+                r.space_id,
+                r.member_count::bigint as member_count,
+                me.role::text as role,
+                me.notification_level,
+                EXISTS(
+                    SELECT 1 FROM room_mutes rmu
+                    WHERE rmu.room_id = r.id AND rmu.user_id = me.user_id
+                      AND (rmu.expires_at IS NULL OR rmu.expires_at > NOW())
+                ) as muted,
+                me.is_favorite,
+                me.sort_order,
+                me.joined_at
+            FROM room_members me
+            JOIN rooms r ON r.id = me.room_id
+            WHERE me.user_id = $1 AND r.room_type NOT IN ('dm', 'group_dm')
+            ORDER BY me.is_favorite DESC, me.sort_order ASC NULLS LAST, me.joined_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rooms)
+    }
+
+    /// Count rooms a user is a member of (excluding DMs)
+    pub async fn count_user_rooms(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM room_members me
+            JOIN rooms r ON r.id = me.room_id
+            WHERE me.user_id = $1 AND r.room_type NOT IN ('dm', 'group_dm')
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Count rooms a user owns (excluding DMs)
+    pub async fn count_owned(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM rooms
+            WHERE owner_id = $1 AND room_type NOT IN ('dm', 'group_dm')
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Count total rooms accessible by user
     pub async fn count_rooms(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
         let count = sqlx::query_scalar::<_, i64>(
@@ -86,7 +246,7 @@ impl RoomRepository {
             SELECT COUNT(DISTINCT r.id)
             FROM rooms r
             LEFT JOIN room_members rm ON r.id = rm.room_id AND rm.user_id = $1
-            WHERE r.room_type = 'public' OR rm.user_id = $1
+            WHERE r.room_type NOT IN ('dm', 'group_dm') AND (r.room_type = 'public' OR rm.user_id = $1)
             "#,
         )
         .bind(user_id)
@@ -122,6 +282,18 @@ impl RoomRepository {
             params.push(format!("max_members = ${}", param_count));
             param_count += 1;
         }
+        if let Some(_) = &updates.avatar_url {
+            params.push(format!("avatar_url = ${}", param_count));
+            param_count += 1;
+        }
+        if let Some(_) = &updates.post_policy {
+            params.push(format!("post_policy = ${}", param_count));
+            param_count += 1;
+        }
+        if let Some(_) = &updates.topic {
+            params.push(format!("topic = NULLIF(${}, '')", param_count));
+            param_count += 1;
+        }
 
         if params.is_empty() {
             return Self::find_by_id(pool, room_id).await;
@@ -129,7 +301,7 @@ impl RoomRepository {
 
         query.push_str(&params.join(", "));
         query.push_str(&format!(
-            " WHERE id = ${} RETURNING id, name, description, room_type::text as room_type, owner_id, max_members, created_at, updated_at",
+            " WHERE id = ${} RETURNING id, name, description, room_type::text as room_type, owner_id, max_members, avatar_url, topic, post_policy, join_password_hash, space_id, locked_at, lock_reason, created_at, updated_at",
             param_count
         ));
 
@@ -147,13 +319,46 @@ impl RoomRepository {
         if let Some(max_members) = updates.max_members {
             sqlx_query = sqlx_query.bind(max_members);
         }
+        if let Some(ref avatar_url) = updates.avatar_url {
+            sqlx_query = sqlx_query.bind(avatar_url);
+        }
+        if let Some(ref post_policy) = updates.post_policy {
+            sqlx_query = sqlx_query.bind(post_policy);
+        }
+        if let Some(ref topic) = updates.topic {
+            sqlx_query = sqlx_query.bind(topic);
+        }
 
         sqlx_query = sqlx_query.bind(room_id);
 
         let room = sqlx_query.fetch_one(pool).await?;
+        Self::invalidate_cache(room_id).await;
+
         Ok(room)
     }
 
+    /// Set or clear (None) the join password hash
+    pub async fn set_join_password(
+        pool: &PgPool,
+        room_id: Uuid,
+        join_password_hash: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE rooms SET join_password_hash = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(room_id)
+        .bind(join_password_hash)
+        .execute(pool)
+        .await?;
+
+        Self::invalidate_cache(room_id).await;
+
+        Ok(())
+    }
+
     /// Delete room (only owner can delete)
     pub async fn delete(pool: &PgPool, room_id: Uuid) -> Result<(), AppError> {
         let result = sqlx::query(
@@ -165,6 +370,8 @@ impl RoomRepository {
         .execute(pool)
         .await?;
 
+        Self::invalidate_cache(room_id).await;
+
         if result.rows_affected() == 0 {
             return Err(AppError::RoomNotFound);
         }
@@ -172,6 +379,86 @@ impl RoomRepository {
         Ok(())
     }
 
+    /// Lock a room with a reason, or unlock it (`reason` = None)
+    pub async fn set_lock(pool: &PgPool, room_id: Uuid, reason: Option<&str>) -> Result<Room, AppError> {
+        let room = sqlx::query_as::<_, Room>(
+            r#"
+            UPDATE rooms
+            SET locked_at = CASE WHEN $2::text IS NULL THEN NULL ELSE NOW() END,
+                lock_reason = $2,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, description, room_type::text as room_type, owner_id, max_members, avatar_url, topic, post_policy, join_password_hash, space_id, locked_at, lock_reason, created_at, updated_at
+            "#,
+        )
+        .bind(room_id)
+        .bind(reason)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::RoomNotFound)?;
+
+        Self::invalidate_cache(room_id).await;
+
+        Ok(room)
+    }
+
+    /// Make a user the owner of a room, joining them if needed; the previous owner
+    /// stays on as an admin
+    pub async fn transfer_ownership(pool: &PgPool, room_id: Uuid, new_owner_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE room_members SET role = 'admin'::member_role
+            WHERE room_id = $1 AND role = 'owner'::member_role AND user_id <> $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(new_owner_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let promoted = sqlx::query(
+            r#"
+            UPDATE room_members SET role = 'owner'::member_role
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(new_owner_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if promoted.rows_affected() == 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO room_members (room_id, user_id, role)
+                VALUES ($1, $2, 'owner'::member_role)
+                "#,
+            )
+            .bind(room_id)
+            .bind(new_owner_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE rooms SET owner_id = $2, updated_at = NOW() WHERE id = $1
+            "#,
+        )
+        .bind(room_id)
+        .bind(new_owner_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Self::invalidate_cache(room_id).await;
+
+        Ok(())
+    }
+
     /// Add member to room
     pub async fn add_member(
         pool: &PgPool,
@@ -195,6 +482,48 @@ impl RoomRepository {
         Ok(member)
     }
 
+    /// Add member to room unless it is full.
+    /// The room row is locked so concurrent joins can't exceed max_members.
+    pub async fn add_member_within_capacity(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        role: &str,
+    ) -> Result<RoomMember, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let (max_members, count) = sqlx::query_as::<_, (Option<i32>, i32)>(
+            r#"
+            SELECT max_members, member_count FROM rooms WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(room_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::RoomNotFound)?;
+
+        if max_members.is_some_and(|max| count >= max) {
+            return Err(AppError::RoomFull);
+        }
+
+        let member = sqlx::query_as::<_, RoomMember>(
+            r#"
+            INSERT INTO room_members (room_id, user_id, role)
+            VALUES ($1, $2, $3::member_role)
+            RETURNING id, room_id, user_id, role::text as role, joined_at
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(role)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(member)
+    }
+
     /// Remove member from room
     pub async fn remove_member(
         pool: &PgPool,
@@ -219,6 +548,32 @@ impl RoomRepository {
         Ok(())
     }
 
+    /// Change a member's role
+    pub async fn update_member_role(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        role: &str,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_members SET role = $3::member_role
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+
     /// Get room members with user info
     pub async fn get_members(
         pool: &PgPool,
@@ -231,14 +586,20 @@ impl RoomRepository {
                 rm.room_id,
                 rm.user_id,
                 u.username,
-                u.display_name,
-                u.avatar_url,
+                COALESCE(rm.nickname, u.display_name, u.username) AS display_name,
+                rm.nickname,
+                COALESCE(u.avatar_url, '/api/avatars/' || u.id) AS avatar_url,
                 rm.role::text as role,
+                u.is_bot,
+                u.is_verified,
+                u.is_staff,
                 u.status,
+                CASE WHEN u.status_expires_at IS NULL OR u.status_expires_at > NOW() THEN u.status_text END AS status_text,
+                CASE WHEN u.status_expires_at IS NULL OR u.status_expires_at > NOW() THEN u.status_emoji END AS status_emoji,
                 rm.joined_at
             FROM room_members rm
             JOIN users u ON rm.user_id = u.id
-            WHERE rm.room_id = $1
+            WHERE rm.room_id = $1 AND u.is_active = true
             ORDER BY rm.joined_at ASC
             "#,
         )
@@ -249,11 +610,33 @@ impl RoomRepository {
         Ok(members)
     }
 
-    /// Count room members
+    /// Mention candidates of a room, highest role first
+    pub async fn mention_candidates(pool: &PgPool, room_id: Uuid) -> Result<Vec<MentionSuggestion>, AppError> {
+        let candidates = sqlx::query_as::<_, MentionSuggestion>(
+            r#"
+            SELECT u.id AS user_id, u.username, COALESCE(rm.nickname, u.display_name) AS display_name,
+                   COALESCE(u.avatar_url, '/api/avatars/' || u.id) AS avatar_url, rm.role::text AS role,
+                   u.is_bot, u.is_verified, u.is_staff
+            FROM room_members rm
+            JOIN users u ON rm.user_id = u.id
+            WHERE rm.room_id = $1 AND u.is_active = true
+            ORDER BY
+                CASE rm.role::text WHEN 'owner' THEN 3 WHEN 'admin' THEN 2 WHEN 'moderator' THEN 1 ELSE 0 END DESC,
+                u.username
+            "#,
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Count room members (maintained on the room row, see the room_members_count trigger)
     pub async fn count_members(pool: &PgPool, room_id: Uuid) -> Result<i64, AppError> {
         let count = sqlx::query_scalar::<_, i64>(
             r#"
-            SELECT COUNT(*) FROM room_members WHERE room_id = $1
+            SELECT COALESCE((SELECT member_count::bigint FROM rooms WHERE id = $1), 0)
             "#,
         )
         .bind(room_id)
@@ -263,6 +646,25 @@ impl RoomRepository {
         Ok(count)
     }
 
+    /// IDs of all rooms the user is a member of
+    pub async fn member_room_ids(pool: &PgPool, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let room_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT room_id FROM room_members WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(room_ids)
+    }
+
+    /// Drop the cached row of a room after a write
+    pub async fn invalidate_cache(room_id: Uuid) {
+        CacheService::invalidate(&CacheService::room_key(room_id)).await;
+    }
+
     /// Check if user is member of room
     pub async fn is_member(
         pool: &PgPool,
@@ -318,4 +720,362 @@ impl RoomRepository {
 
         Ok(exists)
     }
+
+    /// Get the customized permissions of a role in a room (None = defaults)
+    pub async fn get_role_permissions(
+        pool: &PgPool,
+        room_id: Uuid,
+        role: &str,
+    ) -> Result<Option<Vec<String>>, AppError> {
+        let permissions = sqlx::query_scalar::<_, Vec<String>>(
+            r#"
+            SELECT permissions FROM room_role_permissions
+            WHERE room_id = $1 AND role = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(role)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(permissions)
+    }
+
+    /// List all customized role permissions of a room
+    pub async fn list_role_permissions(
+        pool: &PgPool,
+        room_id: Uuid,
+    ) -> Result<Vec<RoomRolePermissions>, AppError> {
+        let permissions = sqlx::query_as::<_, RoomRolePermissions>(
+            r#"
+            SELECT * FROM room_role_permissions
+            WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(permissions)
+    }
+
+    /// Replace the permissions of a role in a room
+    pub async fn set_role_permissions(
+        pool: &PgPool,
+        room_id: Uuid,
+        role: &str,
+        permissions: &[String],
+    ) -> Result<RoomRolePermissions, AppError> {
+        let row = sqlx::query_as::<_, RoomRolePermissions>(
+            r#"
+            INSERT INTO room_role_permissions (room_id, role, permissions)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (room_id, role) DO UPDATE
+            SET permissions = EXCLUDED.permissions,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(role)
+        .bind(permissions)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Set a member's notification level
+    pub async fn set_notification_level(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        level: &str,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_members SET notification_level = $3
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(level)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+
+    /// Set or clear a member's nickname in a room
+    pub async fn set_nickname(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        nickname: Option<&str>,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_members SET nickname = $3
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(nickname)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+
+    /// Mark everything in a room as read for a member
+    pub async fn mark_read(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_members SET last_read_at = NOW()
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+
+    /// Star or unstar a room for a member
+    pub async fn set_favorite(pool: &PgPool, room_id: Uuid, user_id: Uuid, is_favorite: bool) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_members SET is_favorite = $3
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(is_favorite)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+
+    /// Replace a user's custom room order (position in the list); other rooms are unordered
+    pub async fn set_sort_order(pool: &PgPool, user_id: Uuid, room_ids: &[Uuid]) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE room_members SET sort_order = NULL
+            WHERE user_id = $1 AND sort_order IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE room_members me SET sort_order = o.position::int
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS o(room_id, position)
+            WHERE me.user_id = $1 AND me.room_id = o.room_id
+            "#,
+        )
+        .bind(user_id)
+        .bind(room_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Merge a room into another one, atomically: move members (deduped, the old owner
+    /// becomes admin, users banned from the target are skipped), re-parent messages,
+    /// then replace the old room with a redirect. Returns (members moved, messages moved).
+    pub async fn merge(
+        pool: &PgPool,
+        source_id: Uuid,
+        target_id: Uuid,
+        merged_by: Uuid,
+    ) -> Result<(u64, u64), AppError> {
+        let mut tx = pool.begin().await?;
+
+        // Lock both rooms against concurrent joins and merges
+        let locked = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM rooms WHERE id = ANY($1) ORDER BY id FOR UPDATE
+            "#,
+        )
+        .bind(vec![source_id, target_id])
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if locked.len() != 2 {
+            return Err(AppError::RoomNotFound);
+        }
+
+        let members = sqlx::query(
+            r#"
+            INSERT INTO room_members (room_id, user_id, role)
+            SELECT $2, rm.user_id, CASE WHEN rm.role = 'owner' THEN 'admin'::member_role ELSE rm.role END
+            FROM room_members rm
+            WHERE rm.room_id = $1
+              AND NOT EXISTS(SELECT 1 FROM room_members t WHERE t.room_id = $2 AND t.user_id = rm.user_id)
+              AND NOT EXISTS(
+                  SELECT 1 FROM room_bans b
+                  WHERE b.room_id = $2 AND b.user_id = rm.user_id
+                    AND (b.expires_at IS NULL OR b.expires_at > NOW())
+              )
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let messages = sqlx::query(
+            r#"
+            UPDATE messages SET room_id = $2 WHERE room_id = $1
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Rooms merged into the source earlier now point at the target too
+        sqlx::query(
+            r#"
+            UPDATE room_redirects SET new_room_id = $2 WHERE new_room_id = $1
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO room_redirects (old_room_id, new_room_id, merged_by)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .bind(merged_by)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM rooms WHERE id = $1
+            "#,
+        )
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Self::invalidate_cache(source_id).await;
+
+        Ok((members.rows_affected(), messages.rows_affected()))
+    }
+
+    /// Add many users as members in one transaction, respecting capacity and bans.
+    /// Returns the outcome for each user, in order.
+    pub async fn add_members_bulk(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, &'static str)>, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let (max_members, mut count) = sqlx::query_as::<_, (Option<i32>, i32)>(
+            r#"
+            SELECT max_members, member_count FROM rooms WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(room_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::RoomNotFound)?;
+
+        let existing: HashSet<Uuid> = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT user_id FROM room_members WHERE room_id = $1 AND user_id = ANY($2)
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let banned: HashSet<Uuid> = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT user_id FROM room_bans
+            WHERE room_id = $1 AND user_id = ANY($2)
+              AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut results = Vec::with_capacity(user_ids.len());
+        for &user_id in user_ids {
+            let status = if existing.contains(&user_id) {
+                BULK_ALREADY_MEMBER
+            } else if banned.contains(&user_id) {
+                BULK_BANNED
+            } else if max_members.is_some_and(|max| count >= max) {
+                BULK_ROOM_FULL
+            } else {
+                sqlx::query(
+                    r#"
+                    INSERT INTO room_members (room_id, user_id, role)
+                    VALUES ($1, $2, 'member'::member_role)
+                    "#,
+                )
+                .bind(room_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+
+                count += 1;
+                BULK_ADDED
+            };
+
+            results.push((user_id, status));
+        }
+
+        tx.commit().await?;
+
+        Ok(results)
+    }
 }
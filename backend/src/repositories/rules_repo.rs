@@ -0,0 +1,102 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::rules::RoomRules;
+
+pub struct RulesRepository;
+
+impl RulesRepository {
+    /// Find a room's rules
+    pub async fn find(pool: &PgPool, room_id: Uuid) -> Result<Option<RoomRules>, AppError> {
+        let rules = sqlx::query_as::<_, RoomRules>(
+            r#"
+            SELECT * FROM room_rules WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(rules)
+    }
+
+    /// Set a room's rules, bumping the version when they already exist
+    pub async fn upsert(
+        pool: &PgPool,
+        room_id: Uuid,
+        content: &str,
+        updated_by: Uuid,
+    ) -> Result<RoomRules, AppError> {
+        let rules = sqlx::query_as::<_, RoomRules>(
+            r#"
+            INSERT INTO room_rules (room_id, content, updated_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (room_id) DO UPDATE
+            SET content = EXCLUDED.content,
+                version = room_rules.version + 1,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(content)
+        .bind(updated_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(rules)
+    }
+
+    /// Remove a room's rules
+    pub async fn delete(pool: &PgPool, room_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM room_rules WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Rules version a member last accepted
+    pub async fn accepted_version(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<Option<i32>, AppError> {
+        let version = sqlx::query_scalar::<_, Option<i32>>(
+            r#"
+            SELECT rules_accepted_version FROM room_members
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(version.flatten())
+    }
+
+    /// Record a member's acceptance of a rules version
+    pub async fn accept(pool: &PgPool, room_id: Uuid, user_id: Uuid, version: i32) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_members
+            SET rules_accepted_version = $3, rules_accepted_at = NOW()
+            WHERE room_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .bind(version)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+}
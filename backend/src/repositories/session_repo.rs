@@ -0,0 +1,117 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::session::{Session, SessionMeta};
+
+pub struct SessionRepository;
+
+impl SessionRepository {
+    /// Create a new session for a user
+    pub async fn create(pool: &PgPool, user_id: Uuid, meta: &SessionMeta) -> Result<Session, AppError> {
+        let session = sqlx::query_as::<_, Session>(
+            r#"
+            INSERT INTO sessions (user_id, device_name, ip_address, user_agent, location)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&meta.device_name)
+        .bind(&meta.ip_address)
+        .bind(&meta.user_agent)
+        .bind(&meta.location)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Check a device against the user's session history: (has any sessions, has used this device)
+    /// A device is identified by its User-Agent and IP address
+    pub async fn device_history(pool: &PgPool, user_id: Uuid, meta: &SessionMeta) -> Result<(bool, bool), AppError> {
+        let history = sqlx::query_as::<_, (bool, bool)>(
+            r#"
+            SELECT
+                COUNT(*) > 0,
+                COUNT(*) FILTER (
+                    WHERE user_agent IS NOT DISTINCT FROM $2 AND ip_address IS NOT DISTINCT FROM $3
+                ) > 0
+            FROM sessions
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(&meta.user_agent)
+        .bind(&meta.ip_address)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(history)
+    }
+
+    /// Mark an active session as used, returns false if it is revoked or unknown
+    pub async fn touch(pool: &PgPool, session_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions
+            SET last_used_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List active sessions of a user
+    pub async fn list_active(pool: &PgPool, user_id: Uuid) -> Result<Vec<Session>, AppError> {
+        let sessions = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT * FROM sessions
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY last_used_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Revoke a single session of a user
+    pub async fn revoke(pool: &PgPool, session_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions
+            SET revoked_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke all active sessions of a user
+    pub async fn revoke_all(pool: &PgPool, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions
+            SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,279 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::room::RoomResponse;
+use crate::models::space::{CreateSpaceDto, Space, SpaceResponse};
+use crate::repositories::RoomRepository;
+
+pub struct SpaceRepository;
+
+impl SpaceRepository {
+    /// Create a new space
+    pub async fn create(pool: &PgPool, dto: &CreateSpaceDto, owner_id: Uuid) -> Result<Space, AppError> {
+        let space = sqlx::query_as::<_, Space>(
+            r#"
+            INSERT INTO spaces (name, description, owner_id)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(&dto.name)
+        .bind(&dto.description)
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(space)
+    }
+
+    /// Find space by ID
+    pub async fn find_by_id(pool: &PgPool, space_id: Uuid) -> Result<Space, AppError> {
+        let space = sqlx::query_as::<_, Space>(
+            r#"
+            SELECT * FROM spaces WHERE id = $1
+            "#,
+        )
+        .bind(space_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::SpaceNotFound)?;
+
+        Ok(space)
+    }
+
+    /// Find a space as seen by a user (role is None for non-members)
+    pub async fn find_for_user(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<SpaceResponse, AppError> {
+        let space = sqlx::query_as::<_, SpaceResponse>(
+            r#"
+            SELECT
+                s.id,
+                s.name,
+                s.description,
+                s.owner_id,
+                (SELECT COUNT(*) FROM space_members smc WHERE smc.space_id = s.id) as member_count,
+                me.role,
+                s.created_at
+            FROM spaces s
+            LEFT JOIN space_members me ON me.space_id = s.id AND me.user_id = $2
+            WHERE s.id = $1
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::SpaceNotFound)?;
+
+        Ok(space)
+    }
+
+    /// List spaces a user is a member of, by name
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<SpaceResponse>, AppError> {
+        let spaces = sqlx::query_as::<_, SpaceResponse>(
+            r#"
+            SELECT
+                s.id,
+                s.name,
+                s.description,
+                s.owner_id,
+                (SELECT COUNT(*) FROM space_members smc WHERE smc.space_id = s.id) as member_count,
+                me.role,
+                s.created_at
+            FROM space_members me
+            JOIN spaces s ON s.id = me.space_id
+            WHERE me.user_id = $1
+            ORDER BY LOWER(s.name)
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(spaces)
+    }
+
+    /// List the channels of a space visible to a user (public ones and those they belong to)
+    pub async fn list_channels(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<Vec<RoomResponse>, AppError> {
+        let channels = sqlx::query_as::<_, RoomResponse>(
+            r#"
+            SELECT
+                r.id,
+                r.name,
+                r.description,
+                r.room_type::text as room_type,
+                r.owner_id,
+                r.max_members,
+                r.avatar_url,
+                r.topic,
+                r.post_policy,
+                r.join_password_hash IS NOT NULL as has_password,
+                r.space_id,
+                r.locked_at,
+                r.lock_reason,
+                r.created_at,
+                r.updated_at,
+                r.member_count::bigint as member_count
+            FROM rooms r
+            WHERE r.space_id = $1
+              AND (r.room_type = 'public'
+                   OR EXISTS(SELECT 1 FROM room_members me WHERE me.room_id = r.id AND me.user_id = $2))
+            ORDER BY LOWER(r.name)
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(channels)
+    }
+
+    /// Check if a space name is taken (case-insensitive)
+    pub async fn name_exists(pool: &PgPool, name: &str) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM spaces WHERE LOWER(name) = LOWER($1))
+            "#,
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Get a user's role in a space
+    pub async fn get_user_role(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<Option<String>, AppError> {
+        let role = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT role FROM space_members
+            WHERE space_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    /// Add a member to a space
+    pub async fn add_member(pool: &PgPool, space_id: Uuid, user_id: Uuid, role: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO space_members (space_id, user_id, role)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove a member from a space
+    pub async fn remove_member(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM space_members WHERE space_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Add a user to every open channel of a space (public, no join password, not banned)
+    pub async fn join_open_channels(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO room_members (room_id, user_id, role)
+            SELECT r.id, $2, 'member'::member_role
+            FROM rooms r
+            WHERE r.space_id = $1
+              AND r.room_type = 'public'
+              AND r.join_password_hash IS NULL
+              AND NOT EXISTS(SELECT 1 FROM room_members rm WHERE rm.room_id = r.id AND rm.user_id = $2)
+              AND NOT EXISTS(
+                  SELECT 1 FROM room_bans b
+                  WHERE b.room_id = r.id AND b.user_id = $2
+                    AND (b.expires_at IS NULL OR b.expires_at > NOW())
+              )
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Add every space member to a new open channel
+    pub async fn add_members_to_channel(pool: &PgPool, space_id: Uuid, room_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO room_members (room_id, user_id, role)
+            SELECT $2, sm.user_id, 'member'::member_role
+            FROM space_members sm
+            WHERE sm.space_id = $1
+              AND NOT EXISTS(SELECT 1 FROM room_members rm WHERE rm.room_id = $2 AND rm.user_id = sm.user_id)
+            "#,
+        )
+        .bind(space_id)
+        .bind(room_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Remove a user from every channel of a space they don't own, returning the channels left
+    pub async fn leave_channels(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let room_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            DELETE FROM room_members rm
+            USING rooms r
+            WHERE rm.room_id = r.id
+              AND r.space_id = $1
+              AND rm.user_id = $2
+              AND rm.role <> 'owner'
+            RETURNING rm.room_id
+            "#,
+        )
+        .bind(space_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(room_ids)
+    }
+
+    /// Delete a space (its channels are deleted with it)
+    pub async fn delete(pool: &PgPool, space_id: Uuid) -> Result<(), AppError> {
+        let channel_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            WITH deleted AS (
+                DELETE FROM spaces WHERE id = $1
+            )
+            SELECT id FROM rooms WHERE space_id = $1
+            "#,
+        )
+        .bind(space_id)
+        .fetch_all(pool)
+        .await?;
+
+        for channel_id in channel_ids {
+            RoomRepository::invalidate_cache(channel_id).await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::stats::{InstanceDailyMetrics, RoomDailyStats};
+
+pub struct StatsRepository;
+
+impl StatsRepository {
+    /// Recompute daily stats of every room from `since` (a UTC day) onwards
+    pub async fn aggregate_since(pool: &PgPool, since: NaiveDate) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO room_daily_stats (room_id, day, message_count, active_members)
+            SELECT
+                m.room_id,
+                (m.created_at AT TIME ZONE 'UTC')::date as day,
+                COUNT(*),
+                COUNT(DISTINCT m.user_id)
+            FROM messages m
+            WHERE m.created_at >= ($1::date)::timestamp AT TIME ZONE 'UTC'
+            GROUP BY m.room_id, day
+            ON CONFLICT (room_id, day) DO UPDATE
+            SET message_count = EXCLUDED.message_count,
+                active_members = EXCLUDED.active_members,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(since)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Daily stats of a room from `since` onwards, oldest first
+    pub async fn list_for_room(
+        pool: &PgPool,
+        room_id: Uuid,
+        since: NaiveDate,
+    ) -> Result<Vec<RoomDailyStats>, AppError> {
+        let stats = sqlx::query_as::<_, RoomDailyStats>(
+            r#"
+            SELECT day, message_count, active_members, peak_concurrency
+            FROM room_daily_stats
+            WHERE room_id = $1 AND day >= $2
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(room_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Snapshot instance-wide metrics as of now into the row of `day` (a UTC day)
+    pub async fn snapshot_instance(pool: &PgPool, day: NaiveDate) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            WITH bounds AS (
+                SELECT ($1::date)::timestamp AT TIME ZONE 'UTC' AS day_start
+            )
+            INSERT INTO instance_daily_metrics
+                (day, total_users, daily_active_users, monthly_active_users, total_rooms, message_count, storage_bytes)
+            SELECT
+                $1,
+                (SELECT COUNT(*) FROM users WHERE deleted_at IS NULL AND is_bot = false),
+                (SELECT COUNT(*) FROM users
+                 WHERE is_active = true AND is_bot = false AND last_active_at >= b.day_start),
+                (SELECT COUNT(*) FROM users
+                 WHERE is_active = true AND is_bot = false AND last_active_at >= b.day_start - INTERVAL '29 days'),
+                (SELECT COUNT(*) FROM rooms),
+                (SELECT COUNT(*) FROM messages
+                 WHERE created_at >= b.day_start AND created_at < b.day_start + INTERVAL '1 day'),
+                pg_database_size(current_database())
+            FROM bounds b
+            ON CONFLICT (day) DO UPDATE
+            SET total_users = EXCLUDED.total_users,
+                daily_active_users = EXCLUDED.daily_active_users,
+                monthly_active_users = EXCLUDED.monthly_active_users,
+                total_rooms = EXCLUDED.total_rooms,
+                message_count = EXCLUDED.message_count,
+                storage_bytes = EXCLUDED.storage_bytes,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(day)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Recount the messages of a past day; its other metrics stay as last snapshotted
+    pub async fn recount_instance_messages(pool: &PgPool, day: NaiveDate) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE instance_daily_metrics
+            SET message_count = (
+                    SELECT COUNT(*) FROM messages
+                    WHERE created_at >= ($1::date)::timestamp AT TIME ZONE 'UTC'
+                      AND created_at < ($1::date + 1)::timestamp AT TIME ZONE 'UTC'
+                ),
+                updated_at = NOW()
+            WHERE day = $1
+            "#,
+        )
+        .bind(day)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Instance metrics from `since` onwards, oldest first
+    pub async fn list_instance(pool: &PgPool, since: NaiveDate) -> Result<Vec<InstanceDailyMetrics>, AppError> {
+        let metrics = sqlx::query_as::<_, InstanceDailyMetrics>(
+            r#"
+            SELECT * FROM instance_daily_metrics
+            WHERE day >= $1
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(metrics)
+    }
+}
@@ -1,7 +1,41 @@
+use std::sync::LazyLock;
+use chrono::{DateTime, NaiveTime, Utc};
+use futures_util::stream::BoxStream;
 use sqlx::PgPool;
 use uuid::Uuid;
+use crate::cache::CacheService;
 use crate::error::AppError;
-use crate::models::user::{User, CreateUserDto, UpdateUserDto};
+use crate::models::user::{AdminUserFilter, User, CreateUserDto, UpdateUserDto};
+
+/// WHERE clause of the admin user list ($1 = name/email prefix, $2 = role,
+/// $3 = account status, $4 = is_bot); mirrors `User::account_status`
+const ADMIN_USER_FILTER: &str = r#"
+    ($1::text IS NULL OR username ILIKE $1 || '%' OR email ILIKE $1 || '%')
+    AND ($2::text IS NULL OR role = $2)
+    AND ($3::text IS NULL OR $3 = CASE
+        WHEN deleted_at IS NOT NULL THEN 'deleted'
+        WHEN deletion_requested_at IS NOT NULL THEN 'pending_deletion'
+        WHEN suspended_at IS NOT NULL AND (suspended_until IS NULL OR suspended_until > NOW()) THEN 'suspended'
+        WHEN deactivated_at IS NOT NULL THEN 'deactivated'
+        ELSE 'active'
+    END)
+    AND ($4::bool IS NULL OR is_bot = $4)
+"#;
+
+/// Streamed queries borrow their SQL for the whole stream, so it is built once
+static ADMIN_USER_EXPORT: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        r#"
+        SELECT * FROM users
+        WHERE {}
+        ORDER BY created_at
+        "#,
+        ADMIN_USER_FILTER
+    )
+});
+
+/// How long a user row is served from the cache
+const USER_CACHE_TTL_SECONDS: u64 = 300;
 
 pub struct UserRepository;
 
@@ -40,8 +74,14 @@ impl UserRepository {
         Ok(user)
     }
 
-    /// Find user by ID
+    /// Find user by ID. The password hash is left empty, so it is never copied into
+    /// the cache; paths that check a password read it with `find_password_hash`
     pub async fn find_by_id(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        let cache_key = CacheService::user_key(user_id);
+        if let Some(user) = CacheService::get::<User>(&cache_key).await {
+            return Ok(user);
+        }
+
         let user = sqlx::query_as::<_, User>(
             r#"
             SELECT * FROM users
@@ -52,9 +92,35 @@ impl UserRepository {
         .fetch_one(pool)
         .await?;
 
+        let user = User {
+            password_hash: String::new(),
+            ..user
+        };
+        CacheService::set(&cache_key, &user, USER_CACHE_TTL_SECONDS).await;
+
         Ok(user)
     }
 
+    /// Password hash of an active user (always read from the database)
+    pub async fn find_password_hash(pool: &PgPool, user_id: Uuid) -> Result<String, AppError> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT password_hash FROM users
+            WHERE id = $1 AND is_active = true
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)
+    }
+
+    /// Drop the cached row of a user after a write (rows written in bulk, like
+    /// last-active times, are left to expire with the cache TTL)
+    pub async fn invalidate_cache(user_id: Uuid) {
+        CacheService::invalidate(&CacheService::user_key(user_id)).await;
+    }
+
     /// Find user by username
     pub async fn find_by_username(pool: &PgPool, username: &str) -> Result<User, AppError> {
         let user = sqlx::query_as::<_, User>(
@@ -93,6 +159,14 @@ impl UserRepository {
             updates.push(format!("status = ${}", param_count));
             param_count += 1;
         }
+        if dto.timezone.is_some() {
+            updates.push(format!("timezone = ${}", param_count));
+            param_count += 1;
+        }
+        if dto.locale.is_some() {
+            updates.push(format!("locale = ${}", param_count));
+            param_count += 1;
+        }
 
         if updates.is_empty() {
             return Self::find_by_id(pool, user_id).await;
@@ -115,14 +189,74 @@ impl UserRepository {
         if let Some(status) = &dto.status {
             query_builder = query_builder.bind(status);
         }
+        if let Some(timezone) = &dto.timezone {
+            query_builder = query_builder.bind(timezone);
+        }
+        if let Some(locale) = &dto.locale {
+            query_builder = query_builder.bind(locale);
+        }
 
         query_builder = query_builder.bind(user_id);
 
         let user = query_builder.fetch_one(pool).await?;
 
+        Self::invalidate_cache(user_id).await;
+
         Ok(user)
     }
 
+    /// Store last activity times, never moving one backwards
+    pub async fn record_last_active(
+        pool: &PgPool,
+        user_ids: &[Uuid],
+        active_at: &[DateTime<Utc>],
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users u
+            SET last_active_at = GREATEST(u.last_active_at, a.active_at)
+            FROM UNNEST($1::uuid[], $2::timestamptz[]) AS a(user_id, active_at)
+            WHERE u.id = a.user_id
+            "#,
+        )
+        .bind(user_ids)
+        .bind(active_at)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Count users active since the given time
+    pub async fn count_active_since(pool: &PgPool, since: DateTime<Utc>) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM users
+            WHERE last_active_at >= $1 AND is_active = true AND is_bot = false
+            "#,
+        )
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Count how many of the given users exist and are active
+    pub async fn count_active(pool: &PgPool, user_ids: &[Uuid]) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM users
+            WHERE id = ANY($1) AND is_active = true
+            "#,
+        )
+        .bind(user_ids)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Update user status (online/offline/away/busy)
     pub async fn update_status(pool: &PgPool, user_id: Uuid, status: &str) -> Result<(), AppError> {
         sqlx::query(
@@ -137,14 +271,46 @@ impl UserRepository {
         .execute(pool)
         .await?;
 
+        Self::invalidate_cache(user_id).await;
+
         Ok(())
     }
 
+    /// Set or clear (all None) the custom status
+    pub async fn set_custom_status(
+        pool: &PgPool,
+        user_id: Uuid,
+        status_text: Option<&str>,
+        status_emoji: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET status_text = $2, status_emoji = $3, status_expires_at = $4, updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(status_text)
+        .bind(status_emoji)
+        .bind(expires_at)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
     /// Check if email exists
     pub async fn email_exists(pool: &PgPool, email: &str) -> Result<bool, AppError> {
         let result: (bool,) = sqlx::query_as(
             r#"
             SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)
+                OR EXISTS(SELECT 1 FROM user_emails WHERE email = $1 AND verified_at IS NOT NULL)
             "#
         )
         .bind(email)
@@ -167,4 +333,515 @@ impl UserRepository {
 
         Ok(result.0)
     }
+
+    /// Mark user's email as verified
+    pub async fn mark_email_verified(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            "#
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(())
+    }
+
+    /// Update user's email (confirmed addresses are marked verified)
+    pub async fn update_email(pool: &PgPool, user_id: Uuid, email: &str) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET email = $1, email_verified_at = NOW(), updated_at = NOW()
+            WHERE id = $2 AND is_active = true
+            RETURNING *
+            "#
+        )
+        .bind(email)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Replace user's password hash
+    pub async fn update_password_hash(pool: &PgPool, user_id: Uuid, password_hash: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET password_hash = $1, updated_at = NOW()
+            WHERE id = $2
+            "#
+        )
+        .bind(password_hash)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(())
+    }
+
+    /// Change a user's global role
+    pub async fn update_role(pool: &PgPool, user_id: Uuid, role: &str) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET role = $1, updated_at = NOW()
+            WHERE id = $2 AND is_active = true
+            RETURNING *
+            "#
+        )
+        .bind(role)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Set or clear (both None) the do-not-disturb window
+    pub async fn set_quiet_hours(
+        pool: &PgPool,
+        user_id: Uuid,
+        start: Option<NaiveTime>,
+        end: Option<NaiveTime>,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET quiet_hours_start = $2, quiet_hours_end = $3, updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Find a user by ID whatever the account state
+    pub async fn find_any_by_id(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users WHERE id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)
+    }
+
+    /// Users matching the admin filters, newest first
+    pub async fn admin_list(
+        pool: &PgPool,
+        filter: &AdminUserFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<User>, AppError> {
+        let users = sqlx::query_as::<_, User>(&format!(
+            r#"
+            SELECT * FROM users
+            WHERE {}
+            ORDER BY created_at DESC
+            LIMIT $5 OFFSET $6
+            "#,
+            ADMIN_USER_FILTER
+        ))
+        .bind(&filter.q)
+        .bind(&filter.role)
+        .bind(&filter.status)
+        .bind(filter.is_bot)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Users matching the admin filters, oldest first, streamed for the export
+    pub fn admin_export<'a>(
+        pool: &'a PgPool,
+        filter: &'a AdminUserFilter,
+    ) -> BoxStream<'a, Result<User, sqlx::Error>> {
+        sqlx::query_as::<_, User>(&ADMIN_USER_EXPORT)
+            .bind(&filter.q)
+            .bind(&filter.role)
+            .bind(&filter.status)
+            .bind(filter.is_bot)
+            .fetch(pool)
+    }
+
+    /// Count users matching the admin filters
+    pub async fn admin_count(pool: &PgPool, filter: &AdminUserFilter) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(&format!(
+            r#"
+            SELECT COUNT(*) FROM users
+            WHERE {}
+            "#,
+            ADMIN_USER_FILTER
+        ))
+        .bind(&filter.q)
+        .bind(&filter.role)
+        .bind(&filter.status)
+        .bind(filter.is_bot)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Suspend a user instance-wide (`until` = None suspends until lifted)
+    pub async fn suspend(
+        pool: &PgPool,
+        user_id: Uuid,
+        reason: &str,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET suspended_at = NOW(), suspension_reason = $2, suspended_until = $3,
+                status = 'offline', updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .bind(reason)
+        .bind(until)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Lift a suspension
+    pub async fn unsuspend(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET suspended_at = NULL, suspension_reason = NULL, suspended_until = NULL, updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Require a new password before the next login
+    pub async fn require_password_reset(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET password_reset_required = true, updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Record acceptance of a terms of service version
+    pub async fn accept_tos(pool: &PgPool, user_id: Uuid, version: &str) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET tos_version = $2, tos_accepted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .bind(version)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Store a new password chosen through a reset link
+    pub async fn complete_password_reset(pool: &PgPool, user_id: Uuid, password_hash: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET password_hash = $2, password_reset_required = false, updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            "#
+        )
+        .bind(user_id)
+        .bind(password_hash)
+        .execute(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(())
+    }
+
+    /// Grant or revoke badges (None leaves a badge unchanged)
+    pub async fn update_badges(
+        pool: &PgPool,
+        user_id: Uuid,
+        verified: Option<bool>,
+        staff: Option<bool>,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET is_verified = COALESCE($2, is_verified), is_staff = COALESCE($3, is_staff), updated_at = NOW()
+            WHERE id = $1 AND is_active = true
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .bind(verified)
+        .bind(staff)
+        .fetch_one(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Deactivate an account pending deletion
+    pub async fn request_deletion(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET is_active = false, status = 'offline', deletion_requested_at = NOW(), deactivated_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND is_active = true AND deletion_requested_at IS NULL
+            "#
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(())
+    }
+
+    /// Mark an account deleted by an admin, whatever its state (unless already deleted)
+    pub async fn mark_deleted(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET is_active = false, status = 'offline', deletion_requested_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND deletion_requested_at IS NULL AND deleted_at IS NULL
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// Deactivate an account (reversible)
+    pub async fn deactivate(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET is_active = false, status = 'offline', deactivated_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND is_active = true AND deletion_requested_at IS NULL
+            "#
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(())
+    }
+
+    /// Find an account by email whatever its state (callers check `account_status`)
+    pub async fn find_by_email_any_state(pool: &PgPool, email: &str) -> Result<Option<User>, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users
+            WHERE email = $1
+            "#
+        )
+        .bind(email)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Reactivate a deactivated account
+    pub async fn reactivate(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET is_active = true, deactivated_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND deactivated_at IS NOT NULL AND deletion_requested_at IS NULL AND deleted_at IS NULL
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(user)
+    }
+
+    /// IDs of accounts not yet purged whose deletion was requested, or that were
+    /// deactivated, before the respective cutoff
+    pub async fn find_due_for_purge(
+        pool: &PgPool,
+        requested_before: DateTime<Utc>,
+        deactivated_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, AppError> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM users
+            WHERE deleted_at IS NULL
+              AND (deletion_requested_at < $1 OR (deletion_requested_at IS NULL AND deactivated_at < $2))
+            "#
+        )
+        .bind(requested_before)
+        .bind(deactivated_before)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Remove personal data of an account, keeping an anonymized tombstone row
+    /// so rooms and messages that reference it stay intact
+    pub async fn purge(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        for table in ["sessions", "device_tokens", "web_push_subscriptions", "action_tokens", "room_members", "user_emails"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET username = 'deleted-' || LEFT(REPLACE(id::text, '-', ''), 12),
+                email = 'deleted-' || id::text || '@deleted.invalid',
+                password_hash = '!',
+                display_name = NULL,
+                avatar_url = NULL,
+                status = 'offline',
+                status_text = NULL,
+                status_emoji = NULL,
+                status_expires_at = NULL,
+                suspension_reason = NULL,
+                email_verified_at = NULL,
+                deleted_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Self::invalidate_cache(user_id).await;
+
+        Ok(())
+    }
+    /// IDs of anonymized accounts whose content hasn't been purged yet, anonymized before the cutoff
+    pub async fn find_due_for_content_purge(pool: &PgPool, deleted_before: DateTime<Utc>) -> Result<Vec<Uuid>, AppError> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM users
+            WHERE deleted_at < $1 AND purged_at IS NULL
+            "#
+        )
+        .bind(deleted_before)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Remove everything an anonymized account left behind; only the tombstone row
+    /// stays, so rooms and audit entries that reference it remain valid
+    pub async fn purge_content(pool: &PgPool, user_id: Uuid) -> Result<u64, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let messages = sqlx::query("DELETE FROM messages WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        for (table, column) in [("room_bans", "user_id"), ("user_warnings", "user_id"), ("system_messages", "recipient_id")] {
+            sqlx::query(&format!("DELETE FROM {} WHERE {} = $1", table, column))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE users SET purged_at = NOW(), updated_at = NOW() WHERE id = $1
+            "#
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(messages)
+    }
 }
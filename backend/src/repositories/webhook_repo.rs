@@ -0,0 +1,94 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::webhook::RoomWebhook;
+
+pub struct WebhookRepository;
+
+impl WebhookRepository {
+    /// Create an incoming webhook
+    pub async fn create(
+        pool: &PgPool,
+        room_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        created_by: Uuid,
+    ) -> Result<RoomWebhook, AppError> {
+        let webhook = sqlx::query_as::<_, RoomWebhook>(
+            r#"
+            INSERT INTO room_webhooks (room_id, name, token_hash, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// List a room's active webhooks
+    pub async fn list_for_room(pool: &PgPool, room_id: Uuid) -> Result<Vec<RoomWebhook>, AppError> {
+        let webhooks = sqlx::query_as::<_, RoomWebhook>(
+            r#"
+            SELECT * FROM room_webhooks
+            WHERE room_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at
+            "#,
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    /// Find an active webhook
+    pub async fn find_active(pool: &PgPool, webhook_id: Uuid) -> Result<RoomWebhook, AppError> {
+        let webhook = sqlx::query_as::<_, RoomWebhook>(
+            r#"
+            SELECT * FROM room_webhooks WHERE id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(webhook_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::WebhookNotFound)?;
+
+        Ok(webhook)
+    }
+
+    /// Revoke a room's webhook
+    pub async fn revoke(pool: &PgPool, room_id: Uuid, webhook_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE room_webhooks SET revoked_at = NOW()
+            WHERE id = $1 AND room_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(room_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a use of a webhook
+    pub async fn touch(pool: &PgPool, webhook_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE room_webhooks SET last_used_at = NOW() WHERE id = $1
+            "#,
+        )
+        .bind(webhook_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
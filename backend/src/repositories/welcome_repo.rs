@@ -0,0 +1,66 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::welcome::RoomWelcome;
+
+pub struct WelcomeRepository;
+
+impl WelcomeRepository {
+    /// Find a room's welcome message
+    pub async fn find(pool: &PgPool, room_id: Uuid) -> Result<Option<RoomWelcome>, AppError> {
+        let welcome = sqlx::query_as::<_, RoomWelcome>(
+            r#"
+            SELECT * FROM room_welcome_messages WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(welcome)
+    }
+
+    /// Set a room's welcome message (replaces an existing one)
+    pub async fn upsert(
+        pool: &PgPool,
+        room_id: Uuid,
+        template: &str,
+        delivery: &str,
+        updated_by: Uuid,
+    ) -> Result<RoomWelcome, AppError> {
+        let welcome = sqlx::query_as::<_, RoomWelcome>(
+            r#"
+            INSERT INTO room_welcome_messages (room_id, template, delivery, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (room_id) DO UPDATE
+            SET template = EXCLUDED.template,
+                delivery = EXCLUDED.delivery,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(room_id)
+        .bind(template)
+        .bind(delivery)
+        .bind(updated_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(welcome)
+    }
+
+    /// Remove a room's welcome message
+    pub async fn delete(pool: &PgPool, room_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM room_welcome_messages WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,102 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::word_filter::WordFilter;
+
+pub struct WordFilterRepository;
+
+impl WordFilterRepository {
+    /// All filters, oldest first
+    pub async fn list(pool: &PgPool) -> Result<Vec<WordFilter>, AppError> {
+        let filters = sqlx::query_as::<_, WordFilter>(
+            r#"
+            SELECT * FROM word_filters ORDER BY created_at
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(filters)
+    }
+
+    /// Find a filter by ID
+    pub async fn find_by_id(pool: &PgPool, filter_id: Uuid) -> Result<WordFilter, AppError> {
+        sqlx::query_as::<_, WordFilter>(
+            r#"
+            SELECT * FROM word_filters WHERE id = $1
+            "#,
+        )
+        .bind(filter_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::WordFilterNotFound)
+    }
+
+    /// Add a filter
+    pub async fn create(
+        pool: &PgPool,
+        pattern: &str,
+        is_regex: bool,
+        action: &str,
+        created_by: Uuid,
+    ) -> Result<WordFilter, AppError> {
+        let filter = sqlx::query_as::<_, WordFilter>(
+            r#"
+            INSERT INTO word_filters (pattern, is_regex, action, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(pattern)
+        .bind(is_regex)
+        .bind(action)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(filter)
+    }
+
+    /// Replace a filter's pattern and action
+    pub async fn update(
+        pool: &PgPool,
+        filter_id: Uuid,
+        pattern: &str,
+        is_regex: bool,
+        action: &str,
+    ) -> Result<WordFilter, AppError> {
+        sqlx::query_as::<_, WordFilter>(
+            r#"
+            UPDATE word_filters
+            SET pattern = $2, is_regex = $3, action = $4, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(filter_id)
+        .bind(pattern)
+        .bind(is_regex)
+        .bind(action)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::WordFilterNotFound)
+    }
+
+    /// Remove a filter
+    pub async fn delete(pool: &PgPool, filter_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM word_filters WHERE id = $1
+            "#,
+        )
+        .bind(filter_id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::WordFilterNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,296 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::audit::{
+    AUDIT_ACCOUNT_CONTENT_PURGED, AUDIT_ACCOUNT_DEACTIVATED, AUDIT_ACCOUNT_DELETION_REQUESTED, AUDIT_ACCOUNT_PURGED,
+    AUDIT_ACCOUNT_REACTIVATED,
+};
+use crate::jobs;
+use crate::models::export::{DataExportArchive, DataExportStatus, EXPORT_FAILED, EXPORT_PENDING, EXPORT_READY};
+use crate::models::user::{AcceptTosDto, PasswordConfirmationDto, TosStatusResponse, User, ACCOUNT_DEACTIVATED};
+use crate::repositories::{AuditRepository, ExportRepository, SessionRepository, UserRepository};
+use crate::utils::password;
+
+/// How long a finished data export stays downloadable
+const EXPORT_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Redis keys of a data export (scoped by user so IDs can't be used across accounts)
+fn export_status_key(user_id: Uuid, export_id: Uuid) -> String {
+    format!("data_export:{}:{}:status", user_id, export_id)
+}
+
+fn export_archive_key(user_id: Uuid, export_id: Uuid) -> String {
+    format!("data_export:{}:{}:archive", user_id, export_id)
+}
+
+pub struct AccountService;
+
+impl AccountService {
+    /// Delete the own account: deactivate now, purge personal data after the grace period
+    pub async fn delete_account(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: PasswordConfirmationDto,
+    ) -> Result<(), AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("password", "Password is required");
+                AppError::ValidationError(errors)
+            })?;
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        let password_hash = UserRepository::find_password_hash(pool, user.id).await?;
+
+        // Re-authenticate so a stolen session alone cannot delete the account
+        if !password::verify_password(&dto.password, &password_hash)? {
+            return Err(AppError::InvalidCredentials);
+        }
+
+        UserRepository::request_deletion(pool, user.id).await?;
+        SessionRepository::revoke_all(pool, user.id).await?;
+
+        let purge_after = Utc::now() + Duration::days(config.account_deletion_grace_days);
+        AuditRepository::record(
+            pool,
+            Some(user.id),
+            AUDIT_ACCOUNT_DELETION_REQUESTED,
+            Some(user.id),
+            Some(serde_json::json!({ "purge_after": purge_after.to_rfc3339() })),
+        )
+        .await?;
+
+        log::info!("User {} requested account deletion (purge after {})", user.id, purge_after);
+
+        Ok(())
+    }
+
+    /// Deactivate the own account (hidden from other users until reactivated by logging in)
+    pub async fn deactivate_account(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: PasswordConfirmationDto,
+    ) -> Result<(), AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("password", "Password is required");
+                AppError::ValidationError(errors)
+            })?;
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        let password_hash = UserRepository::find_password_hash(pool, user.id).await?;
+
+        if !password::verify_password(&dto.password, &password_hash)? {
+            return Err(AppError::InvalidCredentials);
+        }
+
+        UserRepository::deactivate(pool, user.id).await?;
+        SessionRepository::revoke_all(pool, user.id).await?;
+
+        let reactivate_before = Utc::now() + Duration::days(config.account_reactivation_window_days);
+        AuditRepository::record(
+            pool,
+            Some(user.id),
+            AUDIT_ACCOUNT_DEACTIVATED,
+            Some(user.id),
+            Some(serde_json::json!({ "reactivate_before": reactivate_before.to_rfc3339() })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether a deactivated account may still be brought back by logging in
+    pub fn can_reactivate(config: &Config, user: &User) -> bool {
+        let window_start = Utc::now() - Duration::days(config.account_reactivation_window_days);
+
+        user.account_status() == ACCOUNT_DEACTIVATED
+            && user.deactivated_at.is_some_and(|deactivated_at| deactivated_at >= window_start)
+    }
+
+    /// The instance's current terms of service and the user's acceptance of them
+    pub async fn tos_status(pool: &PgPool, config: &Config, user_id: Uuid) -> Result<TosStatusResponse, AppError> {
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        Ok(Self::tos_status_of(config, user))
+    }
+
+    /// Accept the current terms of service. The client sends the version it showed,
+    /// so accepting an outdated page doesn't count as accepting the current terms.
+    pub async fn accept_tos(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: AcceptTosDto,
+    ) -> Result<TosStatusResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("version", "Version is required");
+                AppError::ValidationError(errors)
+            })?;
+
+        let Some(current_version) = config.tos_version.as_deref() else {
+            return Err(AppError::InvalidFormat("version".to_string()));
+        };
+
+        if dto.version != current_version {
+            return Err(AppError::TosAcceptanceRequired);
+        }
+
+        let user = UserRepository::accept_tos(pool, user_id, current_version).await?;
+
+        log::info!("User {} accepted terms of service {}", user.id, current_version);
+
+        Ok(Self::tos_status_of(config, user))
+    }
+
+    fn tos_status_of(config: &Config, user: User) -> TosStatusResponse {
+        TosStatusResponse {
+            acceptance_required: user.needs_tos_acceptance(config.tos_version.as_deref()),
+            current_version: config.tos_version.clone(),
+            accepted_version: user.tos_version,
+            accepted_at: user.tos_accepted_at,
+        }
+    }
+
+    /// Reactivate a deactivated account (after the user proved their password at login)
+    pub async fn reactivate_account(pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
+        let user = UserRepository::reactivate(pool, user_id).await?;
+        AuditRepository::record(pool, Some(user.id), AUDIT_ACCOUNT_REACTIVATED, Some(user.id), None).await?;
+
+        log::info!("User {} reactivated their account", user.id);
+
+        Ok(user)
+    }
+
+    /// Purge accounts whose deletion grace period or reactivation window has passed,
+    /// returning how many were purged
+    pub async fn purge_due_accounts(pool: &PgPool, config: &Config) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let user_ids = UserRepository::find_due_for_purge(
+            pool,
+            now - Duration::days(config.account_deletion_grace_days),
+            now - Duration::days(config.account_reactivation_window_days),
+        )
+        .await?;
+
+        let mut purged = 0;
+        for user_id in user_ids {
+            UserRepository::purge(pool, user_id).await?;
+            AuditRepository::record(pool, None, AUDIT_ACCOUNT_PURGED, Some(user_id), None).await?;
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+
+    /// Remove the remaining content of accounts anonymized longer than the retention
+    /// window ago, returning how many accounts were cleared
+    pub async fn purge_expired_content(pool: &PgPool, config: &Config) -> Result<u64, AppError> {
+        let user_ids =
+            UserRepository::find_due_for_content_purge(pool, Utc::now() - Duration::days(config.account_retention_days))
+                .await?;
+
+        let mut purged = 0;
+        for user_id in user_ids {
+            let messages = UserRepository::purge_content(pool, user_id).await?;
+            AuditRepository::record(
+                pool,
+                None,
+                AUDIT_ACCOUNT_CONTENT_PURGED,
+                Some(user_id),
+                Some(serde_json::json!({ "messages": messages })),
+            )
+            .await?;
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+
+    /// Start building a data export of the user in the background
+    pub async fn request_export(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        user_id: Uuid,
+    ) -> Result<DataExportStatus, AppError> {
+        let export_id = Uuid::new_v4();
+        cache::set_with_ttl(redis_pool, &export_status_key(user_id, export_id), EXPORT_PENDING, EXPORT_TTL_SECONDS).await?;
+
+        jobs::spawn_data_export(pool.clone(), redis_pool.clone(), user_id, export_id);
+
+        Ok(DataExportStatus {
+            id: export_id,
+            status: EXPORT_PENDING.to_string(),
+        })
+    }
+
+    /// Assemble the export archive and store it for download (run by the export job)
+    pub async fn build_export(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        user_id: Uuid,
+        export_id: Uuid,
+    ) -> Result<(), AppError> {
+        let status_key = export_status_key(user_id, export_id);
+
+        let archive = async {
+            let user = UserRepository::find_by_id(pool, user_id).await?;
+
+            Ok::<_, AppError>(DataExportArchive {
+                exported_at: Utc::now(),
+                profile: user.into(),
+                memberships: ExportRepository::memberships(pool, user_id).await?,
+                messages: ExportRepository::messages(pool, user_id).await?,
+            })
+        }
+        .await;
+
+        let archive = match archive {
+            Ok(archive) => archive,
+            Err(e) => {
+                cache::set_with_ttl(redis_pool, &status_key, EXPORT_FAILED, EXPORT_TTL_SECONDS).await?;
+                return Err(e);
+            }
+        };
+
+        let json = serde_json::to_string_pretty(&archive)
+            .map_err(|e| AppError::InternalError(format!("Failed to serialize export: {}", e)))?;
+
+        cache::set_with_ttl(redis_pool, &export_archive_key(user_id, export_id), &json, EXPORT_TTL_SECONDS).await?;
+        cache::set_with_ttl(redis_pool, &status_key, EXPORT_READY, EXPORT_TTL_SECONDS).await?;
+
+        Ok(())
+    }
+
+    /// Get the state of a data export, with the archive once it is ready
+    pub async fn get_export(
+        redis_pool: &RedisPool,
+        user_id: Uuid,
+        export_id: Uuid,
+    ) -> Result<(DataExportStatus, Option<String>), AppError> {
+        let status = cache::get_value(redis_pool, &export_status_key(user_id, export_id)).await?
+            .ok_or(AppError::ExportNotFound)?;
+
+        let archive = if status == EXPORT_READY {
+            Some(
+                cache::get_value(redis_pool, &export_archive_key(user_id, export_id)).await?
+                    .ok_or(AppError::ExportNotFound)?,
+            )
+        } else {
+            None
+        };
+
+        Ok((DataExportStatus { id: export_id, status }, archive))
+    }
+}
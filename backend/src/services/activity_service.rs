@@ -0,0 +1,69 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::activity::{ActivityItem, UnreadActivityResponse, ACTIVITY_KINDS};
+use crate::repositories::ActivityRepository;
+
+pub struct ActivityService;
+
+impl ActivityService {
+    /// Add an item to a user's activity feed.
+    /// Best effort: a failure is logged and never fails the action that caused it.
+    pub async fn record(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: &str,
+        room_id: Option<Uuid>,
+        actor_id: Option<Uuid>,
+        data: serde_json::Value,
+    ) {
+        if let Err(e) = ActivityRepository::record(pool, user_id, kind, room_id, actor_id, data).await {
+            log::warn!("Failed to record {} activity for user {}: {}", kind, user_id, e);
+        }
+    }
+
+    /// List own activity, newest first, optionally of one kind
+    pub async fn list(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: Option<&str>,
+        unread_only: bool,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<ActivityItem>, i64), AppError> {
+        if let Some(kind) = kind {
+            if !ACTIVITY_KINDS.contains(&kind) {
+                return Err(AppError::InvalidFormat("kind".to_string()));
+            }
+        }
+
+        let offset = ((page - 1) * per_page) as i64;
+        let items = ActivityRepository::list(pool, user_id, kind, unread_only, offset, per_page as i64).await?;
+        let total = ActivityRepository::count(pool, user_id, kind, unread_only).await?;
+
+        Ok((items, total))
+    }
+
+    /// Count own unread activity
+    pub async fn unread_count(pool: &PgPool, user_id: Uuid) -> Result<UnreadActivityResponse, AppError> {
+        let unread = ActivityRepository::count(pool, user_id, None, true).await?;
+
+        Ok(UnreadActivityResponse { unread })
+    }
+
+    /// Mark one of own activity items read
+    pub async fn mark_read(pool: &PgPool, user_id: Uuid, activity_id: Uuid) -> Result<(), AppError> {
+        if !ActivityRepository::mark_read(pool, user_id, activity_id).await? {
+            return Err(AppError::ActivityNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Mark all own activity read
+    pub async fn mark_all_read(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        ActivityRepository::mark_all_read(pool, user_id).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,752 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::audit::{
+    AuditLogFilter, AuditLogPage, AUDIT_BADGES_UPDATED, AUDIT_IMPERSONATED_REQUEST, AUDIT_IMPERSONATION_STARTED, AUDIT_PASSWORD_RESET_FORCED,
+    AUDIT_ROLE_CHANGED, AUDIT_ROOM_FORCE_DELETED, AUDIT_ROOM_LOCKED, AUDIT_ROOM_MERGED, AUDIT_ROOM_OWNER_REASSIGNED, AUDIT_ROOM_UNLOCKED,
+    AUDIT_SYSTEM_MESSAGE_SENT, AUDIT_USER_DELETED, AUDIT_USER_SUSPENDED, AUDIT_USER_UNSUSPENDED,
+};
+use crate::models::dm::{SendSystemMessageDto, SystemMessage, SYSTEM_MESSAGE_KINDS, SYSTEM_MESSAGE_NOTICE};
+use crate::models::moderation::EVENT_USER_SUSPENDED;
+use crate::models::rate_limit::RATE_LIMIT_CLASS_MESSAGE;
+use crate::models::room::{AdminRoomActionDto, MergeRoomDto, ReassignOwnerDto, Room, RoomMergeResponse, RoomResponse};
+use crate::models::session::SessionMeta;
+use crate::models::stats::{DailyMessageCount, MetricsSummaryResponse};
+use crate::models::user::{
+    ActiveUsersResponse, AdminUserFilter, AdminUserResponse, DeleteUserDto, ImpersonationResponse, SuspendUserDto, UpdateBadgesDto,
+    UpdateRoleDto, UserResponse, ACCOUNT_STATUSES, ROLE_ADMIN, ROLE_MODERATOR, ROLE_USER, SYSTEM_USER_ID,
+};
+use crate::repositories::{
+    AuditRepository, DmRepository, RoomRepository, SessionRepository, StatsRepository, UserRepository,
+};
+use crate::services::{AuthService, DmService, PushService, RateLimitService, RoomService, StatsService};
+use crate::utils::cursor;
+use crate::utils::jwt::{self, Claims, JwtKeys};
+
+/// Lifetime of an impersonation token (not renewable)
+const IMPERSONATION_TTL_SECONDS: i64 = 900;
+
+/// Longest window the active user count can look back
+const ACTIVE_USERS_MAX_DAYS: i64 = 90;
+
+/// Longest message history in the metrics summary
+const METRICS_SUMMARY_MAX_DAYS: i64 = 365;
+
+/// Largest page of the audit log query
+const AUDIT_LOG_MAX_LIMIT: u32 = 200;
+
+pub struct AdminService;
+
+impl AdminService {
+    /// Change a user's global role
+    pub async fn set_user_role(
+        pool: &PgPool,
+        admin_id: Uuid,
+        user_id: Uuid,
+        dto: UpdateRoleDto,
+    ) -> Result<UserResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid role data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if ![ROLE_USER, ROLE_MODERATOR, ROLE_ADMIN].contains(&dto.role.as_str()) {
+            return Err(AppError::InvalidFormat("role".to_string()));
+        }
+
+        // Admins can't demote themselves (avoids locking out the last admin)
+        if user_id == admin_id && dto.role != ROLE_ADMIN {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        let old_role = UserRepository::find_by_id(pool, user_id).await?.role;
+        let user = UserRepository::update_role(pool, user_id, &dto.role).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ROLE_CHANGED,
+            Some(user.id),
+            Some(serde_json::json!({
+                "old_role": old_role,
+                "new_role": user.role,
+            })),
+        )
+        .await?;
+
+        log::info!("User {} role set to {} by admin {}", user_id, dto.role, admin_id);
+
+        Ok(user.into())
+    }
+
+    /// Grant or revoke a user's verified and staff badges
+    pub async fn update_badges(
+        pool: &PgPool,
+        admin_id: Uuid,
+        user_id: Uuid,
+        dto: UpdateBadgesDto,
+    ) -> Result<UserResponse, AppError> {
+        let user = UserRepository::update_badges(pool, user_id, dto.verified, dto.staff).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_BADGES_UPDATED,
+            Some(user.id),
+            Some(serde_json::json!({
+                "verified": user.is_verified,
+                "staff": user.is_staff,
+            })),
+        )
+        .await?;
+
+        Ok(user.into())
+    }
+
+    /// Mint a time-boxed token acting as a user, for support debugging.
+    /// The token is marked with the admin's ID and runs in its own session,
+    /// which the user can see and revoke.
+    pub async fn impersonate(
+        pool: &PgPool,
+        keys: &JwtKeys,
+        admin_id: Uuid,
+        user_id: Uuid,
+        meta: SessionMeta,
+    ) -> Result<ImpersonationResponse, AppError> {
+        // Admin accounts can't be impersonated (no escalation between admins)
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        if user.id == admin_id || user.role == ROLE_ADMIN {
+            return Err(AppError::InsufficientPermissions);
+        }
+        if !user.is_active {
+            return Err(AppError::UserNotFound);
+        }
+
+        let meta = SessionMeta {
+            device_name: Some("Support session".to_string()),
+            ..meta
+        };
+        let session = SessionRepository::create(pool, user.id, &meta).await?;
+
+        let token = jwt::generate_impersonation_token(&user, session.id, admin_id, keys, IMPERSONATION_TTL_SECONDS)?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_IMPERSONATION_STARTED,
+            Some(user.id),
+            Some(serde_json::json!({
+                "session_id": session.id,
+                "expires_in": IMPERSONATION_TTL_SECONDS,
+                "ip_address": meta.ip_address,
+            })),
+        )
+        .await?;
+
+        log::warn!("Admin {} started impersonating user {}", admin_id, user.id);
+
+        Ok(ImpersonationResponse {
+            token,
+            user: user.into(),
+            impersonator_id: admin_id,
+            expires_in: IMPERSONATION_TTL_SECONDS,
+        })
+    }
+
+    /// Merge a room into another one; the old room ID keeps redirecting to the target
+    pub async fn merge_rooms(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        room_id: Uuid,
+        dto: MergeRoomDto,
+    ) -> Result<RoomMergeResponse, AppError> {
+        if dto.into_room_id == room_id {
+            return Err(AppError::InvalidFormat("into_room_id".to_string()));
+        }
+
+        let source = RoomRepository::find_by_id(pool, room_id).await?;
+        let target = RoomRepository::find_by_id(pool, dto.into_room_id).await?;
+
+        // Direct message membership is fixed
+        if source.is_direct_message() || target.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        let (members_moved, messages_moved) = RoomRepository::merge(pool, source.id, target.id, admin_id).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ROOM_MERGED,
+            Some(source.id),
+            Some(serde_json::json!({
+                "source_name": source.name,
+                "target_room_id": target.id,
+                "members_moved": members_moved,
+                "messages_moved": messages_moved,
+            })),
+        )
+        .await?;
+
+        // Tell clients still in the old room where it went (best effort)
+        let event = serde_json::json!({
+            "type": "room.merged",
+            "room_id": source.id,
+            "redirect_room_id": target.id,
+        });
+        if let Err(e) = cache::publish(redis_pool, &cache::room_channel(source.id), &event.to_string()).await {
+            log::warn!("Failed to broadcast merge of room {}: {}", source.id, e);
+        }
+
+        log::warn!("Admin {} merged room {} into room {}", admin_id, source.id, target.id);
+
+        Ok(RoomMergeResponse {
+            source_room_id: source.id,
+            target_room_id: target.id,
+            members_moved,
+            messages_moved,
+        })
+    }
+
+    /// Delete any room, whoever owns it; everything in it goes with it
+    pub async fn force_delete_room(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        room_id: Uuid,
+        dto: AdminRoomActionDto,
+    ) -> Result<(), AppError> {
+        Self::require_reason(&dto)?;
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        let member_count = RoomRepository::count_members(pool, room_id).await?;
+
+        RoomRepository::delete(pool, room_id).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ROOM_FORCE_DELETED,
+            Some(room.id),
+            Some(serde_json::json!({
+                "name": room.name,
+                "room_type": room.room_type,
+                "owner_id": room.owner_id,
+                "member_count": member_count,
+                "reason": dto.reason.trim(),
+            })),
+        )
+        .await?;
+
+        Self::broadcast_room(redis_pool, room.id, serde_json::json!({ "type": "room.deleted", "room_id": room.id })).await;
+
+        log::warn!("Admin {} deleted room {} ({})", admin_id, room.id, dto.reason.trim());
+
+        Ok(())
+    }
+
+    /// Lock a room: nobody can post, join or change its settings until it is unlocked
+    pub async fn lock_room(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        room_id: Uuid,
+        dto: AdminRoomActionDto,
+    ) -> Result<RoomResponse, AppError> {
+        Self::require_reason(&dto)?;
+
+        let room = RoomRepository::set_lock(pool, room_id, Some(dto.reason.trim())).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ROOM_LOCKED,
+            Some(room.id),
+            Some(serde_json::json!({ "reason": room.lock_reason })),
+        )
+        .await?;
+
+        Self::broadcast_room(
+            redis_pool,
+            room.id,
+            serde_json::json!({ "type": "room.locked", "room_id": room.id, "reason": room.lock_reason }),
+        ).await;
+
+        Self::room_response(pool, room).await
+    }
+
+    /// Lift a room lock
+    pub async fn unlock_room(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        room_id: Uuid,
+        dto: AdminRoomActionDto,
+    ) -> Result<RoomResponse, AppError> {
+        Self::require_reason(&dto)?;
+
+        let room = RoomRepository::set_lock(pool, room_id, None).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ROOM_UNLOCKED,
+            Some(room.id),
+            Some(serde_json::json!({ "reason": dto.reason.trim() })),
+        )
+        .await?;
+
+        Self::broadcast_room(redis_pool, room.id, serde_json::json!({ "type": "room.unlocked", "room_id": room.id })).await;
+
+        Self::room_response(pool, room).await
+    }
+
+    /// Hand a room to another user, whether or not either admin or user is a member;
+    /// the previous owner becomes a room admin
+    pub async fn reassign_room_owner(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        room_id: Uuid,
+        dto: ReassignOwnerDto,
+    ) -> Result<RoomResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("reason", "Reason must be between 1-500 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        if dto.user_id == SYSTEM_USER_ID {
+            return Err(AppError::InvalidFormat("user_id".to_string()));
+        }
+
+        // New owner must exist and not be banned from the room
+        let new_owner = UserRepository::find_by_id(pool, dto.user_id).await?;
+        RoomService::ensure_not_banned(pool, room.id, new_owner.id).await?;
+
+        RoomRepository::transfer_ownership(pool, room.id, new_owner.id).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ROOM_OWNER_REASSIGNED,
+            Some(room.id),
+            Some(serde_json::json!({
+                "previous_owner_id": room.owner_id,
+                "new_owner_id": new_owner.id,
+                "reason": dto.reason.trim(),
+            })),
+        )
+        .await?;
+
+        Self::broadcast_room(
+            redis_pool,
+            room.id,
+            serde_json::json!({
+                "type": "room.owner_changed",
+                "room_id": room.id,
+                "owner_id": new_owner.id,
+                "previous_owner_id": room.owner_id,
+            }),
+        ).await;
+
+        let room = RoomRepository::find_by_id(pool, room.id).await?;
+        Self::room_response(pool, room).await
+    }
+
+    /// Record a request made with an impersonation token
+    pub async fn record_impersonated_request(
+        pool: &PgPool,
+        claims: &Claims,
+        method: &str,
+        path: &str,
+    ) -> Result<(), AppError> {
+        let admin_id = claims
+            .imp
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or(AppError::InvalidToken)?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_IMPERSONATED_REQUEST,
+            Some(user_id),
+            Some(serde_json::json!({
+                "method": method,
+                "path": path,
+                "session_id": claims.sid,
+                "jti": claims.jti,
+            })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count non-bot users active within the last `days` days
+    pub async fn active_users(pool: &PgPool, days: i64) -> Result<ActiveUsersResponse, AppError> {
+        if !(1..=ACTIVE_USERS_MAX_DAYS).contains(&days) {
+            return Err(AppError::InvalidFormat("days".to_string()));
+        }
+
+        let active_users = UserRepository::count_active_since(pool, Utc::now() - Duration::days(days)).await?;
+
+        Ok(ActiveUsersResponse { days, active_users })
+    }
+
+    /// Audit log entries matching the filters, newest first, one page at a time
+    pub async fn audit_logs(
+        pool: &PgPool,
+        filter: AuditLogFilter,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<AuditLogPage, AppError> {
+        if !(1..=AUDIT_LOG_MAX_LIMIT).contains(&limit) {
+            return Err(AppError::InvalidFormat("limit".to_string()));
+        }
+
+        if let (Some(from), Some(until)) = (filter.from, filter.until) {
+            if from >= until {
+                return Err(AppError::InvalidFormat("until".to_string()));
+            }
+        }
+
+        let after = match cursor {
+            Some(cursor) => Some(cursor::decode(cursor).ok_or_else(|| AppError::InvalidFormat("cursor".to_string()))?),
+            None => None,
+        };
+
+        // One extra row tells whether there is a next page
+        let mut items = AuditRepository::query(pool, &filter, after, limit as i64 + 1).await?;
+
+        let next_cursor = if items.len() > limit as usize {
+            items.truncate(limit as usize);
+            items.last().map(|entry| cursor::encode(entry.created_at, entry.id))
+        } else {
+            None
+        };
+
+        Ok(AuditLogPage { items, next_cursor })
+    }
+
+    /// Instance overview with message counts for the last `days` days, from the metrics table
+    pub async fn metrics_summary(pool: &PgPool, days: i64) -> Result<MetricsSummaryResponse, AppError> {
+        if !(1..=METRICS_SUMMARY_MAX_DAYS).contains(&days) {
+            return Err(AppError::InvalidFormat("days".to_string()));
+        }
+
+        let since = (Utc::now() - Duration::days(days - 1)).date_naive();
+        let mut metrics = StatsRepository::list_instance(pool, since).await?;
+
+        // Fresh instance: take the first snapshot now rather than wait for the job
+        if metrics.is_empty() {
+            StatsService::aggregate_instance(pool).await?;
+            metrics = StatsRepository::list_instance(pool, since).await?;
+        }
+
+        let latest = metrics
+            .last()
+            .cloned()
+            .ok_or_else(|| AppError::InternalError("Instance metrics are missing".to_string()))?;
+
+        Ok(MetricsSummaryResponse {
+            total_users: latest.total_users,
+            daily_active_users: latest.daily_active_users,
+            monthly_active_users: latest.monthly_active_users,
+            total_rooms: latest.total_rooms,
+            storage_bytes: latest.storage_bytes,
+            messages_per_day: metrics
+                .into_iter()
+                .map(|day| DailyMessageCount { day: day.day, message_count: day.message_count })
+                .collect(),
+            updated_at: latest.updated_at,
+        })
+    }
+
+    /// Send an official DM from the System account. It lands in the user's conversation
+    /// with System, which they can read but not mute.
+    pub async fn send_system_message(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        admin_id: Uuid,
+        user_id: Uuid,
+        dto: SendSystemMessageDto,
+    ) -> Result<SystemMessage, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("content", "Content must be between 1-4000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let kind = dto.kind.as_deref().unwrap_or(SYSTEM_MESSAGE_NOTICE);
+        if !SYSTEM_MESSAGE_KINDS.contains(&kind) {
+            return Err(AppError::InvalidFormat("kind".to_string()));
+        }
+
+        // Sent by the admin, so it draws on their own message budget
+        RateLimitService::take_message_token(pool, redis_pool, config, RATE_LIMIT_CLASS_MESSAGE, admin_id).await?;
+
+        // Recipient must exist and be active; the conversation is created on first notice
+        let (conversation, _) = DmService::open(pool, SYSTEM_USER_ID, user_id).await?;
+
+        let message = DmRepository::create_system_message(
+            pool,
+            conversation.room_id,
+            user_id,
+            admin_id,
+            kind,
+            dto.content.trim(),
+        )
+        .await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_SYSTEM_MESSAGE_SENT,
+            Some(user_id),
+            Some(serde_json::json!({ "message_id": message.id, "kind": message.kind })),
+        )
+        .await?;
+
+        let event = serde_json::json!({
+            "type": "dm.system_message",
+            "room_id": message.room_id,
+            "message_id": message.id,
+            "author": {
+                "type": "system",
+                "user_id": SYSTEM_USER_ID,
+                "name": "System",
+            },
+            "kind": message.kind,
+            "content": message.content,
+            "created_at": message.created_at,
+        });
+        if let Err(e) = cache::publish(redis_pool, &cache::room_channel(message.room_id), &event.to_string()).await {
+            log::warn!("Failed to deliver system message {} to user {}: {}", message.id, user_id, e);
+        }
+
+        PushService::notify_direct_message(pool, redis_pool, config, message.room_id, user_id, "System", &message.content);
+
+        Ok(message)
+    }
+
+    /// Users matching the filters, newest first, with the total count
+    pub async fn list_users(
+        pool: &PgPool,
+        filter: AdminUserFilter,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<AdminUserResponse>, i64), AppError> {
+        let filter = Self::user_filter(filter)?;
+
+        let offset = ((page - 1) * per_page) as i64;
+        let users = UserRepository::admin_list(pool, &filter, offset, per_page as i64).await?;
+        let total = UserRepository::admin_count(pool, &filter).await?;
+
+        Ok((users.into_iter().map(AdminUserResponse::from).collect(), total))
+    }
+
+    /// Validate the admin user filters; the search term becomes a literal prefix
+    pub fn user_filter(mut filter: AdminUserFilter) -> Result<AdminUserFilter, AppError> {
+        if filter.status.as_deref().is_some_and(|status| !ACCOUNT_STATUSES.contains(&status)) {
+            return Err(AppError::InvalidFormat("status".to_string()));
+        }
+
+        // The search term is a literal prefix, not a LIKE pattern
+        filter.q = filter
+            .q
+            .map(|q| q.trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+            .filter(|q| !q.is_empty());
+
+        Ok(filter)
+    }
+
+    /// Any user, including deactivated, suspended and deleted accounts
+    pub async fn get_user(pool: &PgPool, user_id: Uuid) -> Result<AdminUserResponse, AppError> {
+        let user = UserRepository::find_any_by_id(pool, user_id).await?;
+
+        Ok(user.into())
+    }
+
+    /// Suspend a user instance-wide, signing them out everywhere
+    pub async fn suspend_user(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        user_id: Uuid,
+        dto: SuspendUserDto,
+    ) -> Result<AdminUserResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("reason", "Reason must be between 1-500 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        if dto.until.is_some_and(|until| until <= Utc::now()) {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("until", "Suspension end must be in the future");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        if user_id == admin_id {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        let user = UserRepository::suspend(pool, user_id, dto.reason.trim(), dto.until).await?;
+        SessionRepository::revoke_all(pool, user.id).await?;
+
+        // Revoked sessions stop new requests; open sockets have to be closed explicitly
+        if let Err(e) = cache::publish(redis_pool, &cache::user_disconnect_channel(user.id), "account_suspended").await {
+            log::warn!("Failed to disconnect suspended user {}: {}", user.id, e);
+        }
+
+        // Other clients hide the user's presence and content
+        let event = serde_json::json!({
+            "type": EVENT_USER_SUSPENDED,
+            "user_id": user.id,
+            "until": user.suspended_until,
+            "created_at": Utc::now(),
+        });
+        if let Err(e) = cache::publish(redis_pool, &cache::broadcast_channel(), &event.to_string()).await {
+            log::warn!("Failed to broadcast suspension of user {}: {}", user.id, e);
+        }
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_USER_SUSPENDED,
+            Some(user.id),
+            Some(serde_json::json!({ "reason": user.suspension_reason, "until": user.suspended_until })),
+        )
+        .await?;
+
+        Ok(user.into())
+    }
+
+    /// Delete a user's account: sign them out now, anonymize them after the deletion
+    /// grace period and purge their content after the retention window (jobs)
+    pub async fn delete_user(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        admin_id: Uuid,
+        user_id: Uuid,
+        dto: DeleteUserDto,
+    ) -> Result<AdminUserResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("reason", "Reason must be between 1-500 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        if user_id == admin_id || user_id == SYSTEM_USER_ID {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        let user = UserRepository::mark_deleted(pool, user_id).await?;
+        SessionRepository::revoke_all(pool, user.id).await?;
+
+        if let Err(e) = cache::publish(redis_pool, &cache::user_disconnect_channel(user.id), "account_deleted").await {
+            log::warn!("Failed to disconnect deleted user {}: {}", user.id, e);
+        }
+
+        let anonymize_after = Utc::now() + Duration::days(config.account_deletion_grace_days);
+        let purge_after = anonymize_after + Duration::days(config.account_retention_days);
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_USER_DELETED,
+            Some(user.id),
+            Some(serde_json::json!({
+                "username": user.username,
+                "reason": dto.reason.trim(),
+                "anonymize_after": anonymize_after.to_rfc3339(),
+                "purge_after": purge_after.to_rfc3339(),
+            })),
+        )
+        .await?;
+
+        log::warn!("Admin {} deleted user {} (anonymize after {})", admin_id, user.id, anonymize_after);
+
+        Ok(user.into())
+    }
+
+    /// Lift a user's suspension
+    pub async fn unsuspend_user(pool: &PgPool, admin_id: Uuid, user_id: Uuid) -> Result<AdminUserResponse, AppError> {
+        let user = UserRepository::unsuspend(pool, user_id).await?;
+
+        AuditRepository::record(pool, Some(admin_id), AUDIT_USER_UNSUSPENDED, Some(user.id), None).await?;
+
+        Ok(user.into())
+    }
+
+    /// Invalidate a user's password: sign them out everywhere and email a reset link
+    pub async fn force_password_reset(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        admin_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<AdminUserResponse, AppError> {
+        let user = UserRepository::require_password_reset(pool, user_id).await?;
+        SessionRepository::revoke_all(pool, user.id).await?;
+
+        // Close their open WebSocket connections too
+        if let Err(e) = cache::publish(redis_pool, &cache::user_disconnect_channel(user.id), "password_reset_required").await {
+            log::warn!("Failed to disconnect user {} after a forced password reset: {}", user.id, e);
+        }
+
+        AuditRepository::record(pool, Some(admin_id), AUDIT_PASSWORD_RESET_FORCED, Some(user.id), None).await?;
+
+        AuthService::send_password_reset(pool, config, &user).await?;
+
+        Ok(user.into())
+    }
+
+    /// Admin actions on rooms must say why
+    fn require_reason(dto: &AdminRoomActionDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("reason", "Reason must be between 1-500 characters");
+                AppError::ValidationError(errors)
+            })
+    }
+
+    /// Room with its member count
+    async fn room_response(pool: &PgPool, room: Room) -> Result<RoomResponse, AppError> {
+        let member_count = RoomRepository::count_members(pool, room.id).await?;
+
+        let mut response = RoomResponse::from(room);
+        response.member_count = member_count;
+
+        Ok(response)
+    }
+
+    /// Tell clients in a room about an admin action (best effort)
+    async fn broadcast_room(redis_pool: &RedisPool, room_id: Uuid, event: serde_json::Value) {
+        if let Err(e) = cache::publish(redis_pool, &cache::room_channel(room_id), &event.to_string()).await {
+            log::warn!("Failed to broadcast admin action on room {}: {}", room_id, e);
+        }
+    }
+}
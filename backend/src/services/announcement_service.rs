@@ -0,0 +1,162 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::error::{AppError, ValidationErrors};
+use crate::models::announcement::{
+    Announcement, CreateAnnouncementDto, UpdateAnnouncementDto, ANNOUNCEMENT_SEVERITIES, ANNOUNCEMENT_SEVERITY_INFO,
+};
+use crate::models::audit::{AUDIT_ANNOUNCEMENT_CREATED, AUDIT_ANNOUNCEMENT_DELETED, AUDIT_ANNOUNCEMENT_UPDATED};
+use crate::repositories::{AnnouncementRepository, AuditRepository};
+
+pub struct AnnouncementService;
+
+impl AnnouncementService {
+    /// Banners the user should see right now
+    pub async fn active(pool: &PgPool, user_id: Uuid) -> Result<Vec<Announcement>, AppError> {
+        AnnouncementRepository::list_active_for_user(pool, user_id).await
+    }
+
+    /// Hide a dismissible banner for the user on all their devices
+    pub async fn dismiss(pool: &PgPool, announcement_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let announcement = AnnouncementRepository::find_by_id(pool, announcement_id).await?;
+        if !announcement.dismissible {
+            return Err(AppError::AnnouncementNotDismissible);
+        }
+
+        AnnouncementRepository::dismiss(pool, announcement.id, user_id).await
+    }
+
+    /// All banners, including scheduled and expired ones (admin only)
+    pub async fn list(pool: &PgPool) -> Result<Vec<Announcement>, AppError> {
+        AnnouncementRepository::list(pool).await
+    }
+
+    /// Add a banner and push it to every connected client
+    pub async fn create(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        dto: CreateAnnouncementDto,
+    ) -> Result<Announcement, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("message", "Message must be between 1-1000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let severity = dto.severity.as_deref().unwrap_or(ANNOUNCEMENT_SEVERITY_INFO);
+        let starts_at = dto.starts_at.unwrap_or_else(Utc::now);
+        Self::check(severity, starts_at, dto.ends_at)?;
+
+        let announcement = AnnouncementRepository::create(
+            pool,
+            dto.message.trim(),
+            severity,
+            dto.dismissible.unwrap_or(true),
+            starts_at,
+            dto.ends_at,
+            admin_id,
+        )
+        .await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ANNOUNCEMENT_CREATED,
+            Some(announcement.id),
+            Some(serde_json::json!({ "message": announcement.message, "severity": announcement.severity })),
+        )
+        .await?;
+
+        let event = serde_json::json!({
+            "type": "announcement.created",
+            "announcement": announcement,
+        });
+
+        if let Err(e) = cache::publish(redis_pool, &cache::broadcast_channel(), &event.to_string()).await {
+            log::warn!("Failed to broadcast announcement {}: {}", announcement.id, e);
+        }
+
+        Ok(announcement)
+    }
+
+    /// Change a banner's content or display window
+    pub async fn update(
+        pool: &PgPool,
+        admin_id: Uuid,
+        announcement_id: Uuid,
+        dto: UpdateAnnouncementDto,
+    ) -> Result<Announcement, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("message", "Message must be between 1-1000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let current = AnnouncementRepository::find_by_id(pool, announcement_id).await?;
+        let message = dto.message.as_deref().map(str::trim).unwrap_or(&current.message);
+        let severity = dto.severity.as_deref().unwrap_or(&current.severity);
+        let dismissible = dto.dismissible.unwrap_or(current.dismissible);
+        let starts_at = dto.starts_at.unwrap_or(current.starts_at);
+        let ends_at = if dto.clear_ends_at { None } else { dto.ends_at.or(current.ends_at) };
+        Self::check(severity, starts_at, ends_at)?;
+
+        let announcement = AnnouncementRepository::update(
+            pool,
+            announcement_id,
+            message,
+            severity,
+            dismissible,
+            starts_at,
+            ends_at,
+        )
+        .await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ANNOUNCEMENT_UPDATED,
+            Some(announcement.id),
+            Some(serde_json::json!({ "message": announcement.message, "severity": announcement.severity })),
+        )
+        .await?;
+
+        Ok(announcement)
+    }
+
+    /// Remove a banner
+    pub async fn delete(pool: &PgPool, admin_id: Uuid, announcement_id: Uuid) -> Result<(), AppError> {
+        let announcement = AnnouncementRepository::find_by_id(pool, announcement_id).await?;
+        AnnouncementRepository::delete(pool, announcement.id).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_ANNOUNCEMENT_DELETED,
+            Some(announcement.id),
+            Some(serde_json::json!({ "message": announcement.message })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Severity must be known and the display window must not be empty
+    fn check(severity: &str, starts_at: DateTime<Utc>, ends_at: Option<DateTime<Utc>>) -> Result<(), AppError> {
+        if !ANNOUNCEMENT_SEVERITIES.contains(&severity) {
+            return Err(AppError::InvalidFormat("severity".to_string()));
+        }
+
+        if ends_at.is_some_and(|ends_at| ends_at <= starts_at) {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("ends_at", "End time must be after the start time");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        Ok(())
+    }
+}
@@ -1,17 +1,62 @@
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 use validator::Validate;
+use crate::cache::{self, RedisPool};
 use crate::config::Config;
 use crate::error::AppError;
-use crate::models::user::{User, CreateUserDto, LoginDto, AuthResponse, UserResponse};
-use crate::repositories::UserRepository;
-use crate::utils::{password, jwt};
+use crate::models::action_token::{ConsumeTokenDto, PURPOSE_CHANGE_EMAIL, PURPOSE_MAGIC_LOGIN, PURPOSE_RESET_PASSWORD, PURPOSE_VERIFY_EMAIL};
+use crate::models::audit::AUDIT_LOGIN_NEW_DEVICE;
+use crate::models::session::{Session, SessionMeta, SessionResponse};
+use crate::models::user::{User, CreateUserDto, LoginDto, ChangeEmailDto, MagicLinkDto, AuthResponse, ResetPasswordDto, ScopedTokenDto, ScopedTokenResponse, UserResponse, WsTicketResponse};
+use crate::repositories::{ActionTokenRepository, AuditRepository, SessionRepository, UserRepository};
+use crate::services::{AccountService, CaptchaService, MailService, PasswordService, UserService};
+use crate::utils::{password, jwt, local_time, secure_token};
+use crate::utils::jwt::{Claims, JwtKeys, SCOPE_FULL, SCOPE_WS};
+
+/// Lifetime of an email verification link
+const EMAIL_VERIFICATION_TTL_HOURS: i64 = 24;
+
+/// Lifetime of an email change confirmation link
+const EMAIL_CHANGE_TTL_HOURS: i64 = 1;
+
+/// Lifetime of a password reset link
+const PASSWORD_RESET_TTL_HOURS: i64 = 24;
+
+/// Lifetime of a magic login link
+const MAGIC_LINK_TTL_MINUTES: i64 = 15;
+
+/// Lifetime of a scoped token
+const SCOPED_TOKEN_TTL_SECONDS: i64 = 300;
+
+/// Lifetime of a WebSocket connection ticket
+const WS_TICKET_TTL_SECONDS: u64 = 30;
+
+/// Redis key of a WebSocket ticket (only its hash is stored)
+fn ws_ticket_key(ticket: &str) -> String {
+    format!("ws_ticket:{}", secure_token::hash(ticket))
+}
+
+/// What a WebSocket ticket stands for: the user and the session it was issued under
+/// (bots authenticate with an API key and have none)
+#[derive(Serialize, Deserialize)]
+struct WsTicket {
+    user_id: Uuid,
+    sid: Option<Uuid>,
+}
 
 pub struct AuthService;
 
 impl AuthService {
     /// Register a new user
-    pub async fn register(pool: &PgPool, config: &Config, dto: CreateUserDto) -> Result<AuthResponse, AppError> {
+    pub async fn register(
+        pool: &PgPool,
+        config: &Config,
+        keys: &JwtKeys,
+        dto: CreateUserDto,
+        meta: SessionMeta,
+    ) -> Result<AuthResponse, AppError> {
         // Validate input
         dto.validate()
             .map_err(|_| {
@@ -20,6 +65,16 @@ impl AuthService {
                 AppError::ValidationError(errors)
             })?;
 
+        // Reject reserved, offensive and lookalike names
+        UserService::check_names(config, Some(&dto.username), dto.display_name.as_deref())?;
+
+        // Verify CAPTCHA (no-op when disabled)
+        CaptchaService::verify(config, dto.captcha_token.as_deref(), meta.ip_address.as_deref()).await?;
+
+        // Enforce password policy
+        let email_local_part = dto.email.split('@').next().unwrap_or_default();
+        PasswordService::validate(config, &dto.password, &[&dto.username, email_local_part]).await?;
+
         // Check if email already exists
         if UserRepository::email_exists(pool, &dto.email).await? {
             return Err(AppError::EmailExists);
@@ -31,19 +86,18 @@ impl AuthService {
         }
 
         // Hash password
-        let password_hash = password::hash_password(&dto.password)?;
+        let password_hash = password::hash_password(&dto.password, &config.argon2)?;
 
         // Create user in database
         let user = UserRepository::create(pool, &dto, &password_hash).await?;
 
-        // Generate JWT token
-        let token = jwt::generate_token(
-            user.id,
-            &user.email,
-            &user.username,
-            &config.jwt_secret,
-            config.jwt_expires_in,
-        )?;
+        // Send verification link (the account stays usable if delivery fails)
+        if let Err(e) = Self::send_verification_email(pool, config, &user).await {
+            log::warn!("Failed to send verification email to user {}: {}", user.id, e);
+        }
+
+        // Start a session and generate JWT token
+        let token = Self::start_session(pool, config, keys, &user, &meta).await?;
 
         Ok(AuthResponse {
             user: user.into(),
@@ -51,11 +105,174 @@ impl AuthService {
         })
     }
 
+    /// Verify email address with the emailed token
+    pub async fn verify_email(
+        pool: &PgPool,
+        dto: ConsumeTokenDto,
+    ) -> Result<UserResponse, AppError> {
+        let token = ActionTokenRepository::consume(pool, PURPOSE_VERIFY_EMAIL, &secure_token::hash(&dto.token))
+            .await?
+            .ok_or(AppError::InvalidActionToken)?;
+
+        UserRepository::mark_email_verified(pool, token.user_id).await?;
+
+        let user = UserRepository::find_by_id(pool, token.user_id).await?;
+        Ok(user.into())
+    }
+
+    /// Re-send the verification link to the current user
+    pub async fn resend_verification(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+
+        // Nothing to do for already verified accounts
+        if user.email_verified_at.is_some() {
+            return Ok(());
+        }
+
+        Self::send_verification_email(pool, config, &user).await
+    }
+
+    /// Request an email change: confirm on the new address, notify the old one
+    pub async fn request_email_change(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: ChangeEmailDto,
+    ) -> Result<(), AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = crate::error::ValidationErrors::new();
+                errors.add_field_error("new_email", "Invalid email format");
+                AppError::ValidationError(errors)
+            })?;
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        let password_hash = UserRepository::find_password_hash(pool, user.id).await?;
+
+        // Re-authenticate so a stolen session alone cannot take over the account
+        if !password::verify_password(&dto.password, &password_hash)? {
+            return Err(AppError::InvalidCredentials);
+        }
+
+        if UserRepository::email_exists(pool, &dto.new_email).await? {
+            return Err(AppError::EmailExists);
+        }
+
+        // Only the latest request stays valid
+        ActionTokenRepository::invalidate(pool, user.id, PURPOSE_CHANGE_EMAIL).await?;
+
+        let token = secure_token::generate();
+        let expires_at = Utc::now() + Duration::hours(EMAIL_CHANGE_TTL_HOURS);
+        ActionTokenRepository::create(
+            pool,
+            user.id,
+            PURPOSE_CHANGE_EMAIL,
+            &secure_token::hash(&token),
+            Some(&dto.new_email),
+            expires_at,
+        )
+        .await?;
+
+        let link = format!("{}/confirm-email?token={}", config.app_base_url.trim_end_matches('/'), token);
+        let confirm_body = format!(
+            "Hi {},\n\nConfirm that you want to use this address for your Ngobrol account:\n\n{}\n\nThe link expires in {} hour(s).",
+            user.username, link, EMAIL_CHANGE_TTL_HOURS
+        );
+        MailService::send(config, &dto.new_email, "Confirm your new Ngobrol email address", confirm_body).await?;
+
+        let notice_body = format!(
+            "Hi {},\n\nA request was made to change the email address of your Ngobrol account to {}.\n\nIf this wasn't you, change your password and log out of all devices.",
+            user.username, dto.new_email
+        );
+        if let Err(e) = MailService::send(config, &user.email, "Your Ngobrol email address is being changed", notice_body).await {
+            log::warn!("Failed to notify user {} about email change: {}", user.id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Confirm an email change with the token sent to the new address
+    pub async fn confirm_email_change(
+        pool: &PgPool,
+        dto: ConsumeTokenDto,
+    ) -> Result<UserResponse, AppError> {
+        let token = ActionTokenRepository::consume(pool, PURPOSE_CHANGE_EMAIL, &secure_token::hash(&dto.token))
+            .await?
+            .ok_or(AppError::InvalidActionToken)?;
+
+        let new_email = token.payload.ok_or(AppError::InvalidActionToken)?;
+
+        // The address may have been taken since the request
+        if UserRepository::email_exists(pool, &new_email).await? {
+            return Err(AppError::EmailExists);
+        }
+
+        let user = UserRepository::update_email(pool, token.user_id, &new_email).await?;
+        Ok(user.into())
+    }
+
+    /// Email a password reset link, invalidating earlier ones
+    pub async fn send_password_reset(pool: &PgPool, config: &Config, user: &User) -> Result<(), AppError> {
+        ActionTokenRepository::invalidate(pool, user.id, PURPOSE_RESET_PASSWORD).await?;
+
+        let token = secure_token::generate();
+        let expires_at = Utc::now() + Duration::hours(PASSWORD_RESET_TTL_HOURS);
+        ActionTokenRepository::create(
+            pool,
+            user.id,
+            PURPOSE_RESET_PASSWORD,
+            &secure_token::hash(&token),
+            None,
+            expires_at,
+        )
+        .await?;
+
+        let link = format!("{}/reset-password?token={}", config.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nYou need to choose a new password for your Ngobrol account before you can sign in again:\n\n{}\n\nThe link expires in {} hour(s).",
+            user.username, link, PASSWORD_RESET_TTL_HOURS
+        );
+
+        MailService::send(config, &user.email, "Reset your Ngobrol password", body).await
+    }
+
+    /// Set a new password with the token from a reset link, signing out everywhere
+    pub async fn reset_password(pool: &PgPool, config: &Config, dto: ResetPasswordDto) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = crate::error::ValidationErrors::new();
+                errors.add_field_error("token", "Token is required");
+                AppError::ValidationError(errors)
+            })?;
+
+        let token = ActionTokenRepository::consume(pool, PURPOSE_RESET_PASSWORD, &secure_token::hash(&dto.token))
+            .await?
+            .ok_or(AppError::InvalidActionToken)?;
+
+        let user = UserRepository::find_by_id(pool, token.user_id).await?;
+
+        let email_local_part = user.email.split('@').next().unwrap_or_default();
+        PasswordService::validate(config, &dto.new_password, &[&user.username, email_local_part]).await?;
+
+        let password_hash = password::hash_password(&dto.new_password, &config.argon2)?;
+        UserRepository::complete_password_reset(pool, user.id, &password_hash).await?;
+        SessionRepository::revoke_all(pool, user.id).await?;
+
+        Ok(())
+    }
+
     /// Login user
     pub async fn login(
         pool: &PgPool,
         config: &Config,
+        keys: &JwtKeys,
         dto: LoginDto,
+        mut meta: SessionMeta,
     ) -> Result<AuthResponse, AppError> {
         // Validate input
         dto.validate()
@@ -65,10 +282,15 @@ impl AuthService {
                 AppError::ValidationError(errors)
             })?;
 
-        // Find user by email
-        let user = UserRepository::find_by_email(pool, &dto.email)
-            .await
-            .map_err(|_| AppError::InvalidCredentials)?;
+        // Find user by email (self-deactivated accounts may still log in to reactivate;
+        // accounts pending deletion may not)
+        let user = match UserRepository::find_by_email(pool, &dto.email).await {
+            Ok(user) => user,
+            Err(_) => UserRepository::find_by_email_any_state(pool, &dto.email)
+                .await?
+                .filter(|user| AccountService::can_reactivate(config, user))
+                .ok_or(AppError::InvalidCredentials)?,
+        };
 
         // Verify password
         let is_valid = password::verify_password(&dto.password, &user.password_hash)?;
@@ -77,17 +299,102 @@ impl AuthService {
             return Err(AppError::InvalidCredentials);
         }
 
+        // Checked before a deactivated account is brought back
+        Self::ensure_can_sign_in(&user)?;
+
+        // Logging in within the reactivation window brings a deactivated account back
+        let user = if user.is_active {
+            user
+        } else {
+            AccountService::reactivate_account(pool, user.id).await?
+        };
+
+        // Upgrade hashes created under weaker Argon2 parameters (best effort)
+        if password::needs_rehash(&user.password_hash, &config.argon2) {
+            match password::hash_password(&dto.password, &config.argon2) {
+                Ok(hash) => {
+                    if let Err(e) = UserRepository::update_password_hash(pool, user.id, &hash).await {
+                        log::warn!("Failed to store rehashed password for user {}: {}", user.id, e);
+                    }
+                }
+                Err(e) => log::warn!("Failed to rehash password for user {}: {}", user.id, e),
+            }
+        }
+
         // Update user status to online
         UserRepository::update_status(pool, user.id, "online").await?;
 
-        // Generate JWT token
-        let token = jwt::generate_token(
-            user.id,
-            &user.email,
-            &user.username,
-            &config.jwt_secret,
-            config.jwt_expires_in,
-        )?;
+        // Start a session and generate JWT token
+        // (a client-supplied device name wins over the User-Agent guess)
+        if dto.device_name.is_some() {
+            meta.device_name = dto.device_name;
+        }
+        let token = Self::start_session(pool, config, keys, &user, &meta).await?;
+
+        Ok(AuthResponse {
+            user: user.into(),
+            token,
+        })
+    }
+
+    /// Email a one-time login link
+    /// Always succeeds for well-formed input so accounts cannot be enumerated
+    pub async fn request_magic_link(
+        pool: &PgPool,
+        config: &Config,
+        dto: MagicLinkDto,
+    ) -> Result<(), AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = crate::error::ValidationErrors::new();
+                errors.add_field_error("email", "Invalid email format");
+                AppError::ValidationError(errors)
+            })?;
+
+        let user = match UserRepository::find_by_email(pool, &dto.email).await {
+            Ok(user) => user,
+            Err(_) => return Ok(()),
+        };
+
+        // Only the latest link stays valid
+        ActionTokenRepository::invalidate(pool, user.id, PURPOSE_MAGIC_LOGIN).await?;
+
+        let token = secure_token::generate();
+        let expires_at = Utc::now() + Duration::minutes(MAGIC_LINK_TTL_MINUTES);
+        ActionTokenRepository::create(pool, user.id, PURPOSE_MAGIC_LOGIN, &secure_token::hash(&token), None, expires_at)
+            .await?;
+
+        let link = format!("{}/magic-login?token={}", config.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nUse the link below to log in to Ngobrol:\n\n{}\n\nThe link can be used once and expires in {} minutes. If you didn't request it, you can ignore this email.",
+            user.username, link, MAGIC_LINK_TTL_MINUTES
+        );
+
+        MailService::send(config, &user.email, "Your Ngobrol login link", body).await
+    }
+
+    /// Log in with a magic link token
+    pub async fn consume_magic_link(
+        pool: &PgPool,
+        config: &Config,
+        keys: &JwtKeys,
+        dto: ConsumeTokenDto,
+        meta: SessionMeta,
+    ) -> Result<AuthResponse, AppError> {
+        let token = ActionTokenRepository::consume(pool, PURPOSE_MAGIC_LOGIN, &secure_token::hash(&dto.token))
+            .await?
+            .ok_or(AppError::InvalidActionToken)?;
+
+        // Checked before the link has any effect on the account
+        Self::ensure_can_sign_in(&UserRepository::find_by_id(pool, token.user_id).await?)?;
+
+        // Opening the link proves control of the mailbox
+        UserRepository::mark_email_verified(pool, token.user_id).await?;
+        UserRepository::update_status(pool, token.user_id, "online").await?;
+
+        let user = UserRepository::find_by_id(pool, token.user_id).await?;
+        let token = Self::start_session(pool, config, keys, &user, &meta).await?;
 
         Ok(AuthResponse {
             user: user.into(),
@@ -104,38 +411,302 @@ impl AuthService {
         Ok(user.into())
     }
 
-    /// Logout user (update status to offline)
+    /// Logout user (revoke current token and session, update status to offline)
     pub async fn logout(
         pool: &PgPool,
+        redis_pool: &RedisPool,
         user_id: Uuid,
+        claims: &Claims,
     ) -> Result<(), AppError> {
+        // Deny the token for the rest of its lifetime
+        let ttl = claims.exp - Utc::now().timestamp();
+        cache::revoke_token(redis_pool, &claims.jti, ttl).await?;
+
+        let session_id = Uuid::parse_str(&claims.sid).map_err(|_| AppError::InvalidToken)?;
+        SessionRepository::revoke(pool, session_id, user_id).await?;
+
         UserRepository::update_status(pool, user_id, "offline").await?;
         Ok(())
     }
 
-    /// Verify JWT token and return user
+    /// Logout from all devices (revoke every session of the user)
+    pub async fn logout_all(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<u64, AppError> {
+        let revoked = SessionRepository::revoke_all(pool, user_id).await?;
+        UserRepository::update_status(pool, user_id, "offline").await?;
+        Ok(revoked)
+    }
+
+    /// List active sessions of the user
+    pub async fn list_sessions(
+        pool: &PgPool,
+        user_id: Uuid,
+        claims: &Claims,
+    ) -> Result<Vec<SessionResponse>, AppError> {
+        let sessions = SessionRepository::list_active(pool, user_id).await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|s| SessionResponse::from_session(s, &claims.sid))
+            .collect())
+    }
+
+    /// Revoke one session of the user
+    pub async fn revoke_session(
+        pool: &PgPool,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<(), AppError> {
+        if !SessionRepository::revoke(pool, session_id, user_id).await? {
+            return Err(AppError::SessionNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Mint a short-lived restricted token (websocket ticket only) from a full-access token
+    pub async fn issue_scoped_token(
+        keys: &JwtKeys,
+        claims: &Claims,
+        dto: ScopedTokenDto,
+    ) -> Result<ScopedTokenResponse, AppError> {
+        if claims.scope != SCOPE_FULL {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        if dto.scope != SCOPE_WS {
+            return Err(AppError::InvalidFormat("scope".to_string()));
+        }
+
+        let token = jwt::generate_scoped_token(claims, &dto.scope, keys, SCOPED_TOKEN_TTL_SECONDS)?;
+
+        Ok(ScopedTokenResponse {
+            token,
+            scope: dto.scope,
+            expires_in: SCOPED_TOKEN_TTL_SECONDS,
+        })
+    }
+
+    /// Issue a single-use ticket for opening a WebSocket connection
+    pub async fn issue_ws_ticket(
+        redis_pool: &RedisPool,
+        user_id: Uuid,
+        session_id: Option<Uuid>,
+    ) -> Result<WsTicketResponse, AppError> {
+        let ticket = secure_token::generate();
+        let value = serde_json::to_string(&WsTicket { user_id, sid: session_id })
+            .map_err(|e| AppError::InternalError(format!("Failed to encode WebSocket ticket: {}", e)))?;
+        cache::set_with_ttl(redis_pool, &ws_ticket_key(&ticket), &value, WS_TICKET_TTL_SECONDS).await?;
+
+        Ok(WsTicketResponse {
+            ticket,
+            expires_in: WS_TICKET_TTL_SECONDS,
+        })
+    }
+
+    /// Redeem a WebSocket ticket (works once) and return its user. The account and
+    /// session are checked again, since either may have changed since it was issued
+    pub async fn consume_ws_ticket(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        ticket: &str,
+    ) -> Result<User, AppError> {
+        let ticket: WsTicket = cache::take_value(redis_pool, &ws_ticket_key(ticket)).await?
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .ok_or(AppError::InvalidToken)?;
+
+        let session_active = match ticket.sid {
+            Some(session_id) => Some(SessionRepository::touch(pool, session_id, ticket.user_id).await?),
+            None => None,
+        };
+
+        let user = UserRepository::find_by_id(pool, ticket.user_id).await?;
+        Self::ensure_can_connect(&user, session_active)?;
+
+        Ok(user)
+    }
+
+    /// Verify JWT token for one of the accepted scopes and return user with the decoded claims
     pub async fn verify_token(
         pool: &PgPool,
-        config: &Config,
+        redis_pool: &RedisPool,
+        keys: &JwtKeys,
         token: &str,
-    ) -> Result<User, AppError> {
+        scopes: &[&str],
+    ) -> Result<(User, Claims), AppError> {
         // Verify and decode token
-        let claims = jwt::verify_token(token, &config.jwt_secret)?;
+        let claims = jwt::verify_token(token, keys)?;
+
+        // Restricted tokens only work where their scope is accepted
+        if !scopes.contains(&claims.scope.as_str()) {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        // Reject tokens revoked by logout
+        if cache::is_token_revoked(redis_pool, &claims.jti).await? {
+            return Err(AppError::TokenRevoked);
+        }
 
-        // Parse user ID from claims
+        // Parse user ID and session ID from claims
         let user_id = Uuid::parse_str(&claims.sub)
             .map_err(|_| AppError::InvalidToken)?;
+        let session_id = Uuid::parse_str(&claims.sid)
+            .map_err(|_| AppError::InvalidToken)?;
+
+        // Reject tokens whose session was revoked
+        if !SessionRepository::touch(pool, session_id, user_id).await? {
+            return Err(AppError::TokenRevoked);
+        }
 
         // Fetch user from database
         let user = UserRepository::find_by_id(pool, user_id).await?;
 
-        Ok(user)
+        if user.is_suspended() {
+            return Err(AppError::AccountLocked);
+        }
+
+        // Role changes take effect immediately rather than at token expiry
+        let mut claims = claims;
+        claims.role = user.role.clone();
+
+        Ok((user, claims))
+    }
+
+    /// Issue a new verification token (invalidating older ones) and email the link
+    async fn send_verification_email(
+        pool: &PgPool,
+        config: &Config,
+        user: &User,
+    ) -> Result<(), AppError> {
+        ActionTokenRepository::invalidate(pool, user.id, PURPOSE_VERIFY_EMAIL).await?;
+
+        let token = secure_token::generate();
+        let expires_at = Utc::now() + Duration::hours(EMAIL_VERIFICATION_TTL_HOURS);
+        ActionTokenRepository::create(pool, user.id, PURPOSE_VERIFY_EMAIL, &secure_token::hash(&token), None, expires_at)
+            .await?;
+
+        let link = format!("{}/verify-email?token={}", config.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nPlease confirm your email address by opening the link below:\n\n{}\n\nThe link expires in {} hours.",
+            user.username, link, EMAIL_VERIFICATION_TTL_HOURS
+        );
+
+        MailService::send(config, &user.email, "Verify your Ngobrol email address", body).await
+    }
+
+    /// Email the user about a login from an unseen device and record it in the audit log
+    async fn alert_new_device(
+        pool: &PgPool,
+        config: &Config,
+        user: &User,
+        session: &Session,
+    ) -> Result<(), AppError> {
+        let unknown = "unknown".to_string();
+
+        AuditRepository::record(
+            pool,
+            Some(user.id),
+            AUDIT_LOGIN_NEW_DEVICE,
+            Some(session.id),
+            Some(serde_json::json!({
+                "device_name": session.device_name,
+                "ip_address": session.ip_address,
+                "location": session.location,
+            })),
+        )
+        .await?;
+
+        let body = format!(
+            "Hi {},\n\nYour Ngobrol account was just signed in from a new device:\n\nDevice: {}\nIP address: {}\nLocation: {}\nTime: {}\n\nIf this was you, you can ignore this email. If not, change your password and sign out the session from your account settings.",
+            user.username,
+            session.device_name.as_ref().unwrap_or(&unknown),
+            session.ip_address.as_ref().unwrap_or(&unknown),
+            session.location.as_ref().unwrap_or(&unknown),
+            local_time::format(session.created_at, &user.timezone),
+        );
+
+        MailService::send(config, &user.email, "New sign-in to your Ngobrol account", body).await
+    }
+
+    /// Refuse sign-in for accounts that may not hold a session: bots (they authenticate
+    /// with their API key only), accounts with an admin-forced password reset pending
+    /// and suspended accounts
+    fn ensure_can_sign_in(user: &User) -> Result<(), AppError> {
+        if user.is_bot {
+            return Err(AppError::InvalidCredentials);
+        }
+
+        // An admin-forced reset invalidates the current password
+        if user.password_reset_required {
+            return Err(AppError::PasswordResetRequired);
+        }
+
+        if user.is_suspended() {
+            return Err(AppError::AccountLocked);
+        }
+
+        Ok(())
+    }
+
+    /// Refuse a WebSocket ticket whose session was revoked (`session_active` is None for
+    /// bots, which have no session) or whose account may no longer sign in
+    fn ensure_can_connect(user: &User, session_active: Option<bool>) -> Result<(), AppError> {
+        match session_active {
+            Some(false) => Err(AppError::TokenRevoked),
+            None if !user.is_bot => Err(AppError::InvalidToken),
+            None if user.is_suspended() => Err(AppError::AccountLocked),
+            None => Ok(()),
+            Some(true) => Self::ensure_can_sign_in(user).map_err(|_| AppError::AccountLocked),
+        }
+    }
+
+    /// Create a session for the user and issue a token bound to it
+    async fn start_session(
+        pool: &PgPool,
+        config: &Config,
+        keys: &JwtKeys,
+        user: &User,
+        meta: &SessionMeta,
+    ) -> Result<String, AppError> {
+        // Every way of signing in ends here, so the account state is enforced once
+        Self::ensure_can_sign_in(user)?;
+
+        // A user's very first session (registration) is not a "new device"
+        let (has_sessions, device_seen) = if config.new_device_alerts {
+            SessionRepository::device_history(pool, user.id, meta).await?
+        } else {
+            (false, true)
+        };
+
+        let session = SessionRepository::create(pool, user.id, meta).await?;
+
+        if has_sessions && !device_seen {
+            // Best effort: an alert failure must not block the login
+            if let Err(e) = Self::alert_new_device(pool, config, user, &session).await {
+                log::warn!("Failed to send new device alert to user {}: {}", user.id, e);
+            }
+        }
+
+        let token = jwt::generate_token(
+            user.id,
+            session.id,
+            &user.email,
+            &user.username,
+            &user.role,
+            keys,
+            config.jwt_expires_in,
+        )?;
+
+        Ok(token)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::user::test_user;
 
     // Note: These tests require a test database setup
     // For now, they are placeholders for the test structure
@@ -164,4 +735,66 @@ mod tests {
     async fn test_login_invalid_credentials() {
         // TODO: Test invalid credentials error
     }
+
+    #[test]
+    fn test_sign_in_allowed_for_regular_user() {
+        assert!(AuthService::ensure_can_sign_in(&test_user()).is_ok());
+    }
+
+    #[test]
+    fn test_sign_in_refused_for_bot() {
+        let user = User { is_bot: true, ..test_user() };
+        assert!(matches!(AuthService::ensure_can_sign_in(&user), Err(AppError::InvalidCredentials)));
+    }
+
+    #[test]
+    fn test_sign_in_refused_while_password_reset_required() {
+        let user = User { password_reset_required: true, ..test_user() };
+        assert!(matches!(AuthService::ensure_can_sign_in(&user), Err(AppError::PasswordResetRequired)));
+    }
+
+    #[test]
+    fn test_sign_in_refused_while_suspended() {
+        let user = User { suspended_at: Some(Utc::now()), ..test_user() };
+        assert!(matches!(AuthService::ensure_can_sign_in(&user), Err(AppError::AccountLocked)));
+
+        // A lapsed suspension no longer blocks
+        let user = User {
+            suspended_at: Some(Utc::now() - Duration::days(2)),
+            suspended_until: Some(Utc::now() - Duration::days(1)),
+            ..test_user()
+        };
+        assert!(AuthService::ensure_can_sign_in(&user).is_ok());
+    }
+
+    #[test]
+    fn test_ws_ticket_usable_while_session_active() {
+        assert!(AuthService::ensure_can_connect(&test_user(), Some(true)).is_ok());
+
+        let bot = User { is_bot: true, ..test_user() };
+        assert!(AuthService::ensure_can_connect(&bot, None).is_ok());
+    }
+
+    #[test]
+    fn test_ws_ticket_refused_for_revoked_session() {
+        assert!(matches!(AuthService::ensure_can_connect(&test_user(), Some(false)), Err(AppError::TokenRevoked)));
+
+        // Only bots hold tickets without a session
+        assert!(matches!(AuthService::ensure_can_connect(&test_user(), None), Err(AppError::InvalidToken)));
+    }
+
+    #[test]
+    fn test_ws_ticket_refused_for_suspended_user() {
+        let user = User { suspended_at: Some(Utc::now()), ..test_user() };
+        assert!(matches!(AuthService::ensure_can_connect(&user, Some(true)), Err(AppError::AccountLocked)));
+
+        let bot = User { is_bot: true, suspended_at: Some(Utc::now()), ..test_user() };
+        assert!(matches!(AuthService::ensure_can_connect(&bot, None), Err(AppError::AccountLocked)));
+    }
+
+    #[test]
+    fn test_ws_ticket_refused_while_password_reset_required() {
+        let user = User { password_reset_required: true, ..test_user() };
+        assert!(matches!(AuthService::ensure_can_connect(&user, Some(true)), Err(AppError::AccountLocked)));
+    }
 }
@@ -0,0 +1,109 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::bot::{
+    BotApiKeyResponse, BotResponse, CreateBotDto, CreatedBotResponse, BOT_API_KEY_PREFIX, BOT_MAX_PER_OWNER,
+};
+use crate::models::user::User;
+use crate::repositories::{BotRepository, UserRepository};
+use crate::services::UserService;
+use crate::utils::{password, secure_token};
+
+pub struct BotService;
+
+impl BotService {
+    /// Create a bot owned by the current user; the API key is only returned here
+    pub async fn create_bot(
+        pool: &PgPool,
+        config: &Config,
+        owner_id: Uuid,
+        dto: CreateBotDto,
+    ) -> Result<CreatedBotResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid bot data");
+                AppError::ValidationError(errors)
+            })?;
+
+        // Bots can't own bots
+        let owner = UserRepository::find_by_id(pool, owner_id).await?;
+        if owner.is_bot {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        UserService::check_names(config, Some(&dto.username), dto.display_name.as_deref())?;
+
+        if UserRepository::username_exists(pool, &dto.username).await? {
+            return Err(AppError::UsernameExists);
+        }
+
+        if BotRepository::count_owned(pool, owner_id).await? >= BOT_MAX_PER_OWNER {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("username", "You have reached the maximum number of bots");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        // Bots never log in with a password; store a hash of a secret nobody knows
+        let password_hash = password::hash_password(&secure_token::generate(), &config.argon2)?;
+        let api_key = Self::generate_api_key();
+
+        let bot = BotRepository::create(pool, owner_id, &dto, &password_hash, &secure_token::hash(&api_key)).await?;
+
+        log::info!("Bot {} created by {}", bot.id, owner_id);
+
+        Ok(CreatedBotResponse { bot, api_key })
+    }
+
+    /// List own bots
+    pub async fn list_bots(pool: &PgPool, owner_id: Uuid) -> Result<Vec<BotResponse>, AppError> {
+        BotRepository::list_owned(pool, owner_id).await
+    }
+
+    /// Issue a new API key for an own bot, revoking the previous one
+    pub async fn rotate_key(pool: &PgPool, owner_id: Uuid, bot_id: Uuid) -> Result<BotApiKeyResponse, AppError> {
+        let api_key = Self::generate_api_key();
+
+        if !BotRepository::rotate_key(pool, owner_id, bot_id, &secure_token::hash(&api_key)).await? {
+            return Err(AppError::UserNotFound);
+        }
+
+        log::info!("API key of bot {} rotated by {}", bot_id, owner_id);
+
+        Ok(BotApiKeyResponse { api_key })
+    }
+
+    /// Delete an own bot
+    pub async fn delete_bot(pool: &PgPool, owner_id: Uuid, bot_id: Uuid) -> Result<(), AppError> {
+        if !BotRepository::delete(pool, owner_id, bot_id).await? {
+            return Err(AppError::UserNotFound);
+        }
+
+        log::info!("Bot {} deleted by {}", bot_id, owner_id);
+
+        Ok(())
+    }
+
+    /// Resolve the bot user of an API key
+    pub async fn authenticate(pool: &PgPool, api_key: &str) -> Result<User, AppError> {
+        if !api_key.starts_with(BOT_API_KEY_PREFIX) {
+            return Err(AppError::InvalidToken);
+        }
+
+        let bot = BotRepository::authenticate(pool, &secure_token::hash(api_key))
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+
+        if bot.is_suspended() {
+            return Err(AppError::AccountLocked);
+        }
+
+        Ok(bot)
+    }
+
+    fn generate_api_key() -> String {
+        format!("{}{}", BOT_API_KEY_PREFIX, secure_token::generate())
+    }
+}
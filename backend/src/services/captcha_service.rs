@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use crate::config::Config;
+use crate::error::AppError;
+
+/// A CAPTCHA backend able to verify a client response token
+#[async_trait]
+pub trait CaptchaProvider: Send + Sync {
+    async fn verify(&self, token: &str, remote_ip: Option<&str>) -> Result<bool, AppError>;
+}
+
+/// Response of the `siteverify` APIs (hCaptcha and Turnstile share the shape)
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(rename = "error-codes", default)]
+    error_codes: Vec<String>,
+}
+
+/// POST a siteverify form and return whether the token was accepted
+async fn site_verify(
+    url: &str,
+    secret: &str,
+    token: &str,
+    remote_ip: Option<&str>,
+) -> Result<bool, AppError> {
+    let mut form = vec![("secret", secret), ("response", token)];
+    if let Some(ip) = remote_ip {
+        form.push(("remoteip", ip));
+    }
+
+    let response: SiteVerifyResponse = reqwest::Client::new()
+        .post(url)
+        .form(&form)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::InternalError(format!("CAPTCHA verification request failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::InternalError(format!("Invalid CAPTCHA verification response: {}", e)))?;
+
+    if !response.success {
+        log::warn!("CAPTCHA rejected: {:?}", response.error_codes);
+    }
+
+    Ok(response.success)
+}
+
+/// hCaptcha (https://www.hcaptcha.com)
+pub struct HCaptcha {
+    secret: String,
+}
+
+#[async_trait]
+impl CaptchaProvider for HCaptcha {
+    async fn verify(&self, token: &str, remote_ip: Option<&str>) -> Result<bool, AppError> {
+        site_verify("https://api.hcaptcha.com/siteverify", &self.secret, token, remote_ip).await
+    }
+}
+
+/// Cloudflare Turnstile (https://www.cloudflare.com/products/turnstile/)
+pub struct Turnstile {
+    secret: String,
+}
+
+#[async_trait]
+impl CaptchaProvider for Turnstile {
+    async fn verify(&self, token: &str, remote_ip: Option<&str>) -> Result<bool, AppError> {
+        site_verify(
+            "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+            &self.secret,
+            token,
+            remote_ip,
+        )
+        .await
+    }
+}
+
+pub struct CaptchaService;
+
+impl CaptchaService {
+    /// Build the configured provider, None when CAPTCHA is disabled
+    pub fn provider(config: &Config) -> Result<Option<Box<dyn CaptchaProvider>>, AppError> {
+        let name = match config.captcha_provider.as_deref() {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+
+        let secret = config
+            .captcha_secret
+            .clone()
+            .ok_or_else(|| AppError::InternalError("CAPTCHA_SECRET is not set".to_string()))?;
+
+        match name {
+            "hcaptcha" => Ok(Some(Box::new(HCaptcha { secret }))),
+            "turnstile" => Ok(Some(Box::new(Turnstile { secret }))),
+            other => Err(AppError::InternalError(format!("Unknown CAPTCHA_PROVIDER '{}'", other))),
+        }
+    }
+
+    /// Enforce a valid CAPTCHA response when CAPTCHA is enabled
+    pub async fn verify(
+        config: &Config,
+        token: Option<&str>,
+        remote_ip: Option<&str>,
+    ) -> Result<(), AppError> {
+        let provider = match Self::provider(config)? {
+            Some(provider) => provider,
+            None => return Ok(()),
+        };
+
+        let token = token
+            .filter(|t| !t.is_empty())
+            .ok_or(AppError::CaptchaFailed)?;
+
+        if !provider.verify(token, remote_ip).await? {
+            return Err(AppError::CaptchaFailed);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::command::{
+    BotCommand, CommandCallbackResponse, CommandExecutionResponse, CommandSummary, ExecuteCommandDto, RegisterCommandDto,
+    RegisteredCommandResponse, COMMAND_RESPONSE_MAX_LEN,
+};
+use crate::repositories::{BotRepository, CommandRepository, RoomRepository, UserRepository};
+use crate::models::rate_limit::RATE_LIMIT_CLASS_MESSAGE;
+use crate::services::spam_service::{SpamAuthor, SpamVerdict};
+use crate::services::{PushService, RateLimitService, RoomService, SpamService, WordFilterService};
+use crate::utils::{outbound_url, secure_token, slash_command, webhook_signature};
+
+/// How long a bot gets to answer a command
+const CALLBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Largest answer read from a bot (the relayed content is much shorter)
+const CALLBACK_MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// How far the timestamp of a signed answer may be from the server clock
+const CALLBACK_MAX_CLOCK_SKEW_SECONDS: i64 = 5 * 60;
+
+pub struct CommandService;
+
+impl CommandService {
+    /// Register or update a slash command (bots that are members of the room only)
+    pub async fn register(
+        pool: &PgPool,
+        room_id: Uuid,
+        bot_id: Uuid,
+        dto: RegisterCommandDto,
+    ) -> Result<RegisteredCommandResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid command data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if !slash_command::is_valid_name(&dto.name) {
+            return Err(AppError::InvalidFormat("name".to_string()));
+        }
+
+        Self::require_bot_member(pool, room_id, bot_id).await?;
+
+        // Checked again on every call, since DNS can change after registration
+        outbound_url::resolve(&dto.callback_url).await.map_err(AppError::UrlNotAllowed)?;
+
+        let command = CommandRepository::upsert(pool, room_id, bot_id, &dto, &secure_token::generate())
+            .await?
+            .ok_or(AppError::CommandNameTaken)?;
+
+        log::info!("Bot {} registered /{} in room {}", bot_id, command.name, room_id);
+
+        Ok(RegisteredCommandResponse {
+            signing_secret: command.signing_secret.clone(),
+            command,
+        })
+    }
+
+    /// Remove a bot's own command from a room
+    pub async fn unregister(pool: &PgPool, room_id: Uuid, bot_id: Uuid, name: &str) -> Result<(), AppError> {
+        if !CommandRepository::delete(pool, room_id, bot_id, name).await? {
+            return Err(AppError::CommandNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// List a room's commands for autocompletion (members only)
+    pub async fn list(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<Vec<CommandSummary>, AppError> {
+        RoomRepository::find_by_id(pool, room_id).await?;
+
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        CommandRepository::list_for_room(pool, room_id).await
+    }
+
+    /// Run a slash command typed in a room: dispatch it to the bot's callback and relay
+    /// the answer to the room (or only to the caller when the bot marks it ephemeral)
+    pub async fn execute(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: ExecuteCommandDto,
+    ) -> Result<CommandExecutionResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("text", "Text must be between 2-4000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let (name, args) = slash_command::parse(&dto.text).ok_or(AppError::InvalidFormat("text".to_string()))?;
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        RoomService::require_can_post(pool, &room, user_id).await?;
+
+        // Running a command posts to the room, so it counts as a message send
+        RateLimitService::take_message_token(pool, redis_pool, config, RATE_LIMIT_CLASS_MESSAGE, user_id).await?;
+
+        let command = CommandRepository::find_by_name(pool, room_id, &name)
+            .await?
+            .ok_or(AppError::CommandNotFound)?;
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+
+        let body = serde_json::json!({
+            "command": command.name,
+            "args": args,
+            "text": dto.text,
+            "room_id": room_id,
+            "user": {
+                "id": user.id,
+                "username": user.username,
+                "display_name": user.display_name,
+            },
+            "issued_at": Utc::now(),
+        })
+        .to_string();
+
+        let answer = Self::call_back(&command, body).await?;
+
+        let content: String = answer.content.chars().take(COMMAND_RESPONSE_MAX_LEN).collect();
+        let content = WordFilterService::filter(pool, redis_pool, &content).await?;
+
+        // Answers relayed into the room are spam-checked like webhook posts, with the bot's
+        // owner answering for them; a held answer still reaches the invoker
+        let mut relay = !content.trim().is_empty();
+        if relay && !answer.ephemeral {
+            let author = SpamAuthor {
+                key: format!("bot:{}", command.bot_id),
+                user_id: BotRepository::find_owner_id(pool, command.bot_id).await?,
+                room_id,
+            };
+            relay = SpamService::check(pool, redis_pool, config, &author, &content).await? != SpamVerdict::Hold;
+        }
+
+        if relay {
+            let bot = UserRepository::find_by_id(pool, command.bot_id).await?;
+            let bot_name = bot.display_name.as_deref().unwrap_or(&bot.username);
+            let event = serde_json::json!({
+                "type": "room.bot_message",
+                "room_id": room_id,
+                "author": {
+                    "type": "bot",
+                    "bot_id": bot.id,
+                    "name": bot_name,
+                },
+                "content": content,
+                "command": command.name,
+                "invoked_by": user_id,
+                "ephemeral": answer.ephemeral,
+                "created_at": Utc::now(),
+            });
+
+            let channel = if answer.ephemeral {
+                cache::user_channel(user_id)
+            } else {
+                cache::room_channel(room_id)
+            };
+            cache::publish(redis_pool, &channel, &event.to_string()).await?;
+
+            if !answer.ephemeral {
+                PushService::notify_mentions(pool, redis_pool, config, &room, bot_name, &content);
+            }
+        }
+
+        Ok(CommandExecutionResponse {
+            command: command.name,
+            bot_id: command.bot_id,
+            content,
+            ephemeral: answer.ephemeral,
+        })
+    }
+
+    /// POST a command to its bot and read the answer. Only answers signed with the
+    /// command's secret are relayed, so a callback URL can't be used to read responses
+    /// of other services; why a call failed is logged, not returned to the caller.
+    async fn call_back(command: &BotCommand, body: String) -> Result<CommandCallbackResponse, AppError> {
+        let failed = |reason: String| {
+            log::warn!("Callback of /{} by bot {} failed: {}", command.name, command.bot_id, reason);
+            AppError::CommandCallbackFailed
+        };
+
+        let client = outbound_url::client(&command.callback_url, CALLBACK_TIMEOUT).await.map_err(failed)?;
+
+        let timestamp = Utc::now().timestamp();
+        let mut response = client
+            .post(&command.callback_url)
+            .header("Content-Type", "application/json")
+            .header("X-Ngobrol-Command", &command.name)
+            .header("X-Ngobrol-Timestamp", timestamp.to_string())
+            .header("X-Ngobrol-Signature", webhook_signature::sign(&command.signing_secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| failed(e.to_string()))?;
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        let answer_timestamp = header("X-Ngobrol-Timestamp").and_then(|t| t.parse::<i64>().ok());
+        let signature = header("X-Ngobrol-Signature").unwrap_or_default();
+
+        let mut answer = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| failed(e.to_string()))? {
+            if answer.len() + chunk.len() > CALLBACK_MAX_RESPONSE_BYTES {
+                return Err(failed("Answer too large".to_string()));
+            }
+            answer.extend_from_slice(&chunk);
+        }
+        let answer = String::from_utf8(answer).map_err(|_| failed("Answer is not UTF-8".to_string()))?;
+
+        let signed = answer_timestamp.is_some_and(|answer_timestamp| {
+            (Utc::now().timestamp() - answer_timestamp).abs() <= CALLBACK_MAX_CLOCK_SKEW_SECONDS
+                && webhook_signature::verify(&command.signing_secret, answer_timestamp, &answer, &signature)
+        });
+        if !signed {
+            return Err(failed("Answer is not signed with the command secret".to_string()));
+        }
+
+        serde_json::from_str(&answer).map_err(|e| failed(format!("Invalid answer: {}", e)))
+    }
+
+    /// Only bot accounts that are members of the room manage its commands
+    async fn require_bot_member(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        if !user.is_bot {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        RoomRepository::find_by_id(pool, room_id).await?;
+
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::RedisPool;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::dm::{
+    AddParticipantDto, CreateGroupDmDto, DmConversationResponse, GroupDmResponse, SystemMessage,
+    GROUP_DM_MAX_PARTICIPANTS, GROUP_DM_MIN_PARTICIPANTS,
+};
+use crate::models::user::SYSTEM_USER_ID;
+use crate::repositories::{DmRepository, RoomRepository, UserRepository};
+use crate::services::RoomService;
+
+/// Name of a group DM created without one
+const DEFAULT_GROUP_NAME: &str = "Group conversation";
+
+pub struct DmService;
+
+impl DmService {
+    /// Open the conversation with another user, creating it on first contact.
+    /// Returns the conversation and whether it was just created.
+    pub async fn open(
+        pool: &PgPool,
+        user_id: Uuid,
+        other_id: Uuid,
+    ) -> Result<(DmConversationResponse, bool), AppError> {
+        if other_id == user_id {
+            return Err(AppError::InvalidFormat("user_id".to_string()));
+        }
+
+        // Other user must exist and be active
+        UserRepository::find_by_id(pool, other_id).await?;
+
+        if let Some(room_id) = DmRepository::find_between(pool, user_id, other_id).await? {
+            let conversation = DmRepository::find_for_user(pool, room_id, user_id).await?;
+            return Ok((conversation, false));
+        }
+
+        let room_id = match DmRepository::create(pool, user_id, other_id).await {
+            Ok(room_id) => room_id,
+            // Both users opened it at the same time: use the one that won
+            Err(e) => DmRepository::find_between(pool, user_id, other_id).await?.ok_or(e)?,
+        };
+
+        let conversation = DmRepository::find_for_user(pool, room_id, user_id).await?;
+
+        Ok((conversation, true))
+    }
+
+    /// List own conversations with unread counts
+    pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<Vec<DmConversationResponse>, AppError> {
+        DmRepository::list_for_user(pool, user_id).await
+    }
+
+    /// Official messages received from the System account, newest first
+    pub async fn list_system_messages(
+        pool: &PgPool,
+        user_id: Uuid,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<SystemMessage>, i64), AppError> {
+        let offset = ((page - 1) * per_page) as i64;
+        let messages = DmRepository::list_system_messages(pool, user_id, offset, per_page as i64).await?;
+        let total = DmRepository::count_system_messages(pool, user_id).await?;
+
+        Ok((messages, total))
+    }
+
+    /// Mark a conversation (1:1 or group) as read
+    pub async fn mark_read(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if !room.is_direct_message() {
+            return Err(AppError::RoomNotFound);
+        }
+
+        // Fails for non-participants
+        RoomRepository::mark_read(pool, room_id, user_id).await
+    }
+
+    /// Create a group DM with the listed users (names need not be unique)
+    pub async fn create_group(
+        pool: &PgPool,
+        owner_id: Uuid,
+        dto: CreateGroupDmDto,
+    ) -> Result<GroupDmResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid group data");
+                AppError::ValidationError(errors)
+            })?;
+
+        let mut participants = vec![owner_id];
+        for user_id in dto.user_ids {
+            if !participants.contains(&user_id) {
+                participants.push(user_id);
+            }
+        }
+
+        if !(GROUP_DM_MIN_PARTICIPANTS..=GROUP_DM_MAX_PARTICIPANTS).contains(&participants.len()) {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error(
+                "user_ids",
+                &format!(
+                    "A group needs {}-{} participants including you",
+                    GROUP_DM_MIN_PARTICIPANTS, GROUP_DM_MAX_PARTICIPANTS
+                ),
+            );
+            return Err(AppError::ValidationError(errors));
+        }
+
+        // System only ever speaks in its own 1:1 conversations
+        if participants.contains(&SYSTEM_USER_ID) {
+            return Err(AppError::SystemConversation);
+        }
+
+        // Every participant must exist and be active
+        if UserRepository::count_active(pool, &participants).await? != participants.len() as i64 {
+            return Err(AppError::UserNotFound);
+        }
+
+        let name = dto.name.as_deref().unwrap_or(DEFAULT_GROUP_NAME);
+        let room_id = DmRepository::create_group(pool, owner_id, name, &participants).await?;
+
+        DmRepository::find_group_for_user(pool, room_id, owner_id).await
+    }
+
+    /// List own group DMs with unread counts
+    pub async fn list_groups(pool: &PgPool, user_id: Uuid) -> Result<Vec<GroupDmResponse>, AppError> {
+        DmRepository::list_groups_for_user(pool, user_id).await
+    }
+
+    /// Add a participant to a group DM (any participant can add)
+    pub async fn add_participant(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: AddParticipantDto,
+    ) -> Result<GroupDmResponse, AppError> {
+        // Only participants can add others
+        DmRepository::find_group_for_user(pool, room_id, user_id).await?;
+
+        if dto.user_id == SYSTEM_USER_ID {
+            return Err(AppError::SystemConversation);
+        }
+
+        // New participant must exist and be active
+        UserRepository::find_by_id(pool, dto.user_id).await?;
+
+        if RoomRepository::is_member(pool, room_id, dto.user_id).await? {
+            return Err(AppError::AlreadyJoined);
+        }
+
+        // Group DM rooms are capped at GROUP_DM_MAX_PARTICIPANTS members
+        RoomRepository::add_member_within_capacity(pool, room_id, dto.user_id, "member").await?;
+
+        DmRepository::find_group_for_user(pool, room_id, user_id).await
+    }
+
+    /// Remove a participant from a group DM (the owner removes others, anyone can leave)
+    pub async fn remove_participant(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        participant_id: Uuid,
+    ) -> Result<(), AppError> {
+        let group = DmRepository::find_group_for_user(pool, room_id, user_id).await?;
+
+        if participant_id != user_id && group.owner_id != user_id {
+            return Err(AppError::OwnerRequired);
+        }
+
+        // Like rooms, the owner can't leave their own group
+        if participant_id == group.owner_id {
+            return Err(AppError::OwnerRequired);
+        }
+
+        RoomRepository::remove_member(pool, room_id, participant_id).await?;
+        RoomService::end_membership(redis_pool, room_id, participant_id).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::error::{AppError, ValidationErrors};
+use crate::models::event::{CreateEventDto, EventResponse, RsvpDto, RSVP_GOING, RSVP_MAYBE, RSVP_NOT_GOING};
+use crate::models::permission::{role_rank, PERM_SEND_MESSAGES};
+use crate::models::room::ROOM_TYPE_PUBLIC;
+use crate::models::welcome::SYSTEM_AUTHOR;
+use crate::repositories::{EventRepository, RoomRepository};
+use crate::services::RoomService;
+
+/// How long before an event starts its reminder is posted
+const REMINDER_LEAD_MINUTES: i64 = 15;
+
+pub struct EventService;
+
+impl EventService {
+    /// Schedule an event in a room (members who can post)
+    pub async fn create_event(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: CreateEventDto,
+    ) -> Result<EventResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid event data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if dto.starts_at <= Utc::now() {
+            return Err(AppError::InvalidFormat("starts_at".to_string()));
+        }
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        RoomService::require_permission(pool, room_id, user_id, PERM_SEND_MESSAGES).await?;
+
+        let event = EventRepository::create(pool, room_id, user_id, &dto).await?;
+
+        EventRepository::find_for_user(pool, event.id, user_id).await
+    }
+
+    /// List a room's upcoming events (members only for non-public rooms)
+    pub async fn list_events(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<EventResponse>, AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+
+        let is_member = RoomRepository::is_member(pool, room_id, user_id).await?;
+        if room.room_type != ROOM_TYPE_PUBLIC && !is_member {
+            return Err(AppError::PrivateNoAccess);
+        }
+
+        EventRepository::list_for_room(pool, room_id, user_id, Utc::now()).await
+    }
+
+    /// Cancel an event (its creator, or moderators and above)
+    pub async fn delete_event(
+        pool: &PgPool,
+        room_id: Uuid,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let event = EventRepository::find(pool, room_id, event_id).await?;
+
+        if event.created_by != Some(user_id) {
+            let role = RoomRepository::get_user_role(pool, room_id, user_id)
+                .await?
+                .ok_or(AppError::NotMember)?;
+
+            if role_rank(&role) < role_rank("moderator") {
+                return Err(AppError::InsufficientPermissions);
+            }
+        }
+
+        EventRepository::delete(pool, event.id).await
+    }
+
+    /// Answer an event (members only)
+    pub async fn rsvp(
+        pool: &PgPool,
+        room_id: Uuid,
+        event_id: Uuid,
+        user_id: Uuid,
+        dto: RsvpDto,
+    ) -> Result<EventResponse, AppError> {
+        if ![RSVP_GOING, RSVP_MAYBE, RSVP_NOT_GOING].contains(&dto.status.as_str()) {
+            return Err(AppError::InvalidFormat("status".to_string()));
+        }
+
+        let event = EventRepository::find(pool, room_id, event_id).await?;
+
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        EventRepository::upsert_rsvp(pool, event.id, user_id, &dto.status).await?;
+
+        EventRepository::find_for_user(pool, event.id, user_id).await
+    }
+
+    /// Withdraw own answer to an event
+    pub async fn remove_rsvp(
+        pool: &PgPool,
+        room_id: Uuid,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let event = EventRepository::find(pool, room_id, event_id).await?;
+
+        EventRepository::delete_rsvp(pool, event.id, user_id).await
+    }
+
+    /// Post a reminder in the room of every event starting soon (run by the reminder job)
+    pub async fn send_due_reminders(pool: &PgPool, redis_pool: &RedisPool) -> Result<usize, AppError> {
+        let until = Utc::now() + Duration::minutes(REMINDER_LEAD_MINUTES);
+        let events = EventRepository::claim_due_reminders(pool, until).await?;
+
+        for event in &events {
+            let reminder = serde_json::json!({
+                "type": "room.event_reminder",
+                "room_id": event.room_id,
+                "event_id": event.id,
+                "author": SYSTEM_AUTHOR,
+                "content": format!("\"{}\" starts soon", event.title),
+                "starts_at": event.starts_at,
+                "created_at": Utc::now(),
+            });
+
+            if let Err(e) = cache::publish(redis_pool, &cache::room_channel(event.room_id), &reminder.to_string()).await {
+                log::warn!("Failed to post reminder of event {}: {}", event.id, e);
+            }
+        }
+
+        Ok(events.len())
+    }
+}
@@ -0,0 +1,113 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::RedisPool;
+use crate::error::AppError;
+use crate::models::activity::ACTIVITY_INVITE;
+use crate::models::invitation::{
+    Invitation, InvitationResponse, InviteUserDto, INVITATION_ACCEPTED, INVITATION_DECLINED,
+};
+use crate::models::permission::PERM_INVITE;
+use crate::models::room::RoomMemberResponse;
+use crate::repositories::{InvitationRepository, RoomRepository, UserRepository};
+use crate::services::{ActivityService, RoomService};
+
+pub struct InvitationService;
+
+impl InvitationService {
+    /// Invite a user to a room (requires the invite permission)
+    pub async fn invite(
+        pool: &PgPool,
+        room_id: Uuid,
+        inviter_id: Uuid,
+        dto: InviteUserDto,
+    ) -> Result<Invitation, AppError> {
+        // Check if room exists
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+        RoomService::ensure_not_locked(&room)?;
+
+        // Check permissions
+        RoomService::require_permission(pool, room_id, inviter_id, PERM_INVITE).await?;
+
+        // Resolve the invitee by ID or username
+        let invitee = match (dto.user_id, dto.username.as_deref()) {
+            (Some(user_id), _) => UserRepository::find_by_id(pool, user_id).await?,
+            (None, Some(username)) => UserRepository::find_by_username(pool, username).await?,
+            (None, None) => return Err(AppError::MissingField("user_id".to_string())),
+        };
+
+        if RoomRepository::is_member(pool, room_id, invitee.id).await? {
+            return Err(AppError::AlreadyJoined);
+        }
+
+        if InvitationRepository::pending_exists(pool, room_id, invitee.id).await? {
+            return Err(AppError::InvitationExists);
+        }
+
+        let invitation = InvitationRepository::create(pool, room_id, inviter_id, invitee.id).await?;
+
+        ActivityService::record(
+            pool,
+            invitee.id,
+            ACTIVITY_INVITE,
+            Some(room_id),
+            Some(inviter_id),
+            serde_json::json!({ "invitation_id": invitation.id }),
+        )
+        .await;
+
+        Ok(invitation)
+    }
+
+    /// List own pending invitations
+    pub async fn list_pending(pool: &PgPool, user_id: Uuid) -> Result<Vec<InvitationResponse>, AppError> {
+        InvitationRepository::list_pending(pool, user_id).await
+    }
+
+    /// Accept an invitation and join its room (works for private rooms)
+    pub async fn accept(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        invitation_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<RoomMemberResponse, AppError> {
+        let invitation = InvitationRepository::find_pending(pool, invitation_id, user_id).await?;
+        let room = RoomRepository::find_by_id(pool, invitation.room_id).await?;
+        RoomService::ensure_not_locked(&room)?;
+
+        RoomService::ensure_not_banned(pool, room.id, user_id).await?;
+
+        // May have joined a public room in the meantime
+        if !RoomRepository::is_member(pool, room.id, user_id).await? {
+            // Fails atomically if the room is full (the invitation stays pending)
+            RoomRepository::add_member_within_capacity(pool, room.id, user_id, "member").await?;
+            RoomService::send_welcome(pool, redis_pool, &room, user_id).await;
+            RoomService::dispatch_member_joined(pool, room.id, user_id).await;
+        }
+
+        // Already resolved by a concurrent request if this matches nothing
+        InvitationRepository::respond(pool, invitation.id, INVITATION_ACCEPTED).await?;
+
+        // Get updated member info
+        let members = RoomRepository::get_members(pool, room.id).await?;
+        let member = members
+            .into_iter()
+            .find(|m| m.user_id == user_id)
+            .ok_or(AppError::InternalError("Failed to retrieve member info".to_string()))?;
+
+        Ok(member)
+    }
+
+    /// Decline an invitation
+    pub async fn decline(pool: &PgPool, invitation_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let invitation = InvitationRepository::find_pending(pool, invitation_id, user_id).await?;
+
+        if !InvitationRepository::respond(pool, invitation.id, INVITATION_DECLINED).await? {
+            return Err(AppError::InvitationNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use std::net::IpAddr;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::error::{AppError, ValidationErrors};
+use crate::models::audit::{AUDIT_IP_BAN_CREATED, AUDIT_IP_BAN_DELETED};
+use crate::models::ip_ban::{CreateIpBanDto, IpBan};
+use crate::repositories::{AuditRepository, IpBanRepository};
+use crate::utils::ip_net::IpNet;
+
+/// Redis key caching the active bans; dropped whenever a ban changes
+const IP_BANS_CACHE_KEY: &str = "ip_bans";
+
+/// Safety net in case an invalidation is lost
+const IP_BANS_CACHE_TTL_SECONDS: u64 = 3600;
+
+pub struct IpBanService;
+
+impl IpBanService {
+    /// All bans, including expired ones (admin only)
+    pub async fn list(pool: &PgPool) -> Result<Vec<IpBan>, AppError> {
+        IpBanRepository::list(pool).await
+    }
+
+    /// Ban an IP or network; applies to the next request. `admin_ip` is where the
+    /// admin is connecting from, so they can't lock themselves out.
+    pub async fn create(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        admin_ip: Option<IpAddr>,
+        dto: CreateIpBanDto,
+    ) -> Result<IpBan, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("reason", "Reason must be between 1-500 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let net = IpNet::parse(&dto.cidr).ok_or_else(|| AppError::InvalidFormat("cidr".to_string()))?;
+
+        if admin_ip.is_some_and(|ip| net.contains(ip)) {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("cidr", "This ban would cover your own IP address");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        if dto.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("expires_at", "Expiry must be in the future");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let ban = IpBanRepository::create(pool, &net.to_string(), dto.reason.trim(), dto.expires_at, admin_id).await?;
+        Self::invalidate(redis_pool).await;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_IP_BAN_CREATED,
+            Some(ban.id),
+            Some(serde_json::json!({ "cidr": ban.cidr, "reason": ban.reason, "expires_at": ban.expires_at })),
+        )
+        .await?;
+
+        log::warn!("Admin {} banned {} ({})", admin_id, ban.cidr, ban.reason);
+
+        Ok(ban)
+    }
+
+    /// Lift a ban
+    pub async fn delete(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        ban_id: Uuid,
+    ) -> Result<(), AppError> {
+        let ban = IpBanRepository::find_by_id(pool, ban_id).await?;
+        IpBanRepository::delete(pool, ban.id).await?;
+        Self::invalidate(redis_pool).await;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_IP_BAN_DELETED,
+            Some(ban.id),
+            Some(serde_json::json!({ "cidr": ban.cidr })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether a client IP is covered by an active ban, checked on every request.
+    /// Fails open: if the bans can't be loaded, nobody is refused.
+    pub async fn is_banned(pool: &PgPool, redis_pool: &RedisPool, ip: IpAddr) -> bool {
+        let bans = match Self::load(pool, redis_pool).await {
+            Ok(bans) => bans,
+            Err(e) => {
+                log::error!("Failed to load IP bans: {}", e);
+                return false;
+            }
+        };
+
+        let now = Utc::now();
+        bans.iter()
+            .filter(|ban| ban.expires_at.is_none_or(|expires_at| expires_at > now))
+            .filter_map(|ban| IpNet::parse(&ban.cidr))
+            .any(|net| net.contains(ip))
+    }
+
+    /// Active bans from the Redis cache, falling back to (and refilling from) Postgres
+    async fn load(pool: &PgPool, redis_pool: &RedisPool) -> Result<Vec<IpBan>, AppError> {
+        match cache::get_value(redis_pool, IP_BANS_CACHE_KEY).await {
+            Ok(Some(cached)) => {
+                if let Ok(bans) = serde_json::from_str(&cached) {
+                    return Ok(bans);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to read cached IP bans: {}", e),
+        }
+
+        let bans = IpBanRepository::list_active(pool).await?;
+
+        if let Ok(serialized) = serde_json::to_string(&bans) {
+            if let Err(e) = cache::set_with_ttl(redis_pool, IP_BANS_CACHE_KEY, &serialized, IP_BANS_CACHE_TTL_SECONDS).await
+            {
+                log::warn!("Failed to cache IP bans: {}", e);
+            }
+        }
+
+        Ok(bans)
+    }
+
+    /// Drop the cached bans so every instance reloads them
+    async fn invalidate(redis_pool: &RedisPool) {
+        if let Err(e) = cache::delete_value(redis_pool, IP_BANS_CACHE_KEY).await {
+            log::warn!("Failed to invalidate cached IP bans: {}", e);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::keys::{KeyBundleResponse, PrekeyCountResponse, PublishKeysDto, SignedPrekeyDto};
+use crate::repositories::{KeyRepository, UserRepository};
+
+pub struct KeyService;
+
+impl KeyService {
+    /// Publish the own key bundle (identity key, signed prekey, one-time prekeys)
+    pub async fn publish(
+        pool: &PgPool,
+        user_id: Uuid,
+        dto: PublishKeysDto,
+    ) -> Result<PrekeyCountResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid key bundle");
+                AppError::ValidationError(errors)
+            })?;
+
+        KeyRepository::publish(pool, user_id, &dto).await?;
+
+        Self::prekey_count(pool, user_id).await
+    }
+
+    /// Fetch a user's key bundle, consuming one of their one-time prekeys
+    pub async fn fetch_bundle(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<KeyBundleResponse, AppError> {
+        // Only active users can be messaged
+        let _user = UserRepository::find_by_id(pool, user_id).await?;
+
+        let identity = KeyRepository::find_identity(pool, user_id)
+            .await?
+            .ok_or(AppError::KeyBundleNotFound)?;
+
+        let one_time_prekey = KeyRepository::claim_prekey(pool, user_id).await?;
+
+        Ok(KeyBundleResponse {
+            user_id,
+            identity_key: identity.identity_key,
+            signed_prekey: SignedPrekeyDto {
+                key_id: identity.signed_prekey_id,
+                public_key: identity.signed_prekey,
+                signature: identity.signed_prekey_signature,
+            },
+            one_time_prekey,
+        })
+    }
+
+    /// Count the own remaining one-time prekeys (clients replenish when low)
+    pub async fn prekey_count(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<PrekeyCountResponse, AppError> {
+        let one_time_prekeys = KeyRepository::count_prekeys(pool, user_id).await?;
+        Ok(PrekeyCountResponse { one_time_prekeys })
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::action_token::{ConsumeTokenDto, PURPOSE_LINK_EMAIL};
+use crate::models::audit::AUDIT_PRIMARY_EMAIL_CHANGED;
+use crate::models::linked_email::{AccountEmailsResponse, AddEmailDto, LinkedEmail, LINKED_EMAILS_MAX};
+use crate::models::user::{PasswordConfirmationDto, UserResponse};
+use crate::repositories::{ActionTokenRepository, AuditRepository, LinkedEmailRepository, UserRepository};
+use crate::services::MailService;
+use crate::utils::{password, secure_token};
+
+/// Lifetime of the link confirming a linked address
+const LINK_EMAIL_TTL_HOURS: i64 = 24;
+
+pub struct LinkedEmailService;
+
+impl LinkedEmailService {
+    /// Primary and linked addresses of the own account
+    pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<AccountEmailsResponse, AppError> {
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        let linked = LinkedEmailRepository::list(pool, user_id).await?;
+
+        Ok(AccountEmailsResponse {
+            primary: user.email,
+            primary_verified: user.email_verified_at.is_some(),
+            linked,
+        })
+    }
+
+    /// Link an address to the own account and email it a confirmation link
+    pub async fn add(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: AddEmailDto,
+    ) -> Result<LinkedEmail, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("email", "Invalid email format");
+                AppError::ValidationError(errors)
+            })?;
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+
+        if UserRepository::email_exists(pool, &dto.email).await? {
+            return Err(AppError::EmailExists);
+        }
+
+        if LinkedEmailRepository::count(pool, user.id).await? >= LINKED_EMAILS_MAX {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("email", &format!("At most {} addresses can be linked", LINKED_EMAILS_MAX));
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let linked = LinkedEmailRepository::create(pool, user.id, &dto.email).await?;
+
+        let token = secure_token::generate();
+        let expires_at = Utc::now() + Duration::hours(LINK_EMAIL_TTL_HOURS);
+        ActionTokenRepository::create(
+            pool,
+            user.id,
+            PURPOSE_LINK_EMAIL,
+            &secure_token::hash(&token),
+            Some(&linked.id.to_string()),
+            expires_at,
+        )
+        .await?;
+
+        let link = format!("{}/confirm-linked-email?token={}", config.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nConfirm that you want to link this address to your Ngobrol account:\n\n{}\n\nThe link expires in {} hour(s).",
+            user.username, link, LINK_EMAIL_TTL_HOURS
+        );
+        MailService::send(config, &linked.email, "Confirm your linked Ngobrol email address", body).await?;
+
+        Ok(linked)
+    }
+
+    /// Confirm a linked address with the token sent to it
+    pub async fn confirm(pool: &PgPool, dto: ConsumeTokenDto) -> Result<LinkedEmail, AppError> {
+        let token = ActionTokenRepository::consume(pool, PURPOSE_LINK_EMAIL, &secure_token::hash(&dto.token))
+            .await?
+            .ok_or(AppError::InvalidActionToken)?;
+
+        let email_id = token
+            .payload
+            .and_then(|payload| Uuid::parse_str(&payload).ok())
+            .ok_or(AppError::InvalidActionToken)?;
+
+        let linked = LinkedEmailRepository::find(pool, token.user_id, email_id).await?;
+
+        // Another account may have claimed the address since it was linked
+        if UserRepository::email_exists(pool, &linked.email).await? {
+            return Err(AppError::EmailExists);
+        }
+
+        LinkedEmailRepository::mark_verified(pool, token.user_id, email_id).await
+    }
+
+    /// Unlink an address from the own account
+    pub async fn remove(pool: &PgPool, user_id: Uuid, email_id: Uuid) -> Result<(), AppError> {
+        if !LinkedEmailRepository::delete(pool, user_id, email_id).await? {
+            return Err(AppError::LinkedEmailNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Make a verified linked address the primary one (requires the password);
+    /// the previous primary address stays linked
+    pub async fn make_primary(
+        pool: &PgPool,
+        user_id: Uuid,
+        email_id: Uuid,
+        dto: PasswordConfirmationDto,
+    ) -> Result<UserResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("password", "Password is required");
+                AppError::ValidationError(errors)
+            })?;
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        let password_hash = UserRepository::find_password_hash(pool, user.id).await?;
+
+        if !password::verify_password(&dto.password, &password_hash)? {
+            return Err(AppError::InvalidCredentials);
+        }
+
+        let linked = LinkedEmailRepository::find(pool, user.id, email_id).await?;
+        if linked.verified_at.is_none() {
+            return Err(AppError::EmailNotVerified);
+        }
+
+        let updated = LinkedEmailRepository::swap_primary(pool, user.id, linked.id).await?;
+
+        AuditRepository::record(
+            pool,
+            Some(user.id),
+            AUDIT_PRIMARY_EMAIL_CHANGED,
+            Some(user.id),
+            Some(serde_json::json!({ "from": user.email, "to": updated.email })),
+        )
+        .await?;
+
+        Ok(updated.into())
+    }
+}
@@ -0,0 +1,52 @@
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use crate::config::Config;
+use crate::error::AppError;
+
+pub struct MailService;
+
+impl MailService {
+    /// Send a plain-text email
+    /// Without SMTP_URL configured the email is only logged (local development)
+    pub async fn send(
+        config: &Config,
+        to: &str,
+        subject: &str,
+        body: String,
+    ) -> Result<(), AppError> {
+        let smtp_url = match &config.smtp_url {
+            Some(url) => url,
+            None => {
+                log::info!("📧 SMTP not configured, email to {} [{}]:\n{}", to, subject, body);
+                return Ok(());
+            }
+        };
+
+        let from: Mailbox = config
+            .mail_from
+            .parse()
+            .map_err(|e| AppError::InternalError(format!("Invalid MAIL_FROM address: {}", e)))?;
+        let to: Mailbox = to
+            .parse()
+            .map_err(|_| AppError::InvalidEmail)?;
+
+        let message = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|e| AppError::InternalError(format!("Failed to build email: {}", e)))?;
+
+        let mailer = AsyncSmtpTransport::<Tokio1Executor>::from_url(smtp_url)
+            .map_err(|e| AppError::InternalError(format!("Invalid SMTP_URL: {}", e)))?
+            .build();
+
+        mailer
+            .send(message)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to send email: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,63 @@
 pub mod auth_service;
 pub mod room_service;
+pub mod push_service;
+pub mod mail_service;
+pub mod captcha_service;
+pub mod password_service;
+pub mod admin_service;
+pub mod account_service;
+pub mod key_service;
+pub mod dm_service;
+pub mod invitation_service;
+pub mod stats_service;
+pub mod space_service;
+pub mod room_export_service;
+pub mod event_service;
+pub mod webhook_service;
+pub mod outgoing_webhook_service;
+pub mod presence_service;
+pub mod user_service;
+pub mod activity_service;
+pub mod report_service;
+pub mod bot_service;
+pub mod command_service;
+pub mod linked_email_service;
+pub mod word_filter_service;
+pub mod announcement_service;
+pub mod rate_limit_service;
+pub mod ip_ban_service;
+pub mod spam_service;
+pub mod user_export_service;
+pub mod quota_service;
 
 pub use auth_service::AuthService;
 pub use room_service::RoomService;
+pub use push_service::PushService;
+pub use mail_service::MailService;
+pub use captcha_service::CaptchaService;
+pub use password_service::PasswordService;
+pub use admin_service::AdminService;
+pub use account_service::AccountService;
+pub use key_service::KeyService;
+pub use dm_service::DmService;
+pub use invitation_service::InvitationService;
+pub use stats_service::StatsService;
+pub use space_service::SpaceService;
+pub use room_export_service::RoomExportService;
+pub use event_service::EventService;
+pub use webhook_service::WebhookService;
+pub use outgoing_webhook_service::OutgoingWebhookService;
+pub use presence_service::PresenceService;
+pub use user_service::UserService;
+pub use activity_service::ActivityService;
+pub use report_service::ReportService;
+pub use bot_service::BotService;
+pub use command_service::CommandService;
+pub use linked_email_service::LinkedEmailService;
+pub use word_filter_service::WordFilterService;
+pub use announcement_service::AnnouncementService;
+pub use rate_limit_service::RateLimitService;
+pub use ip_ban_service::IpBanService;
+pub use spam_service::SpamService;
+pub use user_export_service::UserExportService;
+pub use quota_service::QuotaService;
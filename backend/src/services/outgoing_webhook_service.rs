@@ -0,0 +1,205 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::webhook::{
+    CreateOutgoingWebhookDto, CreatedOutgoingWebhookResponse, DueDelivery, OutgoingWebhook, WebhookDelivery,
+    DELIVERY_FAILED, DELIVERY_PENDING, DELIVERY_SUCCEEDED, OUTGOING_EVENTS,
+};
+use crate::repositories::{OutgoingWebhookRepository, RoomRepository};
+use crate::services::RoomService;
+use crate::utils::{outbound_url, secure_token, webhook_signature};
+
+/// Deliveries sent per job run
+const DELIVERY_BATCH_SIZE: i64 = 50;
+
+/// How long a claimed delivery is hidden from other workers
+const DELIVERY_LEASE_SECONDS: f64 = 120.0;
+
+/// Timeout of one delivery request
+const DELIVERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Attempts before a delivery is given up (backoff 30s, 1m, 2m, 4m, 8m)
+const MAX_DELIVERY_ATTEMPTS: i32 = 6;
+const RETRY_BASE_SECONDS: i64 = 30;
+
+pub struct OutgoingWebhookService;
+
+impl OutgoingWebhookService {
+    /// Subscribe a URL to room events (owner only); the signing secret is only returned here
+    pub async fn create_webhook(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: CreateOutgoingWebhookDto,
+    ) -> Result<CreatedOutgoingWebhookResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "A valid URL and at least one event are required");
+                AppError::ValidationError(errors)
+            })?;
+
+        if let Some(event) = dto.events.iter().find(|e| !OUTGOING_EVENTS.contains(&e.as_str())) {
+            return Err(AppError::InvalidFormat(format!("events: {}", event)));
+        }
+
+        let mut events = dto.events;
+        events.sort();
+        events.dedup();
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        // Checked again on every delivery, since DNS can change after registration
+        outbound_url::resolve(&dto.url).await.map_err(AppError::UrlNotAllowed)?;
+
+        let secret = secure_token::generate();
+        let webhook = OutgoingWebhookRepository::create(pool, room_id, &dto.url, &secret, &events, user_id).await?;
+
+        log::info!("Outgoing webhook {} created in room {} by {}", webhook.id, room_id, user_id);
+
+        Ok(CreatedOutgoingWebhookResponse { webhook, secret })
+    }
+
+    /// List a room's outgoing webhooks (owner only)
+    pub async fn list_webhooks(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<Vec<OutgoingWebhook>, AppError> {
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        OutgoingWebhookRepository::list_for_room(pool, room_id).await
+    }
+
+    /// Delete an outgoing webhook (owner only); pending deliveries are dropped
+    pub async fn delete_webhook(
+        pool: &PgPool,
+        room_id: Uuid,
+        webhook_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        if !OutgoingWebhookRepository::delete(pool, room_id, webhook_id).await? {
+            return Err(AppError::WebhookNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// List a webhook's deliveries, newest first (owner only)
+    pub async fn list_deliveries(
+        pool: &PgPool,
+        room_id: Uuid,
+        webhook_id: Uuid,
+        user_id: Uuid,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<WebhookDelivery>, i64), AppError> {
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        if !OutgoingWebhookRepository::exists_in_room(pool, room_id, webhook_id).await? {
+            return Err(AppError::WebhookNotFound);
+        }
+
+        let offset = ((page - 1) * per_page) as i64;
+        let deliveries = OutgoingWebhookRepository::list_deliveries(pool, webhook_id, offset, per_page as i64).await?;
+        let total = OutgoingWebhookRepository::count_deliveries(pool, webhook_id).await?;
+
+        Ok((deliveries, total))
+    }
+
+    /// Queue an event for the room's subscribed webhooks.
+    /// Best effort: a failure is logged and never fails the action that triggered it.
+    pub async fn dispatch(pool: &PgPool, room_id: Uuid, event_type: &str, data: serde_json::Value) {
+        let payload = serde_json::json!({
+            "event": event_type,
+            "room_id": room_id,
+            "data": data,
+            "occurred_at": Utc::now(),
+        });
+
+        if let Err(e) = OutgoingWebhookRepository::enqueue(pool, room_id, event_type, &payload).await {
+            log::warn!("Failed to queue {} webhooks for room {}: {}", event_type, room_id, e);
+        }
+    }
+
+    /// Send due deliveries, rescheduling failures with exponential backoff.
+    /// Returns the number of successful deliveries.
+    pub async fn deliver_due(pool: &PgPool) -> Result<usize, AppError> {
+        let due = OutgoingWebhookRepository::claim_due(pool, DELIVERY_BATCH_SIZE, DELIVERY_LEASE_SECONDS).await?;
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let mut delivered = 0;
+        for delivery in due {
+            let (response_status, error) = Self::send(&delivery).await;
+
+            if error.is_none() {
+                OutgoingWebhookRepository::record_attempt(pool, delivery.id, DELIVERY_SUCCEEDED, response_status, None, Utc::now())
+                    .await?;
+                delivered += 1;
+                continue;
+            }
+
+            let attempts = delivery.attempts + 1;
+            let status = if attempts >= MAX_DELIVERY_ATTEMPTS { DELIVERY_FAILED } else { DELIVERY_PENDING };
+            let next_attempt_at = Utc::now() + Duration::seconds(RETRY_BASE_SECONDS << (attempts - 1).min(10));
+
+            if status == DELIVERY_FAILED {
+                log::warn!("Webhook delivery {} failed after {} attempts", delivery.id, attempts);
+            }
+
+            OutgoingWebhookRepository::record_attempt(
+                pool,
+                delivery.id,
+                status,
+                response_status,
+                error.as_deref(),
+                next_attempt_at,
+            )
+            .await?;
+        }
+
+        Ok(delivered)
+    }
+
+    /// POST one signed delivery; returns the response status and the error, if any.
+    /// The URL is only called while it resolves to public addresses.
+    async fn send(delivery: &DueDelivery) -> (Option<i32>, Option<String>) {
+        let client = match outbound_url::client(&delivery.url, DELIVERY_TIMEOUT).await {
+            Ok(client) => client,
+            Err(e) => return (None, Some(e)),
+        };
+
+        let body = delivery.payload.to_string();
+        let timestamp = Utc::now().timestamp();
+
+        let result = client
+            .post(&delivery.url)
+            .header("Content-Type", "application/json")
+            .header("X-Ngobrol-Event", &delivery.event_type)
+            .header("X-Ngobrol-Delivery", delivery.id.to_string())
+            .header("X-Ngobrol-Timestamp", timestamp.to_string())
+            .header("X-Ngobrol-Signature", webhook_signature::sign(&delivery.secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await;
+
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    (Some(status.as_u16() as i32), None)
+                } else {
+                    (Some(status.as_u16() as i32), Some(format!("Unexpected response status {}", status)))
+                }
+            }
+            Err(e) => (None, Some(e.to_string())),
+        }
+    }
+}
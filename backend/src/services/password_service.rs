@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use sha1::{Digest, Sha1};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::utils::password;
+
+/// Hook for checking passwords against known breach corpora
+#[async_trait]
+pub trait BreachedPasswordCheck: Send + Sync {
+    async fn is_breached(&self, password: &str) -> Result<bool, AppError>;
+}
+
+/// Have I Been Pwned range API (k-anonymity: only a 5-char hash prefix leaves the server)
+pub struct HaveIBeenPwned;
+
+#[async_trait]
+impl BreachedPasswordCheck for HaveIBeenPwned {
+    async fn is_breached(&self, password: &str) -> Result<bool, AppError> {
+        let hash = format!("{:X}", Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(5);
+
+        let body = reqwest::Client::new()
+            .get(format!("https://api.pwnedpasswords.com/range/{}", prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::InternalError(format!("Breach check request failed: {}", e)))?
+            .text()
+            .await
+            .map_err(|e| AppError::InternalError(format!("Invalid breach check response: {}", e)))?;
+
+        // Lines are "SUFFIX:COUNT"; padding entries have a count of 0
+        let breached = body.lines().any(|line| {
+            let mut parts = line.trim().split(':');
+            parts.next() == Some(suffix)
+                && parts.next().and_then(|c| c.parse::<u64>().ok()).unwrap_or(0) > 0
+        });
+
+        Ok(breached)
+    }
+}
+
+pub struct PasswordService;
+
+impl PasswordService {
+    /// Enforce the configured password policy, reporting violations on the `password` field
+    pub async fn validate(
+        config: &Config,
+        password: &str,
+        user_inputs: &[&str],
+    ) -> Result<(), AppError> {
+        let policy = &config.password_policy;
+        let mut violations = password::policy_violations(password, policy, user_inputs);
+
+        if violations.is_empty() && policy.check_breached {
+            // The breach check is advisory: an unreachable API must not block sign-ups
+            match HaveIBeenPwned.is_breached(password).await {
+                Ok(true) => violations.push("Password has appeared in a data breach".to_string()),
+                Ok(false) => {}
+                Err(e) => log::warn!("Skipping breached password check: {}", e),
+            }
+        }
+
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let mut errors = ValidationErrors::new();
+        for violation in &violations {
+            errors.add_field_error("password", violation);
+        }
+
+        Err(AppError::ValidationError(errors))
+    }
+}
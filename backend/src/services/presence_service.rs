@@ -0,0 +1,60 @@
+use uuid::Uuid;
+use crate::cache::{self, RedisPool};
+use crate::error::{AppError, ValidationErrors};
+use crate::models::presence::{UserPresence, PRESENCE_OFFLINE, PRESENCE_ONLINE, PRESENCE_QUERY_MAX_IDS};
+
+/// How long presence outlives the last heartbeat of a connection that went away uncleanly
+pub const PRESENCE_TTL_SECONDS: u64 = 90;
+
+pub struct PresenceService;
+
+impl PresenceService {
+    /// Current presence of a batch of users, given as comma-separated IDs
+    pub async fn get_presence(redis_pool: &RedisPool, ids: &str) -> Result<Vec<UserPresence>, AppError> {
+        let mut user_ids: Vec<Uuid> = Vec::new();
+        for id in ids.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+            let user_id = Uuid::parse_str(id).map_err(|_| AppError::InvalidFormat("ids".to_string()))?;
+            if !user_ids.contains(&user_id) {
+                user_ids.push(user_id);
+            }
+        }
+
+        if user_ids.is_empty() || user_ids.len() > PRESENCE_QUERY_MAX_IDS {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("ids", "Between 1 and 100 user IDs are allowed");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let online = cache::presence_online(redis_pool, &user_ids).await?;
+
+        Ok(user_ids
+            .into_iter()
+            .zip(online)
+            .map(|(user_id, online)| UserPresence {
+                user_id,
+                status: if online { PRESENCE_ONLINE } else { PRESENCE_OFFLINE }.to_string(),
+            })
+            .collect())
+    }
+
+    /// Mark a user online for a new realtime connection (best effort)
+    pub async fn connected(redis_pool: &RedisPool, user_id: Uuid) {
+        if let Err(e) = cache::presence_connect(redis_pool, user_id, PRESENCE_TTL_SECONDS).await {
+            log::warn!("Failed to record presence of user {}: {}", user_id, e);
+        }
+    }
+
+    /// Keep a connected user online (best effort)
+    pub async fn heartbeat(redis_pool: &RedisPool, user_id: Uuid) {
+        if let Err(e) = cache::presence_refresh(redis_pool, user_id, PRESENCE_TTL_SECONDS).await {
+            log::warn!("Failed to refresh presence of user {}: {}", user_id, e);
+        }
+    }
+
+    /// Drop a closed realtime connection; the user goes offline with the last one (best effort)
+    pub async fn disconnected(redis_pool: &RedisPool, user_id: Uuid) {
+        if let Err(e) = cache::presence_disconnect(redis_pool, user_id).await {
+            log::warn!("Failed to clear presence of user {}: {}", user_id, e);
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::push::{
+    DeviceToken, PushNotification, RegisterDeviceDto, VapidKeyResponse, WebPushSubscription, WebPushSubscriptionDto,
+    PLATFORM_APNS, PLATFORM_FCM, PUSH_BODY_MAX_CHARS, PUSH_DIRECT_MESSAGE, PUSH_MENTION,
+};
+use crate::models::room::Room;
+use crate::repositories::{PushRepository, UserRepository};
+use crate::utils::{mention, outbound_url, web_push};
+
+/// Timeout of one request to a push provider
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a push service keeps a Web Push message for an unreachable browser
+const WEB_PUSH_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Lifetime of a VAPID token (push services accept up to 24 hours)
+const VAPID_TOKEN_LIFETIME_SECONDS: i64 = 12 * 60 * 60;
+
+/// OAuth scope of the FCM HTTP v1 API
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+
+/// How long an APNs provider token is reused. Apple accepts one for an hour but
+/// rejects a new one more often than every 20 minutes.
+const APNS_TOKEN_LIFETIME_SECONDS: i64 = 50 * 60;
+
+/// Provider credential reused until shortly before it expires
+struct CachedToken<T> {
+    value: T,
+    expires_at: DateTime<Utc>,
+}
+
+/// FCM project ID and OAuth access token
+static FCM_ACCESS_TOKEN: Mutex<Option<CachedToken<(String, String)>>> = Mutex::new(None);
+
+/// Signed APNs provider token
+static APNS_PROVIDER_TOKEN: Mutex<Option<CachedToken<String>>> = Mutex::new(None);
+
+fn cached_token<T: Clone>(slot: &Mutex<Option<CachedToken<T>>>) -> Option<T> {
+    let slot = slot.lock().ok()?;
+    slot.as_ref()
+        .filter(|token| token.expires_at > Utc::now())
+        .map(|token| token.value.clone())
+}
+
+fn cache_token<T>(slot: &Mutex<Option<CachedToken<T>>>, value: T, expires_at: DateTime<Utc>) {
+    if let Ok(mut slot) = slot.lock() {
+        *slot = Some(CachedToken { value, expires_at });
+    }
+}
+
+/// Fields of a Google service account key file used to sign FCM access token requests
+#[derive(Deserialize)]
+struct ServiceAccount {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+/// OAuth token endpoint response
+#[derive(Deserialize)]
+struct AccessTokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+/// Error body of an APNs response
+#[derive(Default, Deserialize)]
+struct ApnsErrorResponse {
+    reason: String,
+}
+
+/// Outcome of handing a push to a provider
+#[derive(Debug, PartialEq, Eq)]
+enum Delivery {
+    Sent,
+    /// The token or subscription is no longer valid (app uninstalled, token rotated,
+    /// subscription expired) and should be forgotten
+    Gone,
+}
+
+fn push_failed(e: impl std::fmt::Display) -> AppError {
+    AppError::InternalError(format!("Push request failed: {}", e))
+}
+
+pub struct PushService;
+
+impl PushService {
+    /// Register a device token for push notifications
+    pub async fn register_device(
+        pool: &PgPool,
+        user_id: Uuid,
+        dto: RegisterDeviceDto,
+    ) -> Result<DeviceToken, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid device data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if ![PLATFORM_FCM, PLATFORM_APNS].contains(&dto.platform.as_str()) {
+            return Err(AppError::InvalidFormat("platform".to_string()));
+        }
+
+        PushRepository::upsert_device(pool, user_id, &dto.platform, &dto.token).await
+    }
+
+    /// List devices registered by the user
+    pub async fn list_devices(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<DeviceToken>, AppError> {
+        PushRepository::list_devices(pool, user_id).await
+    }
+
+    /// Unregister a device
+    pub async fn unregister_device(
+        pool: &PgPool,
+        device_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        if !PushRepository::delete_device(pool, device_id, user_id).await? {
+            return Err(AppError::DeviceNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Get the VAPID public key browsers need to subscribe
+    pub fn vapid_public_key(config: &Config) -> Result<VapidKeyResponse, AppError> {
+        let public_key = config
+            .vapid_public_key
+            .clone()
+            .ok_or(AppError::PushNotConfigured)?;
+
+        Ok(VapidKeyResponse { public_key })
+    }
+
+    /// Store a browser push subscription
+    pub async fn subscribe_web(
+        pool: &PgPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: WebPushSubscriptionDto,
+    ) -> Result<WebPushSubscription, AppError> {
+        if config.vapid_public_key.is_none() {
+            return Err(AppError::PushNotConfigured);
+        }
+
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid push subscription data");
+                AppError::ValidationError(errors)
+            })?;
+
+        PushRepository::upsert_web_subscription(
+            pool,
+            user_id,
+            &dto.endpoint,
+            &dto.keys.p256dh,
+            &dto.keys.auth,
+        )
+        .await
+    }
+
+    /// Remove a browser push subscription
+    pub async fn unsubscribe_web(
+        pool: &PgPool,
+        subscription_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        if !PushRepository::delete_web_subscription(pool, subscription_id, user_id).await? {
+            return Err(AppError::DeviceNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Push a message to the room members it @-mentions who aren't connected
+    /// (best effort, in the background)
+    pub fn notify_mentions(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        room: &Room,
+        author: &str,
+        content: &str,
+    ) {
+        let usernames = mention::usernames(content);
+        if usernames.is_empty() {
+            return;
+        }
+
+        let notification = PushNotification {
+            kind: PUSH_MENTION,
+            room_id: room.id,
+            title: format!("{} mentioned you in {}", author, room.name),
+            body: content.chars().take(PUSH_BODY_MAX_CHARS).collect(),
+        };
+        Self::dispatch(pool, redis_pool, config, Vec::new(), usernames, notification);
+    }
+
+    /// Push a direct message to its recipient when they aren't connected
+    /// (best effort, in the background)
+    pub fn notify_direct_message(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        room_id: Uuid,
+        recipient_id: Uuid,
+        author: &str,
+        content: &str,
+    ) {
+        let notification = PushNotification {
+            kind: PUSH_DIRECT_MESSAGE,
+            room_id,
+            title: author.to_string(),
+            body: content.chars().take(PUSH_BODY_MAX_CHARS).collect(),
+        };
+        Self::dispatch(pool, redis_pool, config, vec![recipient_id], Vec::new(), notification);
+    }
+
+    /// Whether pushes can be sent to devices of a platform
+    fn platform_enabled(config: &Config, platform: &str) -> bool {
+        match platform {
+            PLATFORM_FCM => config.fcm_service_account_path.is_some(),
+            PLATFORM_APNS => {
+                config.apns_key_path.is_some()
+                    && config.apns_key_id.is_some()
+                    && config.apns_team_id.is_some()
+                    && config.apns_topic.is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether pushes can be sent to browsers
+    fn web_push_enabled(config: &Config) -> bool {
+        config.vapid_public_key.is_some() && config.vapid_private_key.is_some()
+    }
+
+    fn dispatch(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        user_ids: Vec<Uuid>,
+        usernames: Vec<String>,
+        notification: PushNotification,
+    ) {
+        if !Self::web_push_enabled(config)
+            && ![PLATFORM_FCM, PLATFORM_APNS].iter().any(|platform| Self::platform_enabled(config, platform))
+        {
+            return;
+        }
+
+        let (pool, redis_pool, config) = (pool.clone(), redis_pool.clone(), config.clone());
+        tokio::spawn(async move {
+            if let Err(e) = Self::deliver(&pool, &redis_pool, &config, &user_ids, &usernames, &notification).await {
+                log::warn!("Failed to push {} in room {}: {}", notification.kind, notification.room_id, e);
+            }
+        });
+    }
+
+    /// Send a notification to the devices of members who want it: not connected, not
+    /// muting the room, not in their quiet hours
+    async fn deliver(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        user_ids: &[Uuid],
+        usernames: &[String],
+        notification: &PushNotification,
+    ) -> Result<(), AppError> {
+        let members = PushRepository::notifiable_members(pool, notification.room_id, user_ids, usernames).await?;
+        let online = cache::presence_online(redis_pool, &members).await?;
+
+        let client = reqwest::Client::builder()
+            .timeout(PUSH_TIMEOUT)
+            .build()
+            .map_err(push_failed)?;
+        let now = Utc::now();
+
+        // Connected users see the message in the app
+        for (user_id, _) in members.into_iter().zip(online).filter(|(_, online)| !online) {
+            let user = UserRepository::find_by_id(pool, user_id).await?;
+            if user.in_quiet_hours(now) {
+                continue;
+            }
+
+            let devices = PushRepository::list_devices(pool, user_id).await?;
+            for device in devices.iter().filter(|d| Self::platform_enabled(config, &d.platform)) {
+                let delivery = match device.platform.as_str() {
+                    PLATFORM_FCM => Self::send_fcm(&client, config, &device.token, notification).await,
+                    _ => Self::send_apns(&client, config, &device.token, notification).await,
+                };
+
+                match delivery {
+                    Ok(Delivery::Sent) => {}
+                    Ok(Delivery::Gone) => PushRepository::delete_device_token(pool, &device.token).await?,
+                    Err(e) => log::warn!("Push to device {} of user {} failed: {}", device.id, user_id, e),
+                }
+            }
+
+            if !Self::web_push_enabled(config) {
+                continue;
+            }
+
+            for subscription in PushRepository::list_web_subscriptions(pool, user_id).await? {
+                match Self::send_web_push(config, &subscription, notification).await {
+                    Ok(Delivery::Sent) => {}
+                    Ok(Delivery::Gone) => PushRepository::delete_web_subscription_endpoint(pool, &subscription.endpoint).await?,
+                    Err(e) => log::warn!("Web Push to subscription {} of user {} failed: {}", subscription.id, user_id, e),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send an encrypted Web Push message, signed with the VAPID key
+    async fn send_web_push(
+        config: &Config,
+        subscription: &WebPushSubscription,
+        notification: &PushNotification,
+    ) -> Result<Delivery, AppError> {
+        let private_key = config.vapid_private_key.as_deref().ok_or(AppError::PushNotConfigured)?;
+        let subject = config.vapid_subject.as_deref().unwrap_or(&config.app_base_url);
+
+        let payload = serde_json::to_vec(notification).map_err(push_failed)?;
+        // Keys the browser sent can't be fixed by retrying
+        let Ok(body) = web_push::encrypt(&subscription.p256dh, &subscription.auth, &payload) else {
+            return Ok(Delivery::Gone);
+        };
+
+        let expires_at = (Utc::now() + chrono::Duration::seconds(VAPID_TOKEN_LIFETIME_SECONDS)).timestamp();
+        let authorization = web_push::vapid_authorization(private_key, subject, &subscription.endpoint, expires_at)
+            .map_err(push_failed)?;
+
+        // The endpoint comes from the browser, so it's only reached on a public address
+        let client = outbound_url::client(&subscription.endpoint, PUSH_TIMEOUT).await.map_err(push_failed)?;
+        let response = client
+            .post(&subscription.endpoint)
+            .header("Authorization", authorization)
+            .header("Content-Type", "application/octet-stream")
+            .header("Content-Encoding", "aes128gcm")
+            .header("TTL", WEB_PUSH_TTL_SECONDS.to_string())
+            .header("Urgency", "high")
+            .body(body)
+            .send()
+            .await
+            .map_err(push_failed)?;
+
+        match response.status() {
+            status if status.is_success() => Ok(Delivery::Sent),
+            StatusCode::NOT_FOUND | StatusCode::GONE => Ok(Delivery::Gone),
+            status => Err(push_failed(format!("Push service answered {}", status))),
+        }
+    }
+
+    /// Send through the FCM HTTP v1 API
+    async fn send_fcm(
+        client: &reqwest::Client,
+        config: &Config,
+        token: &str,
+        notification: &PushNotification,
+    ) -> Result<Delivery, AppError> {
+        let (project_id, access_token) = Self::fcm_access_token(client, config).await?;
+
+        let message = serde_json::json!({
+            "message": {
+                "token": token,
+                "notification": {
+                    "title": notification.title,
+                    "body": notification.body,
+                },
+                "data": {
+                    "type": notification.kind,
+                    "room_id": notification.room_id.to_string(),
+                },
+            },
+        });
+
+        let response = client
+            .post(format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", project_id))
+            .bearer_auth(access_token)
+            .json(&message)
+            .send()
+            .await
+            .map_err(push_failed)?;
+
+        match response.status() {
+            status if status.is_success() => Ok(Delivery::Sent),
+            // UNREGISTERED
+            StatusCode::NOT_FOUND => Ok(Delivery::Gone),
+            status => Err(push_failed(format!("FCM answered {}", status))),
+        }
+    }
+
+    /// FCM project ID and an OAuth access token from the service account key
+    async fn fcm_access_token(client: &reqwest::Client, config: &Config) -> Result<(String, String), AppError> {
+        if let Some(token) = cached_token(&FCM_ACCESS_TOKEN) {
+            return Ok(token);
+        }
+
+        let path = config.fcm_service_account_path.as_deref().ok_or(AppError::PushNotConfigured)?;
+        let key_file = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to read FCM_SERVICE_ACCOUNT_PATH: {}", e)))?;
+        let account: ServiceAccount = serde_json::from_str(&key_file)
+            .map_err(|e| AppError::InternalError(format!("Invalid FCM service account key: {}", e)))?;
+
+        let now = Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "iss": account.client_email,
+            "scope": FCM_SCOPE,
+            "aud": account.token_uri,
+            "iat": now,
+            "exp": now + 3600,
+        });
+        let key = EncodingKey::from_rsa_pem(account.private_key.as_bytes())
+            .map_err(|e| AppError::InternalError(format!("Invalid FCM service account key: {}", e)))?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .map_err(|e| AppError::InternalError(format!("Failed to sign FCM token request: {}", e)))?;
+
+        let response: AccessTokenResponse = client
+            .post(&account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(push_failed)?
+            .json()
+            .await
+            .map_err(push_failed)?;
+
+        // Renewed a minute early so a token never expires mid-request
+        let token = (account.project_id, response.access_token);
+        let expires_at = Utc::now() + chrono::Duration::seconds(response.expires_in - 60);
+        cache_token(&FCM_ACCESS_TOKEN, token.clone(), expires_at);
+
+        Ok(token)
+    }
+
+    /// Send through APNs (HTTP/2, token auth)
+    async fn send_apns(
+        client: &reqwest::Client,
+        config: &Config,
+        token: &str,
+        notification: &PushNotification,
+    ) -> Result<Delivery, AppError> {
+        // APNs device tokens are hex; anything else can't be delivered
+        if !token.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(Delivery::Gone);
+        }
+
+        let topic = config.apns_topic.as_deref().ok_or(AppError::PushNotConfigured)?;
+        let provider_token = Self::apns_provider_token(config).await?;
+        let host = if config.apns_sandbox { "api.sandbox.push.apple.com" } else { "api.push.apple.com" };
+
+        let payload = serde_json::json!({
+            "aps": {
+                "alert": {
+                    "title": notification.title,
+                    "body": notification.body,
+                },
+                "sound": "default",
+            },
+            "type": notification.kind,
+            "room_id": notification.room_id,
+        });
+
+        let response = client
+            .post(format!("https://{}/3/device/{}", host, token))
+            .bearer_auth(provider_token)
+            .header("apns-topic", topic)
+            .header("apns-push-type", "alert")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(push_failed)?;
+
+        match response.status() {
+            status if status.is_success() => Ok(Delivery::Sent),
+            // Unregistered
+            StatusCode::GONE => Ok(Delivery::Gone),
+            status => {
+                let error: ApnsErrorResponse = response.json().await.unwrap_or_default();
+                // A token of another app or environment
+                if error.reason == "BadDeviceToken" {
+                    return Ok(Delivery::Gone);
+                }
+                Err(push_failed(format!("APNs answered {} ({})", status, error.reason)))
+            }
+        }
+    }
+
+    /// Provider token signed with the APNs key (ES256)
+    async fn apns_provider_token(config: &Config) -> Result<String, AppError> {
+        if let Some(token) = cached_token(&APNS_PROVIDER_TOKEN) {
+            return Ok(token);
+        }
+
+        let (Some(path), Some(key_id), Some(team_id)) = (&config.apns_key_path, &config.apns_key_id, &config.apns_team_id) else {
+            return Err(AppError::PushNotConfigured);
+        };
+
+        let key_file = tokio::fs::read(path)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to read APNS_KEY_PATH: {}", e)))?;
+        let key = EncodingKey::from_ec_pem(&key_file)
+            .map_err(|e| AppError::InternalError(format!("Invalid APNs key: {}", e)))?;
+
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(key_id.clone());
+        let now = Utc::now();
+        let claims = serde_json::json!({ "iss": team_id, "iat": now.timestamp() });
+        let token = jsonwebtoken::encode(&header, &claims, &key)
+            .map_err(|e| AppError::InternalError(format!("Failed to sign APNs provider token: {}", e)))?;
+
+        cache_token(&APNS_PROVIDER_TOKEN, token.clone(), now + chrono::Duration::seconds(APNS_TOKEN_LIFETIME_SECONDS));
+
+        Ok(token)
+    }
+}
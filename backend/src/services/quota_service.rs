@@ -0,0 +1,96 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::quota::{QuotaUsage, QuotaUsageResponse, QUOTA_API_REQUESTS, QUOTA_ROOMS_OWNED};
+use crate::models::user::ROLE_ADMIN;
+use crate::repositories::{RoomRepository, UserRepository};
+
+/// Daily counters outlive their day a little, so a late request can't restart one
+const API_QUOTA_TTL_SECONDS: u64 = 2 * 24 * 3600;
+
+pub struct QuotaService;
+
+impl QuotaService {
+    /// Refuse a new room when the user already owns the maximum
+    pub async fn check_rooms_owned(pool: &PgPool, config: &Config, user_id: Uuid) -> Result<(), AppError> {
+        let Some(limit) = Self::limit(config.quota.max_rooms_owned) else {
+            return Ok(());
+        };
+
+        let owned = RoomRepository::count_owned(pool, user_id).await?;
+        if owned as u64 >= limit {
+            return Err(AppError::QuotaExceeded(QUOTA_ROOMS_OWNED.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Count an authenticated API request against the user's daily quota
+    /// (fails open if Redis is down)
+    pub async fn hit_api_request(redis_pool: &RedisPool, config: &Config, user_id: Uuid) -> Result<(), AppError> {
+        let Some(limit) = Self::limit(config.quota.max_api_requests_per_day) else {
+            return Ok(());
+        };
+
+        match cache::quota_hit(redis_pool, &Self::api_key(user_id), API_QUOTA_TTL_SECONDS).await {
+            Ok(count) if count > limit => Err(AppError::QuotaExceeded(QUOTA_API_REQUESTS.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                log::error!("API request quota check failed: {}", e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Current usage of every quota of a user
+    pub async fn usage(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        user_id: Uuid,
+    ) -> Result<QuotaUsageResponse, AppError> {
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+
+        let rooms_owned = RoomRepository::count_owned(pool, user_id).await?;
+
+        let api_requests = cache::get_value(redis_pool, &Self::api_key(user_id)).await?
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(0);
+        let api_limit = if user.role == ROLE_ADMIN {
+            None
+        } else {
+            Self::limit(config.quota.max_api_requests_per_day)
+        };
+        let tomorrow = (Utc::now() + Duration::days(1)).date_naive();
+
+        Ok(QuotaUsageResponse {
+            quotas: vec![
+                QuotaUsage {
+                    quota: QUOTA_ROOMS_OWNED,
+                    used: rooms_owned as u64,
+                    limit: Self::limit(config.quota.max_rooms_owned),
+                    resets_at: None,
+                },
+                QuotaUsage {
+                    quota: QUOTA_API_REQUESTS,
+                    used: api_requests,
+                    limit: api_limit,
+                    resets_at: Some(tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()),
+                },
+            ],
+        })
+    }
+
+    /// Daily API request counter of a user (UTC days)
+    fn api_key(user_id: Uuid) -> String {
+        format!("quota:api:{}:{}", user_id, Utc::now().format("%Y-%m-%d"))
+    }
+
+    /// Configured limit, None when unlimited (0)
+    fn limit(value: u32) -> Option<u64> {
+        (value > 0).then_some(value as u64)
+    }
+}
@@ -0,0 +1,209 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::audit::{AUDIT_RATE_LIMIT_RESET, AUDIT_RATE_LIMIT_UPDATED};
+use crate::models::rate_limit::{
+    RateLimitOverride, RateLimitSetting, UpdateRateLimitDto, RATE_LIMIT_CLASSES, RATE_LIMIT_CLASS_MESSAGE,
+    RATE_LIMIT_CLASS_ROOM_CREATE, RATE_LIMIT_CLASS_WEBHOOK,
+};
+use crate::repositories::{AuditRepository, RateLimitRepository};
+
+/// Redis key caching the admin overrides; dropped whenever one changes
+const RATE_LIMITS_CACHE_KEY: &str = "rate_limits";
+
+/// Safety net in case an invalidation is lost
+const RATE_LIMITS_CACHE_TTL_SECONDS: u64 = 3600;
+
+pub struct RateLimitService;
+
+impl RateLimitService {
+    /// Limits in effect for every route class (admin only)
+    pub async fn list(pool: &PgPool, config: &Config) -> Result<Vec<RateLimitSetting>, AppError> {
+        let overrides = RateLimitRepository::list(pool).await?;
+
+        Ok(RATE_LIMIT_CLASSES
+            .iter()
+            .map(|class| Self::effective(config, &overrides, class))
+            .collect())
+    }
+
+    /// Limit in effect for a route class, read on every rate-limited request.
+    /// Falls back to the environment config if the overrides can't be loaded,
+    /// so a database or Redis outage never disables rate limiting.
+    pub async fn get(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        class: &'static str,
+    ) -> RateLimitSetting {
+        let overrides = match Self::load(pool, redis_pool).await {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                log::error!("Failed to load rate limit overrides: {}", e);
+                Vec::new()
+            }
+        };
+
+        Self::effective(config, &overrides, class)
+    }
+
+    /// Take a message send from the sender's bucket of a token-bucket class (a user's,
+    /// or an incoming webhook's); MessageSpam when it is empty. Fails open if Redis is down.
+    pub async fn take_message_token(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        class: &'static str,
+        sender_id: Uuid,
+    ) -> Result<(), AppError> {
+        let bucket_key = format!("rate_limit:{}:{}", class, sender_id);
+        let limit = Self::get(pool, redis_pool, config, class).await;
+
+        match cache::take_bucket_token(redis_pool, &bucket_key, limit.max_requests, limit.window_seconds).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AppError::MessageSpam),
+            Err(e) => {
+                log::error!("Message rate limit check failed: {}", e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Override a route class's limit; applies to the next request
+    pub async fn update(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        admin_id: Uuid,
+        class: &str,
+        dto: UpdateRateLimitDto,
+    ) -> Result<RateLimitSetting, AppError> {
+        let class = Self::known_class(class)?;
+
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "max_requests must be between 1-100000 and window_seconds between 1-86400");
+                AppError::ValidationError(errors)
+            })?;
+
+        let previous = Self::list(pool, config).await?.into_iter().find(|setting| setting.class == class);
+
+        let limit = RateLimitRepository::upsert(pool, class, dto.max_requests, dto.window_seconds, admin_id).await?;
+        Self::invalidate(redis_pool).await;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_RATE_LIMIT_UPDATED,
+            None,
+            Some(serde_json::json!({
+                "class": class,
+                "max_requests": limit.max_requests,
+                "window_seconds": limit.window_seconds,
+                "previous": previous,
+            })),
+        )
+        .await?;
+
+        Ok(Self::effective(config, &[limit], class))
+    }
+
+    /// Drop a route class's override, going back to the environment config
+    pub async fn reset(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        admin_id: Uuid,
+        class: &str,
+    ) -> Result<RateLimitSetting, AppError> {
+        let class = Self::known_class(class)?;
+
+        if RateLimitRepository::delete(pool, class).await? {
+            Self::invalidate(redis_pool).await;
+
+            AuditRepository::record(
+                pool,
+                Some(admin_id),
+                AUDIT_RATE_LIMIT_RESET,
+                None,
+                Some(serde_json::json!({ "class": class })),
+            )
+            .await?;
+        }
+
+        Ok(Self::effective(config, &[], class))
+    }
+
+    /// Override for the class if there is one, the environment config otherwise
+    fn effective(config: &Config, overrides: &[RateLimitOverride], class: &'static str) -> RateLimitSetting {
+        let limits = &config.rate_limit;
+
+        if let Some(limit) = overrides.iter().find(|limit| limit.class == class) {
+            return RateLimitSetting {
+                class,
+                max_requests: limit.max_requests.max(1) as u32,
+                window_seconds: limit.window_seconds.max(1) as u64,
+                is_default: false,
+            };
+        }
+
+        let (max_requests, window_seconds) = match class {
+            RATE_LIMIT_CLASS_ROOM_CREATE => (limits.room_create_burst, limits.room_create_refill_seconds),
+            RATE_LIMIT_CLASS_WEBHOOK => (limits.webhook_burst, limits.webhook_refill_seconds),
+            RATE_LIMIT_CLASS_MESSAGE => (limits.message_burst, limits.message_refill_seconds),
+            _ => (limits.auth_max_requests, limits.auth_window_seconds), // RATE_LIMIT_CLASS_AUTH
+        };
+
+        RateLimitSetting {
+            class,
+            max_requests,
+            window_seconds,
+            is_default: true,
+        }
+    }
+
+    /// The static name of a route class, or InvalidFormat if there is no such class
+    fn known_class(class: &str) -> Result<&'static str, AppError> {
+        RATE_LIMIT_CLASSES
+            .iter()
+            .copied()
+            .find(|known| *known == class)
+            .ok_or_else(|| AppError::InvalidFormat("class".to_string()))
+    }
+
+    /// Overrides from the Redis cache, falling back to (and refilling from) Postgres
+    async fn load(pool: &PgPool, redis_pool: &RedisPool) -> Result<Vec<RateLimitOverride>, AppError> {
+        match cache::get_value(redis_pool, RATE_LIMITS_CACHE_KEY).await {
+            Ok(Some(cached)) => {
+                if let Ok(overrides) = serde_json::from_str(&cached) {
+                    return Ok(overrides);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to read cached rate limits: {}", e),
+        }
+
+        let overrides = RateLimitRepository::list(pool).await?;
+
+        if let Ok(serialized) = serde_json::to_string(&overrides) {
+            if let Err(e) =
+                cache::set_with_ttl(redis_pool, RATE_LIMITS_CACHE_KEY, &serialized, RATE_LIMITS_CACHE_TTL_SECONDS).await
+            {
+                log::warn!("Failed to cache rate limits: {}", e);
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// Drop the cached overrides so every instance reloads them
+    async fn invalidate(redis_pool: &RedisPool) {
+        if let Err(e) = cache::delete_value(redis_pool, RATE_LIMITS_CACHE_KEY).await {
+            log::warn!("Failed to invalidate cached rate limits: {}", e);
+        }
+    }
+}
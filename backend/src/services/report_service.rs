@@ -0,0 +1,229 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::error::{AppError, ValidationErrors};
+use crate::models::moderation::{EVENT_MESSAGE_DELETED, EVENT_USER_SUSPENDED};
+use crate::models::report::{
+    can_transition, CreateReportDto, Report, ReportActionDto, ReportQueueItem, ReportReviewItem,
+    UpdateReportStatusDto, REPORT_ACTIONS, REPORT_ACTION_BAN, REPORT_ACTION_DELETE_MESSAGE, REPORT_ACTION_WARN,
+    REPORT_OPEN, REPORT_REASONS, REPORT_RESOLVED, REPORT_REVIEWING, REPORT_TARGET_MESSAGE, REPORT_TARGET_USER,
+};
+use crate::repositories::{ReportRepository, RoomRepository, UserRepository};
+
+pub struct ReportService;
+
+impl ReportService {
+    /// Report a message (as a member of its room) or a user
+    pub async fn create_report(pool: &PgPool, reporter_id: Uuid, dto: CreateReportDto) -> Result<Report, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("details", "Details must not exceed 1000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        if !REPORT_REASONS.contains(&dto.reason.as_str()) {
+            return Err(AppError::InvalidFormat("reason".to_string()));
+        }
+
+        // Resolve the reported user (the author, for messages)
+        let (target_user_id, room_id) = match dto.target_type.as_str() {
+            REPORT_TARGET_MESSAGE => {
+                let message_id = dto.message_id.ok_or(AppError::MissingField("message_id".to_string()))?;
+                let (author_id, room_id) = ReportRepository::find_message_context(pool, message_id)
+                    .await?
+                    .ok_or(AppError::MessageNotFound)?;
+
+                // Only messages the reporter can see
+                if !RoomRepository::is_member(pool, room_id, reporter_id).await? {
+                    return Err(AppError::MessageNotFound);
+                }
+
+                (author_id, Some(room_id))
+            }
+            REPORT_TARGET_USER => {
+                if dto.message_id.is_some() {
+                    return Err(AppError::InvalidFormat("message_id".to_string()));
+                }
+                let user_id = dto.user_id.ok_or(AppError::MissingField("user_id".to_string()))?;
+                (UserRepository::find_by_id(pool, user_id).await?.id, None)
+            }
+            _ => return Err(AppError::InvalidFormat("target_type".to_string())),
+        };
+
+        if target_user_id == reporter_id {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("target_type", "You can't report yourself");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        if ReportRepository::unresolved_exists(pool, reporter_id, target_user_id, dto.message_id).await? {
+            return Err(AppError::ReportExists);
+        }
+
+        let report = ReportRepository::create(pool, reporter_id, &dto, target_user_id, room_id).await?;
+
+        log::info!("Report {} filed by {} against user {}", report.id, reporter_id, target_user_id);
+
+        Ok(report)
+    }
+
+    /// List the moderation queue (moderators and admins)
+    pub async fn list_queue(
+        pool: &PgPool,
+        status: Option<&str>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<ReportQueueItem>, i64), AppError> {
+        if let Some(status) = status {
+            if ![REPORT_OPEN, REPORT_REVIEWING, REPORT_RESOLVED].contains(&status) {
+                return Err(AppError::InvalidFormat("status".to_string()));
+            }
+        }
+
+        let offset = ((page - 1) * per_page) as i64;
+        let items = ReportRepository::list_queue(pool, status, offset, per_page as i64).await?;
+        let total = ReportRepository::count_queue(pool, status).await?;
+
+        Ok((items, total))
+    }
+
+    /// Move a report through the queue (open, reviewing, resolved)
+    pub async fn update_status(
+        pool: &PgPool,
+        report_id: Uuid,
+        reviewer_id: Uuid,
+        dto: UpdateReportStatusDto,
+    ) -> Result<Report, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("note", "Note must not exceed 1000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let report = ReportRepository::find_by_id(pool, report_id).await?;
+
+        if !can_transition(&report.status, &dto.status) {
+            return Err(AppError::ReportTransitionInvalid);
+        }
+
+        // Lost a race with another moderator if the status changed in the meantime
+        let updated = ReportRepository::transition(
+            pool,
+            report_id,
+            &report.status,
+            &dto.status,
+            reviewer_id,
+            dto.note.as_deref(),
+        )
+        .await?
+        .ok_or(AppError::ReportTransitionInvalid)?;
+
+        log::info!("Report {} moved from {} to {} by {}", report_id, report.status, updated.status, reviewer_id);
+
+        Ok(updated)
+    }
+
+    /// Unresolved reports for admin review, with the offending content inline
+    pub async fn review_queue(
+        pool: &PgPool,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<ReportReviewItem>, i64), AppError> {
+        let offset = ((page - 1) * per_page) as i64;
+        let items = ReportRepository::list_for_review(pool, offset, per_page as i64).await?;
+        let total = ReportRepository::count_queue(pool, None).await?;
+
+        Ok((items, total))
+    }
+
+    /// Resolve a report by dismissing it, deleting the message, warning or banning the
+    /// reported user. The action, resolution and audit entry commit together.
+    pub async fn take_action(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        report_id: Uuid,
+        reviewer_id: Uuid,
+        dto: ReportActionDto,
+    ) -> Result<Report, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("note", "Note must not exceed 1000 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        if !REPORT_ACTIONS.contains(&dto.action.as_str()) {
+            return Err(AppError::InvalidFormat("action".to_string()));
+        }
+
+        let report = ReportRepository::find_by_id(pool, report_id).await?;
+
+        if report.status == REPORT_RESOLVED {
+            return Err(AppError::ReportTransitionInvalid);
+        }
+        if dto.action == REPORT_ACTION_DELETE_MESSAGE && report.message_id.is_none() {
+            return Err(AppError::InvalidFormat("action".to_string()));
+        }
+        if dto.action == REPORT_ACTION_BAN && report.target_user_id == reviewer_id {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        let note = dto.note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+
+        // Lost a race with another reviewer if it was resolved in the meantime
+        let resolved = ReportRepository::resolve_with_action(pool, &report, &dto.action, reviewer_id, note)
+            .await?
+            .ok_or(AppError::ReportTransitionInvalid)?;
+
+        log::info!("Report {} resolved with {} by {}", report.id, dto.action, reviewer_id);
+
+        // Realtime side effects are best effort once the action is committed
+        let notifications = match dto.action.as_str() {
+            REPORT_ACTION_DELETE_MESSAGE => report
+                .room_id
+                .map(|room_id| {
+                    let event = serde_json::json!({
+                        "type": EVENT_MESSAGE_DELETED,
+                        "room_id": room_id,
+                        "message_id": report.message_id,
+                        "created_at": Utc::now(),
+                    });
+                    vec![(cache::room_channel(room_id), event.to_string())]
+                })
+                .unwrap_or_default(),
+            REPORT_ACTION_WARN => {
+                let event = serde_json::json!({
+                    "type": "user.warning",
+                    "reason": note.unwrap_or(&report.reason),
+                    "created_at": Utc::now(),
+                });
+                vec![(cache::user_channel(report.target_user_id), event.to_string())]
+            }
+            REPORT_ACTION_BAN => {
+                let event = serde_json::json!({
+                    "type": EVENT_USER_SUSPENDED,
+                    "user_id": report.target_user_id,
+                    "until": null,
+                    "created_at": Utc::now(),
+                });
+                vec![
+                    (cache::user_disconnect_channel(report.target_user_id), "account_suspended".to_string()),
+                    (cache::broadcast_channel(), event.to_string()),
+                ]
+            }
+            _ => Vec::new(),
+        };
+
+        for (channel, message) in notifications {
+            if let Err(e) = cache::publish(redis_pool, &channel, &message).await {
+                log::warn!("Failed to publish {} of report {}: {}", dto.action, report.id, e);
+            }
+        }
+
+        Ok(resolved)
+    }
+}
@@ -0,0 +1,88 @@
+use async_zip::tokio::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use chrono::Utc;
+use futures_util::stream::BoxStream;
+use futures_util::{AsyncWriteExt, TryStreamExt};
+use sqlx::PgPool;
+use tokio::io::DuplexStream;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::room::Room;
+use crate::repositories::{ExportRepository, RoomRepository};
+
+/// Bytes buffered between the archive writer and the response
+const EXPORT_BUFFER_SIZE: usize = 64 * 1024;
+
+pub struct RoomExportService;
+
+impl RoomExportService {
+    /// Stream a zip export of a room (owner only): room.json, members.jsonl and messages.jsonl.
+    /// The archive is written while it is sent, so memory use does not grow with the room.
+    pub async fn export_room(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<ReaderStream<DuplexStream>, AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+
+        let role = RoomRepository::get_user_role(pool, room_id, user_id).await?;
+        if role.as_deref() != Some("owner") {
+            return Err(AppError::OwnerRequired);
+        }
+
+        let (writer, reader) = tokio::io::duplex(EXPORT_BUFFER_SIZE);
+
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            // The response has already started: a failure leaves a truncated (invalid) archive
+            if let Err(e) = Self::write_archive(&pool, room, writer).await {
+                log::error!("Export of room {} failed: {}", room_id, e);
+            }
+        });
+
+        Ok(ReaderStream::new(reader))
+    }
+
+    async fn write_archive(pool: &PgPool, room: Room, writer: DuplexStream) -> Result<(), AppError> {
+        let mut zip = ZipFileWriter::with_tokio(writer);
+
+        let metadata = serde_json::json!({
+            "exported_at": Utc::now(),
+            "room": room,
+        });
+        let entry = ZipEntryBuilder::new("room.json".to_string().into(), Compression::Deflate);
+        zip.write_entry_whole(entry, metadata.to_string().as_bytes()).await?;
+
+        Self::write_jsonl(&mut zip, "members.jsonl", ExportRepository::room_members(pool, room.id)).await?;
+        Self::write_jsonl(&mut zip, "messages.jsonl", ExportRepository::room_messages(pool, room.id)).await?;
+
+        zip.close().await?;
+
+        Ok(())
+    }
+
+    /// Write rows as one JSON document per line, as they come from the database
+    async fn write_jsonl(
+        zip: &mut ZipFileWriter<DuplexStream>,
+        name: &str,
+        mut rows: BoxStream<'_, Result<serde_json::Value, sqlx::Error>>,
+    ) -> Result<(), AppError> {
+        let entry = ZipEntryBuilder::new(name.to_string().into(), Compression::Deflate);
+        let mut entry_writer = zip.write_entry_stream(entry).await?;
+
+        while let Some(row) = rows.try_next().await? {
+            let mut line = row.to_string();
+            line.push('\n');
+
+            entry_writer
+                .write_all(line.as_bytes())
+                .await
+                .map_err(|e| AppError::InternalError(format!("Failed to write room export: {}", e)))?;
+        }
+
+        entry_writer.close().await?;
+
+        Ok(())
+    }
+}
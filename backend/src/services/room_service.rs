@@ -1,9 +1,39 @@
+use chrono::{Duration, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
 use crate::error::{AppError, ValidationErrors};
-use crate::models::room::{CreateRoomDto, UpdateRoomDto, RoomResponse, RoomMemberResponse, RoomWithMembersResponse};
-use crate::repositories::RoomRepository;
+use crate::models::ban::{BanUserDto, RoomBan};
+use crate::models::moderation::{
+    ModerationLogEntry, EVENT_USER_BANNED, EVENT_USER_KICKED, MOD_BAN, MOD_KICK, MOD_ROLE_CHANGE, MOD_UNBAN,
+};
+use crate::models::mute::{MuteRoomDto, MuteStatusResponse};
+use crate::models::permission::{
+    default_permissions, role_rank, RolePermissionsResponse, UpdateRolePermissionsDto, ALL_PERMISSIONS,
+    CUSTOMIZABLE_ROLES, PERM_BAN, PERM_EDIT_ROOM, PERM_KICK, PERM_MANAGE_ROLES, PERM_SEND_MESSAGES,
+};
+use crate::models::room::{
+    BulkAddMembersDto, BulkAddMembersResponse, BulkMemberResult, CreateRoomDto, JoinRoomDto, MentionSuggestion, MyRoomResponse, ReorderRoomsDto, Room, SetNicknameDto, UpdateMemberRoleDto, UpdateNotificationLevelDto, UpdateRoomDto, RoomResponse, RoomMemberResponse, RoomWithMembersResponse,
+    BULK_ADDED, BULK_DUPLICATE, BULK_NOT_FOUND, EVENT_MEMBERSHIP_ENDED, MENTION_SUGGEST_MAX_LIMIT, NOTIFY_ALL, NOTIFY_MENTIONS, NOTIFY_NONE, POST_POLICY_ADMINS_ONLY, POST_POLICY_EVERYONE, ROOM_TYPE_DM, ROOM_TYPE_PRIVATE, ROOM_TYPE_PUBLIC,
+};
+use crate::models::welcome::{RoomWelcome, SetWelcomeDto, SYSTEM_AUTHOR, WELCOME_DELIVERY_DM, WELCOME_DELIVERY_ROOM};
+use crate::models::rules::{AcceptRulesDto, RulesResponse, SetRulesDto};
+use crate::repositories::{
+    BanRepository, DmRepository, ModerationRepository, MuteRepository, RoomRepository, RulesRepository, SpaceRepository, UserRepository,
+    WelcomeRepository,
+};
+use crate::models::webhook::{OUTGOING_EVENT_MEMBER_JOINED, OUTGOING_EVENT_ROOM_UPDATED};
+use crate::models::activity::ACTIVITY_ROLE_CHANGE;
+use crate::models::rate_limit::RATE_LIMIT_CLASS_ROOM_CREATE;
+use crate::services::{
+    ActivityService, OutgoingWebhookService, QuotaService, RateLimitService, SpaceService, UserService,
+};
+use crate::utils::password;
+
+/// How long a room's mention candidates are cached
+const MENTION_CACHE_TTL_SECONDS: u64 = 30;
 
 pub struct RoomService;
 
@@ -11,6 +41,8 @@ impl RoomService {
     /// Create a new room
     pub async fn create_room(
         pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
         dto: CreateRoomDto,
         owner_id: Uuid,
     ) -> Result<RoomResponse, AppError> {
@@ -22,17 +54,64 @@ impl RoomService {
                 AppError::ValidationError(errors)
             })?;
 
+        // Direct message rooms are only created through the DM endpoints
+        if ![ROOM_TYPE_PUBLIC, ROOM_TYPE_PRIVATE].contains(&dto.room_type.as_str()) {
+            return Err(AppError::InvalidFormat("room_type".to_string()));
+        }
+
+        if let Some(post_policy) = &dto.post_policy {
+            if ![POST_POLICY_EVERYONE, POST_POLICY_ADMINS_ONLY].contains(&post_policy.as_str()) {
+                return Err(AppError::InvalidFormat("post_policy".to_string()));
+            }
+        }
+
+        // Per-user token bucket against room spam (fails open if Redis is down)
+        let bucket_key = format!("rate_limit:room_create:{}", owner_id);
+        let limit = RateLimitService::get(pool, redis_pool, config, RATE_LIMIT_CLASS_ROOM_CREATE).await;
+        match cache::take_bucket_token(redis_pool, &bucket_key, limit.max_requests, limit.window_seconds).await {
+            Ok(true) => {}
+            Ok(false) => return Err(AppError::RateLimitExceeded),
+            Err(e) => log::error!("Room creation rate limit check failed: {}", e),
+        }
+
+        // Optionally require a verified email before creating rooms
+        if config.require_verified_email {
+            let owner = UserRepository::find_by_id(pool, owner_id).await?;
+            if owner.email_verified_at.is_none() {
+                return Err(AppError::EmailNotVerified);
+            }
+        }
+
+        // Channels of a space are created by its admins
+        if let Some(space_id) = dto.space_id {
+            SpaceService::require_space_admin(pool, space_id, owner_id).await?;
+        }
+
+        QuotaService::check_rooms_owned(pool, config, owner_id).await?;
+
         // Check if room name already exists
         if RoomRepository::name_exists(pool, &dto.name).await? {
             return Err(AppError::RoomNameExists);
         }
 
+        let join_password_hash = match &dto.join_password {
+            Some(join_password) => Some(password::hash_password(join_password, &config.argon2)?),
+            None => None,
+        };
+
         // Create room
-        let room = RoomRepository::create(pool, &dto, owner_id).await?;
+        let room = RoomRepository::create(pool, &dto, owner_id, join_password_hash.as_deref()).await?;
 
         // Add creator as owner
         RoomRepository::add_member(pool, room.id, owner_id, "owner").await?;
 
+        // Open channels share the membership of their space
+        if let Some(space_id) = room.space_id {
+            if room.room_type == ROOM_TYPE_PUBLIC && room.join_password_hash.is_none() {
+                SpaceRepository::add_members_to_channel(pool, space_id, room.id).await?;
+            }
+        }
+
         // Get member count
         let member_count = RoomRepository::count_members(pool, room.id).await?;
 
@@ -61,6 +140,56 @@ impl RoomService {
         Ok((rooms, total))
     }
 
+    /// Get rooms the user is a member of, with their notification settings
+    pub async fn get_my_rooms(
+        pool: &PgPool,
+        user_id: Uuid,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<MyRoomResponse>, i64), AppError> {
+        let limit = per_page as i64;
+        let offset = ((page - 1) * per_page) as i64;
+
+        let rooms = RoomRepository::list_user_rooms(pool, user_id, offset, limit).await?;
+        let total = RoomRepository::count_user_rooms(pool, user_id).await?;
+
+        Ok((rooms, total))
+    }
+
+    /// Star or unstar a room in one's own room list
+    pub async fn set_favorite(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        is_favorite: bool,
+    ) -> Result<(), AppError> {
+        RoomRepository::set_favorite(pool, room_id, user_id, is_favorite).await
+    }
+
+    /// Set the custom order of one's own rooms (rooms the user is not in are ignored)
+    pub async fn reorder_my_rooms(
+        pool: &PgPool,
+        user_id: Uuid,
+        dto: ReorderRoomsDto,
+    ) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid room order");
+                AppError::ValidationError(errors)
+            })?;
+
+        // Keep the first position of a room listed twice
+        let mut room_ids = Vec::with_capacity(dto.room_ids.len());
+        for room_id in dto.room_ids {
+            if !room_ids.contains(&room_id) {
+                room_ids.push(room_id);
+            }
+        }
+
+        RoomRepository::set_sort_order(pool, user_id, &room_ids).await
+    }
+
     /// Get room details with members
     pub async fn get_room(
         pool: &PgPool,
@@ -73,15 +202,29 @@ impl RoomService {
         // Check if user has access (public room or is member)
         let is_member = RoomRepository::is_member(pool, room_id, user_id).await?;
 
-        if room.room_type == "private" && !is_member {
+        if room.room_type != ROOM_TYPE_PUBLIC && !is_member {
             return Err(AppError::PrivateNoAccess);
         }
 
         // Get members
         let members = RoomRepository::get_members(pool, room_id).await?;
 
-        // Get user role
+        // Get user role and what it allows
         let user_role = RoomRepository::get_user_role(pool, room_id, user_id).await?;
+        let user_permissions = match &user_role {
+            Some(role) => Self::role_permissions(pool, room_id, role).await?,
+            None => Vec::new(),
+        };
+
+        // Members must accept the current rules (if any) before posting
+        let rules_accepted = match RulesRepository::find(pool, room_id).await? {
+            Some(rules) if is_member => {
+                RulesRepository::accepted_version(pool, room_id, user_id).await? == Some(rules.version)
+            }
+            _ => true,
+        };
+
+        let can_post = Self::can_post(&room, user_role.as_deref(), &user_permissions, rules_accepted);
 
         // Get member count
         let member_count = members.len() as i64;
@@ -94,12 +237,16 @@ impl RoomService {
             members,
             is_member,
             user_role,
+            user_permissions,
+            can_post,
         })
     }
 
-    /// Update room (only owner/admin can update)
+    /// Update room (requires the edit_room permission)
     pub async fn update_room(
         pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
         room_id: Uuid,
         dto: UpdateRoomDto,
         user_id: Uuid,
@@ -112,27 +259,75 @@ impl RoomService {
                 AppError::ValidationError(errors)
             })?;
 
+        if let Some(room_type) = &dto.room_type {
+            if ![ROOM_TYPE_PUBLIC, ROOM_TYPE_PRIVATE].contains(&room_type.as_str()) {
+                return Err(AppError::InvalidFormat("room_type".to_string()));
+            }
+        }
+
+        if let Some(post_policy) = &dto.post_policy {
+            if ![POST_POLICY_EVERYONE, POST_POLICY_ADMINS_ONLY].contains(&post_policy.as_str()) {
+                return Err(AppError::InvalidFormat("post_policy".to_string()));
+            }
+        }
+
         // Check if room exists
-        let _room = RoomRepository::find_by_id(pool, room_id).await?;
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
 
-        // Check permissions (owner or admin)
-        let role = RoomRepository::get_user_role(pool, room_id, user_id).await?;
-        
-        match role.as_deref() {
-            Some("owner") | Some("admin") => {
-                // Update room
-                let updated_room = RoomRepository::update(pool, room_id, &dto).await?;
-                
-                // Get member count
-                let member_count = RoomRepository::count_members(pool, room_id).await?;
-                
-                let mut room_response = RoomResponse::from(updated_room);
-                room_response.member_count = member_count;
-                
-                Ok(room_response)
+        // Group DMs can be renamed, but no direct message room changes type
+        if room.room_type == ROOM_TYPE_DM || (room.is_direct_message() && dto.room_type.is_some()) {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        Self::ensure_not_locked(&room)?;
+
+        // Check permissions
+        Self::require_permission(pool, room_id, user_id, PERM_EDIT_ROOM).await?;
+
+        // Set or remove the join password
+        match dto.join_password.as_deref() {
+            Some("") => RoomRepository::set_join_password(pool, room_id, None).await?,
+            Some(join_password) if join_password.len() < 4 => {
+                return Err(AppError::InvalidFormat("join_password".to_string()));
             }
-            _ => Err(AppError::InsufficientPermissions),
+            Some(join_password) => {
+                let hash = password::hash_password(join_password, &config.argon2)?;
+                RoomRepository::set_join_password(pool, room_id, Some(&hash)).await?;
+            }
+            None => {}
+        }
+
+        // Update room
+        let updated_room = RoomRepository::update(pool, room_id, &dto).await?;
+
+        if updated_room.topic != room.topic {
+            Self::broadcast(
+                redis_pool,
+                room_id,
+                serde_json::json!({
+                    "type": "room.topic_changed",
+                    "room_id": room_id,
+                    "topic": updated_room.topic,
+                    "changed_by": user_id,
+                }),
+            ).await;
         }
+
+        // Get member count
+        let member_count = RoomRepository::count_members(pool, room_id).await?;
+
+        let mut room_response = RoomResponse::from(updated_room);
+        room_response.member_count = member_count;
+
+        OutgoingWebhookService::dispatch(
+            pool,
+            room_id,
+            OUTGOING_EVENT_ROOM_UPDATED,
+            serde_json::json!({ "room": &room_response, "updated_by": user_id }),
+        )
+        .await;
+
+        Ok(room_response)
     }
 
     /// Delete room (only owner can delete)
@@ -157,35 +352,59 @@ impl RoomService {
         Ok(())
     }
 
-    /// Join a room
+    /// Join a public room, or any room with its join password
     pub async fn join_room(
         pool: &PgPool,
+        redis_pool: &RedisPool,
         room_id: Uuid,
         user_id: Uuid,
+        dto: JoinRoomDto,
     ) -> Result<RoomMemberResponse, AppError> {
         // Check if room exists
         let room = RoomRepository::find_by_id(pool, room_id).await?;
 
+        // Direct message participants are managed through the DM endpoints
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
         // Check if already a member
         if RoomRepository::is_member(pool, room_id, user_id).await? {
             return Err(AppError::AlreadyJoined);
         }
 
-        // Check if room is full
-        if let Some(max_members) = room.max_members {
-            let member_count = RoomRepository::count_members(pool, room_id).await?;
-            if member_count >= max_members as i64 {
-                return Err(AppError::RoomFull);
-            }
+        Self::ensure_not_locked(&room)?;
+
+        Self::ensure_not_banned(pool, room_id, user_id).await?;
+
+        // Channels of a space are only open to its members
+        if let Some(space_id) = room.space_id {
+            SpaceService::require_member(pool, space_id, user_id).await?;
         }
 
-        // Check if private room
-        if room.room_type == "private" {
-            return Err(AppError::PrivateNoAccess);
+        // Password-protected rooms (public or private) need the password,
+        // other private rooms are invite-only. The cached room only says whether
+        // a password is set, so its hash is read from the database here
+        let join_password_hash = match room.join_password_hash {
+            Some(_) => RoomRepository::find_join_password_hash(pool, room_id).await?,
+            None => None,
+        };
+        match &join_password_hash {
+            Some(hash) => {
+                let join_password = dto.password.as_deref().ok_or(AppError::RoomPasswordInvalid)?;
+                if !password::verify_password(join_password, hash)? {
+                    return Err(AppError::RoomPasswordInvalid);
+                }
+            }
+            None if room.room_type == ROOM_TYPE_PRIVATE => return Err(AppError::PrivateNoAccess),
+            None => {}
         }
 
-        // Add as member
-        RoomRepository::add_member(pool, room_id, user_id, "member").await?;
+        // Add as member (fails atomically if the room is full)
+        RoomRepository::add_member_within_capacity(pool, room_id, user_id, "member").await?;
+
+        Self::send_welcome(pool, redis_pool, &room, user_id).await;
+        Self::dispatch_member_joined(pool, room_id, user_id).await;
 
         // Get updated member info
         let members = RoomRepository::get_members(pool, room_id).await?;
@@ -197,14 +416,151 @@ impl RoomService {
         Ok(member)
     }
 
+    /// Shortlist members for @-mention autocompletion: prefix match on the username or any
+    /// word of the display name, highest role first. The member list is cached per room
+    /// for a short while, so joins and role changes can take that long to show up.
+    pub async fn suggest_members(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<MentionSuggestion>, AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+
+        if room.room_type != ROOM_TYPE_PUBLIC && !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::PrivateNoAccess);
+        }
+
+        let cache_key = Self::mention_cache_key(room_id);
+        let cached = match cache::get_value(redis_pool, &cache_key).await {
+            Ok(value) => value.and_then(|json| serde_json::from_str::<Vec<MentionSuggestion>>(&json).ok()),
+            Err(e) => {
+                log::warn!("Mention cache read failed for room {}: {}", room_id, e);
+                None
+            }
+        };
+
+        let candidates = match cached {
+            Some(candidates) => candidates,
+            None => {
+                let candidates = RoomRepository::mention_candidates(pool, room_id).await?;
+                if let Ok(json) = serde_json::to_string(&candidates) {
+                    if let Err(e) = cache::set_with_ttl(redis_pool, &cache_key, &json, MENTION_CACHE_TTL_SECONDS).await {
+                        log::warn!("Mention cache write failed for room {}: {}", room_id, e);
+                    }
+                }
+                candidates
+            }
+        };
+
+        let query = query.trim().trim_start_matches('@').to_lowercase();
+        let matches = |candidate: &MentionSuggestion| {
+            candidate.username.to_lowercase().starts_with(&query)
+                || candidate
+                    .display_name
+                    .as_deref()
+                    .is_some_and(|name| name.to_lowercase().split_whitespace().any(|word| word.starts_with(&query)))
+        };
+
+        Ok(candidates
+            .into_iter()
+            .filter(|candidate| candidate.user_id != user_id && matches(candidate))
+            .take(limit.clamp(1, MENTION_SUGGEST_MAX_LIMIT))
+            .collect())
+    }
+
+    /// Import members in bulk by user ID or email (owner only), in one transaction.
+    /// Each entry gets its own result; entries that can't be added don't fail the import.
+    pub async fn bulk_add_members(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: BulkAddMembersDto,
+    ) -> Result<BulkAddMembersResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("entries", "Between 1 and 500 entries are allowed");
+                AppError::ValidationError(errors)
+            })?;
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+        Self::ensure_not_locked(&room)?;
+
+        Self::require_owner(pool, room_id, user_id).await?;
+
+        // Resolve entries to active users (no status yet = to be added)
+        let mut resolved = Vec::with_capacity(dto.entries.len());
+        let mut user_ids = Vec::new();
+        for entry in dto.entries {
+            let entry = entry.trim().to_string();
+
+            let user = match Uuid::parse_str(&entry) {
+                Ok(id) => UserRepository::find_by_id(pool, id).await,
+                Err(_) if entry.contains('@') => UserRepository::find_by_email(pool, &entry).await,
+                Err(_) => Err(AppError::UserNotFound),
+            };
+
+            let (member_id, status) = match user {
+                Ok(user) if user_ids.contains(&user.id) => (Some(user.id), Some(BULK_DUPLICATE)),
+                Ok(user) => {
+                    user_ids.push(user.id);
+                    (Some(user.id), None)
+                }
+                Err(AppError::UserNotFound) => (None, Some(BULK_NOT_FOUND)),
+                Err(e) => return Err(e),
+            };
+
+            resolved.push((entry, member_id, status));
+        }
+
+        let outcomes = RoomRepository::add_members_bulk(pool, room_id, &user_ids).await?;
+
+        let results = resolved
+            .into_iter()
+            .map(|(entry, member_id, status)| {
+                let status = status
+                    .or_else(|| outcomes.iter().find(|(id, _)| Some(*id) == member_id).map(|(_, s)| *s))
+                    .unwrap_or(BULK_NOT_FOUND);
+
+                BulkMemberResult {
+                    entry,
+                    user_id: member_id,
+                    status: status.to_string(),
+                }
+            })
+            .collect();
+
+        let added = outcomes.iter().filter(|(_, status)| *status == BULK_ADDED).count();
+
+        for (member_id, _) in outcomes.iter().filter(|(_, status)| *status == BULK_ADDED) {
+            Self::dispatch_member_joined(pool, room_id, *member_id).await;
+        }
+
+        log::info!("{} members imported into room {} by {}", added, room_id, user_id);
+
+        Ok(BulkAddMembersResponse { added, results })
+    }
+
     /// Leave a room
     pub async fn leave_room(
         pool: &PgPool,
+        redis_pool: &RedisPool,
         room_id: Uuid,
         user_id: Uuid,
     ) -> Result<(), AppError> {
         // Check if room exists
-        let _room = RoomRepository::find_by_id(pool, room_id).await?;
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+
+        // Direct message participants are managed through the DM endpoints
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
 
         // Check if user is owner
         let role = RoomRepository::get_user_role(pool, room_id, user_id).await?;
@@ -214,6 +570,7 @@ impl RoomService {
 
         // Remove member
         RoomRepository::remove_member(pool, room_id, user_id).await?;
+        Self::end_membership(redis_pool, room_id, user_id).await;
 
         Ok(())
     }
@@ -230,7 +587,7 @@ impl RoomService {
         // Check if user has access (member or public room)
         let is_member = RoomRepository::is_member(pool, room_id, user_id).await?;
 
-        if room.room_type == "private" && !is_member {
+        if room.room_type != ROOM_TYPE_PUBLIC && !is_member {
             return Err(AppError::PrivateNoAccess);
         }
 
@@ -239,4 +596,871 @@ impl RoomService {
 
         Ok(members)
     }
+
+    /// Get the permission matrix of a room (members only)
+    pub async fn get_role_permissions(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<RolePermissionsResponse>, AppError> {
+        // Check if room exists
+        let _room = RoomRepository::find_by_id(pool, room_id).await?;
+
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        let customized = RoomRepository::list_role_permissions(pool, room_id).await?;
+
+        let matrix = CUSTOMIZABLE_ROLES
+            .iter()
+            .map(|role| match customized.iter().find(|c| c.role == *role) {
+                Some(c) => RolePermissionsResponse {
+                    role: role.to_string(),
+                    permissions: c.permissions.clone(),
+                    customized: true,
+                },
+                None => RolePermissionsResponse {
+                    role: role.to_string(),
+                    permissions: default_permissions(role).iter().map(|p| p.to_string()).collect(),
+                    customized: false,
+                },
+            })
+            .collect();
+
+        Ok(matrix)
+    }
+
+    /// Customize the permissions of a role in a room (only owner can customize)
+    pub async fn update_role_permissions(
+        pool: &PgPool,
+        room_id: Uuid,
+        role: &str,
+        dto: UpdateRolePermissionsDto,
+        user_id: Uuid,
+    ) -> Result<RolePermissionsResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid permission data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if !CUSTOMIZABLE_ROLES.contains(&role) {
+            return Err(AppError::InvalidFormat("role".to_string()));
+        }
+
+        let mut permissions = Vec::new();
+        for permission in dto.permissions {
+            if !ALL_PERMISSIONS.contains(&permission.as_str()) {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("permissions", &format!("Unknown permission '{}'", permission));
+                return Err(AppError::ValidationError(errors));
+            }
+            if !permissions.contains(&permission) {
+                permissions.push(permission);
+            }
+        }
+
+        // Check if room exists
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        Self::ensure_not_locked(&room)?;
+
+        // Check if user is owner
+        let user_role = RoomRepository::get_user_role(pool, room_id, user_id).await?;
+        if user_role.as_deref() != Some("owner") {
+            return Err(AppError::OwnerRequired);
+        }
+
+        let row = RoomRepository::set_role_permissions(pool, room_id, role, &permissions).await?;
+
+        Ok(RolePermissionsResponse {
+            role: row.role,
+            permissions: row.permissions,
+            customized: true,
+        })
+    }
+
+    /// Kick a member out of a room (requires the kick permission and a higher role)
+    pub async fn kick_member(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        member_id: Uuid,
+    ) -> Result<(), AppError> {
+        // Check if room exists
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        // Check permissions
+        Self::require_permission(pool, room_id, user_id, PERM_KICK).await?;
+        Self::require_outranks(pool, room_id, user_id, member_id).await?;
+
+        RoomRepository::remove_member(pool, room_id, member_id).await?;
+        Self::end_membership(redis_pool, room_id, member_id).await;
+        ModerationRepository::record(pool, room_id, user_id, MOD_KICK, Some(member_id), None).await?;
+
+        Self::broadcast(
+            redis_pool,
+            room_id,
+            serde_json::json!({
+                "type": EVENT_USER_KICKED,
+                "room_id": room_id,
+                "user_id": member_id,
+                "created_at": Utc::now(),
+            }),
+        ).await;
+
+        log::info!("User {} kicked from room {} by {}", member_id, room_id, user_id);
+
+        Ok(())
+    }
+
+    /// Ban a user from a room, removing them if they are a member
+    pub async fn ban_member(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: BanUserDto,
+    ) -> Result<RoomBan, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid ban data");
+                AppError::ValidationError(errors)
+            })?;
+
+        // Check if room exists
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        // Check permissions
+        Self::require_permission(pool, room_id, user_id, PERM_BAN).await?;
+        Self::require_outranks(pool, room_id, user_id, dto.user_id).await?;
+
+        // Check if user exists
+        UserRepository::find_by_id(pool, dto.user_id).await?;
+
+        let expires_at = dto.duration_minutes.map(|minutes| Utc::now() + Duration::minutes(minutes));
+        let ban = BanRepository::upsert(pool, room_id, dto.user_id, user_id, dto.reason.as_deref(), expires_at).await?;
+
+        if RoomRepository::is_member(pool, room_id, dto.user_id).await? {
+            RoomRepository::remove_member(pool, room_id, dto.user_id).await?;
+            Self::end_membership(redis_pool, room_id, dto.user_id).await;
+        }
+
+        ModerationRepository::record(
+            pool,
+            room_id,
+            user_id,
+            MOD_BAN,
+            Some(dto.user_id),
+            Some(serde_json::json!({
+                "reason": ban.reason,
+                "expires_at": ban.expires_at,
+            })),
+        )
+        .await?;
+
+        Self::broadcast(
+            redis_pool,
+            room_id,
+            serde_json::json!({
+                "type": EVENT_USER_BANNED,
+                "room_id": room_id,
+                "user_id": dto.user_id,
+                "expires_at": ban.expires_at,
+                "created_at": Utc::now(),
+            }),
+        ).await;
+
+        log::info!("User {} banned from room {} by {}", dto.user_id, room_id, user_id);
+
+        Ok(ban)
+    }
+
+    /// Lift a ban (requires the ban permission)
+    pub async fn unban_member(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        banned_user_id: Uuid,
+    ) -> Result<(), AppError> {
+        // Check permissions
+        Self::require_permission(pool, room_id, user_id, PERM_BAN).await?;
+
+        if !BanRepository::delete(pool, room_id, banned_user_id).await? {
+            return Err(AppError::BanNotFound);
+        }
+
+        ModerationRepository::record(pool, room_id, user_id, MOD_UNBAN, Some(banned_user_id), None).await?;
+
+        Ok(())
+    }
+
+    /// List active bans of a room (requires the ban permission)
+    pub async fn list_bans(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<RoomBan>, AppError> {
+        // Check permissions
+        Self::require_permission(pool, room_id, user_id, PERM_BAN).await?;
+
+        BanRepository::list_active(pool, room_id).await
+    }
+
+    /// Promote or demote a member (requires manage_roles; only owners touch admins)
+    pub async fn update_member_role(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        member_id: Uuid,
+        dto: UpdateMemberRoleDto,
+    ) -> Result<RoomMemberResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid role data");
+                AppError::ValidationError(errors)
+            })?;
+
+        // Ownership is not handed out this way
+        if !CUSTOMIZABLE_ROLES.contains(&dto.role.as_str()) {
+            return Err(AppError::InvalidFormat("role".to_string()));
+        }
+
+        // Check if room exists
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+
+        // Check permissions
+        Self::require_permission(pool, room_id, user_id, PERM_MANAGE_ROLES).await?;
+        Self::require_outranks(pool, room_id, user_id, member_id).await?;
+
+        let current_role = RoomRepository::get_user_role(pool, room_id, member_id)
+            .await?
+            .ok_or(AppError::NotMember)?;
+
+        // Granting or revoking admin is reserved to the owner
+        if current_role == "admin" || dto.role == "admin" {
+            let role = RoomRepository::get_user_role(pool, room_id, user_id).await?;
+            if role.as_deref() != Some("owner") {
+                return Err(AppError::OwnerRequired);
+            }
+        }
+
+        RoomRepository::update_member_role(pool, room_id, member_id, &dto.role).await?;
+        ModerationRepository::record(
+            pool,
+            room_id,
+            user_id,
+            MOD_ROLE_CHANGE,
+            Some(member_id),
+            Some(serde_json::json!({
+                "role": dto.role,
+                "previous_role": current_role,
+            })),
+        )
+        .await?;
+
+        log::info!("User {} role in room {} set to {} by {}", member_id, room_id, dto.role, user_id);
+
+        ActivityService::record(
+            pool,
+            member_id,
+            ACTIVITY_ROLE_CHANGE,
+            Some(room_id),
+            Some(user_id),
+            serde_json::json!({
+                "role": dto.role,
+                "previous_role": current_role,
+            }),
+        )
+        .await;
+
+        Self::broadcast(
+            redis_pool,
+            room_id,
+            serde_json::json!({
+                "type": "room.member_role_changed",
+                "room_id": room_id,
+                "user_id": member_id,
+                "role": dto.role,
+                "previous_role": current_role,
+                "changed_by": user_id,
+            }),
+        ).await;
+
+        // Get updated member info
+        let members = RoomRepository::get_members(pool, room_id).await?;
+        let member = members
+            .into_iter()
+            .find(|m| m.user_id == member_id)
+            .ok_or(AppError::InternalError("Failed to retrieve member info".to_string()))?;
+
+        Ok(member)
+    }
+
+    /// Set own notification level in a room (all, mentions or none)
+    pub async fn update_notification_level(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: UpdateNotificationLevelDto,
+    ) -> Result<(), AppError> {
+        if ![NOTIFY_ALL, NOTIFY_MENTIONS, NOTIFY_NONE].contains(&dto.level.as_str()) {
+            return Err(AppError::InvalidFormat("level".to_string()));
+        }
+
+        RoomRepository::set_notification_level(pool, room_id, user_id, &dto.level).await
+    }
+
+    /// Set or clear own nickname in a room (checked against the name policy)
+    pub async fn set_nickname(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: SetNicknameDto,
+    ) -> Result<(), AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("nickname", "Nickname must not exceed 50 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let nickname = dto.nickname.as_deref().map(str::trim).filter(|nickname| !nickname.is_empty());
+
+        if let Some(nickname) = nickname {
+            UserService::check_names(config, None, Some(nickname))?;
+        }
+
+        RoomRepository::set_nickname(pool, room_id, user_id, nickname).await?;
+
+        if let Err(e) = cache::delete_value(redis_pool, &Self::mention_cache_key(room_id)).await {
+            log::warn!("Failed to drop mention cache of room {}: {}", room_id, e);
+        }
+
+        Self::broadcast(
+            redis_pool,
+            room_id,
+            serde_json::json!({
+                "type": "room.member_updated",
+                "room_id": room_id,
+                "user_id": user_id,
+                "nickname": nickname,
+            }),
+        ).await;
+
+        Ok(())
+    }
+
+    /// Get a room's welcome message (owner only)
+    pub async fn get_welcome(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<RoomWelcome>, AppError> {
+        Self::require_owner(pool, room_id, user_id).await?;
+
+        WelcomeRepository::find(pool, room_id).await
+    }
+
+    /// Set a room's welcome message (owner only)
+    pub async fn set_welcome(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: SetWelcomeDto,
+    ) -> Result<RoomWelcome, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid welcome message");
+                AppError::ValidationError(errors)
+            })?;
+
+        let delivery = dto.delivery.as_deref().unwrap_or(WELCOME_DELIVERY_ROOM);
+        if ![WELCOME_DELIVERY_ROOM, WELCOME_DELIVERY_DM].contains(&delivery) {
+            return Err(AppError::InvalidFormat("delivery".to_string()));
+        }
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+        Self::ensure_not_locked(&room)?;
+
+        Self::require_owner(pool, room_id, user_id).await?;
+
+        WelcomeRepository::upsert(pool, room_id, &dto.template, delivery, user_id).await
+    }
+
+    /// Remove a room's welcome message (owner only)
+    pub async fn remove_welcome(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        Self::ensure_not_locked(&room)?;
+
+        Self::require_owner(pool, room_id, user_id).await?;
+
+        WelcomeRepository::delete(pool, room_id).await?;
+
+        Ok(())
+    }
+
+    /// Notify the room's outgoing webhooks of a new member
+    pub async fn dispatch_member_joined(pool: &PgPool, room_id: Uuid, user_id: Uuid) {
+        OutgoingWebhookService::dispatch(
+            pool,
+            room_id,
+            OUTGOING_EVENT_MEMBER_JOINED,
+            serde_json::json!({ "user_id": user_id }),
+        )
+        .await;
+    }
+
+    /// Send the room's welcome message to a new member, if one is configured
+    /// Best effort: a failure here never fails the join itself
+    pub async fn send_welcome(pool: &PgPool, redis_pool: &RedisPool, room: &Room, user_id: Uuid) {
+        let welcome = match WelcomeRepository::find(pool, room.id).await {
+            Ok(Some(welcome)) => welcome,
+            Ok(None) => return,
+            Err(e) => {
+                log::warn!("Failed to load welcome message of room {}: {}", room.id, e);
+                return;
+            }
+        };
+
+        let user = match UserRepository::find_by_id(pool, user_id).await {
+            Ok(user) => user,
+            Err(e) => {
+                log::warn!("Failed to load new member {} of room {}: {}", user_id, room.id, e);
+                return;
+            }
+        };
+
+        let event = serde_json::json!({
+            "type": "room.welcome",
+            "room_id": room.id,
+            "user_id": user_id,
+            "author": SYSTEM_AUTHOR,
+            "content": welcome.render(&user.username, &room.name),
+            "created_at": Utc::now(),
+        });
+
+        if welcome.delivery == WELCOME_DELIVERY_DM {
+            if let Err(e) = cache::publish(redis_pool, &cache::user_channel(user_id), &event.to_string()).await {
+                log::warn!("Failed to send welcome message to user {}: {}", user_id, e);
+            }
+        } else {
+            Self::broadcast(redis_pool, room.id, event).await;
+        }
+    }
+
+    /// Get a room's rules with the current user's acceptance
+    pub async fn get_rules(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<RulesResponse, AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        let is_member = RoomRepository::is_member(pool, room_id, user_id).await?;
+
+        if room.room_type != ROOM_TYPE_PUBLIC && !is_member {
+            return Err(AppError::PrivateNoAccess);
+        }
+
+        let rules = RulesRepository::find(pool, room_id)
+            .await?
+            .ok_or(AppError::RulesNotFound)?;
+        let accepted_version = RulesRepository::accepted_version(pool, room_id, user_id).await?;
+
+        Ok(RulesResponse {
+            room_id,
+            content: rules.content,
+            version: rules.version,
+            updated_at: rules.updated_at,
+            accepted_version,
+            accepted: accepted_version == Some(rules.version),
+        })
+    }
+
+    /// Set a room's rules (requires the edit_room permission)
+    /// Every change is a new version that members have to accept again
+    pub async fn set_rules(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: SetRulesDto,
+    ) -> Result<RulesResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid room rules");
+                AppError::ValidationError(errors)
+            })?;
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+        Self::ensure_not_locked(&room)?;
+
+        Self::require_permission(pool, room_id, user_id, PERM_EDIT_ROOM).await?;
+
+        let rules = RulesRepository::upsert(pool, room_id, &dto.content, user_id).await?;
+
+        // The author has obviously read them
+        RulesRepository::accept(pool, room_id, user_id, rules.version).await?;
+
+        Self::broadcast(
+            redis_pool,
+            room_id,
+            serde_json::json!({
+                "type": "room.rules_changed",
+                "room_id": room_id,
+                "version": rules.version,
+                "changed_by": user_id,
+            }),
+        ).await;
+
+        Ok(RulesResponse {
+            room_id,
+            content: rules.content,
+            version: rules.version,
+            updated_at: rules.updated_at,
+            accepted_version: Some(rules.version),
+            accepted: true,
+        })
+    }
+
+    /// Remove a room's rules (requires the edit_room permission)
+    pub async fn remove_rules(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        Self::ensure_not_locked(&room)?;
+
+        Self::require_permission(pool, room_id, user_id, PERM_EDIT_ROOM).await?;
+
+        if !RulesRepository::delete(pool, room_id).await? {
+            return Err(AppError::RulesNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Accept a room's rules; the version must be the current one
+    pub async fn accept_rules(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: AcceptRulesDto,
+    ) -> Result<RulesResponse, AppError> {
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        let rules = RulesRepository::find(pool, room_id)
+            .await?
+            .ok_or(AppError::RulesNotFound)?;
+
+        if dto.version != rules.version {
+            return Err(AppError::RulesOutdated);
+        }
+
+        RulesRepository::accept(pool, room_id, user_id, rules.version).await?;
+
+        Ok(RulesResponse {
+            room_id,
+            content: rules.content,
+            version: rules.version,
+            updated_at: rules.updated_at,
+            accepted_version: Some(rules.version),
+            accepted: true,
+        })
+    }
+
+    /// Get a room's moderation log (moderators and above)
+    pub async fn get_moderation_log(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<ModerationLogEntry>, i64), AppError> {
+        let role = RoomRepository::get_user_role(pool, room_id, user_id)
+            .await?
+            .ok_or(AppError::NotMember)?;
+
+        if role_rank(&role) < role_rank("moderator") {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        let limit = per_page as i64;
+        let offset = ((page - 1) * per_page) as i64;
+
+        let entries = ModerationRepository::list(pool, room_id, offset, limit).await?;
+        let total = ModerationRepository::count(pool, room_id).await?;
+
+        Ok((entries, total))
+    }
+
+    /// Mute a room for oneself (suppresses push notifications and unread badges)
+    pub async fn mute_room(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: MuteRoomDto,
+    ) -> Result<MuteStatusResponse, AppError> {
+        // Validate input
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid mute data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        // Official notices must always get through
+        if DmRepository::is_system_conversation(pool, room_id).await? {
+            return Err(AppError::SystemConversation);
+        }
+
+        let expires_at = dto.duration_minutes.map(|minutes| Utc::now() + Duration::minutes(minutes));
+        let mute = MuteRepository::upsert(pool, room_id, user_id, expires_at).await?;
+
+        Ok(MuteStatusResponse {
+            room_id,
+            muted: true,
+            expires_at: mute.expires_at,
+        })
+    }
+
+    /// Unmute a room
+    pub async fn unmute_room(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        MuteRepository::delete(pool, room_id, user_id).await
+    }
+
+    /// Get own mute state of a room
+    pub async fn get_mute(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<MuteStatusResponse, AppError> {
+        if !RoomRepository::is_member(pool, room_id, user_id).await? {
+            return Err(AppError::NotMember);
+        }
+
+        let mute = MuteRepository::find_active(pool, room_id, user_id).await?;
+
+        Ok(MuteStatusResponse {
+            room_id,
+            muted: mute.is_some(),
+            expires_at: mute.and_then(|m| m.expires_at),
+        })
+    }
+
+    /// Reject banned users (checked on join, invitation accept and message send)
+    pub async fn ensure_not_banned(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        if BanRepository::is_banned(pool, room_id, user_id).await? {
+            return Err(AppError::BannedFromRoom);
+        }
+
+        Ok(())
+    }
+
+    /// Refuse changes to a room an admin has locked
+    pub fn ensure_not_locked(room: &Room) -> Result<(), AppError> {
+        if room.locked_at.is_some() {
+            return Err(AppError::RoomLocked);
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a user holds a permission in a room
+    pub async fn require_permission(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        permission: &str,
+    ) -> Result<(), AppError> {
+        let role = RoomRepository::get_user_role(pool, room_id, user_id)
+            .await?
+            .ok_or(AppError::NotMember)?;
+
+        let permissions = Self::role_permissions(pool, room_id, &role).await?;
+        if !permissions.iter().any(|p| p == permission) {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        Ok(())
+    }
+
+    /// Whether a member may post: the room must not be locked, and the member needs
+    /// send_messages, the current room rules accepted (staff are exempt), and staff rank
+    /// in admins-only rooms.
+    /// The message service must apply the same rule when messages are sent.
+    pub fn can_post(room: &Room, role: Option<&str>, permissions: &[String], rules_accepted: bool) -> bool {
+        let Some(role) = role else {
+            return false;
+        };
+
+        if room.locked_at.is_some() {
+            return false;
+        }
+
+        if !permissions.iter().any(|p| p == PERM_SEND_MESSAGES) {
+            return false;
+        }
+
+        if !rules_accepted && role_rank(role) < role_rank("admin") {
+            return false;
+        }
+
+        room.post_policy != POST_POLICY_ADMINS_ONLY || role_rank(role) >= role_rank("admin")
+    }
+
+    /// Check that a user may post in a room (the can_post rule, as errors)
+    pub async fn require_can_post(pool: &PgPool, room: &Room, user_id: Uuid) -> Result<(), AppError> {
+        let role = RoomRepository::get_user_role(pool, room.id, user_id)
+            .await?
+            .ok_or(AppError::NotMember)?;
+        let permissions = Self::role_permissions(pool, room.id, &role).await?;
+
+        let rules_accepted = match RulesRepository::find(pool, room.id).await? {
+            Some(rules) => RulesRepository::accepted_version(pool, room.id, user_id).await? == Some(rules.version),
+            None => true,
+        };
+        if !rules_accepted && role_rank(&role) < role_rank("admin") {
+            return Err(AppError::RulesOutdated);
+        }
+
+        Self::ensure_not_locked(room)?;
+
+        if !Self::can_post(room, Some(&role), &permissions, true) {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        Ok(())
+    }
+
+    /// Redis key of a room's cached mention candidates
+    fn mention_cache_key(room_id: Uuid) -> String {
+        format!("room:{}:mention_candidates", room_id)
+    }
+
+    /// Room event channels a user's realtime connection listens on
+    pub async fn event_channels(pool: &PgPool, user_id: Uuid) -> Result<Vec<String>, AppError> {
+        let room_ids = RoomRepository::member_room_ids(pool, user_id).await?;
+        Ok(room_ids.into_iter().map(cache::room_channel).collect())
+    }
+
+    /// Tell a former member's open connections to stop delivering the room's events.
+    /// If this can't be published, close them so they resubscribe without the room.
+    pub async fn end_membership(redis_pool: &RedisPool, room_id: Uuid, user_id: Uuid) {
+        let event = serde_json::json!({
+            "type": EVENT_MEMBERSHIP_ENDED,
+            "room_id": room_id,
+            "created_at": Utc::now(),
+        });
+
+        if let Err(e) = cache::publish(redis_pool, &cache::user_channel(user_id), &event.to_string()).await {
+            log::warn!("Failed to end room {} events for user {}: {}", room_id, user_id, e);
+            if let Err(e) = cache::publish(redis_pool, &cache::user_disconnect_channel(user_id), "membership_ended").await {
+                log::warn!("Failed to disconnect user {}: {}", user_id, e);
+            }
+        }
+    }
+
+    /// Send a realtime event to everyone in the room (best effort)
+    async fn broadcast(redis_pool: &RedisPool, room_id: Uuid, event: serde_json::Value) {
+        if let Err(e) = cache::publish(redis_pool, &cache::room_channel(room_id), &event.to_string()).await {
+            log::warn!("Failed to broadcast event to room {}: {}", room_id, e);
+        }
+    }
+
+    /// Owner-only room operations
+    pub async fn require_owner(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let role = RoomRepository::get_user_role(pool, room_id, user_id)
+            .await?
+            .ok_or(AppError::NotMember)?;
+
+        if role != "owner" {
+            return Err(AppError::OwnerRequired);
+        }
+
+        Ok(())
+    }
+
+    /// Moderation only applies to members of a lower role (and never to oneself)
+    async fn require_outranks(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<(), AppError> {
+        let role = RoomRepository::get_user_role(pool, room_id, user_id)
+            .await?
+            .ok_or(AppError::NotMember)?;
+
+        if let Some(target_role) = RoomRepository::get_user_role(pool, room_id, target_id).await? {
+            if role_rank(&target_role) >= role_rank(&role) {
+                return Err(AppError::InsufficientPermissions);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Effective permissions of a role (room customization, else defaults; owners have all)
+    async fn role_permissions(
+        pool: &PgPool,
+        room_id: Uuid,
+        role: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let customized = if role == "owner" {
+            None
+        } else {
+            RoomRepository::get_role_permissions(pool, room_id, role).await?
+        };
+
+        Ok(customized.unwrap_or_else(|| {
+            default_permissions(role).iter().map(|p| p.to_string()).collect()
+        }))
+    }
 }
@@ -0,0 +1,124 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::RedisPool;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::permission::role_rank;
+use crate::models::space::{CreateSpaceDto, SpaceResponse, SpaceWithChannelsResponse};
+use crate::repositories::SpaceRepository;
+use crate::services::RoomService;
+
+pub struct SpaceService;
+
+impl SpaceService {
+    /// Create a new space (the creator becomes its owner)
+    pub async fn create_space(
+        pool: &PgPool,
+        dto: CreateSpaceDto,
+        owner_id: Uuid,
+    ) -> Result<SpaceResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid space data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if SpaceRepository::name_exists(pool, &dto.name).await? {
+            return Err(AppError::SpaceNameExists);
+        }
+
+        let space = SpaceRepository::create(pool, &dto, owner_id).await?;
+        SpaceRepository::add_member(pool, space.id, owner_id, "owner").await?;
+
+        SpaceRepository::find_for_user(pool, space.id, owner_id).await
+    }
+
+    /// List own spaces, each with its channels visible to the user
+    pub async fn list_my_spaces(pool: &PgPool, user_id: Uuid) -> Result<Vec<SpaceWithChannelsResponse>, AppError> {
+        let spaces = SpaceRepository::list_for_user(pool, user_id).await?;
+
+        let mut grouped = Vec::with_capacity(spaces.len());
+        for space in spaces {
+            let channels = SpaceRepository::list_channels(pool, space.id, user_id).await?;
+            grouped.push(SpaceWithChannelsResponse { space, channels });
+        }
+
+        Ok(grouped)
+    }
+
+    /// Get a space with its channels visible to the user
+    pub async fn get_space(
+        pool: &PgPool,
+        space_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<SpaceWithChannelsResponse, AppError> {
+        let space = SpaceRepository::find_for_user(pool, space_id, user_id).await?;
+        let channels = SpaceRepository::list_channels(pool, space_id, user_id).await?;
+
+        Ok(SpaceWithChannelsResponse { space, channels })
+    }
+
+    /// Join a space and its open channels
+    pub async fn join_space(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<SpaceWithChannelsResponse, AppError> {
+        SpaceRepository::find_by_id(pool, space_id).await?;
+
+        if SpaceRepository::get_user_role(pool, space_id, user_id).await?.is_some() {
+            return Err(AppError::AlreadyJoined);
+        }
+
+        SpaceRepository::add_member(pool, space_id, user_id, "member").await?;
+        SpaceRepository::join_open_channels(pool, space_id, user_id).await?;
+
+        Self::get_space(pool, space_id, user_id).await
+    }
+
+    /// Leave a space and all its channels (except owner)
+    pub async fn leave_space(pool: &PgPool, redis_pool: &RedisPool, space_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let role = Self::require_member(pool, space_id, user_id).await?;
+
+        if role == "owner" {
+            return Err(AppError::OwnerRequired);
+        }
+
+        let room_ids = SpaceRepository::leave_channels(pool, space_id, user_id).await?;
+        SpaceRepository::remove_member(pool, space_id, user_id).await?;
+
+        for room_id in room_ids {
+            RoomService::end_membership(redis_pool, room_id, user_id).await;
+        }
+
+        Ok(())
+    }
+
+    /// Delete a space and its channels (only owner can delete)
+    pub async fn delete_space(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let role = Self::require_member(pool, space_id, user_id).await?;
+
+        if role != "owner" {
+            return Err(AppError::OwnerRequired);
+        }
+
+        SpaceRepository::delete(pool, space_id).await
+    }
+
+    /// Channels are created by space admins and owners
+    pub async fn require_space_admin(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let role = Self::require_member(pool, space_id, user_id).await?;
+
+        if role_rank(&role) < role_rank("admin") {
+            return Err(AppError::InsufficientPermissions);
+        }
+
+        Ok(())
+    }
+
+    /// Channels of a space are only open to members of that space
+    pub async fn require_member(pool: &PgPool, space_id: Uuid, user_id: Uuid) -> Result<String, AppError> {
+        SpaceRepository::find_by_id(pool, space_id).await?;
+
+        SpaceRepository::get_user_role(pool, space_id, user_id)
+            .await?
+            .ok_or(AppError::NotSpaceMember)
+    }
+}
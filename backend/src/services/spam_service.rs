@@ -0,0 +1,118 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::report::{CreateReportDto, REPORT_TARGET_USER};
+use crate::models::user::SYSTEM_USER_ID;
+use crate::repositories::ReportRepository;
+use crate::utils::spam::{self, Signals};
+
+/// Longest excerpt of a flagged message kept in the report
+const REPORT_EXCERPT_CHARS: usize = 500;
+
+/// What to do with a scored message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpamVerdict {
+    Allow,
+    /// Deliver, but put the author in the moderation queue
+    Flag,
+    /// Don't deliver (the sender isn't told) and put the author in the moderation queue
+    Hold,
+}
+
+/// Who sent a message, for spam tracking
+pub struct SpamAuthor {
+    /// Redis key part identifying the sender, e.g. "user:<id>" or "webhook:<id>"
+    pub key: String,
+    /// User held responsible in the moderation queue (None: don't file reports)
+    pub user_id: Option<Uuid>,
+    pub room_id: Uuid,
+}
+
+pub struct SpamService;
+
+impl SpamService {
+    /// Score a message on repeated content, link density and burst rate, and file a
+    /// System report about the author when it crosses the flag threshold.
+    /// Fails open: if Redis is down, messages are allowed.
+    pub async fn check(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        author: &SpamAuthor,
+        content: &str,
+    ) -> Result<SpamVerdict, AppError> {
+        let fingerprint = spam::fingerprint(content);
+        let (repeats, recent_messages) =
+            match cache::spam_counters(redis_pool, &author.key, &fingerprint, config.spam.window_seconds).await {
+                Ok(counters) => counters,
+                Err(e) => {
+                    log::error!("Spam check failed: {}", e);
+                    return Ok(SpamVerdict::Allow);
+                }
+            };
+
+        let signals = Signals {
+            repeats,
+            recent_messages,
+            link_density: spam::link_density(content),
+        };
+        let score = spam::score(&signals, config.spam.burst_messages);
+
+        let verdict = if score >= config.spam.hold_threshold {
+            SpamVerdict::Hold
+        } else if score >= config.spam.flag_threshold {
+            SpamVerdict::Flag
+        } else {
+            return Ok(SpamVerdict::Allow);
+        };
+
+        log::warn!("Spam score {:.2} for {} in room {} ({:?})", score, author.key, author.room_id, verdict);
+
+        if let Some(user_id) = author.user_id {
+            Self::flag(pool, user_id, author.room_id, score, &signals, verdict, content).await?;
+        }
+
+        Ok(verdict)
+    }
+
+    /// Put the author in the moderation queue, once per unresolved System report
+    async fn flag(
+        pool: &PgPool,
+        user_id: Uuid,
+        room_id: Uuid,
+        score: f64,
+        signals: &Signals,
+        verdict: SpamVerdict,
+        content: &str,
+    ) -> Result<(), AppError> {
+        if ReportRepository::unresolved_exists(pool, SYSTEM_USER_ID, user_id, None).await? {
+            return Ok(());
+        }
+
+        let excerpt: String = content.chars().take(REPORT_EXCERPT_CHARS).collect();
+        let details = format!(
+            "Automatic spam detection: score {:.2} ({} repeats, {} messages in window, {:.0}% links){}.\n\n{}",
+            score,
+            signals.repeats,
+            signals.recent_messages,
+            signals.link_density * 100.0,
+            if verdict == SpamVerdict::Hold { ", message held" } else { "" },
+            excerpt,
+        );
+
+        let dto = CreateReportDto {
+            target_type: REPORT_TARGET_USER.to_string(),
+            message_id: None,
+            user_id: Some(user_id),
+            reason: "spam".to_string(),
+            details: Some(details),
+        };
+
+        let report = ReportRepository::create(pool, SYSTEM_USER_ID, &dto, user_id, Some(room_id)).await?;
+        log::info!("Report {} filed by spam detection against user {}", report.id, user_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::room::ROOM_TYPE_PUBLIC;
+use crate::models::stats::RoomStatsResponse;
+use crate::repositories::{RoomRepository, StatsRepository};
+
+/// Longest period served by the stats endpoint
+const MAX_STATS_DAYS: i64 = 365;
+
+pub struct StatsService;
+
+impl StatsService {
+    /// Refresh yesterday's and today's stats (yesterday may have changed since the last run)
+    pub async fn aggregate_recent(pool: &PgPool) -> Result<u64, AppError> {
+        let since = (Utc::now() - Duration::days(1)).date_naive();
+        StatsRepository::aggregate_since(pool, since).await
+    }
+
+    /// Snapshot today's instance metrics and finish counting yesterday's messages
+    pub async fn aggregate_instance(pool: &PgPool) -> Result<(), AppError> {
+        let today = Utc::now().date_naive();
+        StatsRepository::recount_instance_messages(pool, today - Duration::days(1)).await?;
+        StatsRepository::snapshot_instance(pool, today).await
+    }
+
+    /// Activity of a room over the last `days` days (members only for non-public rooms)
+    pub async fn get_room_stats(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        days: i64,
+    ) -> Result<RoomStatsResponse, AppError> {
+        // Check if room exists
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+
+        // Check if user has access (member or public room)
+        let is_member = RoomRepository::is_member(pool, room_id, user_id).await?;
+        if room.room_type != ROOM_TYPE_PUBLIC && !is_member {
+            return Err(AppError::PrivateNoAccess);
+        }
+
+        let since = (Utc::now() - Duration::days(days.clamp(1, MAX_STATS_DAYS) - 1)).date_naive();
+        let days = StatsRepository::list_for_room(pool, room_id, since).await?;
+        let member_count = RoomRepository::count_members(pool, room_id).await?;
+
+        Ok(RoomStatsResponse {
+            room_id,
+            member_count,
+            days,
+        })
+    }
+}
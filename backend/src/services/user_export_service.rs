@@ -0,0 +1,94 @@
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+use tokio::io::{AsyncWriteExt, DuplexStream};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+use crate::error::AppError;
+use crate::models::audit::AUDIT_USERS_EXPORTED;
+use crate::models::user::{AdminUserFilter, ExportedUser, USER_EXPORT_CSV, USER_EXPORT_JSONL};
+use crate::repositories::{AuditRepository, UserRepository};
+use crate::services::AdminService;
+use crate::utils::csv;
+
+/// Bytes buffered between the export writer and the response
+const EXPORT_BUFFER_SIZE: usize = 64 * 1024;
+
+pub struct UserExportService;
+
+impl UserExportService {
+    /// Stream the users matching the filters as CSV or JSON Lines (admin only).
+    /// Rows are written while they are sent, so memory use does not grow with the user table.
+    pub async fn export_users(
+        pool: &PgPool,
+        admin_id: Uuid,
+        filter: AdminUserFilter,
+        format: &str,
+    ) -> Result<ReaderStream<DuplexStream>, AppError> {
+        if format != USER_EXPORT_CSV && format != USER_EXPORT_JSONL {
+            return Err(AppError::InvalidFormat("format".to_string()));
+        }
+
+        let filter = AdminService::user_filter(filter)?;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_USERS_EXPORTED,
+            None,
+            Some(serde_json::json!({
+                "format": format,
+                "q": filter.q,
+                "role": filter.role,
+                "status": filter.status,
+                "is_bot": filter.is_bot,
+            })),
+        )
+        .await?;
+
+        let (writer, reader) = tokio::io::duplex(EXPORT_BUFFER_SIZE);
+
+        let pool = pool.clone();
+        let csv = format == USER_EXPORT_CSV;
+        tokio::spawn(async move {
+            // The response has already started: a failure leaves a truncated export
+            if let Err(e) = Self::write_export(&pool, &filter, csv, writer).await {
+                log::error!("User export by admin {} failed: {}", admin_id, e);
+            }
+        });
+
+        Ok(ReaderStream::new(reader))
+    }
+
+    async fn write_export(
+        pool: &PgPool,
+        filter: &AdminUserFilter,
+        csv: bool,
+        mut writer: DuplexStream,
+    ) -> Result<(), AppError> {
+        let write_error = |e: std::io::Error| AppError::InternalError(format!("Failed to write user export: {}", e));
+
+        if csv {
+            writer.write_all(csv::row(ExportedUser::CSV_HEADER).as_bytes()).await.map_err(write_error)?;
+        }
+
+        let mut users = UserRepository::admin_export(pool, filter);
+        while let Some(user) = users.try_next().await? {
+            let user = ExportedUser::from(user);
+
+            let line = if csv {
+                csv::row(&user.csv_fields())
+            } else {
+                let mut line = serde_json::to_string(&user)
+                    .map_err(|e| AppError::InternalError(format!("Failed to serialize user export: {}", e)))?;
+                line.push('\n');
+                line
+            };
+
+            writer.write_all(line.as_bytes()).await.map_err(write_error)?;
+        }
+
+        writer.shutdown().await.map_err(write_error)?;
+
+        Ok(())
+    }
+}
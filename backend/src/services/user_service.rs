@@ -0,0 +1,215 @@
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::user::{SetCustomStatusDto, SetQuietHoursDto, UpdateUserDto, UserResponse};
+use crate::repositories::UserRepository;
+use crate::utils::name_policy::{self, NameViolation};
+use crate::utils::{avatar, local_time};
+
+/// Presence statuses a user can set on their profile
+const PROFILE_STATUSES: &[&str] = &["online", "offline", "away", "busy"];
+
+/// How long a generated avatar stays cached (dropped early when the name changes)
+const AVATAR_CACHE_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Record a user's activity at most this often
+const LAST_ACTIVE_THROTTLE_SECONDS: u64 = 60;
+
+pub struct UserService;
+
+impl UserService {
+    /// Check a username and/or display name against the configured name policy
+    pub fn check_names(config: &Config, username: Option<&str>, display_name: Option<&str>) -> Result<(), AppError> {
+        let mut errors = ValidationErrors::new();
+
+        for (field, name) in [("username", username), ("display_name", display_name)] {
+            let Some(name) = name else { continue };
+
+            match name_policy::check(name, &config.name_policy) {
+                Ok(()) => {}
+                Err(NameViolation::Reserved) => errors.add_field_error(field, "This name is reserved"),
+                Err(NameViolation::BannedWord) => errors.add_field_error(field, "This name is not allowed"),
+                Err(NameViolation::MixedScripts) => {
+                    errors.add_field_error(field, "Names must not mix characters of different scripts")
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::ValidationError(errors));
+        }
+
+        Ok(())
+    }
+
+    /// Update own profile (username, display name, avatar and status)
+    pub async fn update_profile(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        user_id: Uuid,
+        dto: UpdateUserDto,
+    ) -> Result<UserResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid profile data");
+                AppError::ValidationError(errors)
+            })?;
+
+        if let Some(status) = &dto.status {
+            if !PROFILE_STATUSES.contains(&status.as_str()) {
+                return Err(AppError::InvalidFormat("status".to_string()));
+            }
+        }
+
+        if let Some(timezone) = &dto.timezone {
+            if !local_time::is_valid_timezone(timezone) {
+                return Err(AppError::InvalidFormat("timezone".to_string()));
+            }
+        }
+
+        if let Some(locale) = &dto.locale {
+            if !local_time::is_valid_locale(locale) {
+                return Err(AppError::InvalidFormat("locale".to_string()));
+            }
+        }
+
+        Self::check_names(config, dto.username.as_deref(), dto.display_name.as_deref())?;
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+
+        if let Some(username) = &dto.username {
+            if *username != user.username && UserRepository::username_exists(pool, username).await? {
+                return Err(AppError::UsernameExists);
+            }
+        }
+
+        let user = UserRepository::update(pool, user_id, &dto).await?;
+
+        if dto.username.is_some() || dto.display_name.is_some() {
+            if let Err(e) = cache::delete_value(redis_pool, &Self::avatar_cache_key(user_id)).await {
+                log::warn!("Failed to drop cached avatar of user {}: {}", user_id, e);
+            }
+        }
+
+        Ok(UserResponse::from(user))
+    }
+
+    /// Set own custom status text and/or emoji, optionally expiring
+    pub async fn set_custom_status(
+        pool: &PgPool,
+        user_id: Uuid,
+        dto: SetCustomStatusDto,
+    ) -> Result<UserResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid custom status");
+                AppError::ValidationError(errors)
+            })?;
+
+        let status_text = dto.status_text.as_deref().map(str::trim).filter(|text| !text.is_empty());
+        let status_emoji = dto.status_emoji.as_deref().map(str::trim).filter(|emoji| !emoji.is_empty());
+
+        if status_text.is_none() && status_emoji.is_none() {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("input", "Status text or emoji is required");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let expires_at = dto.expires_in_minutes.map(|minutes| Utc::now() + Duration::minutes(minutes));
+
+        let user = UserRepository::set_custom_status(pool, user_id, status_text, status_emoji, expires_at).await?;
+
+        Ok(UserResponse::from(user))
+    }
+
+    /// Clear own custom status
+    pub async fn clear_custom_status(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        UserRepository::set_custom_status(pool, user_id, None, None, None).await?;
+
+        Ok(())
+    }
+
+    /// Set own do-not-disturb window
+    pub async fn set_quiet_hours(pool: &PgPool, user_id: Uuid, dto: SetQuietHoursDto) -> Result<UserResponse, AppError> {
+        let parse = |field: &str, value: &str| {
+            NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| AppError::InvalidFormat(field.to_string()))
+        };
+        let start = parse("start", &dto.start)?;
+        let end = parse("end", &dto.end)?;
+
+        if start == end {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("end", "Quiet hours must not start and end at the same time");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        let user = UserRepository::set_quiet_hours(pool, user_id, Some(start), Some(end)).await?;
+
+        Ok(UserResponse::from(user))
+    }
+
+    /// Turn off own do-not-disturb window
+    pub async fn clear_quiet_hours(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        UserRepository::set_quiet_hours(pool, user_id, None, None).await?;
+
+        Ok(())
+    }
+
+    /// Note an authenticated request (best effort, throttled per user in Redis)
+    pub async fn touch_last_active(redis_pool: &RedisPool, user_id: Uuid) {
+        if let Err(e) = cache::last_active_touch(redis_pool, user_id, Utc::now().timestamp(), LAST_ACTIVE_THROTTLE_SECONDS).await {
+            log::warn!("Failed to record activity of user {}: {}", user_id, e);
+        }
+    }
+
+    /// Move pending activity from Redis to Postgres (run by the last-active job)
+    pub async fn flush_last_active(pool: &PgPool, redis_pool: &RedisPool) -> Result<usize, AppError> {
+        let pending = cache::last_active_take(redis_pool).await?;
+
+        let (user_ids, active_at): (Vec<Uuid>, Vec<DateTime<Utc>>) = pending
+            .into_iter()
+            .filter_map(|(user_id, unix_time)| {
+                Some((Uuid::parse_str(&user_id).ok()?, DateTime::from_timestamp(unix_time, 0)?))
+            })
+            .unzip();
+
+        if user_ids.is_empty() {
+            return Ok(0);
+        }
+
+        UserRepository::record_last_active(pool, &user_ids, &active_at).await?;
+
+        Ok(user_ids.len())
+    }
+
+    fn avatar_cache_key(user_id: Uuid) -> String {
+        format!("avatar:{}", user_id)
+    }
+
+    /// Generated initials avatar of a user (SVG), cached in Redis
+    pub async fn fallback_avatar(pool: &PgPool, redis_pool: &RedisPool, user_id: Uuid) -> Result<String, AppError> {
+        let cache_key = Self::avatar_cache_key(user_id);
+
+        match cache::get_value(redis_pool, &cache_key).await {
+            Ok(Some(svg)) => return Ok(svg),
+            Ok(None) => {}
+            Err(e) => log::warn!("Avatar cache read failed for user {}: {}", user_id, e),
+        }
+
+        let user = UserRepository::find_by_id(pool, user_id).await?;
+        let svg = avatar::render_svg(user.id, user.display_name.as_deref().unwrap_or(&user.username));
+
+        if let Err(e) = cache::set_with_ttl(redis_pool, &cache_key, &svg, AVATAR_CACHE_TTL_SECONDS).await {
+            log::warn!("Avatar cache write failed for user {}: {}", user_id, e);
+        }
+
+        Ok(svg)
+    }
+}
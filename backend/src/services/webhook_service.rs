@@ -0,0 +1,135 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::config::Config;
+use crate::error::{AppError, ValidationErrors};
+use crate::models::rate_limit::RATE_LIMIT_CLASS_WEBHOOK;
+use crate::models::webhook::{CreateWebhookDto, CreatedWebhookResponse, RoomWebhook, WebhookMessageDto};
+use crate::repositories::{RoomRepository, WebhookRepository};
+use crate::services::spam_service::{SpamAuthor, SpamVerdict};
+use crate::services::{PushService, RateLimitService, RoomService, SpamService, WordFilterService};
+use crate::utils::secure_token;
+
+pub struct WebhookService;
+
+impl WebhookService {
+    /// Create an incoming webhook (owner only); the token is only returned here
+    pub async fn create_webhook(
+        pool: &PgPool,
+        room_id: Uuid,
+        user_id: Uuid,
+        dto: CreateWebhookDto,
+    ) -> Result<CreatedWebhookResponse, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("name", "Name must be between 1-80 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let room = RoomRepository::find_by_id(pool, room_id).await?;
+        if room.is_direct_message() {
+            return Err(AppError::DirectMessageRoom);
+        }
+        RoomService::ensure_not_locked(&room)?;
+
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        let token = secure_token::generate();
+        let webhook = WebhookRepository::create(pool, room_id, &dto.name, &secure_token::hash(&token), user_id).await?;
+
+        log::info!("Webhook {} created in room {} by {}", webhook.id, room_id, user_id);
+
+        Ok(CreatedWebhookResponse {
+            url_path: format!("/api/hooks/{}/{}", webhook.id, token),
+            webhook,
+            token,
+        })
+    }
+
+    /// List a room's active webhooks (owner only)
+    pub async fn list_webhooks(pool: &PgPool, room_id: Uuid, user_id: Uuid) -> Result<Vec<RoomWebhook>, AppError> {
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        WebhookRepository::list_for_room(pool, room_id).await
+    }
+
+    /// Revoke a webhook (owner only); its URL stops working immediately
+    pub async fn revoke_webhook(
+        pool: &PgPool,
+        room_id: Uuid,
+        webhook_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        RoomService::require_owner(pool, room_id, user_id).await?;
+
+        if !WebhookRepository::revoke(pool, room_id, webhook_id).await? {
+            return Err(AppError::WebhookNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Post a message into the webhook's room as the webhook identity
+    pub async fn post_message(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        config: &Config,
+        webhook_id: Uuid,
+        token: &str,
+        dto: WebhookMessageDto,
+    ) -> Result<(), AppError> {
+        // Unknown, revoked and wrong-token webhooks all look the same
+        let webhook = WebhookRepository::find_active(pool, webhook_id).await?;
+        if !secure_token::constant_time_eq(&secure_token::hash(token), &webhook.token_hash) {
+            return Err(AppError::WebhookNotFound);
+        }
+
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("input", "Invalid webhook message");
+                AppError::ValidationError(errors)
+            })?;
+
+        let room = RoomRepository::find_by_id(pool, webhook.room_id).await?;
+        RoomService::ensure_not_locked(&room)?;
+
+        // Per-webhook token bucket
+        RateLimitService::take_message_token(pool, redis_pool, config, RATE_LIMIT_CLASS_WEBHOOK, webhook.id).await?;
+
+        let content = WordFilterService::filter(pool, redis_pool, &dto.content).await?;
+
+        // The webhook's creator answers for its spam; held messages look sent to the caller
+        let author = SpamAuthor {
+            key: format!("webhook:{}", webhook.id),
+            user_id: webhook.created_by,
+            room_id: webhook.room_id,
+        };
+        if SpamService::check(pool, redis_pool, config, &author, &content).await? == SpamVerdict::Hold {
+            WebhookRepository::touch(pool, webhook.id).await?;
+            return Ok(());
+        }
+
+        let event = serde_json::json!({
+            "type": "room.webhook_message",
+            "room_id": webhook.room_id,
+            "author": {
+                "type": "webhook",
+                "webhook_id": webhook.id,
+                "name": dto.username.as_deref().unwrap_or(&webhook.name),
+            },
+            "content": content,
+            "created_at": Utc::now(),
+        });
+        cache::publish(redis_pool, &cache::room_channel(webhook.room_id), &event.to_string()).await?;
+
+        PushService::notify_mentions(pool, redis_pool, config, &room, dto.username.as_deref().unwrap_or(&webhook.name), &content);
+
+        WebhookRepository::touch(pool, webhook.id).await?;
+
+        Ok(())
+    }
+}
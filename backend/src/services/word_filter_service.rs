@@ -0,0 +1,189 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+use crate::cache::{self, RedisPool};
+use crate::error::{AppError, ValidationErrors};
+use crate::models::audit::{AUDIT_WORD_FILTER_CREATED, AUDIT_WORD_FILTER_DELETED, AUDIT_WORD_FILTER_UPDATED};
+use crate::models::word_filter::{
+    CreateWordFilterDto, UpdateWordFilterDto, WordFilter, FILTER_ACTION_BLOCK, FILTER_ACTION_MASK,
+};
+use crate::repositories::{AuditRepository, WordFilterRepository};
+use crate::utils::word_filter::{self, Outcome, Rule};
+
+/// Redis key caching the filter list; dropped whenever a filter changes
+const WORD_FILTERS_CACHE_KEY: &str = "word_filters";
+
+/// Safety net in case an invalidation is lost
+const WORD_FILTERS_CACHE_TTL_SECONDS: u64 = 3600;
+
+pub struct WordFilterService;
+
+impl WordFilterService {
+    /// All banned words and patterns (admin only)
+    pub async fn list(pool: &PgPool) -> Result<Vec<WordFilter>, AppError> {
+        WordFilterRepository::list(pool).await
+    }
+
+    /// Add a banned word or pattern; it applies to the next message
+    pub async fn create(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        dto: CreateWordFilterDto,
+    ) -> Result<WordFilter, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("pattern", "Pattern must be between 1-200 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let action = dto.action.as_deref().unwrap_or(FILTER_ACTION_MASK);
+        Self::check_rule(&dto.pattern, dto.is_regex, action)?;
+
+        let filter = WordFilterRepository::create(pool, dto.pattern.trim(), dto.is_regex, action, admin_id).await?;
+        Self::invalidate(redis_pool).await;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_WORD_FILTER_CREATED,
+            Some(filter.id),
+            Some(serde_json::json!({ "pattern": filter.pattern, "action": filter.action })),
+        )
+        .await?;
+
+        Ok(filter)
+    }
+
+    /// Change a banned word or pattern
+    pub async fn update(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        filter_id: Uuid,
+        dto: UpdateWordFilterDto,
+    ) -> Result<WordFilter, AppError> {
+        dto.validate()
+            .map_err(|_| {
+                let mut errors = ValidationErrors::new();
+                errors.add_field_error("pattern", "Pattern must be between 1-200 characters");
+                AppError::ValidationError(errors)
+            })?;
+
+        let current = WordFilterRepository::find_by_id(pool, filter_id).await?;
+        let pattern = dto.pattern.as_deref().map(str::trim).unwrap_or(&current.pattern);
+        let is_regex = dto.is_regex.unwrap_or(current.is_regex);
+        let action = dto.action.as_deref().unwrap_or(&current.action);
+        Self::check_rule(pattern, is_regex, action)?;
+
+        let filter = WordFilterRepository::update(pool, filter_id, pattern, is_regex, action).await?;
+        Self::invalidate(redis_pool).await;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_WORD_FILTER_UPDATED,
+            Some(filter.id),
+            Some(serde_json::json!({ "pattern": filter.pattern, "action": filter.action })),
+        )
+        .await?;
+
+        Ok(filter)
+    }
+
+    /// Remove a banned word or pattern
+    pub async fn delete(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        admin_id: Uuid,
+        filter_id: Uuid,
+    ) -> Result<(), AppError> {
+        let filter = WordFilterRepository::find_by_id(pool, filter_id).await?;
+        WordFilterRepository::delete(pool, filter_id).await?;
+        Self::invalidate(redis_pool).await;
+
+        AuditRepository::record(
+            pool,
+            Some(admin_id),
+            AUDIT_WORD_FILTER_DELETED,
+            Some(filter.id),
+            Some(serde_json::json!({ "pattern": filter.pattern })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Run message content through the word filter: returns the content with masked
+    /// words replaced, or MessageBlocked if a blocking pattern matches
+    pub async fn filter(pool: &PgPool, redis_pool: &RedisPool, content: &str) -> Result<String, AppError> {
+        let rules: Vec<Rule> = Self::load(pool, redis_pool)
+            .await?
+            .iter()
+            .filter_map(|filter| match word_filter::compile(&filter.pattern, filter.is_regex) {
+                Ok(regex) => Some(Rule::new(regex, filter.action == FILTER_ACTION_BLOCK)),
+                Err(e) => {
+                    log::warn!("Skipping invalid word filter {}: {}", filter.id, e);
+                    None
+                }
+            })
+            .collect();
+
+        match word_filter::apply(content, &rules) {
+            Outcome::Allowed(content) => Ok(content),
+            Outcome::Blocked => Err(AppError::MessageBlocked),
+        }
+    }
+
+    /// Filters from the Redis cache, falling back to (and refilling from) Postgres
+    async fn load(pool: &PgPool, redis_pool: &RedisPool) -> Result<Vec<WordFilter>, AppError> {
+        match cache::get_value(redis_pool, WORD_FILTERS_CACHE_KEY).await {
+            Ok(Some(cached)) => {
+                if let Ok(filters) = serde_json::from_str(&cached) {
+                    return Ok(filters);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to read cached word filters: {}", e),
+        }
+
+        let filters = WordFilterRepository::list(pool).await?;
+
+        if let Ok(serialized) = serde_json::to_string(&filters) {
+            if let Err(e) =
+                cache::set_with_ttl(redis_pool, WORD_FILTERS_CACHE_KEY, &serialized, WORD_FILTERS_CACHE_TTL_SECONDS).await
+            {
+                log::warn!("Failed to cache word filters: {}", e);
+            }
+        }
+
+        Ok(filters)
+    }
+
+    /// Validate a pattern and action before storing them
+    fn check_rule(pattern: &str, is_regex: bool, action: &str) -> Result<(), AppError> {
+        if ![FILTER_ACTION_MASK, FILTER_ACTION_BLOCK].contains(&action) {
+            return Err(AppError::InvalidFormat("action".to_string()));
+        }
+
+        if pattern.trim().is_empty() {
+            return Err(AppError::MissingField("pattern".to_string()));
+        }
+
+        if word_filter::compile(pattern, is_regex).is_err() {
+            let mut errors = ValidationErrors::new();
+            errors.add_field_error("pattern", "Pattern is not a valid regular expression");
+            return Err(AppError::ValidationError(errors));
+        }
+
+        Ok(())
+    }
+
+    /// Make every instance pick up a change on the next message
+    async fn invalidate(redis_pool: &RedisPool) {
+        if let Err(e) = cache::delete_value(redis_pool, WORD_FILTERS_CACHE_KEY).await {
+            log::warn!("Failed to invalidate word filter cache: {}", e);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::LazyLock;
+use tokio::sync::watch;
+
+/// Set once the server starts shutting down. Long-lived tasks such as WebSocket
+/// sessions watch it, since the HTTP server only waits for them to end on their own.
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+/// Wait for SIGTERM or SIGINT (Ctrl+C)
+pub async fn signal() {
+    let interrupt = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                log::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => {}
+        _ = terminate => {}
+    }
+}
+
+/// Tell every watcher the server is shutting down
+pub fn trigger() {
+    SHUTDOWN.send_replace(true);
+}
+
+/// Resolve once the server is shutting down (immediately if it already is)
+pub async fn wait() {
+    let mut shutdown = SHUTDOWN.subscribe();
+    // The sender is static, so this can't fail
+    let _ = shutdown.wait_for(|shutting_down| *shutting_down).await;
+}
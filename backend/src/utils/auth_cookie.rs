@@ -0,0 +1,67 @@
+use actix_web::cookie::{time::Duration, Cookie, SameSite};
+
+/// HttpOnly cookie carrying the JWT in cookie auth mode
+pub const AUTH_COOKIE: &str = "ngobrol_token";
+
+/// Script-readable cookie holding the CSRF token (double-submit pattern)
+pub const CSRF_COOKIE: &str = "ngobrol_csrf";
+
+/// Header in which browser clients echo the CSRF cookie on unsafe requests
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Build the auth and CSRF cookies for a freshly issued token
+pub fn issue(token: &str, csrf_token: &str, max_age_seconds: i64, secure: bool) -> (Cookie<'static>, Cookie<'static>) {
+    let auth = Cookie::build(AUTH_COOKIE, token.to_string())
+        .path("/")
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Strict)
+        .max_age(Duration::seconds(max_age_seconds))
+        .finish();
+
+    let csrf = Cookie::build(CSRF_COOKIE, csrf_token.to_string())
+        .path("/")
+        .http_only(false)
+        .secure(secure)
+        .same_site(SameSite::Strict)
+        .max_age(Duration::seconds(max_age_seconds))
+        .finish();
+
+    (auth, csrf)
+}
+
+/// Build cookies that make the browser drop the auth and CSRF cookies
+pub fn clear(secure: bool) -> (Cookie<'static>, Cookie<'static>) {
+    issue("", "", 0, secure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_sets_security_attributes() {
+        let (auth, csrf) = issue("jwt", "csrf", 3600, true);
+
+        assert_eq!(auth.name(), AUTH_COOKIE);
+        assert_eq!(auth.value(), "jwt");
+        assert_eq!(auth.http_only(), Some(true));
+        assert_eq!(auth.secure(), Some(true));
+        assert_eq!(auth.same_site(), Some(SameSite::Strict));
+        assert_eq!(auth.max_age(), Some(Duration::seconds(3600)));
+
+        // The CSRF cookie must be readable by scripts so they can echo it
+        assert_eq!(csrf.name(), CSRF_COOKIE);
+        assert_eq!(csrf.value(), "csrf");
+        assert_eq!(csrf.http_only(), Some(false));
+    }
+
+    #[test]
+    fn test_clear_expires_cookies() {
+        let (auth, csrf) = clear(false);
+
+        assert_eq!(auth.value(), "");
+        assert_eq!(auth.max_age(), Some(Duration::ZERO));
+        assert_eq!(csrf.max_age(), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,75 @@
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Background colors of generated avatars (white text stays readable on all of them)
+const PALETTE: &[&str] = &[
+    "#e57373", "#f06292", "#ba68c8", "#9575cd", "#7986cb", "#64b5f6", "#4fc3f7", "#4db6ac",
+    "#81c784", "#aed581", "#ffb74d", "#ff8a65", "#a1887f", "#90a4ae",
+];
+
+/// Path of the generated avatar of a user (served by GET /api/avatars/:id)
+pub fn fallback_url(user_id: Uuid) -> String {
+    format!("/api/avatars/{}", user_id)
+}
+
+/// Up to two initials: first letters of the first two words, or the first letter alone
+pub fn initials(name: &str) -> String {
+    let letters: String = name
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-' || c == '.')
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect();
+
+    if letters.is_empty() {
+        "?".to_string()
+    } else {
+        letters
+    }
+}
+
+/// Background color picked from the user id, so it stays the same across renames
+pub fn color(user_id: Uuid) -> &'static str {
+    let digest = Sha256::digest(user_id.as_bytes());
+    PALETTE[digest[0] as usize % PALETTE.len()]
+}
+
+/// Render a square SVG initials avatar
+pub fn render_svg(user_id: Uuid, name: &str) -> String {
+    // Initials are alphanumeric only, so they need no XML escaping
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="128" height="128" viewBox="0 0 128 128">"#,
+            r#"<rect width="128" height="128" fill="{}"/>"#,
+            r##"<text x="50%" y="50%" dy=".35em" text-anchor="middle" fill="#ffffff" "##,
+            r#"font-family="Helvetica, Arial, sans-serif" font-size="56" font-weight="600">{}</text>"#,
+            r#"</svg>"#,
+        ),
+        color(user_id),
+        initials(name),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("Hikam Ihsan"), "HI");
+        assert_eq!(initials("budi"), "B");
+        assert_eq!(initials("john_doe"), "JD");
+        assert_eq!(initials("ana maria lopez"), "AM");
+        assert_eq!(initials("  "), "?");
+        assert_eq!(initials("<script>"), "S");
+    }
+
+    #[test]
+    fn test_render_is_deterministic() {
+        let user_id = Uuid::new_v4();
+
+        assert_eq!(render_svg(user_id, "Budi"), render_svg(user_id, "Budi"));
+        assert!(render_svg(user_id, "Budi").contains(color(user_id)));
+        assert!(render_svg(user_id, "Budi").contains(">B</text>"));
+    }
+}
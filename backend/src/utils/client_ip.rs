@@ -0,0 +1,130 @@
+use actix_web::{web, HttpRequest};
+use std::net::IpAddr;
+use crate::config::Config;
+use crate::utils::ip_net::{self, IpNet};
+
+/// Address of the client behind a request. Forwarding headers are only believed when
+/// the connection comes from a trusted proxy, since anyone can send them.
+pub fn get(req: &HttpRequest) -> Option<IpAddr> {
+    let peer = req.peer_addr()?.ip();
+    let trusted = req
+        .app_data::<web::Data<Config>>()
+        .map(|config| config.trusted_proxies.as_slice())
+        .unwrap_or_default();
+
+    let forwarded = header(req, "Forwarded");
+    let forwarded_for = header(req, "X-Forwarded-For");
+
+    Some(resolve(peer, forwarded.as_deref(), forwarded_for.as_deref(), trusted))
+}
+
+/// Whether the request arrived through a trusted proxy, so headers the proxy sets
+/// (forwarding, geo) can be believed
+pub fn from_trusted_proxy(req: &HttpRequest, config: &Config) -> bool {
+    req.peer_addr()
+        .is_some_and(|peer| config.trusted_proxies.iter().any(|net| net.contains(peer.ip())))
+}
+
+/// Walk the forwarding chain from the nearest hop back while the hops are trusted
+/// proxies; the first untrusted address is the client. `Forwarded` wins over
+/// `X-Forwarded-For` when both are present.
+pub fn resolve(peer: IpAddr, forwarded: Option<&str>, forwarded_for: Option<&str>, trusted: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|net| net.contains(ip));
+
+    if !is_trusted(peer) {
+        return peer;
+    }
+
+    let chain: Vec<&str> = match (forwarded, forwarded_for) {
+        (Some(forwarded), _) => forwarded_hops(forwarded),
+        (None, Some(forwarded_for)) => forwarded_for.split(',').collect(),
+        (None, None) => Vec::new(),
+    };
+
+    let mut client = peer;
+    for hop in chain.iter().rev() {
+        // An unreadable hop ends the chain we can vouch for
+        let Some(ip) = ip_net::parse_client_ip(hop) else {
+            break;
+        };
+
+        client = ip;
+        if !is_trusted(ip) {
+            break;
+        }
+    }
+
+    client
+}
+
+/// `for=` values of an RFC 7239 Forwarded header, nearest hop last
+fn forwarded_hops(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                name.eq_ignore_ascii_case("for").then(|| value.trim().trim_matches('"'))
+            })
+        })
+        .collect()
+}
+
+fn header(req: &HttpRequest, name: &str) -> Option<String> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn proxies() -> Vec<IpNet> {
+        vec![IpNet::parse("10.0.0.0/8").unwrap()]
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_forwarding_headers() {
+        let client = resolve(ip("203.0.113.7"), None, Some("198.51.100.1"), &proxies());
+        assert_eq!(client, ip("203.0.113.7"));
+
+        let client = resolve(ip("203.0.113.7"), Some("for=198.51.100.1"), None, &[]);
+        assert_eq!(client, ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_trusted_proxy_forwards_client() {
+        let client = resolve(ip("10.0.0.2"), None, Some("198.51.100.1"), &proxies());
+        assert_eq!(client, ip("198.51.100.1"));
+    }
+
+    #[test]
+    fn test_spoofed_hops_before_the_proxy_are_skipped() {
+        // The client sent a fake X-Forwarded-For; the proxy appended the real address
+        let client = resolve(ip("10.0.0.2"), None, Some("1.2.3.4, 198.51.100.1, 10.0.0.9"), &proxies());
+        assert_eq!(client, ip("198.51.100.1"));
+    }
+
+    #[test]
+    fn test_forwarded_header() {
+        let client = resolve(
+            ip("10.0.0.2"),
+            Some("for=1.2.3.4, for=\"[2001:db8::1]:4711\";proto=https"),
+            Some("5.6.7.8"),
+            &proxies(),
+        );
+        assert_eq!(client, ip("2001:db8::1"));
+    }
+
+    #[test]
+    fn test_trusted_proxy_without_headers_is_the_client() {
+        assert_eq!(resolve(ip("10.0.0.2"), None, None, &proxies()), ip("10.0.0.2"));
+        assert_eq!(resolve(ip("10.0.0.2"), None, Some("garbage"), &proxies()), ip("10.0.0.2"));
+    }
+}
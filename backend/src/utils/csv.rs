@@ -0,0 +1,59 @@
+/// Characters that make spreadsheet apps evaluate a cell as a formula
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Quote a field per RFC 4180 when needed. Values that would be read as a formula
+/// are prefixed with a single quote, since exports are usually opened in a spreadsheet.
+pub fn escape(field: &str) -> String {
+    let field = if field.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// One CSV record, terminated by CRLF
+pub fn row<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| escape(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_plain_field_is_unchanged() {
+        assert_eq!(escape("alice"), "alice");
+        assert_eq!(escape(""), "");
+    }
+
+    #[test]
+    fn test_escape_quotes_special_characters() {
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_escape_neutralizes_formulas() {
+        assert_eq!(escape("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(escape("@cmd"), "'@cmd");
+        assert_eq!(escape("-1,2"), "\"'-1,2\"");
+    }
+
+    #[test]
+    fn test_row_joins_fields_with_crlf() {
+        assert_eq!(row(&["id", "a,b", "c"]), "id,\"a,b\",c\r\n");
+    }
+}
@@ -0,0 +1,50 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Opaque keyset-pagination cursor pointing after the row with this timestamp and ID
+pub fn encode(created_at: DateTime<Utc>, id: Uuid) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}:{}", created_at.timestamp_micros(), id))
+}
+
+/// Parse a cursor made by `encode`; None if it was tampered with or is malformed
+pub fn decode(cursor: &str) -> Option<(DateTime<Utc>, Uuid)> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    let raw = String::from_utf8(bytes).ok()?;
+    let (micros, id) = raw.split_once(':')?;
+
+    let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+    let id = Uuid::parse_str(id).ok()?;
+
+    Some((created_at, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_microseconds() {
+        let created_at = DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap();
+        let id = Uuid::new_v4();
+
+        assert_eq!(decode(&encode(created_at, id)), Some((created_at, id)));
+    }
+
+    #[test]
+    fn test_cursor_is_url_safe() {
+        let cursor = encode(Utc::now(), Uuid::new_v4());
+
+        assert!(cursor.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_malformed_cursors_are_rejected() {
+        assert_eq!(decode(""), None);
+        assert_eq!(decode("not a cursor!"), None);
+        assert_eq!(decode(&URL_SAFE_NO_PAD.encode("123")), None);
+        assert_eq!(decode(&URL_SAFE_NO_PAD.encode("abc:00000000-0000-0000-0000-000000000000")), None);
+        assert_eq!(decode(&URL_SAFE_NO_PAD.encode("123:not-a-uuid")), None);
+    }
+}
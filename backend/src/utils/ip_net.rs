@@ -0,0 +1,140 @@
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// IP network in CIDR notation; a bare address is a /32 (IPv4) or /128 (IPv6) network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    /// Parse "203.0.113.0/24", "2001:db8::/32" or a bare address. Host bits are
+    /// cleared, so "203.0.113.7/24" becomes "203.0.113.0/24".
+    pub fn parse(value: &str) -> Option<IpNet> {
+        let value = value.trim();
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+            None => (value, None),
+        };
+
+        let addr: IpAddr = addr.parse().ok()?;
+        let prefix = prefix.unwrap_or(max_prefix(addr));
+        if prefix > max_prefix(addr) {
+            return None;
+        }
+
+        Some(IpNet {
+            network: mask(addr, prefix),
+            prefix,
+        })
+    }
+
+    /// Whether the address is inside the network (IPv4-mapped IPv6 addresses match IPv4 networks)
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        ip.is_ipv4() == self.network.is_ipv4() && mask(ip, self.prefix) == self.network
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Client address as reported by the connection info: a bare IP, or an IP with a
+/// port ("203.0.113.7:5678", "[2001:db8::1]:443")
+pub fn parse_client_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+
+    value
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| value.trim_start_matches('[').trim_end_matches(']').parse().ok())
+}
+
+fn max_prefix(addr: IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Clear all but the first `prefix` bits
+fn mask(addr: IpAddr, prefix: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(addr) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            IpAddr::V4((u32::from(addr) & mask).into())
+        }
+        IpAddr::V6(addr) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            IpAddr::V6((u128::from(addr) & mask).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ipv4_network_contains() {
+        let net = IpNet::parse("203.0.113.0/24").unwrap();
+
+        assert!(net.contains(ip("203.0.113.0")));
+        assert!(net.contains(ip("203.0.113.255")));
+        assert!(!net.contains(ip("203.0.114.1")));
+        assert!(!net.contains(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn test_bare_address_is_single_host() {
+        let net = IpNet::parse("198.51.100.7").unwrap();
+
+        assert_eq!(net.to_string(), "198.51.100.7/32");
+        assert!(net.contains(ip("198.51.100.7")));
+        assert!(!net.contains(ip("198.51.100.8")));
+    }
+
+    #[test]
+    fn test_host_bits_are_cleared() {
+        assert_eq!(IpNet::parse("203.0.113.77/24").unwrap().to_string(), "203.0.113.0/24");
+        assert_eq!(IpNet::parse("2001:db8:abcd::1/32").unwrap().to_string(), "2001:db8::/32");
+        assert_eq!(IpNet::parse("10.1.2.3/0").unwrap().to_string(), "0.0.0.0/0");
+    }
+
+    #[test]
+    fn test_ipv6_network_and_mapped_addresses() {
+        let v6 = IpNet::parse("2001:db8::/32").unwrap();
+        assert!(v6.contains(ip("2001:db8:ffff::1")));
+        assert!(!v6.contains(ip("2001:db9::1")));
+
+        let v4 = IpNet::parse("192.0.2.0/24").unwrap();
+        assert!(v4.contains(ip("::ffff:192.0.2.10")));
+    }
+
+    #[test]
+    fn test_invalid_networks_are_rejected() {
+        assert_eq!(IpNet::parse(""), None);
+        assert_eq!(IpNet::parse("not-an-ip"), None);
+        assert_eq!(IpNet::parse("203.0.113.0/33"), None);
+        assert_eq!(IpNet::parse("2001:db8::/129"), None);
+        assert_eq!(IpNet::parse("203.0.113.0/abc"), None);
+    }
+
+    #[test]
+    fn test_parse_client_ip() {
+        assert_eq!(parse_client_ip("203.0.113.7"), Some(ip("203.0.113.7")));
+        assert_eq!(parse_client_ip("203.0.113.7:5678"), Some(ip("203.0.113.7")));
+        assert_eq!(parse_client_ip("[2001:db8::1]:443"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_client_ip("[2001:db8::1]"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_client_ip("unknown"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+/// Whether the name is a known IANA timezone (e.g. "Asia/Jakarta")
+pub fn is_valid_timezone(name: &str) -> bool {
+    name.parse::<Tz>().is_ok()
+}
+
+/// Whether the tag looks like a BCP 47 locale (e.g. "en", "id-ID", "zh-Hant-TW")
+pub fn is_valid_locale(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+
+    let language_ok = subtags
+        .next()
+        .is_some_and(|language| (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic()));
+
+    language_ok
+        && tag.len() <= 35
+        && subtags.all(|subtag| (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Render a timestamp in the user's timezone, falling back to UTC for unknown names
+pub fn format(timestamp: DateTime<Utc>, timezone: &str) -> String {
+    match timezone.parse::<Tz>() {
+        Ok(tz) => timestamp.with_timezone(&tz).format("%Y-%m-%d %H:%M %Z").to_string(),
+        Err(_) => timestamp.format("%Y-%m-%d %H:%M UTC").to_string(),
+    }
+}
+
+/// Whether the local time in the timezone falls in [start, end), where the window may
+/// span midnight (e.g. 22:00-07:00). Unknown timezones are treated as UTC.
+pub fn within_daily_window(now: DateTime<Utc>, timezone: &str, start: NaiveTime, end: NaiveTime) -> bool {
+    let local = match timezone.parse::<Tz>() {
+        Ok(tz) => now.with_timezone(&tz).time(),
+        Err(_) => now.time(),
+    };
+
+    if start <= end {
+        start <= local && local < end
+    } else {
+        local >= start || local < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_is_valid_timezone() {
+        assert!(is_valid_timezone("UTC"));
+        assert!(is_valid_timezone("Asia/Jakarta"));
+        assert!(!is_valid_timezone("Mars/Olympus_Mons"));
+        assert!(!is_valid_timezone(""));
+    }
+
+    #[test]
+    fn test_is_valid_locale() {
+        assert!(is_valid_locale("en"));
+        assert!(is_valid_locale("id-ID"));
+        assert!(is_valid_locale("zh-Hant-TW"));
+        assert!(!is_valid_locale(""));
+        assert!(!is_valid_locale("e"));
+        assert!(!is_valid_locale("en_US"));
+        assert!(!is_valid_locale("en-"));
+    }
+
+    #[test]
+    fn test_format_converts_to_timezone() {
+        let timestamp = Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+
+        assert_eq!(format(timestamp, "Asia/Jakarta"), "2026-10-16 16:30 WIB");
+        assert_eq!(format(timestamp, "UTC"), "2026-10-16 09:30 UTC");
+        assert_eq!(format(timestamp, "Nowhere/Unknown"), "2026-10-16 09:30 UTC");
+    }
+
+    #[test]
+    fn test_within_daily_window() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        // 15:30 UTC is 22:30 in Jakarta
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 15, 30, 0).unwrap();
+
+        assert!(within_daily_window(now, "Asia/Jakarta", time(22, 0), time(7, 0)));
+        assert!(!within_daily_window(now, "UTC", time(22, 0), time(7, 0)));
+        assert!(within_daily_window(now, "UTC", time(9, 0), time(17, 0)));
+        assert!(!within_daily_window(now, "UTC", time(9, 0), time(15, 30)));
+    }
+}
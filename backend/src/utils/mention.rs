@@ -0,0 +1,52 @@
+/// Most mentions of one message that are acted on
+pub const MAX_MENTIONS: usize = 20;
+
+/// Usernames @-mentioned in a message, in order of appearance and without duplicates.
+/// A mention starts at a word boundary and runs to the next whitespace, minus
+/// trailing punctuation ("thanks @budi!" mentions "budi").
+pub fn usernames(text: &str) -> Vec<String> {
+    let mut usernames: Vec<String> = Vec::new();
+
+    for word in text.split_whitespace() {
+        let Some(name) = word.strip_prefix('@') else {
+            continue;
+        };
+
+        let name = name.trim_end_matches(['.', ',', '!', '?', ':', ';', ')', '\'', '"']);
+        if !(3..=50).contains(&name.chars().count()) || usernames.iter().any(|u| u == name) {
+            continue;
+        }
+
+        usernames.push(name.to_string());
+        if usernames.len() == MAX_MENTIONS {
+            break;
+        }
+    }
+
+    usernames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usernames() {
+        assert_eq!(usernames("hi @budi and @sari_99"), vec!["budi", "sari_99"]);
+        assert_eq!(usernames("@budi: thanks @sari!"), vec!["budi", "sari"]);
+        assert_eq!(usernames("@budi @budi @budi?"), vec!["budi"]);
+    }
+
+    #[test]
+    fn test_usernames_skips_non_mentions() {
+        assert!(usernames("mail budi@example.com").is_empty());
+        assert!(usernames("@ @a @bo").is_empty());
+        assert!(usernames(&format!("@{}", "x".repeat(51))).is_empty());
+    }
+
+    #[test]
+    fn test_usernames_are_capped() {
+        let text: Vec<String> = (0..30).map(|i| format!("@user{}", i)).collect();
+        assert_eq!(usernames(&text.join(" ")).len(), MAX_MENTIONS);
+    }
+}
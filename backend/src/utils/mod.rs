@@ -1,2 +1,19 @@
 pub mod password;
 pub mod jwt;
+pub mod user_agent;
+pub mod secure_token;
+pub mod auth_cookie;
+pub mod webhook_signature;
+pub mod name_policy;
+pub mod slash_command;
+pub mod local_time;
+pub mod avatar;
+pub mod word_filter;
+pub mod cursor;
+pub mod ip_net;
+pub mod spam;
+pub mod csv;
+pub mod client_ip;
+pub mod outbound_url;
+pub mod mention;
+pub mod web_push;
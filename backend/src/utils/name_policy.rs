@@ -0,0 +1,97 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::{skeleton, MixedScript};
+use crate::config::NamePolicy;
+
+/// Why a name was rejected
+#[derive(Debug, PartialEq, Eq)]
+pub enum NameViolation {
+    /// Looks like a reserved name (e.g. "Admin", "ѕystem", "adrnin")
+    Reserved,
+    /// Contains a banned word, however it is spelled
+    BannedWord,
+    /// Mixes scripts, e.g. Cyrillic letters in a Latin name
+    MixedScripts,
+}
+
+/// Canonical form used to compare names: compatibility-normalized (NFKC), case-folded,
+/// confusables mapped to their prototype (UTS #39 skeleton) and separators dropped,
+/// so "A.dmin", "аdmin" (Cyrillic а) and "ＡＤＭＩＮ" all compare equal to "admin"
+pub fn canonical(name: &str) -> String {
+    let normalized: String = name.nfkc().collect::<String>().to_lowercase();
+    let folded: String = skeleton(&normalized).collect::<String>().to_lowercase();
+    skeleton(&folded).filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// Check a username or display name against reserved names and banned words
+pub fn check(name: &str, policy: &NamePolicy) -> Result<(), NameViolation> {
+    if !name.is_single_script() {
+        return Err(NameViolation::MixedScripts);
+    }
+
+    let name = canonical(name);
+
+    if policy.reserved_names.iter().any(|reserved| canonical(reserved) == name) {
+        return Err(NameViolation::Reserved);
+    }
+
+    if policy
+        .banned_words
+        .iter()
+        .map(|word| canonical(word))
+        .any(|word| !word.is_empty() && name.contains(&word))
+    {
+        return Err(NameViolation::BannedWord);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(reserved_names: &[&str], banned_words: &[&str]) -> NamePolicy {
+        NamePolicy {
+            reserved_names: reserved_names.iter().map(|s| s.to_string()).collect(),
+            banned_words: banned_words.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_canonical_folds_case_confusables_and_separators() {
+        assert_eq!(canonical("Admin"), canonical("admin"));
+        assert_eq!(canonical("a.d_m-i n"), canonical("admin"));
+        assert_eq!(canonical("ＡＤＭＩＮ"), canonical("admin"));
+        assert_eq!(canonical("\u{0430}dmin"), canonical("admin")); // Cyrillic а
+        assert_eq!(canonical("adrnin"), canonical("admin")); // "rn" looks like "m"
+        assert_ne!(canonical("alice"), canonical("admin"));
+    }
+
+    #[test]
+    fn test_check_reserved_names() {
+        let policy = policy(&["admin", "system"], &[]);
+
+        assert_eq!(check("ADMIN", &policy), Err(NameViolation::Reserved));
+        assert_eq!(check("Sys.tem", &policy), Err(NameViolation::Reserved));
+        assert!(check("administrator_fan", &policy).is_ok());
+        assert!(check("alice", &policy).is_ok());
+    }
+
+    #[test]
+    fn test_check_banned_words() {
+        let policy = policy(&[], &["badword"]);
+
+        assert_eq!(check("xxBADWORDxx", &policy), Err(NameViolation::BannedWord));
+        assert_eq!(check("BadW0rd", &policy), Err(NameViolation::BannedWord));
+        assert!(check("goodword", &policy).is_ok());
+    }
+
+    #[test]
+    fn test_check_mixed_scripts() {
+        let policy = policy(&[], &[]);
+
+        assert_eq!(check("p\u{0430}ypal", &policy), Err(NameViolation::MixedScripts));
+        assert!(check("Алиса", &policy).is_ok());
+        assert!(check("Budi 🎉", &policy).is_ok());
+    }
+}
@@ -0,0 +1,144 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::LazyLock;
+use std::time::Duration;
+use reqwest::Url;
+use crate::utils::ip_net::IpNet;
+
+/// Networks user-supplied URLs may not reach: loopback, private, link-local,
+/// unique-local and other special-purpose ranges (IANA registries)
+static BLOCKED_NETWORKS: LazyLock<Vec<IpNet>> = LazyLock::new(|| {
+    [
+        "0.0.0.0/8",
+        "10.0.0.0/8",
+        "100.64.0.0/10",
+        "127.0.0.0/8",
+        "169.254.0.0/16",
+        "172.16.0.0/12",
+        "192.0.0.0/24",
+        "192.0.2.0/24",
+        "192.168.0.0/16",
+        "198.18.0.0/15",
+        "198.51.100.0/24",
+        "203.0.113.0/24",
+        "224.0.0.0/4",
+        "240.0.0.0/4",
+        "::/128",
+        "::1/128",
+        "64:ff9b::/96",
+        "100::/64",
+        "2001:db8::/32",
+        "2002::/16",
+        "fc00::/7",
+        "fe80::/10",
+        "fec0::/10",
+        "ff00::/8",
+    ]
+    .iter()
+    .filter_map(|net| IpNet::parse(net))
+    .collect()
+});
+
+/// Whether an address is reachable on the public internet
+/// (IPv4-mapped IPv6 addresses are judged as IPv4)
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    !BLOCKED_NETWORKS.iter().any(|net| net.contains(ip))
+}
+
+/// Check that a user-supplied URL is https and that every address its host resolves
+/// to is public. Returns the host and those addresses, so the request can be pinned
+/// to them (a second lookup could answer differently).
+pub async fn resolve(url: &str) -> Result<(String, Vec<SocketAddr>), String> {
+    let url = Url::parse(url).map_err(|_| "Invalid URL".to_string())?;
+
+    // Payloads are signed, not encrypted
+    if url.scheme() != "https" {
+        return Err("URL must use https".to_string());
+    }
+
+    let host = url.host_str().ok_or("URL has no host")?.to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_start_matches('[').trim_end_matches(']'), port))
+        .await
+        .map_err(|_| format!("Could not resolve {}", host))?
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(format!("Could not resolve {}", host));
+    }
+
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(format!("{} does not resolve to a public address", host));
+    }
+
+    Ok((host, addrs))
+}
+
+/// HTTP client for one request to a user-supplied URL: connections go only to the
+/// checked addresses and redirects are not followed
+pub async fn client(url: &str, timeout: Duration) -> Result<reqwest::Client, String> {
+    let (host, addrs) = resolve(url).await?;
+
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(&host, &addrs)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_internal_addresses_are_not_public() {
+        for addr in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip(addr)), "{} should not be public", addr);
+        }
+    }
+
+    #[test]
+    fn test_internet_addresses_are_public() {
+        for addr in ["1.1.1.1", "8.8.8.8", "172.32.0.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip(addr)), "{} should be public", addr);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_unsafe_urls() {
+        assert!(resolve("http://example.com/hook").await.is_err());
+        assert!(resolve("not a url").await.is_err());
+        assert!(resolve("https://127.0.0.1/hook").await.is_err());
+        assert!(resolve("https://169.254.169.254/latest/meta-data").await.is_err());
+        assert!(resolve("https://[::1]:8443/hook").await.is_err());
+        assert!(resolve("https://10.0.0.5/hook").await.is_err());
+        assert!(resolve("https://localhost/hook").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_accepts_public_address() {
+        let (host, addrs) = resolve("https://1.1.1.1:8443/hook").await.unwrap();
+
+        assert_eq!(host, "1.1.1.1");
+        assert_eq!(addrs, vec!["1.1.1.1:8443".parse::<SocketAddr>().unwrap()]);
+    }
+}
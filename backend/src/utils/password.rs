@@ -1,13 +1,31 @@
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, Params, Version,
 };
+use crate::config::{Argon2Config, PasswordPolicy};
 use crate::error::AppError;
 
-/// Hash a password using Argon2
-pub fn hash_password(password: &str) -> Result<String, AppError> {
+/// Frequently used passwords that are always scored 0
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456", "12345678", "123456789", "1234567890", "password", "password1",
+    "password123", "qwerty", "qwerty123", "qwertyuiop", "abc123", "111111",
+    "iloveyou", "admin", "admin123", "welcome", "welcome1", "letmein",
+    "monkey", "dragon", "football", "baseball", "sunshine", "princess",
+    "passw0rd", "p@ssw0rd", "1q2w3e4r", "zaq12wsx", "rahasia", "bismillah",
+];
+
+/// Build an Argon2id hasher from configured cost parameters
+fn hasher(config: &Argon2Config) -> Result<Argon2<'static>, AppError> {
+    let params = Params::new(config.memory_kib, config.iterations, config.parallelism, None)
+        .map_err(|e| AppError::InternalError(format!("Invalid Argon2 parameters: {}", e)))?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+/// Hash a password using Argon2id with the configured parameters
+pub fn hash_password(password: &str, config: &Argon2Config) -> Result<String, AppError> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
+    let argon2 = hasher(config)?;
     
     let password_hash = argon2
         .hash_password(password.as_bytes(), &salt)?
@@ -29,16 +47,138 @@ pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, AppE
     }
 }
 
+/// Check whether a stored hash was created with weaker parameters than configured
+/// (or a different algorithm) and should be replaced on next successful login
+pub fn needs_rehash(password_hash: &str, config: &Argon2Config) -> bool {
+    let parsed = match PasswordHash::new(password_hash) {
+        Ok(parsed) => parsed,
+        Err(_) => return false,
+    };
+
+    if parsed.algorithm != Algorithm::Argon2id.ident() {
+        return true;
+    }
+
+    match Params::try_from(&parsed) {
+        Ok(params) => {
+            params.m_cost() < config.memory_kib
+                || params.t_cost() < config.iterations
+                || params.p_cost() < config.parallelism
+        }
+        Err(_) => false,
+    }
+}
+
+/// Estimate password strength on the zxcvbn 0-4 scale
+///
+/// Lightweight approximation: character-class entropy over the length left
+/// after collapsing repeats and sequences, with common passwords and
+/// passwords containing the user's own inputs (username, email) penalized.
+pub fn estimate_strength(password: &str, user_inputs: &[&str]) -> u8 {
+    let lower = password.to_lowercase();
+
+    if COMMON_PASSWORDS.contains(&lower.as_str()) {
+        return 0;
+    }
+
+    // Count characters that add information: skip repeats ("aaa") and
+    // ascending/descending runs ("abc", "321")
+    let chars: Vec<char> = lower.chars().collect();
+    let mut effective_length = 0usize;
+    for (i, c) in chars.iter().enumerate() {
+        if i == 0 {
+            effective_length += 1;
+            continue;
+        }
+        let step = *c as i64 - chars[i - 1] as i64;
+        if step.abs() > 1 {
+            effective_length += 1;
+        }
+    }
+
+    let mut charset = 0u32;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        charset += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        charset += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        charset += 10;
+    }
+    if password.chars().any(|c| !c.is_ascii_alphanumeric()) {
+        charset += 33;
+    }
+
+    let bits = effective_length as f64 * (charset.max(1) as f64).log2();
+    let mut score = match bits {
+        b if b < 25.0 => 0,
+        b if b < 35.0 => 1,
+        b if b < 50.0 => 2,
+        b if b < 65.0 => 3,
+        _ => 4,
+    };
+
+    // Passwords built around the username or email are easy to guess
+    let contains_user_input = user_inputs
+        .iter()
+        .map(|input| input.to_lowercase())
+        .any(|input| input.len() >= 3 && lower.contains(&input));
+    if contains_user_input {
+        score = score.min(1);
+    }
+
+    score
+}
+
+/// Check a password against the policy, returning human-readable violations
+pub fn policy_violations(password: &str, policy: &PasswordPolicy, user_inputs: &[&str]) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        violations.push(format!("Password must be at least {} characters", policy.min_length));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        violations.push("Password must contain an uppercase letter".to_string());
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        violations.push("Password must contain a lowercase letter".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push("Password must contain a digit".to_string());
+    }
+    if policy.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+        violations.push("Password must contain a symbol".to_string());
+    }
+    if estimate_strength(password, user_inputs) < policy.min_strength {
+        violations.push("Password is too easy to guess".to_string());
+    }
+
+    violations
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            min_strength: 2,
+            check_breached: false,
+        }
+    }
+
     #[test]
     fn test_hash_and_verify_password() {
         let password = "super_secret_password_123";
         
         // Hash the password
-        let hash = hash_password(password).expect("Failed to hash password");
+        let hash = hash_password(password, &Argon2Config::default()).expect("Failed to hash password");
         
         // Verify correct password
         assert!(verify_password(password, &hash).unwrap());
@@ -51,8 +191,8 @@ mod tests {
     fn test_hash_produces_different_salts() {
         let password = "same_password";
         
-        let hash1 = hash_password(password).expect("Failed to hash password");
-        let hash2 = hash_password(password).expect("Failed to hash password");
+        let hash1 = hash_password(password, &Argon2Config::default()).expect("Failed to hash password");
+        let hash2 = hash_password(password, &Argon2Config::default()).expect("Failed to hash password");
         
         // Different salts should produce different hashes
         assert_ne!(hash1, hash2);
@@ -61,4 +201,63 @@ mod tests {
         assert!(verify_password(password, &hash1).unwrap());
         assert!(verify_password(password, &hash2).unwrap());
     }
+
+    #[test]
+    fn test_estimate_strength() {
+        assert_eq!(estimate_strength("password", &[]), 0);
+        assert_eq!(estimate_strength("abcdefgh", &[]), 0);
+        assert!(estimate_strength("correct horse battery staple", &[]) >= 3);
+        assert!(estimate_strength("Tr0ub4dor&3x", &[]) >= 3);
+    }
+
+    #[test]
+    fn test_estimate_strength_penalizes_user_inputs() {
+        let strong_alone = estimate_strength("hikam-ngobrol-2024!", &[]);
+        let with_username = estimate_strength("hikam-ngobrol-2024!", &["hikam"]);
+
+        assert!(strong_alone >= 3);
+        assert!(with_username <= 1);
+    }
+
+    #[test]
+    fn test_policy_violations() {
+        let mut policy = policy();
+        assert!(policy_violations("Zebra-Lamp-42", &policy, &[]).is_empty());
+
+        let violations = policy_violations("short", &policy, &[]);
+        assert!(violations.iter().any(|v| v.contains("at least 8")));
+
+        policy.require_digit = true;
+        policy.require_symbol = true;
+        let violations = policy_violations("ZebraLampRiver", &policy, &[]);
+        assert!(violations.iter().any(|v| v.contains("digit")));
+        assert!(violations.iter().any(|v| v.contains("symbol")));
+    }
+
+    #[test]
+    fn test_needs_rehash_when_parameters_increase() {
+        let weak = Argon2Config {
+            memory_kib: 8192,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let hash = hash_password("rehash_me_please", &weak).expect("Failed to hash password");
+
+        // Same parameters: keep the hash
+        assert!(!needs_rehash(&hash, &weak));
+
+        // Stronger configuration: rehash
+        assert!(needs_rehash(&hash, &Argon2Config::default()));
+
+        // Weaker configuration never downgrades an existing hash
+        let weaker = Argon2Config {
+            memory_kib: 4096,
+            iterations: 1,
+            parallelism: 1,
+        };
+        assert!(!needs_rehash(&hash, &weaker));
+
+        // Old hash still verifies
+        assert!(verify_password("rehash_me_please", &hash).unwrap());
+    }
 }
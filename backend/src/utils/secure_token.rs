@@ -0,0 +1,58 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Generate a random URL-safe token (256 bits, hex encoded)
+pub fn generate() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash a token for storage (SHA-256, hex encoded)
+/// Tokens are high-entropy, so a fast unsalted hash is sufficient
+pub fn hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Compare two tokens in constant time (for the same length)
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_produces_unique_hex_tokens() {
+        let token1 = generate();
+        let token2 = generate();
+
+        assert_eq!(token1.len(), 64);
+        assert!(token1.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token1, token2);
+    }
+
+    #[test]
+    fn test_hash_is_deterministic() {
+        let token = generate();
+
+        assert_eq!(hash(&token), hash(&token));
+        assert_ne!(hash(&token), token);
+        assert_eq!(hash(&token).len(), 64);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        let token = generate();
+
+        assert!(constant_time_eq(&token, &token.clone()));
+        assert!(!constant_time_eq(&token, &generate()));
+        assert!(!constant_time_eq(&token, &token[1..]));
+        assert!(constant_time_eq("", ""));
+    }
+}
@@ -0,0 +1,52 @@
+/// Longest allowed command name
+pub const NAME_MAX_LEN: usize = 32;
+
+/// Whether a command name is valid: 1-32 lowercase letters, digits, '_' or '-'
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= NAME_MAX_LEN
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+/// Split "/name args..." into the lowercased command name and its trimmed arguments.
+/// Returns None for text that isn't a command (including "//escaped" text).
+pub fn parse(text: &str) -> Option<(String, &str)> {
+    let rest = text.trim_start().strip_prefix('/')?;
+    let (name, args) = match rest.find(char::is_whitespace) {
+        Some(i) => (&rest[..i], rest[i..].trim()),
+        None => (rest, ""),
+    };
+
+    let name = name.to_ascii_lowercase();
+    is_valid_name(&name).then_some((name, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_name() {
+        assert!(is_valid_name("weather"));
+        assert!(is_valid_name("poll-2_x"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("Weather"));
+        assert!(!is_valid_name("wea ther"));
+        assert!(!is_valid_name(&"a".repeat(NAME_MAX_LEN + 1)));
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse("/weather"), Some(("weather".to_string(), "")));
+        assert_eq!(parse("  /Weather  Jakarta, ID "), Some(("weather".to_string(), "Jakarta, ID")));
+        assert_eq!(parse("/poll\n\"Lunch?\" yes no"), Some(("poll".to_string(), "\"Lunch?\" yes no")));
+    }
+
+    #[test]
+    fn test_parse_rejects_non_commands() {
+        assert_eq!(parse("hello /weather"), None);
+        assert_eq!(parse("/"), None);
+        assert_eq!(parse("//not a command"), None);
+        assert_eq!(parse("/we@ther"), None);
+    }
+}
@@ -0,0 +1,112 @@
+use sha2::{Digest, Sha256};
+
+/// Weights of the individual signals; they add up to 1.0
+const REPEAT_WEIGHT: f64 = 0.4;
+const LINK_WEIGHT: f64 = 0.3;
+const BURST_WEIGHT: f64 = 0.3;
+
+/// Identical messages within the window at which the repeat signal saturates
+const REPEAT_SATURATION: u64 = 4;
+
+/// Links per word at which the link signal saturates
+const LINK_DENSITY_SATURATION: f64 = 0.5;
+
+/// What was observed about a message and its author's recent activity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Signals {
+    /// Times the same content was sent by the same author within the window (this one included)
+    pub repeats: u64,
+    /// Messages by the author within the window (this one included)
+    pub recent_messages: u64,
+    /// Links per word in this message
+    pub link_density: f64,
+}
+
+/// Spam score between 0.0 (clean) and 1.0. `burst_limit` is how many messages
+/// within the window count as a full burst.
+pub fn score(signals: &Signals, burst_limit: u64) -> f64 {
+    // A message on its own is never a repeat or a burst
+    let repeat = ratio(signals.repeats.saturating_sub(1), REPEAT_SATURATION - 1);
+    let burst = ratio(signals.recent_messages.saturating_sub(1), burst_limit.max(2) - 1);
+    let links = (signals.link_density / LINK_DENSITY_SATURATION).min(1.0);
+
+    REPEAT_WEIGHT * repeat + LINK_WEIGHT * links + BURST_WEIGHT * burst
+}
+
+/// Links per whitespace-separated word (0.0 for empty content)
+pub fn link_density(content: &str) -> f64 {
+    let words: Vec<&str> = content.split_whitespace().collect();
+    if words.is_empty() {
+        return 0.0;
+    }
+
+    let links = words.iter().filter(|word| is_link(word)).count();
+    links as f64 / words.len() as f64
+}
+
+/// Fingerprint for spotting repeated content: case, whitespace and trailing
+/// punctuation differences don't make a message new
+pub fn fingerprint(content: &str) -> String {
+    let normalized = content
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let normalized = normalized.trim_end_matches(|c: char| c.is_ascii_punctuation());
+
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+fn is_link(word: &str) -> bool {
+    let word = word.to_lowercase();
+    word.starts_with("http://") || word.starts_with("https://") || word.starts_with("www.")
+}
+
+fn ratio(value: u64, saturation: u64) -> f64 {
+    (value as f64 / saturation as f64).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(repeats: u64, recent_messages: u64, link_density: f64) -> Signals {
+        Signals {
+            repeats,
+            recent_messages,
+            link_density,
+        }
+    }
+
+    #[test]
+    fn test_single_plain_message_scores_zero() {
+        assert_eq!(score(&signals(1, 1, 0.0), 10), 0.0);
+    }
+
+    #[test]
+    fn test_signals_saturate_at_full_score() {
+        assert!((score(&signals(50, 500, 1.0), 10) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_repeats_alone_stay_below_the_other_signals_combined() {
+        let repeated = score(&signals(10, 1, 0.0), 10);
+
+        assert!((repeated - REPEAT_WEIGHT).abs() < 1e-9);
+        assert!(score(&signals(10, 10, 0.0), 10) > repeated);
+    }
+
+    #[test]
+    fn test_link_density() {
+        assert_eq!(link_density(""), 0.0);
+        assert_eq!(link_density("hello there"), 0.0);
+        assert_eq!(link_density("see https://example.com"), 0.5);
+        assert_eq!(link_density("HTTP://a.example www.b.example"), 1.0);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_case_spacing_and_trailing_punctuation() {
+        assert_eq!(fingerprint("Buy  NOW at example"), fingerprint("buy now at example!!!"));
+        assert_ne!(fingerprint("buy now"), fingerprint("buy later"));
+    }
+}
@@ -0,0 +1,80 @@
+/// Build a short human-readable device label from a User-Agent header,
+/// e.g. "Firefox on Linux" or "Safari on iOS"
+pub fn describe(user_agent: &str) -> Option<String> {
+    let browser = detect_browser(user_agent);
+    let os = detect_os(user_agent);
+
+    match (browser, os) {
+        (Some(browser), Some(os)) => Some(format!("{} on {}", browser, os)),
+        (Some(browser), None) => Some(browser.to_string()),
+        (None, Some(os)) => Some(os.to_string()),
+        (None, None) => None,
+    }
+}
+
+fn detect_browser(ua: &str) -> Option<&'static str> {
+    // Order matters: most browsers also claim to be Chrome and/or Safari
+    if ua.contains("Edg/") {
+        Some("Edge")
+    } else if ua.contains("OPR/") || ua.contains("Opera") {
+        Some("Opera")
+    } else if ua.contains("Firefox/") || ua.contains("FxiOS/") {
+        Some("Firefox")
+    } else if ua.contains("Chrome/") || ua.contains("CriOS/") {
+        Some("Chrome")
+    } else if ua.contains("Safari/") {
+        Some("Safari")
+    } else if ua.contains("okhttp") {
+        Some("Android app")
+    } else if ua.contains("CFNetwork") {
+        Some("iOS app")
+    } else {
+        None
+    }
+}
+
+fn detect_os(ua: &str) -> Option<&'static str> {
+    if ua.contains("iPhone") || ua.contains("iPad") {
+        Some("iOS")
+    } else if ua.contains("Android") {
+        Some("Android")
+    } else if ua.contains("Windows") {
+        Some("Windows")
+    } else if ua.contains("Mac OS X") || ua.contains("Macintosh") {
+        Some("macOS")
+    } else if ua.contains("CrOS") {
+        Some("ChromeOS")
+    } else if ua.contains("Linux") {
+        Some("Linux")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_desktop_browsers() {
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+        assert_eq!(describe(firefox).as_deref(), Some("Firefox on Linux"));
+
+        let chrome = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+        assert_eq!(describe(chrome).as_deref(), Some("Chrome on Windows"));
+
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 Edg/126.0.0.0";
+        assert_eq!(describe(edge).as_deref(), Some("Edge on Windows"));
+    }
+
+    #[test]
+    fn test_describe_mobile() {
+        let safari = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+        assert_eq!(describe(safari).as_deref(), Some("Safari on iOS"));
+    }
+
+    #[test]
+    fn test_describe_unknown() {
+        assert_eq!(describe("curl/8.5.0"), None);
+    }
+}
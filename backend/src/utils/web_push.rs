@@ -0,0 +1,182 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hkdf::Hkdf;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use reqwest::Url;
+use sha2::Sha256;
+
+/// Record size announced in the aes128gcm header; the whole payload goes in one record
+const RECORD_SIZE: u32 = 4096;
+
+/// AES-GCM authentication tag length
+const TAG_LEN: usize = 16;
+
+/// Decode a base64url value; browsers may or may not pad their subscription keys
+fn decode(value: &str) -> Option<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')).ok()
+}
+
+/// Encrypt a push message for a subscription (RFC 8291, aes128gcm content coding).
+/// `p256dh` and `auth` are the subscription's keys as the browser reports them.
+pub fn encrypt(p256dh: &str, auth: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    encrypt_with(&SecretKey::random(&mut OsRng), salt, p256dh, auth, plaintext)
+}
+
+/// `encrypt` with the sender's key pair and the salt given
+fn encrypt_with(
+    sender_key: &SecretKey,
+    salt: [u8; 16],
+    p256dh: &str,
+    auth: &str,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, String> {
+    let receiver_key = decode(p256dh)
+        .and_then(|bytes| PublicKey::from_sec1_bytes(&bytes).ok())
+        .ok_or("Invalid p256dh key")?;
+    let auth_secret = decode(auth).filter(|secret| secret.len() == 16).ok_or("Invalid auth secret")?;
+
+    if plaintext.len() + 1 + TAG_LEN > RECORD_SIZE as usize {
+        return Err("Push message too large".to_string());
+    }
+
+    let receiver_public = receiver_key.to_encoded_point(false);
+    let sender_public = sender_key.public_key().to_encoded_point(false);
+    let shared_secret = p256::ecdh::diffie_hellman(sender_key.to_nonzero_scalar(), receiver_key.as_affine());
+
+    // Input keying material: the ECDH secret bound to the auth secret and both public keys
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(receiver_public.as_bytes());
+    key_info.extend_from_slice(sender_public.as_bytes());
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&auth_secret), shared_secret.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|e| e.to_string())?;
+
+    let prk = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+    let mut content_key = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    prk.expand(b"Content-Encoding: aes128gcm\0", &mut content_key)
+        .and_then(|_| prk.expand(b"Content-Encoding: nonce\0", &mut nonce))
+        .map_err(|e| e.to_string())?;
+
+    // The payload followed by the last-record delimiter, no padding
+    let mut record = plaintext.to_vec();
+    record.push(2);
+    let ciphertext = Aes128Gcm::new_from_slice(&content_key)
+        .map_err(|e| e.to_string())?
+        .encrypt(Nonce::from_slice(&nonce), record.as_slice())
+        .map_err(|e| e.to_string())?;
+
+    // Header: salt, record size and the sender's public key as key ID
+    let mut body = salt.to_vec();
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(sender_public.len() as u8);
+    body.extend_from_slice(sender_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+
+    Ok(body)
+}
+
+/// `Authorization` header of a push to `endpoint` (RFC 8292): a JWT for the endpoint's
+/// origin signed with the VAPID private key (base64url, as key generators print it),
+/// followed by the matching public key
+pub fn vapid_authorization(private_key: &str, subject: &str, endpoint: &str, expires_at: i64) -> Result<String, String> {
+    let signing_key = decode(private_key)
+        .and_then(|bytes| SigningKey::from_slice(&bytes).ok())
+        .ok_or("Invalid VAPID private key")?;
+    let audience = Url::parse(endpoint)
+        .map_err(|_| "Invalid push endpoint")?
+        .origin()
+        .ascii_serialization();
+
+    let header = URL_SAFE_NO_PAD.encode(r#"{"typ":"JWT","alg":"ES256"}"#);
+    let claims = serde_json::json!({
+        "aud": audience,
+        "exp": expires_at,
+        "sub": subject,
+    });
+    let signing_input = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(claims.to_string()));
+    let signature: Signature = signing_key.sign(signing_input.as_bytes());
+
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+
+    Ok(format!(
+        "vapid t={}.{}, k={}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+        URL_SAFE_NO_PAD.encode(public_key.as_bytes()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Verifier;
+    use p256::ecdsa::VerifyingKey;
+
+    #[test]
+    fn test_encrypt_known_vector() {
+        // RFC 8291, appendix A
+        let sender_key = SecretKey::from_slice(&decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw").unwrap()).unwrap();
+        let salt: [u8; 16] = decode("DGv6ra1nlYgDCS1FRnbzlw").unwrap().try_into().unwrap();
+
+        let body = encrypt_with(
+            &sender_key,
+            salt,
+            "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+            "BTBZMqHH6r4Tts7J_aSIgg",
+            b"When I grow up, I want to be a watermelon",
+        )
+        .unwrap();
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn test_encrypt_rejects_bad_keys() {
+        let auth = "BTBZMqHH6r4Tts7J_aSIgg";
+        let p256dh = "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+
+        assert!(encrypt("not a key", auth, b"hi").is_err());
+        assert!(encrypt(p256dh, "c2hvcnQ", b"hi").is_err());
+        assert!(encrypt(p256dh, auth, &[0u8; 4096]).is_err());
+        assert!(encrypt(p256dh, auth, b"hi").is_ok());
+    }
+
+    #[test]
+    fn test_vapid_authorization() {
+        let private_key = "yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw";
+        let value = vapid_authorization(
+            private_key,
+            "mailto:admin@example.com",
+            "https://push.example.net/send/abc?x=1",
+            1_700_000_000,
+        )
+        .unwrap();
+
+        let (token, public_key) = value.strip_prefix("vapid t=").unwrap().split_once(", k=").unwrap();
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(&decode(public_key).unwrap()).unwrap();
+        let signature = Signature::from_slice(&decode(signature).unwrap()).unwrap();
+        assert!(verifying_key.verify(signing_input.as_bytes(), &signature).is_ok());
+
+        let claims: serde_json::Value =
+            serde_json::from_slice(&decode(signing_input.split_once('.').unwrap().1).unwrap()).unwrap();
+        assert_eq!(claims["aud"], "https://push.example.net");
+        assert_eq!(claims["exp"], 1_700_000_000);
+        assert_eq!(claims["sub"], "mailto:admin@example.com");
+    }
+}
@@ -0,0 +1,57 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::utils::secure_token;
+
+/// HMAC-SHA256 of a message, hex encoded
+pub fn hmac_sha256_hex(secret: &str, message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Signature header value of a webhook delivery: HMAC over "{timestamp}.{body}",
+/// so receivers can also reject replays of old deliveries
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    format!("sha256={}", hmac_sha256_hex(secret, &format!("{}.{}", timestamp, body)))
+}
+
+/// Check a signature made with `sign` (constant-time comparison)
+pub fn verify(secret: &str, timestamp: i64, body: &str, signature: &str) -> bool {
+    secure_token::constant_time_eq(&sign(secret, timestamp, body), signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_known_vector() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hmac_sha256_hex("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_sign_covers_secret_timestamp_and_body() {
+        let signature = sign("secret", 1700000000, r#"{"event":"member.joined"}"#);
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature, sign("secret", 1700000000, r#"{"event":"member.joined"}"#));
+        assert_ne!(signature, sign("other", 1700000000, r#"{"event":"member.joined"}"#));
+        assert_ne!(signature, sign("secret", 1700000001, r#"{"event":"member.joined"}"#));
+        assert_ne!(signature, sign("secret", 1700000000, r#"{"event":"room.updated"}"#));
+    }
+
+    #[test]
+    fn test_verify_accepts_only_matching_signature() {
+        let body = r#"{"content":"pong"}"#;
+        let signature = sign("secret", 1700000000, body);
+
+        assert!(verify("secret", 1700000000, body, &signature));
+        assert!(!verify("other", 1700000000, body, &signature));
+        assert!(!verify("secret", 1700000000, r#"{"content":"leak"}"#, &signature));
+        assert!(!verify("secret", 1700000000, body, ""));
+    }
+}
@@ -0,0 +1,109 @@
+use regex::{Regex, RegexBuilder};
+
+/// Replacement for masked matches
+pub const MASK: &str = "***";
+
+/// Upper bound on the compiled size of a single admin-supplied pattern
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+/// A compiled filter rule
+pub struct Rule {
+    regex: Regex,
+    block: bool,
+}
+
+impl Rule {
+    pub fn new(regex: Regex, block: bool) -> Self {
+        Self { regex, block }
+    }
+}
+
+/// Content after filtering
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Content to deliver, with masked matches replaced
+    Allowed(String),
+    /// A blocking rule matched; the content must not be delivered
+    Blocked,
+}
+
+/// Compile a filter pattern, case-insensitively. Plain words and phrases only match
+/// whole words ("ass" doesn't hit "class"); regex patterns are used as given.
+pub fn compile(pattern: &str, is_regex: bool) -> Result<Regex, regex::Error> {
+    let source = if is_regex {
+        pattern.to_string()
+    } else {
+        let pattern = pattern.trim();
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let start = if is_word(pattern.chars().next()) { r"\b" } else { "" };
+        let end = if is_word(pattern.chars().last()) { r"\b" } else { "" };
+        format!("{}{}{}", start, regex::escape(pattern), end)
+    };
+
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .build()
+}
+
+/// Run content through the rules: any blocking match rejects it, masking matches are replaced
+pub fn apply(content: &str, rules: &[Rule]) -> Outcome {
+    if rules.iter().any(|rule| rule.block && rule.regex.is_match(content)) {
+        return Outcome::Blocked;
+    }
+
+    let mut filtered = content.to_string();
+    for rule in rules.iter().filter(|rule| !rule.block) {
+        if rule.regex.is_match(&filtered) {
+            filtered = rule.regex.replace_all(&filtered, MASK).into_owned();
+        }
+    }
+
+    Outcome::Allowed(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(pattern: &str, is_regex: bool) -> Rule {
+        Rule::new(compile(pattern, is_regex).unwrap(), false)
+    }
+
+    #[test]
+    fn test_words_match_whole_words_case_insensitively() {
+        let rules = [mask("darn", false)];
+
+        assert_eq!(apply("Darn it, DARN!", &rules), Outcome::Allowed("*** it, ***!".to_string()));
+        assert_eq!(apply("darned", &rules), Outcome::Allowed("darned".to_string()));
+    }
+
+    #[test]
+    fn test_word_patterns_are_literal() {
+        let rules = [mask("f*ck", false), mask("a.b", false)];
+
+        assert_eq!(apply("oh f*ck", &rules), Outcome::Allowed("oh ***".to_string()));
+        assert_eq!(apply("axb a.b", &rules), Outcome::Allowed("axb ***".to_string()));
+    }
+
+    #[test]
+    fn test_regex_patterns() {
+        let rules = [mask(r"\bs+p+a+m+\b", true)];
+
+        assert_eq!(apply("SSSPAAAM here", &rules), Outcome::Allowed("*** here".to_string()));
+    }
+
+    #[test]
+    fn test_block_wins_over_mask() {
+        let rules = [mask("darn", false), Rule::new(compile("buy now", false).unwrap(), true)];
+
+        assert_eq!(apply("darn, buy now", &rules), Outcome::Blocked);
+        assert_eq!(apply("darn", &rules), Outcome::Allowed("***".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected() {
+        assert!(compile("(unclosed", true).is_err());
+        assert!(compile("(unclosed", false).is_ok());
+    }
+}
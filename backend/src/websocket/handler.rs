@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_ws::{CloseCode, CloseReason, Message};
+use futures_util::stream::{self, StreamExt};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::cache::{self, RedisPool};
+use crate::error::AppError;
+use crate::models::room::EVENT_MEMBERSHIP_ENDED;
+use crate::services::presence_service::PRESENCE_TTL_SECONDS;
+use crate::services::{AuthService, PresenceService, RoomService};
+use crate::shutdown;
+
+/// Query params of the WebSocket handshake
+#[derive(Deserialize)]
+pub struct ConnectQuery {
+    pub ticket: String,
+}
+
+/// GET /ws?ticket=...
+/// Upgrade to a WebSocket connection authenticated by a one-time ticket
+pub async fn connect(
+    req: HttpRequest,
+    body: web::Payload,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    query: web::Query<ConnectQuery>,
+) -> Result<HttpResponse, AppError> {
+    // Consume the ticket before upgrading so it can't be replayed
+    let user = AuthService::consume_ws_ticket(&pool, &redis_pool, &query.ticket).await?;
+
+    // Lets an admin action (e.g. a suspension) close this connection from any instance
+    let mut disconnects = match cache::subscribe(&redis_pool, &[cache::user_disconnect_channel(user.id)]).await {
+        Ok(pubsub) => pubsub.into_on_message().boxed_local(),
+        Err(e) => {
+            log::warn!("Failed to subscribe to disconnects of user {}: {}", user.id, e);
+            stream::pending().boxed_local()
+        }
+    };
+
+    // Instance-wide events (e.g. announcement banners), the user's own events and those
+    // of their rooms (e.g. moderation actions). Rooms joined later are picked up on reconnect
+    let own_channel = cache::user_channel(user.id);
+    let mut channels = vec![cache::broadcast_channel(), own_channel.clone()];
+    channels.extend(RoomService::event_channels(&pool, user.id).await?);
+
+    let mut events = match cache::subscribe(&redis_pool, &channels).await {
+        Ok(pubsub) => pubsub.into_on_message().boxed_local(),
+        Err(e) => {
+            log::warn!("Failed to subscribe to events for user {}: {}", user.id, e);
+            stream::pending().boxed_local()
+        }
+    };
+
+    let (response, mut session, mut msg_stream) = actix_ws::handle(&req, body)
+        .map_err(|e| AppError::InternalError(format!("WebSocket handshake failed: {}", e)))?;
+
+    log::info!("🔌 WebSocket connected: user {}", user.id);
+
+    let redis_pool = redis_pool.get_ref().clone();
+    PresenceService::connected(&redis_pool, user.id).await;
+
+    actix_web::rt::spawn(async move {
+        // Refresh presence well within its TTL, even when the client is quiet
+        let mut heartbeat = tokio::time::interval(std::time::Duration::from_secs(PRESENCE_TTL_SECONDS / 3));
+        let shutdown = shutdown::wait();
+        tokio::pin!(shutdown);
+
+        // Channels of rooms the user has left since connecting; still subscribed, never delivered
+        let mut left_rooms = HashSet::new();
+
+        loop {
+            tokio::select! {
+                msg = msg_stream.recv() => match msg {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(reason))) => {
+                        let _ = session.close(reason).await;
+                        break;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break,
+                },
+                Some(msg) = disconnects.next() => {
+                    let reason = msg.get_payload::<String>().unwrap_or_default();
+                    let _ = session
+                        .close(Some(CloseReason {
+                            code: CloseCode::Policy,
+                            description: Some(reason),
+                        }))
+                        .await;
+                    break;
+                }
+                Some(msg) = events.next() => {
+                    let channel = msg.get_channel_name();
+                    if left_rooms.contains(channel) {
+                        continue;
+                    }
+
+                    let payload = msg.get_payload::<String>().unwrap_or_default();
+                    if channel == own_channel {
+                        if let Some(room_id) = ended_membership(&payload) {
+                            left_rooms.insert(cache::room_channel(room_id));
+                        }
+                    }
+
+                    if session.text(payload).await.is_err() {
+                        break;
+                    }
+                }
+                _ = &mut shutdown => {
+                    // Clients are expected to reconnect (to another instance)
+                    let _ = session
+                        .close(Some(CloseReason {
+                            code: CloseCode::Restart,
+                            description: Some("Server shutting down".to_string()),
+                        }))
+                        .await;
+                    break;
+                }
+                _ = heartbeat.tick() => PresenceService::heartbeat(&redis_pool, user.id).await,
+            }
+        }
+
+        PresenceService::disconnected(&redis_pool, user.id).await;
+
+        log::info!("🔌 WebSocket disconnected: user {}", user.id);
+    });
+
+    Ok(response)
+}
+
+/// Room of a membership-ended event, whose events the connection must stop delivering
+fn ended_membership(payload: &str) -> Option<Uuid> {
+    let event = serde_json::from_str::<serde_json::Value>(payload).ok()?;
+    if event.get("type")?.as_str()? != EVENT_MEMBERSHIP_ENDED {
+        return None;
+    }
+
+    event.get("room_id")?.as_str()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ended_membership() {
+        let room_id = Uuid::new_v4();
+        let ended = serde_json::json!({ "type": EVENT_MEMBERSHIP_ENDED, "room_id": room_id }).to_string();
+        assert_eq!(ended_membership(&ended), Some(room_id));
+
+        let other = serde_json::json!({ "type": "room.topic_changed", "room_id": room_id }).to_string();
+        assert_eq!(ended_membership(&other), None);
+        assert_eq!(ended_membership("not json"), None);
+    }
+}
@@ -1,2 +1,3 @@
-// WebSocket module - will contain WebSocket server and handlers
-// To be implemented in FASE 7
+// WebSocket module - WebSocket server and handlers
+// Connections authenticate with one-time tickets from POST /api/auth/ws-ticket
+pub mod handler;